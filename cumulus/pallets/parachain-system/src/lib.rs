@@ -56,7 +56,11 @@ use sp_runtime::{
 	BoundedSlice, DispatchError, FixedU128, RuntimeDebug, Saturating,
 };
 use sp_std::{cmp, collections::btree_map::BTreeMap, prelude::*};
-use xcm::latest::XcmHash;
+use xcm::{
+	latest::{MultiLocation, XcmHash},
+	VersionedMultiLocation, VersionedXcm,
+};
+use xcm_builder::InspectMessageQueues;
 
 mod benchmarking;
 pub mod migration;
@@ -1641,6 +1645,25 @@ impl<T: Config> UpwardMessageSender for Pallet<T> {
 	}
 }
 
+impl<T: Config> InspectMessageQueues for Pallet<T> {
+	fn clear_messages() {
+		PendingUpwardMessages::<T>::kill();
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let messages: Vec<VersionedXcm<()>> = PendingUpwardMessages::<T>::get()
+			.iter()
+			.filter_map(|message| VersionedXcm::<()>::decode(&mut &message[..]).ok())
+			.collect();
+
+		if messages.is_empty() {
+			vec![]
+		} else {
+			vec![(VersionedMultiLocation::from(MultiLocation::parent()), messages)]
+		}
+	}
+}
+
 /// Something that can check the inherents of a block.
 #[cfg_attr(
 	feature = "parameterized-consensus-hook",
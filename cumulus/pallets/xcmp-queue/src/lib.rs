@@ -70,7 +70,10 @@ use scale_info::TypeInfo;
 use sp_core::MAX_POSSIBLE_ALLOCATION;
 use sp_runtime::{FixedU128, RuntimeDebug, Saturating};
 use sp_std::prelude::*;
-use xcm::{latest::prelude::*, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH};
+use xcm::{
+	latest::prelude::*, VersionedMultiLocation, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH,
+};
+use xcm_builder::InspectMessageQueues;
 use xcm_executor::traits::ConvertOrigin;
 
 pub use pallet::*;
@@ -957,6 +960,51 @@ impl<T: Config> SendXcm for Pallet<T> {
 	}
 }
 
+impl<T: Config> InspectMessageQueues for Pallet<T> {
+	fn clear_messages() {
+		// Only the pages are removed, so channels which are suspended or have signals queued keep
+		// their state.
+		let _ = OutboundXcmpMessages::<T>::clear(u32::MAX, None);
+		OutboundXcmpStatus::<T>::mutate(|channels| {
+			for channel in channels.iter_mut() {
+				channel.first_index = 0;
+				channel.last_index = 0;
+			}
+		});
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		OutboundXcmpStatus::<T>::get()
+			.iter()
+			.filter_map(|channel| {
+				let mut messages = Vec::new();
+				for page_index in channel.first_index..channel.last_index {
+					let page = OutboundXcmpMessages::<T>::get(channel.recipient, page_index);
+					let mut data = &page[..];
+					if XcmpMessageFormat::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut data) !=
+						Ok(XcmpMessageFormat::ConcatenatedVersionedXcm)
+					{
+						continue
+					}
+					while !data.is_empty() {
+						let Ok(xcm) = VersionedXcm::<()>::decode_with_depth_limit(
+							MAX_XCM_DECODE_DEPTH,
+							&mut data,
+						) else {
+							defensive!("Bad XCM in outbound queue; skipping the rest of the page");
+							break
+						};
+						messages.push(xcm);
+					}
+				}
+
+				let destination = MultiLocation::new(1, X1(Parachain(channel.recipient.into())));
+				(!messages.is_empty()).then(|| (destination.into(), messages))
+			})
+			.collect()
+	}
+}
+
 /// Checks that the XCM is decodable with `MAX_XCM_DECODE_DEPTH`.
 ///
 /// Note that this uses the limit of the sender - not the receiver. It it best effort.
@@ -731,6 +731,30 @@ fn xcmp_queue_send_xcm_works() {
 	})
 }
 
+#[test]
+fn inspect_message_queues_works() {
+	new_test_ext().execute_with(|| {
+		let sibling_para_id = ParaId::from(12345);
+		let dest: MultiLocation = (Parent, X1(Parachain(sibling_para_id.into()))).into();
+		let msg = Xcm(vec![ClearOrigin]);
+		ParachainSystem::open_outbound_hrmp_channel_for_benchmarks_or_tests(sibling_para_id);
+
+		assert!(XcmpQueue::get_messages().is_empty());
+
+		// Both messages end up in the same page.
+		assert_ok!(send_xcm::<XcmpQueue>(dest, msg.clone()));
+		assert_ok!(send_xcm::<XcmpQueue>(dest, msg.clone()));
+		assert_eq!(
+			XcmpQueue::get_messages(),
+			vec![(dest.into(), vec![VersionedXcm::from(msg.clone()), VersionedXcm::from(msg)])],
+		);
+
+		XcmpQueue::clear_messages();
+		assert!(XcmpQueue::get_messages().is_empty());
+		assert!(XcmpQueue::take_outbound_messages(usize::MAX).is_empty());
+	})
+}
+
 #[test]
 fn verify_fee_factor_increase_and_decrease() {
 	use cumulus_primitives_core::AbridgedHrmpChannel;
@@ -160,6 +160,7 @@ mod tests {
 		type RuntimeOrigin = RuntimeOrigin;
 		type Nonce = u64;
 		type RuntimeCall = RuntimeCall;
+		type RuntimeTask = RuntimeTask;
 		type Hash = H256;
 		type Hashing = BlakeTwo256;
 		type AccountId = AccountId;
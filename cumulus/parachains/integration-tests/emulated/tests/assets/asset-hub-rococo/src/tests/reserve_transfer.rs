@@ -15,8 +15,13 @@
 
 use crate::*;
 use asset_hub_rococo_runtime::xcm_config::XcmConfig as AssetHubRococoXcmConfig;
+use emulated_integration_tests_common::xcm_emulator::{
+	xcm_trace, xcm_traces, xcm_traces_json, BarrierDecision, HopTrace, InstructionTrace, Network,
+	SentFrom,
+};
 use penpal_runtime::xcm_config::XcmConfig as PenpalRococoXcmConfig;
 use rococo_runtime::xcm_config::XcmConfig as RococoXcmConfig;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 
 fn relay_to_para_sender_assertions(t: RelayToParaTest) {
 	type RuntimeEvent = <Rococo as Chain>::RuntimeEvent;
@@ -208,6 +213,20 @@ fn para_to_system_para_limited_reserve_transfer_assets(t: ParaToSystemParaTest)
 	)
 }
 
+/// The name of a traced instruction, without its operands
+fn instruction_name(instruction: &InstructionTrace) -> &str {
+	instruction
+		.instruction
+		.split(|c: char| !c.is_alphanumeric())
+		.next()
+		.unwrap_or_default()
+}
+
+/// Whether the traced execution finished with all of its instructions executed
+fn completed(hop: &HopTrace) -> bool {
+	hop.outcome.as_deref().map_or(false, |outcome| outcome.starts_with("Complete"))
+}
+
 /// Reserve Transfers of native asset from Relay Chain to the System Parachain shouldn't work
 #[test]
 fn reserve_transfer_native_asset_from_relay_to_system_para_fails() {
@@ -304,7 +323,9 @@ fn reserve_transfer_native_asset_from_relay_to_para() {
 	let delivery_fees = Rococo::execute_with(|| {
 		xcm_helpers::transfer_assets_delivery_fees::<
 			<RococoXcmConfig as xcm_executor::Config>::XcmSender,
-		>(test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest)
+		>(
+			test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest
+		)
 	});
 
 	let sender_balance_after = test.sender.balance;
@@ -316,6 +337,172 @@ fn reserve_transfer_native_asset_from_relay_to_para() {
 	assert!(receiver_balance_after > receiver_balance_before);
 }
 
+/// Reserve Transfers of native asset from Relay to Parachain executed as an XCM program should be
+/// traced on both chains, with the message received by the Parachain linked to the execution on
+/// the Relay which sent it
+#[test]
+fn reserve_transfer_native_asset_from_relay_to_para_is_traced() {
+	// Init values for Relay
+	let network = <<Rococo as Chain>::Network as Network>::name();
+	let signed_origin = <Rococo as Chain>::RuntimeOrigin::signed(RococoSender::get().into());
+	let destination = Rococo::child_location_of(PenpalA::para_id());
+	let beneficiary: MultiLocation =
+		AccountId32Junction { network: None, id: PenpalAReceiver::get().into() }.into();
+	let amount_to_send: Balance = ROCOCO_ED * 1000;
+	let message: VersionedXcm<<Rococo as Chain>::RuntimeCall> = VersionedXcm::V3(Xcm(vec![
+		SetFeesMode { jit_withdraw: true },
+		TransferReserveAsset {
+			assets: (Here, amount_to_send).into(),
+			dest: destination,
+			xcm: Xcm(vec![
+				BuyExecution {
+					fees: (Parent, amount_to_send).into(),
+					weight_limit: WeightLimit::Unlimited,
+				},
+				DepositAsset { assets: Wild(AllCounted(1)), beneficiary },
+			]),
+		},
+	]));
+	// `pallet-xcm` identifies the executed program by the hash of its versioned encoding
+	let local_message_id = BlakeTwo256::hash_of(&message).0;
+
+	Rococo::execute_with(|| {
+		assert_ok!(<Rococo as RococoPallet>::XcmPallet::execute(
+			signed_origin,
+			bx!(message),
+			Weight::from_parts(2_000_000_000, 200_000),
+		));
+	});
+
+	PenpalA::execute_with(|| {
+		type RuntimeEvent = <PenpalA as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			PenpalA,
+			vec![
+				RuntimeEvent::MessageQueue(
+					pallet_message_queue::Event::Processed { success: true, .. }
+				) => {},
+			]
+		);
+	});
+
+	// The program executed on the Relay forwards a single message to the Parachain
+	let sent = xcm_trace(network, &local_message_id).expect("the program was executed; qed");
+	assert_eq!(sent.sent_from, None);
+	assert_eq!(sent.hops.len(), 1);
+	let relay_hop = &sent.hops[0];
+	assert_eq!(relay_hop.chain, "Rococo");
+	assert_eq!(relay_hop.barrier, BarrierDecision::Passed);
+	assert_eq!(
+		relay_hop.instructions.iter().map(instruction_name).collect::<Vec<_>>(),
+		vec!["SetFeesMode", "TransferReserveAsset"],
+	);
+	assert!(relay_hop.instructions.iter().all(|instruction| instruction.error.is_none()));
+	assert!(relay_hop.fees_paid.iter().any(|fee| fee.reason == "TransferReserveAsset"));
+	assert!(relay_hop.assets_trapped.is_empty());
+	assert!(completed(relay_hop));
+	assert_eq!(relay_hop.messages_forwarded.len(), 1);
+	let forwarded = &relay_hop.messages_forwarded[0];
+	assert_eq!(forwarded.destination, destination);
+
+	// The forwarded message is executed on the Parachain and links back to the Relay
+	let received = xcm_traces(network)
+		.into_iter()
+		.find(|trace| trace.message_id == forwarded.message_id)
+		.expect("the forwarded message was executed; qed");
+	assert_eq!(
+		received.sent_from,
+		Some(SentFrom { chain: "Rococo".into(), message_id: Some(sent.message_id.clone()) }),
+	);
+	assert_eq!(received.hops.len(), 1);
+	let para_hop = &received.hops[0];
+	assert_eq!(para_hop.chain, "PenpalA");
+	assert_eq!(para_hop.origin, MultiLocation::parent());
+	assert_eq!(para_hop.barrier, BarrierDecision::Passed);
+	assert_eq!(
+		para_hop.instructions.iter().map(instruction_name).collect::<Vec<_>>(),
+		vec!["ReserveAssetDeposited", "ClearOrigin", "BuyExecution", "DepositAsset", "SetTopic"],
+	);
+	assert!(para_hop.instructions.iter().all(|instruction| instruction.error.is_none()));
+	// Everything left after buying execution was deposited to the beneficiary
+	assert!(para_hop.instructions[3].holding.is_empty());
+	assert!(para_hop.assets_trapped.is_empty());
+	assert!(completed(para_hop));
+
+	// The exported JSON holds the same traces and links
+	let json: serde_json::Value =
+		serde_json::from_str(&xcm_traces_json(network)).expect("traces are valid JSON; qed");
+	let exported = json
+		.as_array()
+		.expect("traces are exported as an array; qed")
+		.iter()
+		.find(|trace| trace["message_id"] == received.message_id.as_str())
+		.expect("the forwarded message was exported; qed");
+	assert_eq!(exported["sent_from"]["chain"], "Rococo");
+	assert_eq!(exported["sent_from"]["message_id"], sent.message_id.as_str());
+	assert_eq!(exported["hops"][0]["chain"], "PenpalA");
+	assert_eq!(exported["hops"][0]["barrier"], "passed");
+	assert_eq!(exported["hops"][0]["instructions"][0]["index"], 0);
+}
+
+/// Reserve Transfers of native asset from Relay to Parachain through the extrinsic should be traced,
+/// with the message received by the Parachain linked to the Relay which sent it
+#[test]
+fn limited_reserve_transfer_native_asset_from_relay_to_para_is_traced() {
+	// Init values for Relay
+	let network = <<Rococo as Chain>::Network as Network>::name();
+	let signed_origin = <Rococo as Chain>::RuntimeOrigin::signed(RococoSender::get().into());
+	let destination = Rococo::child_location_of(PenpalA::para_id());
+	let beneficiary: MultiLocation =
+		AccountId32Junction { network: None, id: PenpalAReceiver::get().into() }.into();
+	let amount_to_send: Balance = ROCOCO_ED * 1000;
+	let assets: MultiAssets = (Here, amount_to_send).into();
+
+	let message_id = Rococo::execute_with(|| {
+		type RuntimeEvent = <Rococo as Chain>::RuntimeEvent;
+		assert_ok!(<Rococo as RococoPallet>::XcmPallet::limited_reserve_transfer_assets(
+			signed_origin,
+			bx!(destination.into()),
+			bx!(beneficiary.into()),
+			bx!(assets.into()),
+			0,
+			WeightLimit::Unlimited,
+		));
+		Rococo::events()
+			.into_iter()
+			.find_map(|event| match event {
+				RuntimeEvent::XcmPallet(pallet_xcm::Event::Sent { message_id, .. }) =>
+					Some(message_id),
+				_ => None,
+			})
+			.expect("the transfer sent a message; qed")
+	});
+
+	PenpalA::execute_with(|| {
+		type RuntimeEvent = <PenpalA as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			PenpalA,
+			vec![
+				RuntimeEvent::MessageQueue(
+					pallet_message_queue::Event::Processed { success: true, .. }
+				) => {},
+			]
+		);
+	});
+
+	// The message sent by the extrinsic is linked to the Relay and executed on the Parachain
+	let received = xcm_trace(network, &message_id).expect("the message was executed; qed");
+	assert_eq!(received.sent_from, Some(SentFrom { chain: "Rococo".into(), message_id: None }));
+	assert_eq!(received.hops.len(), 1);
+	let para_hop = &received.hops[0];
+	assert_eq!(para_hop.chain, "PenpalA");
+	assert_eq!(para_hop.origin, MultiLocation::parent());
+	assert_eq!(para_hop.barrier, BarrierDecision::Passed);
+	assert!(para_hop.instructions.iter().all(|instruction| instruction.error.is_none()));
+	assert!(para_hop.assets_trapped.is_empty());
+	assert!(completed(para_hop));
+}
+
 /// Reserve Transfers of native asset from System Parachain to Parachain should work
 #[test]
 fn reserve_transfer_native_asset_from_system_para_to_para() {
@@ -347,7 +534,9 @@ fn reserve_transfer_native_asset_from_system_para_to_para() {
 	let delivery_fees = AssetHubRococo::execute_with(|| {
 		xcm_helpers::transfer_assets_delivery_fees::<
 			<AssetHubRococoXcmConfig as xcm_executor::Config>::XcmSender,
-		>(test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest)
+		>(
+			test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest
+		)
 	});
 
 	// Sender's balance is reduced
@@ -393,7 +582,9 @@ fn reserve_transfer_native_asset_from_para_to_system_para() {
 	let delivery_fees = PenpalA::execute_with(|| {
 		xcm_helpers::transfer_assets_delivery_fees::<
 			<PenpalRococoXcmConfig as xcm_executor::Config>::XcmSender,
-		>(test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest)
+		>(
+			test.args.assets.clone(), 0, test.args.weight_limit, test.args.beneficiary, test.args.dest
+		)
 	});
 
 	// Sender's balance is reduced
@@ -31,8 +31,8 @@ use frame_support::{
 use polkadot_runtime_common::xcm_sender::PriceForMessageDelivery;
 use sp_runtime::{traits::Saturating, SaturatedConversion};
 use sp_std::{marker::PhantomData, prelude::*};
-use xcm::{latest::prelude::*, WrapVersion};
-use xcm_builder::TakeRevenue;
+use xcm::{latest::prelude::*, VersionedMultiLocation, VersionedXcm, WrapVersion};
+use xcm_builder::{InspectMessageQueues, TakeRevenue};
 use xcm_executor::traits::{MatchesFungibles, TransactAsset, WeightTrader};
 
 /// Xcm router which recognises the `Parent` destination and handles it by sending the message into
@@ -84,6 +84,19 @@ where
 	}
 }
 
+impl<T, W, P> InspectMessageQueues for ParentAsUmp<T, W, P>
+where
+	T: UpwardMessageSender + InspectMessageQueues,
+{
+	fn clear_messages() {
+		T::clear_messages();
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		T::get_messages()
+	}
+}
+
 /// Contains information to handle refund/payment for xcm-execution
 #[derive(Clone, Eq, PartialEq, Debug)]
 struct AssetTraderRefunder {
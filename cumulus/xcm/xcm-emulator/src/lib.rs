@@ -65,6 +65,13 @@ pub use xcm::v3::prelude::{
 };
 pub use xcm_executor::traits::ConvertLocation;
 
+mod trace;
+pub use trace::{
+	clear_xcm_traces, observe_xcm_execution, xcm_trace, xcm_traces, xcm_traces_json,
+	BarrierDecision, ChainObserver, FeeTrace, ForwardTrace, HopTrace, InstructionTrace, SentFrom,
+	WeightTrace, XcmTrace, XcmTraceReport, XCM_TRACES,
+};
+
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 
 thread_local! {
@@ -514,8 +521,10 @@ macro_rules! __impl_test_ext_for_relay_chain {
 				// Make sure the Network is initialized
 				<$network>::init();
 
-				// Execute
-				let r = $local_ext.with(|v| v.borrow_mut().execute_with(execute));
+				// Execute, tracing any XCM executed along the way
+				let r = $local_ext.with(|v| v.borrow_mut().execute_with(|| {
+					$crate::observe_xcm_execution(<$network>::name(), stringify!($name), execute)
+				}));
 
 				// Send messages if needed
 				$local_ext.with(|v| {
@@ -555,7 +564,11 @@ macro_rules! __impl_test_ext_for_relay_chain {
 			fn ext_wrapper<R>(func: impl FnOnce() -> R) -> R {
 				$local_ext.with(|v| {
 					v.borrow_mut().execute_with(|| {
-						func()
+						$crate::observe_xcm_execution(
+							<$network as $crate::Network>::name(),
+							stringify!($name),
+							func,
+						)
 					})
 				})
 			}
@@ -813,8 +826,10 @@ macro_rules! __impl_test_ext_for_parachain {
 				// Initialize a new block
 				Self::new_block();
 
-				// Execute
-				let r = $local_ext.with(|v| v.borrow_mut().execute_with(execute));
+				// Execute, tracing any XCM executed along the way
+				let r = $local_ext.with(|v| v.borrow_mut().execute_with(|| {
+					$crate::observe_xcm_execution(<$network>::name(), stringify!($name), execute)
+				}));
 
 				// Finalize the block
 				Self::finalize_block();
@@ -879,7 +894,11 @@ macro_rules! __impl_test_ext_for_parachain {
 			fn ext_wrapper<R>(func: impl FnOnce() -> R) -> R {
 				$local_ext.with(|v| {
 					v.borrow_mut().execute_with(|| {
-						func()
+						$crate::observe_xcm_execution(
+							<$network as $crate::Network>::name(),
+							stringify!($name),
+							func,
+						)
 					})
 				})
 			}
@@ -923,6 +942,7 @@ macro_rules! decl_test_networks {
 					$crate::HORIZONTAL_MESSAGES.with(|b| b.borrow_mut().remove(Self::name()));
 					$crate::BRIDGED_MESSAGES.with(|b| b.borrow_mut().remove(Self::name()));
 					$crate::LAST_HEAD.with(|b| b.borrow_mut().remove(Self::name()));
+					$crate::clear_xcm_traces(Self::name());
 
 					<$relay_chain<Self>>::reset_ext();
 					$( <$parachain<Self>>::reset_ext(); )*
@@ -1506,6 +1526,8 @@ where
 	}
 	/// Executes all dispatchables and assertions in order from `Origin` to `Destination`
 	pub fn assert(&mut self) {
+		// Print what happened to the messages involved if any of the assertions fail.
+		let _report = XcmTraceReport(<Origin::Network as Network>::name());
 		Origin::check_assertion(self.clone());
 		Hops::check_assertion(self.clone());
 		Destination::check_assertion(self.clone());
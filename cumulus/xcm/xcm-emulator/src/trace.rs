@@ -0,0 +1,344 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracing of XCM messages as they are executed by the chains of an emulated network.
+//!
+//! Every execution of the XCM executor on a chain of the network is recorded as a [`HopTrace`]
+//! under the id of the message being executed, which is its `SetTopic` id when present. All hops
+//! of a message, together with the execution which sent it, form its [`XcmTrace`].
+
+use crate::{fmt, HashMap, RefCell, Weight, XcmHash};
+use codec::Decode;
+use frame_support::traits::ProcessMessageError;
+use serde::Serialize;
+use sp_core::hexdisplay::HexDisplay;
+use xcm::v3::{Instruction, MultiAsset, MultiAssets, MultiLocation, Outcome};
+use xcm_executor::{traits::FeeReason, Assets, ExecutionObserver};
+
+thread_local! {
+	/// Traces of the XCM messages executed in each Network, in order of first appearance.
+	pub static XCM_TRACES: RefCell<HashMap<String, Vec<XcmTrace>>> = RefCell::new(HashMap::new());
+}
+
+/// The weight of an execution step, in a form fit for export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct WeightTrace {
+	pub ref_time: u64,
+	pub proof_size: u64,
+}
+
+impl From<Weight> for WeightTrace {
+	fn from(weight: Weight) -> Self {
+		Self { ref_time: weight.ref_time(), proof_size: weight.proof_size() }
+	}
+}
+
+/// What the barrier of a chain decided about an incoming message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarrierDecision {
+	Passed,
+	Rejected(String),
+}
+
+/// A single instruction executed on a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InstructionTrace {
+	/// Position of the instruction in the program being executed. Error handlers and appendices
+	/// are separate programs and so start again from zero.
+	pub index: u32,
+	pub instruction: String,
+	pub weight: WeightTrace,
+	/// The error the instruction failed with, if any.
+	pub error: Option<String>,
+	/// Contents of the holding register after the instruction was executed.
+	pub holding: Vec<MultiAsset>,
+}
+
+/// Fees paid during the execution of a message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeTrace {
+	pub assets: Vec<MultiAsset>,
+	pub reason: String,
+}
+
+/// A message sent onwards during the execution of a message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ForwardTrace {
+	pub destination: MultiLocation,
+	pub message_id: String,
+}
+
+/// The execution of a message on a single chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HopTrace {
+	pub chain: String,
+	pub origin: MultiLocation,
+	/// The weight the message was estimated to need before execution.
+	pub weight: WeightTrace,
+	pub barrier: BarrierDecision,
+	pub instructions: Vec<InstructionTrace>,
+	pub fees_paid: Vec<FeeTrace>,
+	pub messages_forwarded: Vec<ForwardTrace>,
+	pub assets_trapped: Vec<MultiAsset>,
+	/// The outcome of the execution, `None` if it did not finish.
+	pub outcome: Option<String>,
+}
+
+/// The chain and execution which sent a message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SentFrom {
+	pub chain: String,
+	/// The message whose execution sent this one, `None` if it was sent outside of the executor,
+	/// e.g. by an extrinsic of `pallet-xcm`.
+	pub message_id: Option<String>,
+}
+
+/// Everything recorded about a single XCM message across the chains of a network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct XcmTrace {
+	pub message_id: String,
+	/// Where this message was sent from, if it was sent in the network.
+	pub sent_from: Option<SentFrom>,
+	/// The executions of this message, in order.
+	pub hops: Vec<HopTrace>,
+}
+
+impl XcmTrace {
+	fn new(message_id: String) -> Self {
+		Self { message_id, sent_from: None, hops: Vec::new() }
+	}
+
+	/// Export the trace as pretty-printed JSON.
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("traces only hold serializable values; qed")
+	}
+}
+
+impl fmt::Display for XcmTrace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "XCM {}", self.message_id)?;
+		if let Some(sent_from) = &self.sent_from {
+			write!(f, ", sent from {}", sent_from.chain)?;
+			if let Some(message_id) = &sent_from.message_id {
+				write!(f, " while executing {}", message_id)?;
+			}
+		}
+		writeln!(f)?;
+		for (i, hop) in self.hops.iter().enumerate() {
+			writeln!(f, "  [{}] {} from {:?}, weight {:?}", i, hop.chain, hop.origin, hop.weight)?;
+			match &hop.barrier {
+				BarrierDecision::Passed => writeln!(f, "      barrier: passed")?,
+				BarrierDecision::Rejected(e) => writeln!(f, "      barrier: rejected ({})", e)?,
+			}
+			for instruction in &hop.instructions {
+				let result = instruction.error.as_deref().unwrap_or("ok");
+				writeln!(
+					f,
+					"      {:>3}. {} ({}, ref_time {})",
+					instruction.index, instruction.instruction, result, instruction.weight.ref_time,
+				)?;
+				writeln!(f, "           holding: {:?}", instruction.holding)?;
+			}
+			for fee in &hop.fees_paid {
+				writeln!(f, "      fees paid: {:?} ({})", fee.assets, fee.reason)?;
+			}
+			for forward in &hop.messages_forwarded {
+				writeln!(f, "      forwarded {} to {:?}", forward.message_id, forward.destination)?;
+			}
+			if !hop.assets_trapped.is_empty() {
+				writeln!(f, "      assets trapped: {:?}", hop.assets_trapped)?;
+			}
+			writeln!(f, "      outcome: {}", hop.outcome.as_deref().unwrap_or("unfinished"))?;
+		}
+		Ok(())
+	}
+}
+
+fn message_id_string(id: &XcmHash) -> String {
+	format!("0x{}", HexDisplay::from(id))
+}
+
+fn with_traces<R>(network: &str, f: impl FnOnce(&mut Vec<XcmTrace>) -> R) -> R {
+	XCM_TRACES.with(|t| f(t.borrow_mut().entry(network.to_string()).or_default()))
+}
+
+fn with_trace<R>(network: &str, message_id: &str, f: impl FnOnce(&mut XcmTrace) -> R) -> R {
+	with_traces(network, |traces| {
+		let position = match traces.iter().position(|t| t.message_id == message_id) {
+			Some(position) => position,
+			None => {
+				traces.push(XcmTrace::new(message_id.to_string()));
+				traces.len() - 1
+			},
+		};
+		f(&mut traces[position])
+	})
+}
+
+/// All traces recorded in `network`, in order of first appearance.
+pub fn xcm_traces(network: &str) -> Vec<XcmTrace> {
+	with_traces(network, |traces| traces.clone())
+}
+
+/// The trace of the message with the given `message_id` in `network`, if it was seen.
+pub fn xcm_trace(network: &str, message_id: &XcmHash) -> Option<XcmTrace> {
+	let message_id = message_id_string(message_id);
+	with_traces(network, |traces| traces.iter().find(|t| t.message_id == message_id).cloned())
+}
+
+/// All traces recorded in `network` as a pretty-printed JSON array.
+pub fn xcm_traces_json(network: &str) -> String {
+	serde_json::to_string_pretty(&xcm_traces(network))
+		.expect("traces only hold serializable values; qed")
+}
+
+/// Forget all traces recorded in `network`.
+pub fn clear_xcm_traces(network: &str) {
+	XCM_TRACES.with(|t| t.borrow_mut().remove(network));
+}
+
+/// Prints every trace recorded in a network if dropped while the thread is panicking, i.e. when
+/// a test fails.
+#[must_use = "the report is only printed when the guard is dropped"]
+pub struct XcmTraceReport(pub &'static str);
+
+impl Drop for XcmTraceReport {
+	fn drop(&mut self) {
+		if !std::thread::panicking() {
+			return
+		}
+		let traces = xcm_traces(self.0);
+		if traces.is_empty() {
+			return
+		}
+		eprintln!("XCM traces of {}:", self.0);
+		traces.iter().for_each(|trace| eprintln!("{}", trace));
+	}
+}
+
+/// Records the executions happening on a single chain of a network into [`XCM_TRACES`].
+pub struct ChainObserver {
+	network: &'static str,
+	chain: &'static str,
+	/// Ids and hop positions of the executions in progress, innermost last.
+	executing: Vec<(String, usize)>,
+}
+
+impl ChainObserver {
+	pub fn new(network: &'static str, chain: &'static str) -> Self {
+		Self { network, chain, executing: Vec::new() }
+	}
+
+	fn with_current_hop(&self, f: impl FnOnce(&mut HopTrace)) {
+		if let Some((message_id, hop)) = self.executing.last() {
+			with_trace(self.network, message_id, |trace| f(&mut trace.hops[*hop]));
+		}
+	}
+}
+
+impl ExecutionObserver for ChainObserver {
+	fn on_barrier(
+		&mut self,
+		origin: &MultiLocation,
+		message_id: &XcmHash,
+		weight: Weight,
+		result: &Result<(), ProcessMessageError>,
+	) {
+		let message_id = message_id_string(message_id);
+		let barrier = match result {
+			Ok(()) => BarrierDecision::Passed,
+			Err(e) => BarrierDecision::Rejected(format!("{:?}", e)),
+		};
+		let hop = HopTrace {
+			chain: self.chain.to_string(),
+			origin: *origin,
+			weight: weight.into(),
+			barrier,
+			instructions: Vec::new(),
+			fees_paid: Vec::new(),
+			messages_forwarded: Vec::new(),
+			assets_trapped: Vec::new(),
+			outcome: None,
+		};
+		let position = with_trace(self.network, &message_id, |trace| {
+			trace.hops.push(hop);
+			trace.hops.len() - 1
+		});
+		self.executing.push((message_id, position));
+	}
+
+	fn on_instruction(
+		&mut self,
+		index: u32,
+		instruction: &[u8],
+		weight: Weight,
+		result: &xcm::v3::Result,
+		holding: &Assets,
+	) {
+		let instruction = match Instruction::<()>::decode(&mut &instruction[..]) {
+			Ok(instruction) => format!("{:?}", instruction),
+			Err(_) => format!("<undecodable 0x{}>", HexDisplay::from(&instruction)),
+		};
+		let trace = InstructionTrace {
+			index,
+			instruction,
+			weight: weight.into(),
+			error: result.err().map(|e| format!("{:?}", e)),
+			holding: holding.clone().into(),
+		};
+		self.with_current_hop(|hop| hop.instructions.push(trace));
+	}
+
+	fn on_fees_paid(&mut self, fees: &MultiAssets, reason: FeeReason) {
+		let fee = FeeTrace { assets: fees.clone().into_inner(), reason: format!("{:?}", reason) };
+		self.with_current_hop(|hop| hop.fees_paid.push(fee));
+	}
+
+	fn on_message_sent(&mut self, destination: &MultiLocation, message_id: &XcmHash) {
+		let message_id = message_id_string(message_id);
+		let forward = ForwardTrace { destination: *destination, message_id: message_id.clone() };
+		self.with_current_hop(|hop| hop.messages_forwarded.push(forward));
+		let sender_id = self.executing.last().map(|(sender_id, _)| sender_id.clone());
+		if sender_id.as_ref() != Some(&message_id) {
+			let sent_from = SentFrom { chain: self.chain.to_string(), message_id: sender_id };
+			with_trace(self.network, &message_id, |trace| {
+				trace.sent_from.get_or_insert(sent_from);
+			});
+		}
+	}
+
+	fn on_assets_trapped(&mut self, _origin: &MultiLocation, assets: &Assets) {
+		let assets: Vec<MultiAsset> = assets.clone().into();
+		self.with_current_hop(|hop| hop.assets_trapped.extend(assets));
+	}
+
+	fn on_outcome(&mut self, outcome: &Outcome) {
+		let outcome = format!("{:?}", outcome);
+		self.with_current_hop(|hop| hop.outcome = Some(outcome));
+		self.executing.pop();
+	}
+}
+
+/// Execute `f` on `chain` of `network`, recording the XCM executions happening within it.
+pub fn observe_xcm_execution<R>(
+	network: &'static str,
+	chain: &'static str,
+	f: impl FnOnce() -> R,
+) -> R {
+	let mut observer = ChainObserver::new(network, chain);
+	xcm_executor::observe_execution(&mut observer, f)
+}
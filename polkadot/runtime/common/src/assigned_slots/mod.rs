@@ -685,6 +685,7 @@ mod tests {
 		type BlockLength = ();
 		type RuntimeOrigin = RuntimeOrigin;
 		type RuntimeCall = RuntimeCall;
+		type RuntimeTask = RuntimeTask;
 		type Nonce = u64;
 		type Hash = H256;
 		type Hashing = BlakeTwo256;
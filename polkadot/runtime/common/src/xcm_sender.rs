@@ -18,7 +18,7 @@
 
 use frame_support::traits::Get;
 use frame_system::pallet_prelude::BlockNumberFor;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
 use primitives::Id as ParaId;
 use runtime_parachains::{
 	configuration::{self, HostConfiguration},
@@ -27,6 +27,7 @@ use runtime_parachains::{
 use sp_runtime::FixedPointNumber;
 use sp_std::{marker::PhantomData, prelude::*};
 use xcm::prelude::*;
+use xcm_builder::InspectMessageQueues;
 use SendError::*;
 
 /// Simple value-bearing trait for determining/expressing the assets required to be paid for a
@@ -136,6 +137,25 @@ where
 	}
 }
 
+impl<T: dmp::Config, W, P> InspectMessageQueues for ChildParachainRouter<T, W, P> {
+	fn clear_messages() {
+		dmp::Pallet::<T>::clear_dmq_contents_all();
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		dmp::Pallet::<T>::dmq_contents_all()
+			.into_iter()
+			.map(|(para, queue)| {
+				let messages = queue
+					.iter()
+					.filter_map(|message| VersionedXcm::<()>::decode(&mut &message.msg[..]).ok())
+					.collect();
+				(MultiLocation::from(Parachain(para.into())).into(), messages)
+			})
+			.collect()
+	}
+}
+
 /// Implementation of `pallet_xcm_benchmarks::EnsureDelivery` which helps to ensure delivery to the
 /// `ParaId` parachain (sibling or child). Deposits existential deposit for origin (if needed).
 /// Deposits estimated fee to the origin account (if needed).
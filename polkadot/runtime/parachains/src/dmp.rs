@@ -336,6 +336,19 @@ impl<T: Config> Pallet<T> {
 	) -> Vec<InboundDownwardMessage<BlockNumberFor<T>>> {
 		DownwardMessageQueues::<T>::get(&recipient)
 	}
+
+	/// Returns the contents of all non-empty downward message queues, keyed by the recipient.
+	pub fn dmq_contents_all() -> Vec<(ParaId, Vec<InboundDownwardMessage<BlockNumberFor<T>>>)> {
+		DownwardMessageQueues::<T>::iter().collect()
+	}
+
+	/// Removes all messages from all downward message queues.
+	///
+	/// This does not touch the message queue chain heads and is only meant to be used while
+	/// inspecting the messages sent by a dry-run.
+	pub fn clear_dmq_contents_all() {
+		let _ = DownwardMessageQueues::<T>::clear(u32::MAX, None);
+	}
 }
 
 impl<T: Config> FeeTracker for Pallet<T> {
@@ -0,0 +1,167 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dry-running of calls and XCM programs.
+//!
+//! The [`DryRunApi`] runtime API executes a call or an XCM program against the current state and
+//! reports what it would do: the outcome, the emitted events and the messages queued for delivery
+//! to other chains. All changes are reverted afterwards. The messages sent by one dry-run can be
+//! passed to [`DryRunApi::dry_run_xcm`] of their destination to follow a transfer across hops.
+//!
+//! A runtime implements the API with the functions of [`Pallet`], passing the router whose queues
+//! are inspected:
+//!
+//! ```ignore
+//! impl pallet_xcm::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller>
+//! 	for Runtime
+//! {
+//! 	fn dry_run_call(
+//! 		origin: OriginCaller,
+//! 		call: RuntimeCall,
+//! 	) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+//! 		XcmPallet::dry_run_call::<XcmRouter, _>(origin, call)
+//! 	}
+//!
+//! 	fn dry_run_xcm(
+//! 		origin_location: VersionedMultiLocation,
+//! 		xcm: VersionedXcm<RuntimeCall>,
+//! 	) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+//! 		XcmPallet::dry_run_xcm::<XcmRouter>(origin_location, xcm)
+//! 	}
+//! }
+//! ```
+
+use crate::{Config, Pallet};
+use codec::{Codec, Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	storage::{with_transaction_unchecked, TransactionOutcome},
+};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Dispatchable, RuntimeDebug};
+use sp_std::prelude::*;
+use xcm::prelude::*;
+use xcm_builder::InspectMessageQueues;
+
+/// The effects of dry-running a call.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct CallDryRunEffects<Event> {
+	/// The result of dispatching the call.
+	pub execution_result: DispatchResultWithPostInfo,
+	/// The events emitted while dispatching the call.
+	pub emitted_events: Vec<Event>,
+	/// The messages queued for delivery, grouped by their destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+}
+
+/// The effects of dry-running an XCM program.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct XcmDryRunEffects<Event> {
+	/// The outcome of executing the program.
+	pub execution_result: Outcome,
+	/// The events emitted while executing the program.
+	pub emitted_events: Vec<Event>,
+	/// The messages queued for delivery, grouped by their destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+}
+
+/// Errors of the [`DryRunApi`].
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum XcmDryRunApiError {
+	/// The given XCM program or location could not be converted to the version used by the
+	/// runtime.
+	#[codec(index = 0)]
+	VersionedConversionFailed,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Dry-running of calls and XCM programs.
+	///
+	/// Calls and programs are executed against the current state and all changes are reverted
+	/// afterwards.
+	pub trait DryRunApi<Call, Event, OriginCaller>
+	where
+		Call: Codec,
+		Event: Codec,
+		OriginCaller: Codec,
+	{
+		/// Dry-run `call` dispatched from `origin`.
+		fn dry_run_call(origin: OriginCaller, call: Call) -> Result<CallDryRunEffects<Event>, XcmDryRunApiError>;
+
+		/// Dry-run the XCM program `xcm` executed on behalf of `origin_location`.
+		fn dry_run_xcm(origin_location: VersionedMultiLocation, xcm: VersionedXcm<Call>) -> Result<XcmDryRunEffects<Event>, XcmDryRunApiError>;
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Dry-run `call` dispatched from `origin`, reporting the messages queued by `Router`.
+	///
+	/// Implements [`DryRunApi::dry_run_call`].
+	pub fn dry_run_call<Router: InspectMessageQueues, OriginCaller>(
+		origin: OriginCaller,
+		call: <T as Config>::RuntimeCall,
+	) -> Result<CallDryRunEffects<<T as frame_system::Config>::RuntimeEvent>, XcmDryRunApiError>
+	where
+		<T as Config>::RuntimeOrigin: From<OriginCaller>,
+	{
+		let (execution_result, emitted_events, forwarded_xcms) =
+			Self::dry_run::<Router, _>(|| call.dispatch(origin.into()));
+		Ok(CallDryRunEffects { execution_result, emitted_events, forwarded_xcms })
+	}
+
+	/// Dry-run the XCM program `xcm` executed on behalf of `origin_location`, reporting the
+	/// messages queued by `Router`.
+	///
+	/// The program is executed without a weight limit. Implements [`DryRunApi::dry_run_xcm`].
+	pub fn dry_run_xcm<Router: InspectMessageQueues>(
+		origin_location: VersionedMultiLocation,
+		xcm: VersionedXcm<<T as Config>::RuntimeCall>,
+	) -> Result<XcmDryRunEffects<<T as frame_system::Config>::RuntimeEvent>, XcmDryRunApiError> {
+		let origin_location: MultiLocation = origin_location
+			.try_into()
+			.map_err(|()| XcmDryRunApiError::VersionedConversionFailed)?;
+		let xcm: Xcm<<T as Config>::RuntimeCall> =
+			xcm.try_into().map_err(|()| XcmDryRunApiError::VersionedConversionFailed)?;
+		let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+
+		let (execution_result, emitted_events, forwarded_xcms) = Self::dry_run::<Router, _>(|| {
+			T::XcmExecutor::execute_xcm(origin_location, xcm, hash, Weight::MAX)
+		});
+		Ok(XcmDryRunEffects { execution_result, emitted_events, forwarded_xcms })
+	}
+
+	/// Run `f` with empty event and message queues, and revert all changes afterwards.
+	fn dry_run<Router: InspectMessageQueues, R>(
+		f: impl FnOnce() -> R,
+	) -> (
+		R,
+		Vec<<T as frame_system::Config>::RuntimeEvent>,
+		Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+	) {
+		with_transaction_unchecked(|| {
+			frame_system::Pallet::<T>::reset_events();
+			Router::clear_messages();
+
+			let result = f();
+
+			let events = frame_system::Pallet::<T>::read_events_no_consensus()
+				.map(|record| record.event)
+				.collect();
+			let messages = Router::get_messages();
+			TransactionOutcome::Rollback((result, events, messages))
+		})
+	}
+}
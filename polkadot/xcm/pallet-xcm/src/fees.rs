@@ -0,0 +1,146 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Estimation of the fees of XCM programs.
+//!
+//! The [`XcmPaymentApi`] runtime API exposes the weight of an XCM program, the price of weight
+//! in a given asset and the price of delivering a message to a destination. Together with the
+//! [`dry_run`](crate::dry_run) API, the fees of every hop of a transfer can be computed.
+//!
+//! A runtime implements the API with the functions of [`Pallet`], passing the trader that its
+//! XCM executor uses:
+//!
+//! ```ignore
+//! impl pallet_xcm::fees::XcmPaymentApi<Block> for Runtime {
+//! 	fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError> {
+//! 		XcmPallet::query_xcm_weight(message)
+//! 	}
+//!
+//! 	fn query_weight_to_asset_fee(
+//! 		weight: Weight,
+//! 		asset: VersionedAssetId,
+//! 	) -> Result<u128, XcmPaymentApiError> {
+//! 		XcmPallet::query_weight_to_asset_fee::<<XcmConfig as xcm_executor::Config>::Trader>(
+//! 			weight, asset,
+//! 		)
+//! 	}
+//!
+//! 	fn query_delivery_fees(
+//! 		destination: VersionedMultiLocation,
+//! 		message: VersionedXcm<()>,
+//! 	) -> Result<VersionedMultiAssets, XcmPaymentApiError> {
+//! 		XcmPallet::query_delivery_fees(destination, message)
+//! 	}
+//! }
+//! ```
+
+use crate::{Config, Pallet};
+use codec::{Decode, Encode};
+use frame_support::storage::{with_transaction_unchecked, TransactionOutcome};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{WeightBounds, WeightTrader},
+	Assets,
+};
+
+/// Errors of the [`XcmPaymentApi`].
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum XcmPaymentApiError {
+	/// The given message, location or asset could not be converted to the version used by the
+	/// runtime.
+	#[codec(index = 0)]
+	VersionedConversionFailed,
+	/// The weight of the message could not be computed.
+	#[codec(index = 1)]
+	WeightNotComputable,
+	/// The given asset is not accepted for the payment of fees.
+	#[codec(index = 2)]
+	AssetNotFound,
+	/// The message can not be delivered to the destination.
+	#[codec(index = 3)]
+	Unroutable,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Estimation of the fees of XCM programs.
+	pub trait XcmPaymentApi {
+		/// The weight of executing `message` on this chain.
+		fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError>;
+
+		/// The amount of `asset` that has to be paid for `weight` on this chain.
+		fn query_weight_to_asset_fee(weight: Weight, asset: VersionedAssetId) -> Result<u128, XcmPaymentApiError>;
+
+		/// The assets that have to be paid for delivering `message` from this chain to
+		/// `destination`.
+		fn query_delivery_fees(destination: VersionedMultiLocation, message: VersionedXcm<()>) -> Result<VersionedMultiAssets, XcmPaymentApiError>;
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight of executing `message` as measured by `T::Weigher`.
+	///
+	/// Implements [`XcmPaymentApi::query_xcm_weight`].
+	pub fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError> {
+		let message: Xcm<()> =
+			message.try_into().map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+		T::Weigher::weight(&mut message.into())
+			.map_err(|()| XcmPaymentApiError::WeightNotComputable)
+	}
+
+	/// The amount of `asset` that `Trader` charges for `weight`.
+	///
+	/// The trader is given an unlimited amount of `asset` and the fee is what it does not return.
+	/// Any changes made by the trader, including when it is dropped, are reverted. Implements
+	/// [`XcmPaymentApi::query_weight_to_asset_fee`].
+	pub fn query_weight_to_asset_fee<Trader: WeightTrader>(
+		weight: Weight,
+		asset: VersionedAssetId,
+	) -> Result<u128, XcmPaymentApiError> {
+		let asset: AssetId =
+			asset.try_into().map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+		let context = XcmContext::with_message_id([0; 32]);
+
+		with_transaction_unchecked(|| {
+			let mut trader = Trader::new();
+			let result = trader
+				.buy_weight(weight, Assets::from(MultiAsset::from((asset, u128::MAX))), &context)
+				.map(|unused| u128::MAX - unused.fungible.get(&asset).copied().unwrap_or_default())
+				.map_err(|_| XcmPaymentApiError::AssetNotFound);
+			drop(trader);
+			TransactionOutcome::Rollback(result)
+		})
+	}
+
+	/// The price of delivering `message` to `destination` with `T::XcmRouter`.
+	///
+	/// Implements [`XcmPaymentApi::query_delivery_fees`].
+	pub fn query_delivery_fees(
+		destination: VersionedMultiLocation,
+		message: VersionedXcm<()>,
+	) -> Result<VersionedMultiAssets, XcmPaymentApiError> {
+		let destination: MultiLocation = destination
+			.try_into()
+			.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+		let message: Xcm<()> =
+			message.try_into().map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+
+		let (_, fees) = validate_send::<T::XcmRouter>(destination, message)
+			.map_err(|_| XcmPaymentApiError::Unroutable)?;
+		Ok(fees.into())
+	}
+}
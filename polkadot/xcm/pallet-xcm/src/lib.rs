@@ -25,6 +25,8 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod dry_run;
+pub mod fees;
 pub mod migration;
 
 use codec::{Decode, Encode, EncodeLike, MaxEncodedLen};
@@ -177,7 +179,7 @@ pub mod pallet {
 		pub const CurrentXcmVersion: u32 = XCM_VERSION;
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -1219,6 +1221,72 @@ pub mod pallet {
 			XcmExecutionSuspended::<T>::set(suspended);
 			Ok(())
 		}
+
+		/// Transfer some assets from the local chain to the destination chain through their local,
+		/// destination or remote reserve, or through teleports.
+		///
+		/// The transfer type of every asset is determined by the XCM executor and assets may have
+		/// different transfer types. Assets transferred through a remote reserve are the exception:
+		/// all assets, including the fee asset, must then share the same remote reserve.
+		///
+		/// Fee payment on the destination side is made from the asset in the `assets` vector of
+		/// index `fee_asset_item`, up to enough to pay for `weight_limit` of weight. If more weight
+		/// is needed than `weight_limit`, then the operation will fail and the assets sent may be
+		/// at risk. When transferring through a remote reserve, half of the fees are used on the
+		/// reserve and the other half on the destination.
+		///
+		/// - `origin`: Must be capable of withdrawing the `assets` and executing XCM.
+		/// - `dest`: Destination context for the assets. Will typically be `X2(Parent,
+		///   Parachain(..))` to send from parachain to parachain, or `X1(Parachain(..))` to send
+		///   from relay to parachain.
+		/// - `beneficiary`: A beneficiary location for the assets in the context of `dest`. Will
+		///   generally be an `AccountId32` value.
+		/// - `assets`: The assets to be withdrawn. This should include the assets used to pay the
+		///   fee on the `dest` (and possibly reserve) chains.
+		/// - `fee_asset_item`: The index into `assets` of the item which should be used to pay
+		///   fees.
+		/// - `weight_limit`: The remote-side weight limit, if any, for the XCM fee purchase.
+		#[pallet::call_index(11)]
+		#[pallet::weight({
+			let maybe_assets: Result<MultiAssets, ()> = (*assets.clone()).try_into();
+			let maybe_dest: Result<MultiLocation, ()> = (*dest.clone()).try_into();
+			match (maybe_assets, maybe_dest) {
+				(Ok(assets), Ok(dest)) => {
+					use sp_std::vec;
+					// heaviest version of locally executed XCM program: equivalent in weight to
+					// withdrawing and burning fees, transferring the other assets to SA, reanchoring
+					// them, extending XCM program, and sending onward XCM
+					let mut message = Xcm(vec![
+						SetFeesMode { jit_withdraw: true },
+						WithdrawAsset(assets.clone()),
+						BurnAsset(assets.clone()),
+						TransferReserveAsset { assets, dest, xcm: Xcm(vec![]) }
+					]);
+					let base = T::WeightInfo::reserve_transfer_assets()
+						.max(T::WeightInfo::teleport_assets());
+					T::Weigher::weight(&mut message)
+						.map_or(Weight::MAX, |w| base.saturating_add(w))
+				}
+				_ => Weight::MAX,
+			}
+		})]
+		pub fn transfer_assets(
+			origin: OriginFor<T>,
+			dest: Box<VersionedMultiLocation>,
+			beneficiary: Box<VersionedMultiLocation>,
+			assets: Box<VersionedMultiAssets>,
+			fee_asset_item: u32,
+			weight_limit: WeightLimit,
+		) -> DispatchResult {
+			Self::do_transfer_assets(
+				origin,
+				dest,
+				beneficiary,
+				assets,
+				fee_asset_item,
+				weight_limit,
+			)
+		}
 	}
 }
 
@@ -1370,19 +1438,14 @@ impl<T: Config> Pallet<T> {
 				!matches!(assets_transfer_type, TransferType::RemoteReserve(_)),
 				Error::<T>::InvalidAssetUnsupportedReserve
 			);
-			let fees = fees.clone();
-			let weight_limit = weight_limit.clone();
 			// build fees transfer instructions to be added to assets transfers XCM programs
-			separate_fees_instructions = Some(match fees_transfer_type {
-				TransferType::LocalReserve =>
-					Self::local_reserve_fees_instructions(dest, fees, weight_limit)?,
-				TransferType::DestinationReserve =>
-					Self::destination_reserve_fees_instructions(dest, fees, weight_limit)?,
-				TransferType::Teleport =>
-					Self::teleport_fees_instructions(origin_location, dest, fees, weight_limit)?,
-				TransferType::RemoteReserve(_) =>
-					return Err(Error::<T>::InvalidAssetUnsupportedReserve.into()),
-			});
+			separate_fees_instructions = Some(Self::fees_instructions(
+				origin_location,
+				dest,
+				fees.clone(),
+				fees_transfer_type,
+				weight_limit.clone(),
+			)?);
 		};
 
 		Self::build_and_execute_xcm_transfer_type(
@@ -1434,6 +1497,108 @@ impl<T: Config> Pallet<T> {
 		)
 	}
 
+	/// Find the transfer type of each of the `assets` when transferring them to `dest`.
+	fn find_transfer_types(
+		assets: &[MultiAsset],
+		dest: &MultiLocation,
+	) -> Result<Vec<TransferType>, Error<T>> {
+		assets
+			.iter()
+			.map(|asset| {
+				if let Fungible(x) = asset.fun {
+					// If fungible asset, ensure non-zero amount.
+					ensure!(!x.is_zero(), Error::<T>::Empty);
+				}
+				T::XcmExecutor::determine_for(asset, dest).map_err(Error::<T>::from)
+			})
+			.collect()
+	}
+
+	fn do_transfer_assets(
+		origin: OriginFor<T>,
+		dest: Box<VersionedMultiLocation>,
+		beneficiary: Box<VersionedMultiLocation>,
+		assets: Box<VersionedMultiAssets>,
+		fee_asset_item: u32,
+		weight_limit: WeightLimit,
+	) -> DispatchResult {
+		let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin)?;
+		let dest = (*dest).try_into().map_err(|()| Error::<T>::BadVersion)?;
+		let beneficiary: MultiLocation =
+			(*beneficiary).try_into().map_err(|()| Error::<T>::BadVersion)?;
+		let assets: MultiAssets = (*assets).try_into().map_err(|()| Error::<T>::BadVersion)?;
+		log::trace!(
+			target: "xcm::pallet_xcm::do_transfer_assets",
+			"origin {:?}, dest {:?}, beneficiary {:?}, assets {:?}, fee-idx {:?}",
+			origin_location, dest, beneficiary, assets, fee_asset_item,
+		);
+
+		ensure!(assets.len() <= MAX_ASSETS_FOR_TRANSFER, Error::<T>::TooManyAssets);
+		let mut assets = assets.into_inner();
+		let fee_asset_item = fee_asset_item as usize;
+		let fees = assets.get(fee_asset_item).ok_or(Error::<T>::Empty)?.clone();
+		let mut transfer_types = Self::find_transfer_types(&assets, &dest)?;
+
+		// Teleported and reserve-transferred assets must pass their respective filters.
+		let (teleported, reserve_transferred): (Vec<_>, Vec<_>) = assets
+			.iter()
+			.cloned()
+			.zip(transfer_types.iter())
+			.partition(|(_, transfer_type)| **transfer_type == TransferType::Teleport);
+		let teleported = teleported.into_iter().map(|(asset, _)| asset).collect::<Vec<_>>();
+		let reserve_transferred =
+			reserve_transferred.into_iter().map(|(asset, _)| asset).collect::<Vec<_>>();
+		if !teleported.is_empty() {
+			let value = (origin_location, teleported);
+			ensure!(T::XcmTeleportFilter::contains(&value), Error::<T>::Filtered);
+		}
+		if !reserve_transferred.is_empty() {
+			let value = (origin_location, reserve_transferred);
+			ensure!(T::XcmReserveTransferFilter::contains(&value), Error::<T>::Filtered);
+		}
+
+		let fees_transfer_type = transfer_types[fee_asset_item];
+		if transfer_types.iter().all(|transfer_type| transfer_type == &fees_transfer_type) {
+			// Same transfer type for all assets, fees are batched together with assets.
+			return Self::build_and_execute_xcm_transfer_type(
+				origin_location,
+				dest,
+				beneficiary,
+				assets,
+				fees_transfer_type,
+				fees,
+				None,
+				weight_limit,
+			)
+		}
+
+		// Disallow _remote reserves_ unless all assets & fees have the same remote reserve
+		// (covered by branch above). The reason for this is that we'd need to send XCMs to
+		// separate chains with no guarantee of delivery order on final destination; therefore we
+		// cannot guarantee to have fees in place on final destination chain to pay for assets
+		// transfer.
+		ensure!(
+			!transfer_types
+				.iter()
+				.any(|transfer_type| matches!(transfer_type, TransferType::RemoteReserve(_))),
+			Error::<T>::InvalidAssetUnsupportedReserve
+		);
+		// remove `fees` from `assets` and build separate fees transfer instructions to be added
+		// to the assets transfers XCM programs
+		let fees = assets.remove(fee_asset_item);
+		transfer_types.remove(fee_asset_item);
+		let fees_instructions =
+			Self::fees_instructions(origin_location, dest, fees, fees_transfer_type, weight_limit)?;
+		let (local_xcm, remote_xcm) = Self::mixed_transfer_programs(
+			dest,
+			beneficiary,
+			assets,
+			transfer_types,
+			fees_instructions,
+		)?;
+		Self::execute_xcm_transfer(origin_location, dest, local_xcm, Some(remote_xcm))
+	}
+
 	fn build_and_execute_xcm_transfer_type(
 		origin: MultiLocation,
 		dest: MultiLocation,
@@ -1450,7 +1615,7 @@ impl<T: Config> Pallet<T> {
 			fees {:?}, fees_xcm: {:?}, weight_limit: {:?}",
 			origin, dest, beneficiary, assets, transfer_type, fees, separate_fees_instructions, weight_limit,
 		);
-		let (mut local_xcm, remote_xcm) = match transfer_type {
+		let (local_xcm, remote_xcm) = match transfer_type {
 			TransferType::LocalReserve => {
 				let (local, remote) = Self::local_reserve_transfer_programs(
 					dest,
@@ -1489,6 +1654,17 @@ impl<T: Config> Pallet<T> {
 				None,
 			),
 		};
+		Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
+	}
+
+	/// Execute `local_xcm` on behalf of `origin` and, if it completes, send `remote_xcm` to
+	/// `dest`.
+	fn execute_xcm_transfer(
+		origin: MultiLocation,
+		dest: MultiLocation,
+		mut local_xcm: Xcm<<T as Config>::RuntimeCall>,
+		remote_xcm: Option<Xcm<()>>,
+	) -> DispatchResult {
 		let weight =
 			T::Weigher::weight(&mut local_xcm).map_err(|()| Error::<T>::UnweighableMessage)?;
 		let hash = local_xcm.using_encoded(sp_io::hashing::blake2_256);
@@ -1504,6 +1680,7 @@ impl<T: Config> Pallet<T> {
 				Self::charge_fees(origin, price).map_err(|_| Error::<T>::FeesNotMet)?;
 			}
 			let message_id = T::XcmRouter::deliver(ticket).map_err(Error::<T>::from)?;
+			xcm_executor::note_message_sent(&dest, &message_id);
 
 			let e = Event::Sent { origin, destination: dest, message: remote_xcm, message_id };
 			Self::deposit_event(e);
@@ -1511,6 +1688,26 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Build the instructions that transfer `fees` to `dest` with `transfer_type` and buy
+	/// execution there, to be added to the XCM programs transferring the other assets.
+	fn fees_instructions(
+		origin: MultiLocation,
+		dest: MultiLocation,
+		fees: MultiAsset,
+		transfer_type: TransferType,
+		weight_limit: WeightLimit,
+	) -> Result<(Xcm<<T as Config>::RuntimeCall>, Xcm<()>), Error<T>> {
+		match transfer_type {
+			TransferType::LocalReserve =>
+				Self::local_reserve_fees_instructions(dest, fees, weight_limit),
+			TransferType::DestinationReserve =>
+				Self::destination_reserve_fees_instructions(dest, fees, weight_limit),
+			TransferType::Teleport =>
+				Self::teleport_fees_instructions(origin, dest, fees, weight_limit),
+			TransferType::RemoteReserve(_) => Err(Error::<T>::InvalidAssetUnsupportedReserve),
+		}
+	}
+
 	fn local_reserve_fees_instructions(
 		dest: MultiLocation,
 		fees: MultiAsset,
@@ -1729,6 +1926,29 @@ impl<T: Config> Pallet<T> {
 			.reanchored(&dest, context)
 			.map_err(|_| Error::<T>::CannotReanchor)?;
 
+		Self::check_out_teleported_asset(&dest, &fees)?;
+
+		let fees: MultiAssets = fees.into();
+		let local_execute_xcm = Xcm(vec![
+			// withdraw fees
+			WithdrawAsset(fees.clone()),
+			// burn fees
+			BurnAsset(fees),
+		]);
+		let xcm_on_dest = Xcm(vec![
+			// (dest) chain receive teleported assets burned on origin chain
+			ReceiveTeleportedAsset(reanchored_fees.clone().into()),
+			// buy exec using `fees` in holding received in above instruction
+			BuyExecution { fees: reanchored_fees, weight_limit },
+		]);
+		Ok((local_execute_xcm, xcm_on_dest))
+	}
+
+	/// Check `asset` out of the local chain for teleporting it to `dest`.
+	fn check_out_teleported_asset(
+		dest: &MultiLocation,
+		asset: &MultiAsset,
+	) -> Result<(), Error<T>> {
 		// XcmContext irrelevant in teleports checks
 		let dummy_context =
 			XcmContext { origin: None, message_id: Default::default(), topic: None };
@@ -1737,31 +1957,91 @@ impl<T: Config> Pallet<T> {
 		// so it's unlikely, but we don't want to allow that kind of bug to leak into
 		// a trusted chain.
 		<T::XcmExecutor as XcmAssetTransfers>::AssetTransactor::can_check_out(
-			&dest,
-			&fees,
+			dest,
+			asset,
 			&dummy_context,
 		)
 		.map_err(|_| Error::<T>::CannotCheckOutTeleport)?;
 		<T::XcmExecutor as XcmAssetTransfers>::AssetTransactor::check_out(
-			&dest,
-			&fees,
+			dest,
+			asset,
 			&dummy_context,
 		);
+		Ok(())
+	}
 
-		let fees: MultiAssets = fees.into();
-		let local_execute_xcm = Xcm(vec![
-			// withdraw fees
-			WithdrawAsset(fees.clone()),
-			// burn fees
-			BurnAsset(fees),
-		]);
-		let xcm_on_dest = Xcm(vec![
-			// (dest) chain receive teleported assets burned on origin chain
-			ReceiveTeleportedAsset(reanchored_fees.clone().into()),
-			// buy exec using `fees` in holding received in above instruction
-			BuyExecution { fees: reanchored_fees, weight_limit },
+	/// Build the programs transferring `assets` to `dest` when they do not all share the transfer
+	/// type of the fees, which are transferred separately through `fees_instructions`.
+	///
+	/// Assets with the same transfer type are moved together. None of `transfer_types` may be a
+	/// remote reserve.
+	fn mixed_transfer_programs(
+		dest: MultiLocation,
+		beneficiary: MultiLocation,
+		assets: Vec<MultiAsset>,
+		transfer_types: Vec<TransferType>,
+		(fees_local_xcm, fees_remote_xcm): (Xcm<<T as Config>::RuntimeCall>, Xcm<()>),
+	) -> Result<(Xcm<<T as Config>::RuntimeCall>, Xcm<()>), Error<T>> {
+		// max assets is `assets` + separately handled fee
+		let max_assets = assets.len() as u32 + 1;
+		// group assets by transfer type, in the order the transfer types first appear
+		let mut groups: Vec<(TransferType, Vec<MultiAsset>)> = Vec::new();
+		for (asset, transfer_type) in assets.into_iter().zip(transfer_types) {
+			match groups.iter_mut().find(|(existing, _)| existing == &transfer_type) {
+				Some((_, group)) => group.push(asset),
+				None => groups.push((transfer_type, vec![asset])),
+			}
+		}
+
+		// start off with the fees specific instructions, which also buy execution on `dest`
+		let mut local_execute_xcm = fees_local_xcm.into_inner();
+		let mut xcm_on_dest = fees_remote_xcm.into_inner();
+		// continue with the groups of assets
+		let context = T::UniversalLocation::get();
+		for (transfer_type, assets) in groups {
+			if transfer_type == TransferType::Teleport {
+				for asset in assets.iter() {
+					Self::check_out_teleported_asset(&dest, asset)?;
+				}
+			}
+			let assets: MultiAssets = assets.into();
+			let mut reanchored_assets = assets.clone();
+			reanchored_assets
+				.reanchor(&dest, context)
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+			match transfer_type {
+				TransferType::LocalReserve => {
+					// move `assets` to `dest`s local sovereign account
+					local_execute_xcm.push(TransferAsset { assets, beneficiary: dest });
+					// let (dest) chain know assets are in its SA on reserve
+					xcm_on_dest.push(ReserveAssetDeposited(reanchored_assets));
+				},
+				TransferType::DestinationReserve => {
+					// withdraw and burn reserve-based assets (derivatives)
+					local_execute_xcm
+						.extend_from_slice(&[WithdrawAsset(assets.clone()), BurnAsset(assets)]);
+					// withdraw `assets` from origin chain's sovereign account
+					xcm_on_dest.push(WithdrawAsset(reanchored_assets));
+				},
+				TransferType::Teleport => {
+					// withdraw and burn assets to be teleported
+					local_execute_xcm
+						.extend_from_slice(&[WithdrawAsset(assets.clone()), BurnAsset(assets)]);
+					// (dest) chain receive teleported assets burned on origin chain
+					xcm_on_dest.push(ReceiveTeleportedAsset(reanchored_assets));
+				},
+				TransferType::RemoteReserve(_) =>
+					return Err(Error::<T>::InvalidAssetUnsupportedReserve),
+			}
+		}
+		xcm_on_dest.extend_from_slice(&[
+			// following instructions are not exec'ed on behalf of origin chain anymore
+			ClearOrigin,
+			// deposit all remaining assets in holding to `beneficiary` location
+			DepositAsset { assets: Wild(AllCounted(max_assets)), beneficiary },
 		]);
-		Ok((local_execute_xcm, xcm_on_dest))
+
+		Ok((Xcm(local_execute_xcm), Xcm(xcm_on_dest)))
 	}
 
 	fn teleport_assets_program(
@@ -2017,7 +2297,9 @@ impl<T: Config> Pallet<T> {
 		if let Some(fee_payer) = maybe_fee_payer {
 			Self::charge_fees(fee_payer, price).map_err(|_| SendError::Fees)?;
 		}
-		T::XcmRouter::deliver(ticket)
+		let message_id = T::XcmRouter::deliver(ticket)?;
+		xcm_executor::note_message_sent(&dest, &message_id);
+		Ok(message_id)
 	}
 
 	pub fn check_account() -> T::AccountId {
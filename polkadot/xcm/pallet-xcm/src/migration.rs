@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Config, Pallet, VersionNotifyTargets};
+use crate::{Config, CurrentMigration, Pallet, VersionMigrationStage, VersionNotifyTargets};
 use frame_support::{
 	pallet_prelude::*,
 	traits::{OnRuntimeUpgrade, StorageVersion},
@@ -25,7 +25,6 @@ const DEFAULT_PROOF_SIZE: u64 = 64 * 1024;
 
 pub mod v1 {
 	use super::*;
-	use crate::{CurrentMigration, VersionMigrationStage};
 
 	/// Named with the 'VersionUnchecked'-prefix because although this implements some version
 	/// checking, the version checking is not complete as it will begin failing after the upgrade is
@@ -73,3 +72,57 @@ pub mod v1 {
 		<T as frame_system::Config>::DbWeight,
 	>;
 }
+
+pub mod v2 {
+	use super::*;
+
+	/// Start the lazy migration of the version-dependent storage of the pallet.
+	///
+	/// Entries keyed by an older XCM version are moved to the current one, and subscribers of
+	/// version notifications are told about the advertised version.
+	///
+	/// Use [`MigrateToV2`] instead, which only starts it once.
+	pub struct VersionUncheckedMigrateToV2<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for VersionUncheckedMigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			CurrentMigration::<T>::put(VersionMigrationStage::default());
+			T::DbWeight::get().writes(1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<sp_std::vec::Vec<u8>, sp_runtime::TryRuntimeError> {
+			Ok((VersionNotifyTargets::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: sp_std::vec::Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			ensure!(
+				CurrentMigration::<T>::get() == Some(VersionMigrationStage::default()),
+				"the version migration was not started"
+			);
+			// The entries are only migrated lazily, in `on_initialize`.
+			let targets = u32::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+			ensure!(
+				VersionNotifyTargets::<T>::iter_keys().count() as u32 == targets,
+				"version notify targets changed during the upgrade"
+			);
+			Ok(())
+		}
+	}
+
+	/// Version checked migration to v2.
+	///
+	/// Wrapped in [`frame_support::migrations::VersionedMigration`] so the version migration is
+	/// started by a single runtime upgrade.
+	pub type MigrateToV2<T> = frame_support::migrations::VersionedMigration<
+		1,
+		2,
+		VersionUncheckedMigrateToV2<T>,
+		crate::pallet::Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
+
+/// Start the lazy migration of the version-dependent storage of the pallet to the current XCM
+/// version, see [`v2::MigrateToV2`].
+pub type MigrateToLatestXcmVersion<T> = v2::MigrateToV2<T>;
@@ -36,9 +36,10 @@ use xcm_builder::{
 	AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
 	AllowTopLevelPaidExecutionFrom, Case, ChildParachainAsNative, ChildParachainConvertsVia,
 	ChildSystemParachainAsSuperuser, CurrencyAdapter as XcmCurrencyAdapter, DescribeAllTerminal,
-	FixedRateOfFungible, FixedWeightBounds, FungiblesAdapter, HashedDescription, IsConcrete,
-	MatchedConvertedConcreteId, NoChecking, SignedAccountId32AsNative, SignedToAccountId32,
-	SovereignSignedViaLocation, TakeWeightCredit, XcmFeeManagerFromComponents, XcmFeeToAccount,
+	FixedRateOfFungible, FixedWeightBounds, FungiblesAdapter, HashedDescription,
+	InspectMessageQueues, IsConcrete, MatchedConvertedConcreteId, NoChecking,
+	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit,
+	XcmFeeManagerFromComponents, XcmFeeToAccount,
 };
 use xcm_executor::{
 	traits::{Identity, JustTry},
@@ -181,6 +182,19 @@ impl SendXcm for TestSendXcm {
 		Ok(hash)
 	}
 }
+impl InspectMessageQueues for TestSendXcm {
+	fn clear_messages() {
+		SENT_XCM.with(|q| q.borrow_mut().clear());
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let mut queues = BTreeMap::<MultiLocation, Vec<VersionedXcm<()>>>::new();
+		for (dest, message) in sent_xcm() {
+			queues.entry(dest).or_default().push(VersionedXcm::from(message));
+		}
+		queues.into_iter().map(|(dest, messages)| (dest.into(), messages)).collect()
+	}
+}
 /// Sender that returns error if `X8` junction and stops routing
 pub struct TestSendXcmErrX8;
 impl SendXcm for TestSendXcmErrX8 {
@@ -203,6 +217,15 @@ impl SendXcm for TestSendXcmErrX8 {
 	}
 }
 
+// The messages of all test routers are queued in `SENT_XCM`, which is inspected by `TestSendXcm`.
+impl InspectMessageQueues for TestSendXcmErrX8 {
+	fn clear_messages() {}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		Vec::new()
+	}
+}
+
 parameter_types! {
 	pub Para3000: u32 = 3000;
 	pub Para3000Location: MultiLocation = Parachain(Para3000::get()).into();
@@ -235,6 +258,14 @@ impl SendXcm for TestPaidForPara3000SendXcm {
 	}
 }
 
+impl InspectMessageQueues for TestPaidForPara3000SendXcm {
+	fn clear_messages() {}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		Vec::new()
+	}
+}
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 }
@@ -242,6 +273,7 @@ parameter_types! {
 impl frame_system::Config for Test {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Nonce = u64;
 	type Hash = H256;
 	type Hashing = ::sp_runtime::traits::BlakeTwo256;
@@ -117,6 +117,28 @@ fn limited_teleport_assets_works() {
 	);
 }
 
+/// Test `transfer_assets` with a single teleportable asset
+///
+/// Asserts that `transfer_assets` teleports assets that are teleport-trusted on the destination.
+#[test]
+fn transfer_assets_with_teleportable_asset_works() {
+	let beneficiary: MultiLocation = AccountId32 { network: None, id: BOB.into() }.into();
+	do_test_and_verify_teleport_assets(
+		beneficiary,
+		|| {
+			assert_ok!(XcmPallet::transfer_assets(
+				RuntimeOrigin::signed(ALICE),
+				Box::new(RelayLocation::get().into()),
+				Box::new(beneficiary.into()),
+				Box::new((Here, SEND_AMOUNT).into()),
+				0,
+				Unlimited,
+			));
+		},
+		Unlimited,
+	);
+}
+
 /// `limited_teleport_assets` should fail for filtered assets
 #[test]
 fn limited_teleport_filtered_assets_disallowed() {
@@ -1457,3 +1479,395 @@ fn reserve_transfer_assets_with_filtered_teleported_fee_disallowed() {
 		);
 	});
 }
+
+/// Test `transfer_assets` with teleported asset and local reserve fee.
+///
+/// Transferring teleport-trusted USDT to `USDT_PARA_ID`. Using native asset (local reserve) for
+/// fees.
+///
+/// ```nocompile
+///    Here (source)                               USDT_PARA_ID (destination)
+///    |  `fees` reserve                           `assets` teleport-trust
+///    |
+///    |  1. execute `TransferAsset(fees)`, `WithdrawAsset(assets)`, `BurnAsset(assets)`
+///    |  2. send `ReserveAssetDeposited(fees), BuyExecution(fees),
+///    |     ReceiveTeleportedAsset(assets), ClearOrigin, DepositAsset`
+///    \------------------------------------------>
+/// ```
+#[test]
+fn transfer_assets_with_teleported_asset_and_local_reserve_fee_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDT (0 total issuance)
+		let usdt_initial_local_amount = 42;
+		let (usdt_chain, usdt_chain_sovereign_account, usdt_id_multilocation) =
+			set_up_foreign_asset(USDT_PARA_ID, None, usdt_initial_local_amount, true);
+
+		// transfer destination is USDT chain (teleport trust only for USDT)
+		let dest = usdt_chain;
+
+		let (assets, fee_index, fee_asset, xfer_asset) = into_multiassets_checked(
+			// native asset for fees - local reserve
+			(MultiLocation::here(), FEE_AMOUNT).into(),
+			// USDT to transfer (not used for fees) - teleported
+			(usdt_id_multilocation, SEND_AMOUNT).into(),
+		);
+
+		// reanchor according to test-case
+		let context = UniversalLocation::get();
+		let expected_fee = fee_asset.reanchored(&dest, context).unwrap();
+		let expected_asset = xfer_asset.reanchored(&dest, context).unwrap();
+
+		// balances checks before
+		assert_eq!(Assets::balance(usdt_id_multilocation, ALICE), usdt_initial_local_amount);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+
+		// do the transfer
+		assert_ok!(XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.into()),
+			Box::new(beneficiary.into()),
+			Box::new(assets.into()),
+			fee_index as u32,
+			Unlimited,
+		));
+		let weight = BaseXcmWeight::get() * 3;
+		let mut last_events = last_events(3).into_iter();
+		assert_eq!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete(weight) })
+		);
+		assert_eq!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::FeesPaid {
+				paying: beneficiary,
+				fees: MultiAssets::new(),
+			})
+		);
+		assert!(matches!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::Sent { .. })
+		));
+		// Alice spent native asset for fees
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - FEE_AMOUNT);
+		// Alice transferred USDT
+		assert_eq!(
+			Assets::balance(usdt_id_multilocation, ALICE),
+			usdt_initial_local_amount - SEND_AMOUNT
+		);
+		// Sovereign account of dest parachain holds `FEE_AMOUNT` native asset in local reserve
+		assert_eq!(Balances::free_balance(usdt_chain_sovereign_account.clone()), FEE_AMOUNT);
+		assert_eq!(Assets::balance(usdt_id_multilocation, usdt_chain_sovereign_account), 0);
+		// Verify total and active issuance of USDT have decreased (teleported)
+		let expected_usdt_issuance = usdt_initial_local_amount - SEND_AMOUNT;
+		assert_eq!(Assets::total_issuance(usdt_id_multilocation), expected_usdt_issuance);
+		assert_eq!(Assets::active_issuance(usdt_id_multilocation), expected_usdt_issuance);
+
+		// Verify sent XCM program
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				dest,
+				Xcm(vec![
+					// fees are deposited in the local reserve of the destination chain
+					ReserveAssetDeposited(expected_fee.clone().into()),
+					buy_limited_execution(expected_fee, Unlimited),
+					// assets are teleported to destination chain
+					ReceiveTeleportedAsset(expected_asset.into()),
+					ClearOrigin,
+					DepositAsset { assets: AllCounted(2).into(), beneficiary },
+				])
+			)]
+		);
+	});
+}
+
+/// Test `transfer_assets` with remote asset reserve and teleported fee is disallowed.
+///
+/// Transferring foreign asset (reserve on `FOREIGN_ASSET_RESERVE_PARA_ID`) to `USDT_PARA_ID`.
+/// Using teleport-trusted USDT for fees.
+#[test]
+fn transfer_assets_with_remote_asset_reserve_and_teleported_fee_disallowed() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDT (0 total issuance)
+		let usdt_initial_local_amount = 42;
+		let (usdt_chain, _, usdt_id_multilocation) =
+			set_up_foreign_asset(USDT_PARA_ID, None, usdt_initial_local_amount, true);
+
+		// create non-sufficient foreign asset BLA (0 total issuance)
+		let foreign_initial_amount = 142;
+		let (_, _, foreign_asset_id_multilocation) = set_up_foreign_asset(
+			FOREIGN_ASSET_RESERVE_PARA_ID,
+			Some(FOREIGN_ASSET_INNER_JUNCTION),
+			foreign_initial_amount,
+			false,
+		);
+
+		// transfer destination is USDT chain (foreign asset needs to go through its reserve chain)
+		let dest = usdt_chain;
+
+		let (assets, fee_index, _, _) = into_multiassets_checked(
+			// USDT for fees (is sufficient on local chain too) - teleported
+			(usdt_id_multilocation, FEE_AMOUNT).into(),
+			// foreign asset to transfer (not used for fees) - remote reserve
+			(foreign_asset_id_multilocation, SEND_AMOUNT).into(),
+		);
+
+		// do the transfer
+		let result = XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.into()),
+			Box::new(beneficiary.into()),
+			Box::new(assets.into()),
+			fee_index as u32,
+			Unlimited,
+		);
+		assert_eq!(
+			result,
+			Err(DispatchError::Module(ModuleError {
+				index: 4,
+				error: [22, 0, 0, 0],
+				message: Some("InvalidAssetUnsupportedReserve")
+			}))
+		);
+		// Alice balances untouched
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+		assert_eq!(Assets::balance(usdt_id_multilocation, ALICE), usdt_initial_local_amount);
+		assert_eq!(Assets::balance(foreign_asset_id_multilocation, ALICE), foreign_initial_amount);
+	});
+}
+
+/// Test `transfer_assets` with remote asset reserve and remote fee reserve.
+///
+/// Transferring USDC (reserve on `USDC_RESERVE_PARA_ID`) to some `OTHER_PARA_ID`. Using USDC for
+/// fees as well.
+///
+/// ```nocompile
+///    | chain `A`           |  chain `C`                      |  chain `B`
+///    | Here (source)       |  USDC_RESERVE_PARA_ID           |  OTHER_PARA_ID (destination)
+///    |                     |  `fees` reserve                 |
+///    |                     |  `assets` reserve               |
+///    |
+///    |  1. `A` executes `InitiateReserveWithdraw(both)` dest `C`
+///    |     -----------------> `C` executes `DepositReserveAsset(both)` dest `B`
+///    |                             --------------------------> `DepositAsset(both)`
+/// ```
+#[test]
+fn transfer_assets_with_remote_asset_reserve_and_remote_fee_reserve_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDC (0 total issuance)
+		let usdc_initial_local_amount = 142;
+		let (usdc_chain, usdc_chain_sovereign_account, usdc_id_multilocation) =
+			set_up_foreign_asset(
+				USDC_RESERVE_PARA_ID,
+				Some(USDC_INNER_JUNCTION),
+				usdc_initial_local_amount,
+				true,
+			);
+
+		// transfer destination is some other parachain
+		let dest = RelayLocation::get().pushed_with_interior(Parachain(OTHER_PARA_ID)).unwrap();
+
+		let assets: MultiAssets = vec![(usdc_id_multilocation, SEND_AMOUNT).into()].into();
+		let fee_index = 0;
+
+		// reanchor according to test-case
+		let context = UniversalLocation::get();
+		let expected_dest_on_reserve = dest.reanchored(&usdc_chain, context).unwrap();
+		let fees = assets.get(fee_index).unwrap().clone();
+		let (fees_half_1, fees_half_2) = XcmPallet::halve_fees(fees).unwrap();
+		let mut expected_assets_on_reserve = assets.clone();
+		expected_assets_on_reserve.reanchor(&usdc_chain, context).unwrap();
+		let expected_fee_on_reserve = fees_half_1.reanchored(&usdc_chain, context).unwrap();
+		let expected_fee_on_dest = fees_half_2.reanchored(&dest, context).unwrap();
+
+		// do the transfer
+		assert_ok!(XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.into()),
+			Box::new(beneficiary.into()),
+			Box::new(assets.into()),
+			fee_index as u32,
+			Unlimited,
+		));
+		assert!(matches!(
+			last_event(),
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete(_) })
+		));
+
+		// Alice spent (transferred) amount
+		assert_eq!(
+			Assets::balance(usdc_id_multilocation, ALICE),
+			usdc_initial_local_amount - SEND_AMOUNT
+		);
+		// Alice's native asset balance is untouched
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+		// Reserve sovereign account has same balances
+		assert_eq!(Balances::free_balance(usdc_chain_sovereign_account.clone()), 0);
+		assert_eq!(Assets::balance(usdc_id_multilocation, usdc_chain_sovereign_account), 0);
+		// Verify total and active issuance of USDC have decreased (burned on reserve-withdraw)
+		let expected_usdc_issuance = usdc_initial_local_amount - SEND_AMOUNT;
+		assert_eq!(Assets::total_issuance(usdc_id_multilocation), expected_usdc_issuance);
+		assert_eq!(Assets::active_issuance(usdc_id_multilocation), expected_usdc_issuance);
+
+		// Verify sent XCM program
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				// first message sent to reserve chain
+				usdc_chain,
+				Xcm(vec![
+					WithdrawAsset(expected_assets_on_reserve),
+					ClearOrigin,
+					BuyExecution { fees: expected_fee_on_reserve, weight_limit: Unlimited },
+					DepositReserveAsset {
+						assets: Wild(AllCounted(1)),
+						// final destination is `dest` as seen by `reserve`
+						dest: expected_dest_on_reserve,
+						// message sent onward to `dest`
+						xcm: Xcm(vec![
+							buy_limited_execution(expected_fee_on_dest, Unlimited),
+							DepositAsset { assets: AllCounted(1).into(), beneficiary }
+						])
+					}
+				])
+			)],
+		);
+	});
+}
+
+/// Test `transfer_assets` with assets of different transfer types.
+///
+/// Transferring teleport-trusted USDT and foreign asset (reserve on destination) to
+/// `FOREIGN_ASSET_RESERVE_PARA_ID`. Using native asset (local reserve) for fees.
+///
+/// ```nocompile
+///    Here (source)                               FOREIGN_ASSET_RESERVE_PARA_ID (destination)
+///    |  `fees` reserve                           `usdt` teleport-trust
+///    |                                           `foreign` reserve
+///    |
+///    |  1. execute `TransferAsset(fees)`, `WithdrawAsset(usdt)`, `BurnAsset(usdt)`,
+///    |     `WithdrawAsset(foreign)`, `BurnAsset(foreign)`
+///    |  2. send `ReserveAssetDeposited(fees), BuyExecution(fees), ReceiveTeleportedAsset(usdt),
+///    |     WithdrawAsset(foreign), ClearOrigin, DepositAsset`
+///    \------------------------------------------>
+/// ```
+#[test]
+fn transfer_assets_with_mixed_transfer_types_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDT (0 total issuance)
+		let usdt_initial_local_amount = 42;
+		let (_, _, usdt_id_multilocation) =
+			set_up_foreign_asset(USDT_PARA_ID, None, usdt_initial_local_amount, true);
+
+		// create non-sufficient foreign asset BLA (0 total issuance)
+		let foreign_initial_amount = 142;
+		let (reserve_location, reserve_sovereign_account, foreign_asset_id_multilocation) =
+			set_up_foreign_asset(
+				FOREIGN_ASSET_RESERVE_PARA_ID,
+				Some(FOREIGN_ASSET_INNER_JUNCTION),
+				foreign_initial_amount,
+				false,
+			);
+
+		// transfer destination is the reserve of the foreign asset, USDT is teleported there
+		let dest = reserve_location;
+
+		let fee_asset: MultiAsset = (MultiLocation::here(), FEE_AMOUNT).into();
+		let usdt_asset: MultiAsset = (usdt_id_multilocation, SEND_AMOUNT).into();
+		let foreign_asset: MultiAsset = (foreign_asset_id_multilocation, SEND_AMOUNT).into();
+		let assets: MultiAssets =
+			vec![fee_asset.clone(), usdt_asset.clone(), foreign_asset.clone()].into();
+		// native asset is sorted first
+		let fee_index = 0;
+		assert_eq!(assets.get(fee_index), Some(&fee_asset));
+
+		// reanchor according to test-case
+		let context = UniversalLocation::get();
+		let expected_fee = fee_asset.reanchored(&dest, context).unwrap();
+		let expected_usdt = usdt_asset.reanchored(&dest, context).unwrap();
+		let expected_foreign = foreign_asset.reanchored(&dest, context).unwrap();
+
+		// do the transfer
+		assert_ok!(XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.into()),
+			Box::new(beneficiary.into()),
+			Box::new(assets.into()),
+			fee_index as u32,
+			Unlimited,
+		));
+		let weight = BaseXcmWeight::get() * 5;
+		let mut last_events = last_events(3).into_iter();
+		assert_eq!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete(weight) })
+		);
+		assert_eq!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::FeesPaid {
+				paying: beneficiary,
+				fees: MultiAssets::new(),
+			})
+		);
+		assert!(matches!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::Sent { .. })
+		));
+		// Alice spent native asset for fees
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - FEE_AMOUNT);
+		// Alice transferred USDT and the foreign asset
+		assert_eq!(
+			Assets::balance(usdt_id_multilocation, ALICE),
+			usdt_initial_local_amount - SEND_AMOUNT
+		);
+		assert_eq!(
+			Assets::balance(foreign_asset_id_multilocation, ALICE),
+			foreign_initial_amount - SEND_AMOUNT
+		);
+		// Sovereign account of dest parachain holds `FEE_AMOUNT` native asset in local reserve
+		assert_eq!(Balances::free_balance(reserve_sovereign_account.clone()), FEE_AMOUNT);
+		assert_eq!(Assets::balance(usdt_id_multilocation, reserve_sovereign_account.clone()), 0);
+		assert_eq!(Assets::balance(foreign_asset_id_multilocation, reserve_sovereign_account), 0);
+		// Verify total issuance of USDT (teleported) and foreign asset (reserve-withdrawn) have
+		// decreased
+		assert_eq!(
+			Assets::total_issuance(usdt_id_multilocation),
+			usdt_initial_local_amount - SEND_AMOUNT
+		);
+		assert_eq!(
+			Assets::total_issuance(foreign_asset_id_multilocation),
+			foreign_initial_amount - SEND_AMOUNT
+		);
+
+		// Verify sent XCM program
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				dest,
+				Xcm(vec![
+					// fees are deposited in the local reserve of the destination chain
+					ReserveAssetDeposited(expected_fee.clone().into()),
+					buy_limited_execution(expected_fee, Unlimited),
+					// USDT is teleported to destination chain
+					ReceiveTeleportedAsset(expected_usdt.into()),
+					// foreign asset is withdrawn from our sovereign account on its reserve
+					WithdrawAsset(expected_foreign.into()),
+					ClearOrigin,
+					DepositAsset { assets: AllCounted(3).into(), beneficiary },
+				])
+			)]
+		);
+	});
+}
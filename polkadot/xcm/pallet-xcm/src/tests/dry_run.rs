@@ -0,0 +1,121 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(test)]
+
+use crate::{
+	dry_run::{CallDryRunEffects, XcmDryRunEffects},
+	mock::*,
+	tests::{ALICE, INITIAL_BALANCE, SEND_AMOUNT},
+};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use polkadot_parachain_primitives::primitives::Id as ParaId;
+use sp_runtime::traits::AccountIdConversion;
+use xcm::prelude::*;
+
+#[test]
+fn dry_run_call_reports_effects_and_reverts() {
+	let balances = vec![
+		(ALICE, INITIAL_BALANCE),
+		(ParaId::from(OTHER_PARA_ID).into_account_truncating(), INITIAL_BALANCE),
+	];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	let dest: MultiLocation = Parachain(OTHER_PARA_ID).into();
+	let call = RuntimeCall::XcmPallet(crate::Call::limited_reserve_transfer_assets {
+		dest: Box::new(dest.into()),
+		beneficiary: Box::new(beneficiary.into()),
+		assets: Box::new((Here, SEND_AMOUNT).into()),
+		fee_asset_item: 0,
+		weight_limit: Unlimited,
+	});
+
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let CallDryRunEffects { execution_result, emitted_events, forwarded_xcms } =
+			XcmPallet::dry_run_call::<XcmRouter, _>(
+				OriginCaller::system(RawOrigin::Signed(ALICE)),
+				call,
+			)
+			.unwrap();
+
+		assert!(execution_result.is_ok());
+		assert!(emitted_events.iter().any(|event| matches!(
+			event,
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete(_) })
+		)));
+		assert_eq!(
+			forwarded_xcms,
+			vec![(
+				dest.into(),
+				vec![VersionedXcm::from(Xcm(vec![
+					ReserveAssetDeposited((Parent, SEND_AMOUNT).into()),
+					ClearOrigin,
+					buy_execution((Parent, SEND_AMOUNT)),
+					DepositAsset { assets: AllCounted(1).into(), beneficiary },
+				]))],
+			)]
+		);
+
+		// Nothing happened.
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn dry_run_xcm_reports_effects_and_reverts() {
+	let balances = vec![
+		(ALICE, INITIAL_BALANCE),
+		(ParaId::from(OTHER_PARA_ID).into_account_truncating(), INITIAL_BALANCE),
+	];
+	let beneficiary: MultiLocation =
+		Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	let dest: MultiLocation = Parachain(OTHER_PARA_ID).into();
+	let xcm = Xcm(vec![
+		WithdrawAsset((Here, SEND_AMOUNT).into()),
+		buy_execution((Here, SEND_AMOUNT)),
+		DepositReserveAsset {
+			assets: AllCounted(1).into(),
+			dest,
+			xcm: Xcm(vec![DepositAsset { assets: AllCounted(1).into(), beneficiary }]),
+		},
+	]);
+
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let XcmDryRunEffects { execution_result, emitted_events, forwarded_xcms } =
+			XcmPallet::dry_run_xcm::<XcmRouter>(beneficiary.into(), VersionedXcm::from(xcm))
+				.unwrap();
+
+		assert!(matches!(execution_result, Outcome::Complete(_)));
+		assert!(!emitted_events.is_empty());
+		assert_eq!(
+			forwarded_xcms,
+			vec![(
+				dest.into(),
+				vec![VersionedXcm::from(Xcm(vec![
+					ReserveAssetDeposited((Parent, SEND_AMOUNT).into()),
+					ClearOrigin,
+					DepositAsset { assets: AllCounted(1).into(), beneficiary },
+				]))],
+			)]
+		);
+
+		// Nothing happened.
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+		assert!(System::events().is_empty());
+	});
+}
@@ -0,0 +1,96 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(test)]
+
+use crate::{
+	fees::XcmPaymentApiError,
+	mock::*,
+	tests::{ALICE, INITIAL_BALANCE, SEND_AMOUNT},
+};
+use frame_support::weights::{
+	constants::{WEIGHT_PROOF_SIZE_PER_MB, WEIGHT_REF_TIME_PER_SECOND},
+	Weight,
+};
+use xcm::prelude::*;
+
+type Trader = <XcmConfig as xcm_executor::Config>::Trader;
+
+#[test]
+fn query_xcm_weight_works() {
+	new_test_ext_with_balances(vec![(ALICE, INITIAL_BALANCE)]).execute_with(|| {
+		let message = Xcm::<()>(vec![
+			WithdrawAsset((Here, SEND_AMOUNT).into()),
+			buy_execution((Here, SEND_AMOUNT)),
+			DepositAsset { assets: AllCounted(1).into(), beneficiary: Parachain(1).into() },
+		]);
+		assert_eq!(
+			XcmPallet::query_xcm_weight(VersionedXcm::from(message)),
+			Ok(BaseXcmWeight::get() * 3)
+		);
+	});
+}
+
+#[test]
+fn query_weight_to_asset_fee_works() {
+	new_test_ext_with_balances(vec![(ALICE, INITIAL_BALANCE)]).execute_with(|| {
+		// One unit per second of execution time and per megabyte of proof.
+		let weight = Weight::from_parts(2 * WEIGHT_REF_TIME_PER_SECOND, WEIGHT_PROOF_SIZE_PER_MB);
+		let native: AssetId = Concrete(RelayLocation::get());
+		assert_eq!(XcmPallet::query_weight_to_asset_fee::<Trader>(weight, native.into()), Ok(3));
+
+		let other: AssetId = Concrete(Parachain(1).into());
+		assert_eq!(
+			XcmPallet::query_weight_to_asset_fee::<Trader>(weight, other.into()),
+			Err(XcmPaymentApiError::AssetNotFound)
+		);
+	});
+}
+
+#[test]
+fn query_delivery_fees_works() {
+	new_test_ext_with_balances(vec![(ALICE, INITIAL_BALANCE)]).execute_with(|| {
+		let message = VersionedXcm::from(Xcm::<()>(vec![ClearOrigin]));
+
+		assert_eq!(
+			XcmPallet::query_delivery_fees(Para3000Location::get().into(), message.clone()),
+			Ok(Para3000PaymentMultiAssets::get().into())
+		);
+		assert_eq!(
+			XcmPallet::query_delivery_fees(Parachain(OTHER_PARA_ID).into(), message.clone()),
+			Ok(MultiAssets::new().into())
+		);
+
+		// `TestSendXcmErrX8` fails to route to locations with eight junctions.
+		let full = MultiLocation::new(
+			0,
+			X8(
+				Parachain(1),
+				PalletInstance(1),
+				GeneralIndex(1),
+				GeneralIndex(2),
+				GeneralIndex(3),
+				GeneralIndex(4),
+				GeneralIndex(5),
+				GeneralIndex(6),
+			),
+		);
+		assert_eq!(
+			XcmPallet::query_delivery_fees(full.into(), message),
+			Err(XcmPaymentApiError::Unroutable)
+		);
+	});
+}
@@ -17,15 +17,17 @@
 #![cfg(test)]
 
 mod assets_transfer;
+mod dry_run;
+mod fees;
 
 use crate::{
-	mock::*, AssetTraps, CurrentMigration, Error, LatestVersionedMultiLocation, Queries,
-	QueryStatus, VersionDiscoveryQueue, VersionMigrationStage, VersionNotifiers,
-	VersionNotifyTargets,
+	migration::MigrateToLatestXcmVersion, mock::*, AssetTraps, CurrentMigration, Error,
+	LatestVersionedMultiLocation, Queries, QueryStatus, VersionDiscoveryQueue,
+	VersionMigrationStage, VersionNotifiers, VersionNotifyTargets,
 };
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::{Currency, Hooks},
+	traits::{Currency, Hooks, OnRuntimeUpgrade, StorageVersion},
 	weights::Weight,
 };
 use polkadot_parachain_primitives::primitives::Id as ParaId;
@@ -726,6 +728,53 @@ fn subscription_side_upgrades_work_without_notify() {
 	});
 }
 
+#[test]
+fn migrate_to_latest_xcm_version_notifies_subscribers() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		// A subscriber which was notified of the previously advertised version.
+		let location = Parachain(1001).into_versioned();
+		VersionNotifyTargets::<Test>::insert(XCM_VERSION, location, (70, Weight::zero(), 3));
+
+		// A runtime upgrade which advertises a new version includes the migration.
+		StorageVersion::new(1).put::<XcmPallet>();
+		AdvertisedXcmVersion::set(4);
+		MigrateToLatestXcmVersion::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<XcmPallet>(), 2);
+		XcmPallet::on_initialize(1);
+
+		let instr = QueryResponse {
+			query_id: 70,
+			max_weight: Weight::zero(),
+			response: Response::Version(4),
+			querier: None,
+		};
+		assert_eq!(take_sent_xcm(), vec![(Parachain(1001).into(), Xcm(vec![instr]))]);
+		assert_eq!(
+			VersionNotifyTargets::<Test>::iter().collect::<Vec<_>>(),
+			vec![(XCM_VERSION, Parachain(1001).into_versioned(), (70, Weight::zero(), 4))]
+		);
+
+		// Later upgrades don't start the migration again.
+		MigrateToLatestXcmVersion::<Test>::on_runtime_upgrade();
+		assert!(CurrentMigration::<Test>::get().is_none());
+	});
+}
+
+#[test]
+fn wrap_version_caps_at_latest_version() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let message = Xcm::<()>(vec![ClearOrigin]);
+		let v4_dest: MultiLocation = Parachain(1000).into();
+		assert_ok!(XcmPallet::force_xcm_version(RuntimeOrigin::root(), Box::new(v4_dest), 4));
+
+		// Messages are not sent in a version ahead of the one we execute.
+		assert_eq!(
+			XcmPallet::wrap_version(&v4_dest, message.clone()),
+			Ok(VersionedXcm::V3(message))
+		);
+	});
+}
+
 #[test]
 fn subscriber_side_subscription_works() {
 	new_test_ext_with_balances(vec![]).execute_with(|| {
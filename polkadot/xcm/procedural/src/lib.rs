@@ -22,6 +22,7 @@ use syn::{parse_macro_input, DeriveInput};
 mod builder_pattern;
 mod v2;
 mod v3;
+mod v4;
 mod weight_info;
 
 #[proc_macro]
@@ -50,6 +51,20 @@ pub fn impl_conversion_functions_for_junctions_v3(input: TokenStream) -> TokenSt
 		.into()
 }
 
+#[proc_macro]
+pub fn impl_conversion_functions_for_location_v4(input: TokenStream) -> TokenStream {
+	v4::location::generate_conversion_functions(input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+#[proc_macro]
+pub fn impl_conversion_functions_for_junctions_v4(input: TokenStream) -> TokenStream {
+	v4::junctions::generate_conversion_functions(input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
 /// This is called on the `Instruction` enum, not on the `Xcm` struct,
 /// and allows for the following syntax for building XCMs:
 /// let message = Xcm::builder()
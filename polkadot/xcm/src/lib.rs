@@ -30,6 +30,7 @@ use scale_info::TypeInfo;
 
 pub mod v2;
 pub mod v3;
+pub mod v4;
 
 pub mod lts {
 	pub use super::v3::*;
@@ -51,6 +52,13 @@ pub const MAX_XCM_DECODE_DEPTH: u32 = 8;
 /// A version of XCM.
 pub type Version = u32;
 
+/// The highest version of XCM that versioned types can be converted into.
+///
+/// Messages are converted into [`latest`] before they are executed, so this may be ahead of
+/// [`latest::VERSION`] as long as the newer versions convert back into it. Chains keep
+/// advertising and sending [`latest::VERSION`] until they execute this version.
+pub const MAX_XCM_VERSION: Version = v4::VERSION;
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum Unsupported {}
 impl Encode for Unsupported {}
@@ -79,6 +87,8 @@ macro_rules! versioned_type {
 	($(#[$attr:meta])* pub enum $n:ident {
 		$(#[$index3:meta])+
 		V3($v3:ty),
+		$(#[$index4:meta])+
+		V4($v4:ty),
 	}) => {
 		#[derive(Derivative, Encode, Decode, TypeInfo)]
 		#[derivative(
@@ -94,6 +104,8 @@ macro_rules! versioned_type {
 		pub enum $n {
 			$(#[$index3])*
 			V3($v3),
+			$(#[$index4])*
+			V4($v4),
 		}
 		impl $n {
 			pub fn try_as<T>(&self) -> Result<&T, ()> where Self: TryAs<T> {
@@ -104,6 +116,15 @@ macro_rules! versioned_type {
 			fn try_as(&self) -> Result<&$v3, ()> {
 				match &self {
 					Self::V3(ref x) => Ok(x),
+					_ => Err(()),
+				}
+			}
+		}
+		impl TryAs<$v4> for $n {
+			fn try_as(&self) -> Result<&$v4, ()> {
+				match &self {
+					Self::V4(ref x) => Ok(x),
+					_ => Err(()),
 				}
 			}
 		}
@@ -111,6 +132,7 @@ macro_rules! versioned_type {
 			fn into_version(self, n: Version) -> Result<Self, ()> {
 				Ok(match n {
 					3 => Self::V3(self.try_into()?),
+					4 => Self::V4(self.try_into()?),
 					_ => return Err(()),
 				})
 			}
@@ -120,18 +142,34 @@ macro_rules! versioned_type {
 				$n::V3(x.into())
 			}
 		}
+		impl From<$v4> for $n {
+			fn from(x: $v4) -> Self {
+				$n::V4(x)
+			}
+		}
 		impl TryFrom<$n> for $v3 {
 			type Error = ();
 			fn try_from(x: $n) -> Result<Self, ()> {
 				use $n::*;
 				match x {
 					V3(x) => Ok(x),
+					V4(x) => x.try_into(),
+				}
+			}
+		}
+		impl TryFrom<$n> for $v4 {
+			type Error = ();
+			fn try_from(x: $n) -> Result<Self, ()> {
+				use $n::*;
+				match x {
+					V3(x) => x.try_into(),
+					V4(x) => Ok(x),
 				}
 			}
 		}
 		impl MaxEncodedLen for $n {
 			fn max_encoded_len() -> usize {
-				<$v3>::max_encoded_len()
+				<$v3>::max_encoded_len().max(<$v4>::max_encoded_len())
 			}
 		}
 	};
@@ -225,6 +263,133 @@ macro_rules! versioned_type {
 			}
 		}
 	};
+
+	($(#[$attr:meta])* pub enum $n:ident {
+		$(#[$index2:meta])+
+		V2($v2:ty),
+		$(#[$index3:meta])+
+		V3($v3:ty),
+		$(#[$index4:meta])+
+		V4($v4:ty),
+	}) => {
+		#[derive(Derivative, Encode, Decode, TypeInfo)]
+		#[derivative(
+			Clone(bound = ""),
+			Eq(bound = ""),
+			PartialEq(bound = ""),
+			Debug(bound = "")
+		)]
+		#[codec(encode_bound())]
+		#[codec(decode_bound())]
+		#[scale_info(replace_segment("staging_xcm", "xcm"))]
+		$(#[$attr])*
+		pub enum $n {
+			$(#[$index2])*
+			V2($v2),
+			$(#[$index3])*
+			V3($v3),
+			$(#[$index4])*
+			V4($v4),
+		}
+		impl $n {
+			pub fn try_as<T>(&self) -> Result<&T, ()> where Self: TryAs<T> {
+				<Self as TryAs<T>>::try_as(&self)
+			}
+		}
+		impl TryAs<$v2> for $n {
+			fn try_as(&self) -> Result<&$v2, ()> {
+				match &self {
+					Self::V2(ref x) => Ok(x),
+					_ => Err(()),
+				}
+			}
+		}
+		impl TryAs<$v3> for $n {
+			fn try_as(&self) -> Result<&$v3, ()> {
+				match &self {
+					Self::V3(ref x) => Ok(x),
+					_ => Err(()),
+				}
+			}
+		}
+		impl TryAs<$v4> for $n {
+			fn try_as(&self) -> Result<&$v4, ()> {
+				match &self {
+					Self::V4(ref x) => Ok(x),
+					_ => Err(()),
+				}
+			}
+		}
+		impl IntoVersion for $n {
+			fn into_version(self, n: Version) -> Result<Self, ()> {
+				Ok(match n {
+					1 | 2 => Self::V2(self.try_into()?),
+					3 => Self::V3(self.try_into()?),
+					4 => Self::V4(self.try_into()?),
+					_ => return Err(()),
+				})
+			}
+		}
+		impl From<$v2> for $n {
+			fn from(x: $v2) -> Self {
+				$n::V2(x)
+			}
+		}
+		impl<T: Into<$v3>> From<T> for $n {
+			fn from(x: T) -> Self {
+				$n::V3(x.into())
+			}
+		}
+		impl From<$v4> for $n {
+			fn from(x: $v4) -> Self {
+				$n::V4(x)
+			}
+		}
+		impl TryFrom<$n> for $v2 {
+			type Error = ();
+			fn try_from(x: $n) -> Result<Self, ()> {
+				use $n::*;
+				match x {
+					V2(x) => Ok(x),
+					V3(x) => x.try_into(),
+					V4(x) => {
+						let v3: $v3 = x.try_into()?;
+						v3.try_into()
+					},
+				}
+			}
+		}
+		impl TryFrom<$n> for $v3 {
+			type Error = ();
+			fn try_from(x: $n) -> Result<Self, ()> {
+				use $n::*;
+				match x {
+					V2(x) => x.try_into(),
+					V3(x) => Ok(x),
+					V4(x) => x.try_into(),
+				}
+			}
+		}
+		impl TryFrom<$n> for $v4 {
+			type Error = ();
+			fn try_from(x: $n) -> Result<Self, ()> {
+				use $n::*;
+				match x {
+					V2(x) => {
+						let v3: $v3 = x.try_into()?;
+						v3.try_into()
+					},
+					V3(x) => x.try_into(),
+					V4(x) => Ok(x),
+				}
+			}
+		}
+		impl MaxEncodedLen for $n {
+			fn max_encoded_len() -> usize {
+				<$v3>::max_encoded_len().max(<$v4>::max_encoded_len())
+			}
+		}
+	};
 }
 
 versioned_type! {
@@ -232,6 +397,8 @@ versioned_type! {
 	pub enum VersionedAssetId {
 		#[codec(index = 3)]
 		V3(v3::AssetId),
+		#[codec(index = 4)]
+		V4(v4::AssetId),
 	}
 }
 
@@ -242,6 +409,8 @@ versioned_type! {
 		V2(v2::Response),
 		#[codec(index = 3)]
 		V3(v3::Response),
+		#[codec(index = 4)]
+		V4(v4::Response),
 	}
 }
 
@@ -262,6 +431,8 @@ versioned_type! {
 		V2(v2::Junction),
 		#[codec(index = 3)]
 		V3(v3::Junction),
+		#[codec(index = 4)]
+		V4(v4::Junction),
 	}
 }
 
@@ -273,6 +444,8 @@ versioned_type! {
 		V2(v2::MultiLocation),
 		#[codec(index = 3)]
 		V3(v3::MultiLocation),
+		#[codec(index = 4)]
+		V4(v4::Location),
 	}
 }
 
@@ -283,6 +456,8 @@ versioned_type! {
 		V2(v2::InteriorMultiLocation),
 		#[codec(index = 3)]
 		V3(v3::InteriorMultiLocation),
+		#[codec(index = 4)]
+		V4(v4::InteriorLocation),
 	}
 }
 
@@ -293,6 +468,8 @@ versioned_type! {
 		V2(v2::MultiAsset),
 		#[codec(index = 3)]
 		V3(v3::MultiAsset),
+		#[codec(index = 4)]
+		V4(v4::Asset),
 	}
 }
 
@@ -303,6 +480,8 @@ versioned_type! {
 		V2(v2::MultiAssets),
 		#[codec(index = 3)]
 		V3(v3::MultiAssets),
+		#[codec(index = 4)]
+		V4(v4::Assets),
 	}
 }
 
@@ -318,6 +497,8 @@ pub enum VersionedXcm<RuntimeCall> {
 	V2(v2::Xcm<RuntimeCall>),
 	#[codec(index = 3)]
 	V3(v3::Xcm<RuntimeCall>),
+	#[codec(index = 4)]
+	V4(v4::Xcm<RuntimeCall>),
 }
 
 impl<C> IntoVersion for VersionedXcm<C> {
@@ -325,6 +506,7 @@ impl<C> IntoVersion for VersionedXcm<C> {
 		Ok(match n {
 			2 => Self::V2(self.try_into()?),
 			3 => Self::V3(self.try_into()?),
+			4 => Self::V4(self.try_into()?),
 			_ => return Err(()),
 		})
 	}
@@ -342,6 +524,12 @@ impl<RuntimeCall> From<v3::Xcm<RuntimeCall>> for VersionedXcm<RuntimeCall> {
 	}
 }
 
+impl<RuntimeCall> From<v4::Xcm<RuntimeCall>> for VersionedXcm<RuntimeCall> {
+	fn from(x: v4::Xcm<RuntimeCall>) -> Self {
+		VersionedXcm::V4(x)
+	}
+}
+
 impl<RuntimeCall> TryFrom<VersionedXcm<RuntimeCall>> for v2::Xcm<RuntimeCall> {
 	type Error = ();
 	fn try_from(x: VersionedXcm<RuntimeCall>) -> Result<Self, ()> {
@@ -349,6 +537,10 @@ impl<RuntimeCall> TryFrom<VersionedXcm<RuntimeCall>> for v2::Xcm<RuntimeCall> {
 		match x {
 			V2(x) => Ok(x),
 			V3(x) => x.try_into(),
+			V4(x) => {
+				let v3: v3::Xcm<RuntimeCall> = x.try_into()?;
+				v3.try_into()
+			},
 		}
 	}
 }
@@ -360,6 +552,22 @@ impl<Call> TryFrom<VersionedXcm<Call>> for v3::Xcm<Call> {
 		match x {
 			V2(x) => x.try_into(),
 			V3(x) => Ok(x),
+			V4(x) => x.try_into(),
+		}
+	}
+}
+
+impl<Call> TryFrom<VersionedXcm<Call>> for v4::Xcm<Call> {
+	type Error = ();
+	fn try_from(x: VersionedXcm<Call>) -> Result<Self, ()> {
+		use VersionedXcm::*;
+		match x {
+			V2(x) => {
+				let v3: v3::Xcm<Call> = x.try_into()?;
+				v3.try_into()
+			},
+			V3(x) => x.try_into(),
+			V4(x) => Ok(x),
 		}
 	}
 }
@@ -408,6 +616,18 @@ impl WrapVersion for AlwaysV3 {
 	}
 }
 
+/// `WrapVersion` implementation which attempts to always convert the XCM to version 4 before
+/// wrapping it.
+pub struct AlwaysV4;
+impl WrapVersion for AlwaysV4 {
+	fn wrap_version<Call>(
+		_: &latest::MultiLocation,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()> {
+		Ok(VersionedXcm::<Call>::V4(xcm.into().try_into()?))
+	}
+}
+
 /// `WrapVersion` implementation which attempts to always convert the XCM to the latest version
 /// before wrapping it.
 pub type AlwaysLatest = AlwaysV3;
@@ -418,8 +638,8 @@ pub type AlwaysLts = AlwaysV3;
 
 pub mod prelude {
 	pub use super::{
-		latest::prelude::*, AlwaysLatest, AlwaysLts, AlwaysV2, AlwaysV3, IntoVersion, Unsupported,
-		Version as XcmVersion, VersionedAssetId, VersionedInteriorMultiLocation,
+		latest::prelude::*, AlwaysLatest, AlwaysLts, AlwaysV2, AlwaysV3, AlwaysV4, IntoVersion,
+		Unsupported, Version as XcmVersion, VersionedAssetId, VersionedInteriorMultiLocation,
 		VersionedMultiAsset, VersionedMultiAssets, VersionedMultiLocation, VersionedResponse,
 		VersionedXcm, WrapVersion,
 	};
@@ -438,6 +658,12 @@ pub mod opaque {
 		// Then override with the opaque types in v3
 		pub use crate::v3::opaque::{Instruction, Xcm};
 	}
+	pub mod v4 {
+		// Everything from v4
+		pub use crate::v4::*;
+		// Then override with the opaque types in v4
+		pub use crate::v4::opaque::{Instruction, Xcm};
+	}
 
 	pub mod latest {
 		pub use super::v3::*;
@@ -33,6 +33,18 @@ fn encode_decode_versioned_asset_id_v3() {
 	assert_eq!(asset_id, decoded);
 }
 
+#[test]
+fn encode_decode_versioned_asset_id_v4() {
+	let asset_id = VersionedAssetId::V4(v4::AssetId(v4::Location::default()));
+	let encoded = asset_id.encode();
+
+	assert_eq!(encoded, hex_literal::hex!("040000"), "encode format changed");
+	assert_eq!(encoded[0], 4, "bad version number");
+
+	let decoded = VersionedAssetId::decode(&mut &encoded[..]).unwrap();
+	assert_eq!(asset_id, decoded);
+}
+
 #[test]
 fn encode_decode_versioned_response_v2() {
 	let response = VersionedResponse::V2(v2::Response::Null);
@@ -81,6 +93,18 @@ fn encode_decode_versioned_multi_location_v3() {
 	assert_eq!(location, decoded);
 }
 
+#[test]
+fn encode_decode_versioned_multi_location_v4() {
+	let location = VersionedMultiLocation::V4(v4::Location::default());
+	let encoded = location.encode();
+
+	assert_eq!(encoded, hex_literal::hex!("040000"), "encode format changed");
+	assert_eq!(encoded[0], 4, "bad version number");
+
+	let decoded = VersionedMultiLocation::decode(&mut &encoded[..]).unwrap();
+	assert_eq!(location, decoded);
+}
+
 #[test]
 fn encode_decode_versioned_interior_multi_location_v2() {
 	let location = VersionedInteriorMultiLocation::V2(v2::InteriorMultiLocation::Here);
@@ -158,6 +182,20 @@ fn encode_decode_versioned_multi_assets_v3() {
 	assert_eq!(assets, decoded);
 }
 
+#[test]
+fn encode_decode_versioned_multi_assets_v4() {
+	let assets = VersionedMultiAssets::V4(v4::Assets::from(vec![
+		(v4::Asset::from((v4::Location::default(), 1))),
+	]));
+	let encoded = assets.encode();
+
+	assert_eq!(encoded, hex_literal::hex!("040400000004"), "encode format changed");
+	assert_eq!(encoded[0], 4, "bad version number");
+
+	let decoded = VersionedMultiAssets::decode(&mut &encoded[..]).unwrap();
+	assert_eq!(assets, decoded);
+}
+
 #[test]
 fn encode_decode_versioned_xcm_v2() {
 	let xcm = VersionedXcm::V2(v2::Xcm::<()>::new());
@@ -182,6 +220,39 @@ fn encode_decode_versioned_xcm_v3() {
 	assert_eq!(xcm, decoded);
 }
 
+#[test]
+fn encode_decode_versioned_xcm_v4() {
+	let xcm = VersionedXcm::V4(v4::Xcm::<()>::new());
+	let encoded = xcm.encode();
+
+	assert_eq!(encoded, hex_literal::hex!("0400"), "encode format changed");
+	assert_eq!(encoded[0], 4, "bad version number");
+
+	let decoded = VersionedXcm::decode(&mut &encoded[..]).unwrap();
+	assert_eq!(xcm, decoded);
+}
+
+#[test]
+fn versioned_multi_location_converts_between_v3_and_v4() {
+	let v3_location = v3::MultiLocation::new(1, v3::Junctions::X1(v3::Junction::Parachain(1000)));
+	let v4_location = v4::Location::new(1, [v4::Junction::Parachain(1000)]);
+
+	let versioned = VersionedMultiLocation::V3(v3_location);
+	assert_eq!(
+		versioned.clone().into_version(4),
+		Ok(VersionedMultiLocation::V4(v4_location.clone()))
+	);
+	assert_eq!(
+		VersionedMultiLocation::V4(v4_location.clone()).into_version(3),
+		Ok(versioned.clone())
+	);
+	// v2 locations are reached through v3.
+	assert_eq!(
+		versioned.into_version(2).and_then(|l| l.into_version(4)),
+		Ok(VersionedMultiLocation::V4(v4_location))
+	);
+}
+
 // With the renaming of the crate to `staging-xcm` the naming in the metadata changed as well and
 // this broke downstream users. This test ensures that the name in the metadata isn't changed.
 #[test]
@@ -22,6 +22,7 @@ use crate::{
 		BodyId as OldBodyId, BodyPart as OldBodyPart, Junction as OldJunction,
 		NetworkId as OldNetworkId,
 	},
+	v4::Junction as NewJunction,
 	VersionedMultiLocation,
 };
 use bounded_collections::{BoundedSlice, BoundedVec, ConstU32};
@@ -408,6 +409,25 @@ impl TryFrom<OldJunction> for Junction {
 	}
 }
 
+impl TryFrom<NewJunction> for Junction {
+	type Error = ();
+	fn try_from(value: NewJunction) -> Result<Self, ()> {
+		use NewJunction::*;
+		Ok(match value {
+			Parachain(id) => Self::Parachain(id),
+			AccountId32 { network, id } => Self::AccountId32 { network, id },
+			AccountIndex64 { network, index } => Self::AccountIndex64 { network, index },
+			AccountKey20 { network, key } => Self::AccountKey20 { network, key },
+			PalletInstance(index) => Self::PalletInstance(index),
+			GeneralIndex(id) => Self::GeneralIndex(id),
+			GeneralKey { length, data } => Self::GeneralKey { length, data },
+			OnlyChild => Self::OnlyChild,
+			Plurality { id, part } => Self::Plurality { id, part },
+			GlobalConsensus(network) => Self::GlobalConsensus(network),
+		})
+	}
+}
+
 impl Junction {
 	/// Convert `self` into a `MultiLocation` containing 0 parents.
 	///
@@ -17,7 +17,11 @@
 //! XCM `Junctions`/`InteriorMultiLocation` datatype.
 
 use super::{Junction, MultiLocation, NetworkId};
-use core::{convert::TryFrom, mem, result};
+use crate::v4::Junctions as NewJunctions;
+use core::{
+	convert::{TryFrom, TryInto},
+	mem, result,
+};
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 
@@ -600,6 +604,72 @@ impl TryFrom<MultiLocation> for Junctions {
 	}
 }
 
+impl TryFrom<NewJunctions> for Junctions {
+	type Error = ();
+	fn try_from(new: NewJunctions) -> result::Result<Self, Self::Error> {
+		use Junctions::*;
+		Ok(match new {
+			NewJunctions::Here => Here,
+			NewJunctions::X1(j) => {
+				let [a] = *j;
+				X1(a.try_into()?)
+			},
+			NewJunctions::X2(j) => {
+				let [a, b] = *j;
+				X2(a.try_into()?, b.try_into()?)
+			},
+			NewJunctions::X3(j) => {
+				let [a, b, c] = *j;
+				X3(a.try_into()?, b.try_into()?, c.try_into()?)
+			},
+			NewJunctions::X4(j) => {
+				let [a, b, c, d] = *j;
+				X4(a.try_into()?, b.try_into()?, c.try_into()?, d.try_into()?)
+			},
+			NewJunctions::X5(j) => {
+				let [a, b, c, d, e] = *j;
+				X5(a.try_into()?, b.try_into()?, c.try_into()?, d.try_into()?, e.try_into()?)
+			},
+			NewJunctions::X6(j) => {
+				let [a, b, c, d, e, f] = *j;
+				X6(
+					a.try_into()?,
+					b.try_into()?,
+					c.try_into()?,
+					d.try_into()?,
+					e.try_into()?,
+					f.try_into()?,
+				)
+			},
+			NewJunctions::X7(j) => {
+				let [a, b, c, d, e, f, g] = *j;
+				X7(
+					a.try_into()?,
+					b.try_into()?,
+					c.try_into()?,
+					d.try_into()?,
+					e.try_into()?,
+					f.try_into()?,
+					g.try_into()?,
+				)
+			},
+			NewJunctions::X8(j) => {
+				let [a, b, c, d, e, f, g, h] = *j;
+				X8(
+					a.try_into()?,
+					b.try_into()?,
+					c.try_into()?,
+					d.try_into()?,
+					e.try_into()?,
+					f.try_into()?,
+					g.try_into()?,
+					h.try_into()?,
+				)
+			},
+		})
+	}
+}
+
 impl<T: Into<Junction>> From<T> for Junctions {
 	fn from(x: T) -> Self {
 		Self::X1(x.into())
@@ -16,9 +16,15 @@
 
 //! Version 3 of the Cross-Consensus Message format data structures.
 
-use super::v2::{
-	Instruction as OldInstruction, Response as OldResponse, WeightLimit as OldWeightLimit,
-	Xcm as OldXcm,
+use super::{
+	v2::{
+		Instruction as OldInstruction, Response as OldResponse, WeightLimit as OldWeightLimit,
+		Xcm as OldXcm,
+	},
+	v4::{
+		Instruction as NewInstruction, QueryResponseInfo as NewQueryResponseInfo,
+		Response as NewResponse, Xcm as NewXcm,
+	},
 };
 use crate::DoubleEncoded;
 use alloc::{vec, vec::Vec};
@@ -1253,6 +1259,155 @@ impl<Call> TryFrom<OldXcm<Call>> for Xcm<Call> {
 	}
 }
 
+// Convert from a v4 response to a v3 response.
+impl TryFrom<NewResponse> for Response {
+	type Error = ();
+	fn try_from(response: NewResponse) -> result::Result<Self, Self::Error> {
+		use NewResponse::*;
+		Ok(match response {
+			Null => Self::Null,
+			Assets(assets) => Self::Assets(assets.try_into()?),
+			ExecutionResult(result) => Self::ExecutionResult(result),
+			Version(version) => Self::Version(version),
+			PalletsInfo(pallet_info) => Self::PalletsInfo(pallet_info),
+			DispatchResult(maybe_error) => Self::DispatchResult(maybe_error),
+		})
+	}
+}
+
+// Convert from a v4 query response info to a v3 query response info.
+impl TryFrom<NewQueryResponseInfo> for QueryResponseInfo {
+	type Error = ();
+	fn try_from(new: NewQueryResponseInfo) -> result::Result<Self, Self::Error> {
+		Ok(Self {
+			destination: new.destination.try_into()?,
+			query_id: new.query_id,
+			max_weight: new.max_weight,
+		})
+	}
+}
+
+// Convert from a v4 XCM to a v3 XCM.
+impl<Call> TryFrom<NewXcm<Call>> for Xcm<Call> {
+	type Error = ();
+	fn try_from(new_xcm: NewXcm<Call>) -> result::Result<Self, Self::Error> {
+		Ok(Xcm(new_xcm.0.into_iter().map(TryInto::try_into).collect::<result::Result<_, _>>()?))
+	}
+}
+
+// Convert from a v4 instruction to a v3 instruction.
+impl<Call> TryFrom<NewInstruction<Call>> for Instruction<Call> {
+	type Error = ();
+	fn try_from(instruction: NewInstruction<Call>) -> result::Result<Self, Self::Error> {
+		use NewInstruction::*;
+		Ok(match instruction {
+			WithdrawAsset(assets) => Self::WithdrawAsset(assets.try_into()?),
+			ReserveAssetDeposited(assets) => Self::ReserveAssetDeposited(assets.try_into()?),
+			ReceiveTeleportedAsset(assets) => Self::ReceiveTeleportedAsset(assets.try_into()?),
+			QueryResponse { query_id, response, max_weight, querier } => Self::QueryResponse {
+				query_id,
+				response: response.try_into()?,
+				max_weight,
+				querier: querier.map(TryInto::try_into).transpose()?,
+			},
+			TransferAsset { assets, beneficiary } => Self::TransferAsset {
+				assets: assets.try_into()?,
+				beneficiary: beneficiary.try_into()?,
+			},
+			TransferReserveAsset { assets, dest, xcm } => Self::TransferReserveAsset {
+				assets: assets.try_into()?,
+				dest: dest.try_into()?,
+				xcm: xcm.try_into()?,
+			},
+			HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity } =>
+				Self::HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity },
+			HrmpChannelAccepted { recipient } => Self::HrmpChannelAccepted { recipient },
+			HrmpChannelClosing { initiator, sender, recipient } =>
+				Self::HrmpChannelClosing { initiator, sender, recipient },
+			Transact { origin_kind, require_weight_at_most, call } =>
+				Self::Transact { origin_kind, require_weight_at_most, call: call.into() },
+			ReportError(response_info) => Self::ReportError(response_info.try_into()?),
+			DepositAsset { assets, beneficiary } => Self::DepositAsset {
+				assets: assets.try_into()?,
+				beneficiary: beneficiary.try_into()?,
+			},
+			DepositReserveAsset { assets, dest, xcm } => Self::DepositReserveAsset {
+				assets: assets.try_into()?,
+				dest: dest.try_into()?,
+				xcm: xcm.try_into()?,
+			},
+			ExchangeAsset { give, want, maximal } =>
+				Self::ExchangeAsset { give: give.try_into()?, want: want.try_into()?, maximal },
+			InitiateReserveWithdraw { assets, reserve, xcm } => Self::InitiateReserveWithdraw {
+				assets: assets.try_into()?,
+				reserve: reserve.try_into()?,
+				xcm: xcm.try_into()?,
+			},
+			InitiateTeleport { assets, dest, xcm } => Self::InitiateTeleport {
+				assets: assets.try_into()?,
+				dest: dest.try_into()?,
+				xcm: xcm.try_into()?,
+			},
+			ReportHolding { response_info, assets } => Self::ReportHolding {
+				response_info: response_info.try_into()?,
+				assets: assets.try_into()?,
+			},
+			BuyExecution { fees, weight_limit } =>
+				Self::BuyExecution { fees: fees.try_into()?, weight_limit },
+			ClearOrigin => Self::ClearOrigin,
+			DescendOrigin(who) => Self::DescendOrigin(who.try_into()?),
+			RefundSurplus => Self::RefundSurplus,
+			SetErrorHandler(xcm) => Self::SetErrorHandler(xcm.try_into()?),
+			SetAppendix(xcm) => Self::SetAppendix(xcm.try_into()?),
+			ClearError => Self::ClearError,
+			ClaimAsset { assets, ticket } => {
+				let assets = assets.try_into()?;
+				let ticket = ticket.try_into()?;
+				Self::ClaimAsset { assets, ticket }
+			},
+			Trap(code) => Self::Trap(code),
+			SubscribeVersion { query_id, max_response_weight } =>
+				Self::SubscribeVersion { query_id, max_response_weight },
+			UnsubscribeVersion => Self::UnsubscribeVersion,
+			BurnAsset(assets) => Self::BurnAsset(assets.try_into()?),
+			ExpectAsset(assets) => Self::ExpectAsset(assets.try_into()?),
+			ExpectOrigin(maybe_location) =>
+				Self::ExpectOrigin(maybe_location.map(TryInto::try_into).transpose()?),
+			ExpectError(maybe_error) => Self::ExpectError(maybe_error),
+			ExpectTransactStatus(maybe_error_code) => Self::ExpectTransactStatus(maybe_error_code),
+			QueryPallet { module_name, response_info } =>
+				Self::QueryPallet { module_name, response_info: response_info.try_into()? },
+			ExpectPallet { index, name, module_name, crate_major, min_crate_minor } =>
+				Self::ExpectPallet { index, name, module_name, crate_major, min_crate_minor },
+			ReportTransactStatus(response_info) =>
+				Self::ReportTransactStatus(response_info.try_into()?),
+			ClearTransactStatus => Self::ClearTransactStatus,
+			UniversalOrigin(junction) => Self::UniversalOrigin(junction.try_into()?),
+			ExportMessage { network, destination, xcm } => Self::ExportMessage {
+				network,
+				destination: destination.try_into()?,
+				xcm: xcm.try_into()?,
+			},
+			LockAsset { asset, unlocker } =>
+				Self::LockAsset { asset: asset.try_into()?, unlocker: unlocker.try_into()? },
+			UnlockAsset { asset, target } =>
+				Self::UnlockAsset { asset: asset.try_into()?, target: target.try_into()? },
+			NoteUnlockable { asset, owner } =>
+				Self::NoteUnlockable { asset: asset.try_into()?, owner: owner.try_into()? },
+			RequestUnlock { asset, locker } =>
+				Self::RequestUnlock { asset: asset.try_into()?, locker: locker.try_into()? },
+			SetFeesMode { jit_withdraw } => Self::SetFeesMode { jit_withdraw },
+			SetTopic(topic) => Self::SetTopic(topic),
+			ClearTopic => Self::ClearTopic,
+			AliasOrigin(location) => Self::AliasOrigin(location.try_into()?),
+			UnpaidExecution { weight_limit, check_origin } => Self::UnpaidExecution {
+				weight_limit,
+				check_origin: check_origin.map(TryInto::try_into).transpose()?,
+			},
+		})
+	}
+}
+
 /// Default value for the proof size weight component when converting from V2. Set at 64 KB.
 /// NOTE: Make sure this is removed after we properly account for PoV weights.
 const DEFAULT_PROOF_SIZE: u64 = 64 * 1024;
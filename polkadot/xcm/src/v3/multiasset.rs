@@ -27,11 +27,17 @@
 //!   filtering an XCM holding account.
 
 use super::{InteriorMultiLocation, MultiLocation};
-use crate::v2::{
-	AssetId as OldAssetId, AssetInstance as OldAssetInstance, Fungibility as OldFungibility,
-	MultiAsset as OldMultiAsset, MultiAssetFilter as OldMultiAssetFilter,
-	MultiAssets as OldMultiAssets, WildFungibility as OldWildFungibility,
-	WildMultiAsset as OldWildMultiAsset,
+use crate::{
+	v2::{
+		AssetId as OldAssetId, AssetInstance as OldAssetInstance, Fungibility as OldFungibility,
+		MultiAsset as OldMultiAsset, MultiAssetFilter as OldMultiAssetFilter,
+		MultiAssets as OldMultiAssets, WildFungibility as OldWildFungibility,
+		WildMultiAsset as OldWildMultiAsset,
+	},
+	v4::{
+		Asset as NewMultiAsset, AssetFilter as NewMultiAssetFilter, AssetId as NewAssetId,
+		Assets as NewMultiAssets, WildAsset as NewWildMultiAsset,
+	},
 };
 use alloc::{vec, vec::Vec};
 use bounded_collections::{BoundedVec, ConstU32};
@@ -374,6 +380,13 @@ impl TryFrom<OldAssetId> for AssetId {
 	}
 }
 
+impl TryFrom<NewAssetId> for AssetId {
+	type Error = ();
+	fn try_from(new: NewAssetId) -> Result<Self, Self::Error> {
+		Ok(Self::Concrete(new.0.try_into()?))
+	}
+}
+
 impl AssetId {
 	/// Prepend a `MultiLocation` to a concrete asset, giving it a new root location.
 	pub fn prepend_with(&mut self, prepend: &MultiLocation) -> Result<(), ()> {
@@ -501,6 +514,13 @@ impl TryFrom<OldMultiAsset> for MultiAsset {
 	}
 }
 
+impl TryFrom<NewMultiAsset> for MultiAsset {
+	type Error = ();
+	fn try_from(new: NewMultiAsset) -> Result<Self, Self::Error> {
+		Ok(Self { id: new.id.try_into()?, fun: new.fun })
+	}
+}
+
 /// A `Vec` of `MultiAsset`s.
 ///
 /// There are a number of invariants which the construction and mutation functions must ensure are
@@ -543,6 +563,18 @@ impl TryFrom<OldMultiAssets> for MultiAssets {
 	}
 }
 
+impl TryFrom<NewMultiAssets> for MultiAssets {
+	type Error = ();
+	fn try_from(new: NewMultiAssets) -> Result<Self, Self::Error> {
+		let v = new
+			.into_inner()
+			.into_iter()
+			.map(MultiAsset::try_from)
+			.collect::<Result<Vec<_>, ()>>()?;
+		Ok(MultiAssets(v))
+	}
+}
+
 impl From<Vec<MultiAsset>> for MultiAssets {
 	fn from(mut assets: Vec<MultiAsset>) -> Self {
 		let mut res = Vec::with_capacity(assets.len());
@@ -753,6 +785,20 @@ impl TryFrom<(OldWildMultiAsset, u32)> for WildMultiAsset {
 	}
 }
 
+impl TryFrom<NewWildMultiAsset> for WildMultiAsset {
+	type Error = ();
+	fn try_from(new: NewWildMultiAsset) -> Result<Self, ()> {
+		use NewWildMultiAsset::*;
+		Ok(match new {
+			AllOf { id, fun } => Self::AllOf { id: id.try_into()?, fun },
+			AllOfCounted { id, fun, count } =>
+				Self::AllOfCounted { id: id.try_into()?, fun, count },
+			All => Self::All,
+			AllCounted(count) => Self::AllCounted(count),
+		})
+	}
+}
+
 impl WildMultiAsset {
 	/// Returns true if `self` is a super-set of the given `inner` asset.
 	pub fn contains(&self, inner: &MultiAsset) -> bool {
@@ -922,6 +968,17 @@ impl TryFrom<(OldMultiAssetFilter, u32)> for MultiAssetFilter {
 	}
 }
 
+impl TryFrom<NewMultiAssetFilter> for MultiAssetFilter {
+	type Error = ();
+	fn try_from(new: NewMultiAssetFilter) -> Result<MultiAssetFilter, Self::Error> {
+		use NewMultiAssetFilter::*;
+		Ok(match new {
+			Definite(x) => Self::Definite(x.try_into()?),
+			Wild(x) => Self::Wild(x.try_into()?),
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::super::prelude::*;
@@ -17,7 +17,9 @@
 //! XCM `MultiLocation` datatype.
 
 use super::{Junction, Junctions};
-use crate::{v2::MultiLocation as OldMultiLocation, VersionedMultiLocation};
+use crate::{
+	v2::MultiLocation as OldMultiLocation, v4::Location as NewMultiLocation, VersionedMultiLocation,
+};
 use core::{
 	convert::{TryFrom, TryInto},
 	result,
@@ -468,6 +470,13 @@ impl TryFrom<OldMultiLocation> for MultiLocation {
 	}
 }
 
+impl TryFrom<NewMultiLocation> for MultiLocation {
+	type Error = ();
+	fn try_from(new: NewMultiLocation) -> result::Result<Self, ()> {
+		Ok(MultiLocation { parents: new.parents, interior: new.interior.try_into()? })
+	}
+}
+
 /// A unit struct which can be converted into a `MultiLocation` of `parents` value 1.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Parent;
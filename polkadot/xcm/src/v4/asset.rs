@@ -0,0 +1,677 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cross-Consensus Message format asset data structures.
+//!
+//! This encompasses four types for representing assets:
+//! - `Asset`: A description of a single asset, either an instance of a non-fungible or some amount
+//!   of a fungible.
+//! - `Assets`: A collection of `Asset`s. These are stored in a `Vec` and sorted with fungibles
+//!   first.
+//! - `Wild`: A single asset wildcard, this can either be "all" assets, or all assets of a specific
+//!   kind.
+//! - `AssetFilter`: A combination of `Wild` and `Assets` designed for efficiently filtering an XCM
+//!   holding account.
+//!
+//! Unlike in v3, an `AssetId` is always a concrete `Location`; abstract asset identifiers are no
+//! longer supported.
+
+use super::{InteriorLocation, Location};
+use crate::v3::{
+	AssetId as OldAssetId, MultiAsset as OldAsset, MultiAssetFilter as OldAssetFilter,
+	MultiAssets as OldAssets, WildMultiAsset as OldWildAsset,
+};
+pub use crate::v3::{AssetInstance, Fungibility, WildFungibility};
+use alloc::{vec, vec::Vec};
+use bounded_collections::{BoundedVec, ConstU32};
+use core::{
+	cmp::Ordering,
+	convert::{TryFrom, TryInto},
+};
+use parity_scale_codec::{self as codec, Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Location to identify an asset.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[scale_info(replace_segment("staging_xcm", "xcm"))]
+pub struct AssetId(pub Location);
+
+impl<T: Into<Location>> From<T> for AssetId {
+	fn from(x: T) -> Self {
+		Self(x.into())
+	}
+}
+
+impl TryFrom<OldAssetId> for AssetId {
+	type Error = ();
+	fn try_from(old: OldAssetId) -> Result<Self, ()> {
+		use OldAssetId::*;
+		Ok(match old {
+			Concrete(l) => Self(l.try_into()?),
+			Abstract(_) => return Err(()),
+		})
+	}
+}
+
+impl AssetId {
+	/// Prepend a `Location` to an asset id, giving it a new root location.
+	pub fn prepend_with(&mut self, prepend: &Location) -> Result<(), ()> {
+		self.0.prepend_with(prepend.clone()).map_err(|_| ())?;
+		Ok(())
+	}
+
+	/// Mutate the asset to represent the same value from the perspective of a new `target`
+	/// location. The local chain's location is provided in `context`.
+	pub fn reanchor(&mut self, target: &Location, context: &InteriorLocation) -> Result<(), ()> {
+		self.0.reanchor(target, context)?;
+		Ok(())
+	}
+
+	/// Use the value of `self` along with a `fun` fungibility specifier to create the corresponding
+	/// `Asset` value.
+	pub fn into_asset(self, fun: Fungibility) -> Asset {
+		Asset { fun, id: self }
+	}
+
+	/// Use the value of `self` along with a `fun` fungibility specifier to create the corresponding
+	/// `WildAsset` wildcard (`AllOf`) value.
+	pub fn into_wild(self, fun: WildFungibility) -> WildAsset {
+		WildAsset::AllOf { fun, id: self }
+	}
+}
+
+/// Either an amount of a single fungible asset, or a single well-identified non-fungible asset.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[scale_info(replace_segment("staging_xcm", "xcm"))]
+pub struct Asset {
+	/// The overall asset identity (aka *class*, in the case of a non-fungible).
+	pub id: AssetId,
+	/// The fungibility of the asset, which contains either the amount (in the case of a fungible
+	/// asset) or the *instance ID*, the secondary asset identifier.
+	pub fun: Fungibility,
+}
+
+impl PartialOrd for Asset {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Asset {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match (&self.fun, &other.fun) {
+			(Fungibility::Fungible(..), Fungibility::NonFungible(..)) => Ordering::Less,
+			(Fungibility::NonFungible(..), Fungibility::Fungible(..)) => Ordering::Greater,
+			_ => (&self.id, &self.fun).cmp(&(&other.id, &other.fun)),
+		}
+	}
+}
+
+impl<A: Into<AssetId>, B: Into<Fungibility>> From<(A, B)> for Asset {
+	fn from((id, fun): (A, B)) -> Asset {
+		Asset { fun: fun.into(), id: id.into() }
+	}
+}
+
+impl Asset {
+	pub fn is_fungible(&self, maybe_id: Option<AssetId>) -> bool {
+		use Fungibility::*;
+		matches!(self.fun, Fungible(..)) && maybe_id.map_or(true, |i| i == self.id)
+	}
+
+	pub fn is_non_fungible(&self, maybe_id: Option<AssetId>) -> bool {
+		use Fungibility::*;
+		matches!(self.fun, NonFungible(..)) && maybe_id.map_or(true, |i| i == self.id)
+	}
+
+	/// Prepend a `Location` to a concrete asset, giving it a new root location.
+	pub fn prepend_with(&mut self, prepend: &Location) -> Result<(), ()> {
+		self.id.prepend_with(prepend)
+	}
+
+	/// Mutate the location of the asset identifier, giving it the same location relative to a
+	/// `target` context. The local context is provided as `context`.
+	pub fn reanchor(&mut self, target: &Location, context: &InteriorLocation) -> Result<(), ()> {
+		self.id.reanchor(target, context)
+	}
+
+	/// Mutate the location of the asset identifier, giving it the same location relative to a
+	/// `target` context. The local context is provided as `context`.
+	pub fn reanchored(mut self, target: &Location, context: &InteriorLocation) -> Result<Self, ()> {
+		self.id.reanchor(target, context)?;
+		Ok(self)
+	}
+
+	/// Returns true if `self` is a super-set of the given `inner` asset.
+	pub fn contains(&self, inner: &Asset) -> bool {
+		use Fungibility::*;
+		if self.id == inner.id {
+			match (&self.fun, &inner.fun) {
+				(Fungible(a), Fungible(i)) if a >= i => return true,
+				(NonFungible(a), NonFungible(i)) if a == i => return true,
+				_ => (),
+			}
+		}
+		false
+	}
+}
+
+impl TryFrom<OldAsset> for Asset {
+	type Error = ();
+	fn try_from(old: OldAsset) -> Result<Self, ()> {
+		Ok(Self { id: old.id.try_into()?, fun: old.fun })
+	}
+}
+
+/// A `Vec` of `Asset`s.
+///
+/// There are a number of invariants which the construction and mutation functions must ensure are
+/// maintained:
+/// - It may contain no items of duplicate asset class;
+/// - All items must be ordered;
+/// - The number of items should grow no larger than `MAX_ITEMS_IN_ASSETS`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, TypeInfo, Default)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[scale_info(replace_segment("staging_xcm", "xcm"))]
+pub struct Assets(Vec<Asset>);
+
+/// Maximum number of items in a single `Assets` value that can be decoded.
+pub const MAX_ITEMS_IN_ASSETS: usize = 20;
+
+impl MaxEncodedLen for Assets {
+	fn max_encoded_len() -> usize {
+		Asset::max_encoded_len() * MAX_ITEMS_IN_ASSETS
+	}
+}
+
+impl Decode for Assets {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let bounded_instructions =
+			BoundedVec::<Asset, ConstU32<{ MAX_ITEMS_IN_ASSETS as u32 }>>::decode(input)?;
+		Self::from_sorted_and_deduplicated(bounded_instructions.into_inner())
+			.map_err(|()| "Out of order".into())
+	}
+}
+
+impl TryFrom<OldAssets> for Assets {
+	type Error = ();
+	fn try_from(old: OldAssets) -> Result<Self, ()> {
+		let v = old
+			.into_inner()
+			.into_iter()
+			.map(Asset::try_from)
+			.collect::<Result<Vec<_>, ()>>()?;
+		Ok(Assets(v))
+	}
+}
+
+impl From<Vec<Asset>> for Assets {
+	fn from(mut assets: Vec<Asset>) -> Self {
+		let mut res = Vec::with_capacity(assets.len());
+		if !assets.is_empty() {
+			assets.sort();
+			let mut iter = assets.into_iter();
+			if let Some(first) = iter.next() {
+				let last = iter.fold(first, |a, b| -> Asset {
+					match (a, b) {
+						(
+							Asset { fun: Fungibility::Fungible(a_amount), id: a_id },
+							Asset { fun: Fungibility::Fungible(b_amount), id: b_id },
+						) if a_id == b_id => Asset {
+							id: a_id,
+							fun: Fungibility::Fungible(a_amount.saturating_add(b_amount)),
+						},
+						(
+							Asset { fun: Fungibility::NonFungible(a_instance), id: a_id },
+							Asset { fun: Fungibility::NonFungible(b_instance), id: b_id },
+						) if a_id == b_id && a_instance == b_instance =>
+							Asset { fun: Fungibility::NonFungible(a_instance), id: a_id },
+						(to_push, to_remember) => {
+							res.push(to_push);
+							to_remember
+						},
+					}
+				});
+				res.push(last);
+			}
+		}
+		Self(res)
+	}
+}
+
+impl<T: Into<Asset>> From<T> for Assets {
+	fn from(x: T) -> Self {
+		Self(vec![x.into()])
+	}
+}
+
+impl Assets {
+	/// A new (empty) value.
+	pub fn new() -> Self {
+		Self(Vec::new())
+	}
+
+	/// Create a new instance of `Assets` from a `Vec<Asset>` whose contents are sorted
+	/// and which contain no duplicates.
+	///
+	/// Returns `Ok` if the operation succeeds and `Err` if `r` is out of order or had duplicates.
+	/// If you can't guarantee that `r` is sorted and deduplicated, then use
+	/// `From::<Vec<Asset>>::from` which is infallible.
+	pub fn from_sorted_and_deduplicated(r: Vec<Asset>) -> Result<Self, ()> {
+		if r.is_empty() {
+			return Ok(Self(Vec::new()))
+		}
+		r.iter().skip(1).try_fold(&r[0], |a, b| -> Result<&Asset, ()> {
+			if a.id < b.id || a < b && (a.is_non_fungible(None) || b.is_non_fungible(None)) {
+				Ok(b)
+			} else {
+				Err(())
+			}
+		})?;
+		Ok(Self(r))
+	}
+
+	/// Create a new instance of `Assets` from a `Vec<Asset>` whose contents are sorted
+	/// and which contain no duplicates.
+	///
+	/// In release mode, this skips any checks to ensure that `r` is correct, making it a
+	/// negligible-cost operation. Generally though you should avoid using it unless you have a
+	/// strict proof that `r` is valid.
+	#[cfg(test)]
+	pub fn from_sorted_and_deduplicated_skip_checks(r: Vec<Asset>) -> Self {
+		Self::from_sorted_and_deduplicated(r).expect("Invalid input r is not sorted/deduped")
+	}
+	/// Create a new instance of `Assets` from a `Vec<Asset>` whose contents are sorted
+	/// and which contain no duplicates.
+	///
+	/// In release mode, this skips any checks to ensure that `r` is correct, making it a
+	/// negligible-cost operation. Generally though you should avoid using it unless you have a
+	/// strict proof that `r` is valid.
+	///
+	/// In test mode, this checks anyway and panics on fail.
+	#[cfg(not(test))]
+	pub fn from_sorted_and_deduplicated_skip_checks(r: Vec<Asset>) -> Self {
+		Self(r)
+	}
+
+	/// Add some asset onto the list, saturating. This is quite a laborious operation since it
+	/// maintains the ordering.
+	pub fn push(&mut self, a: Asset) {
+		for asset in self.0.iter_mut().filter(|x| x.id == a.id) {
+			match (&a.fun, &mut asset.fun) {
+				(Fungibility::Fungible(amount), Fungibility::Fungible(balance)) => {
+					*balance = balance.saturating_add(*amount);
+					return
+				},
+				(Fungibility::NonFungible(inst1), Fungibility::NonFungible(inst2))
+					if inst1 == inst2 =>
+					return,
+				_ => (),
+			}
+		}
+		self.0.push(a);
+		self.0.sort();
+	}
+
+	/// Returns `true` if this definitely represents no asset.
+	pub fn is_none(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns true if `self` is a super-set of the given `inner` asset.
+	pub fn contains(&self, inner: &Asset) -> bool {
+		self.0.iter().any(|i| i.contains(inner))
+	}
+
+	/// Consume `self` and return the inner vec.
+	pub fn into_inner(self) -> Vec<Asset> {
+		self.0
+	}
+
+	/// Return a reference to the inner vec.
+	pub fn inner(&self) -> &Vec<Asset> {
+		&self.0
+	}
+
+	/// Return the number of distinct asset instances contained.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Prepend a `Location` to any concrete asset items, giving it a new root location.
+	pub fn prepend_with(&mut self, prefix: &Location) -> Result<(), ()> {
+		self.0.iter_mut().try_for_each(|i| i.prepend_with(prefix))?;
+		self.0.sort();
+		Ok(())
+	}
+
+	/// Mutate the location of the asset identifier if concrete, giving it the same location
+	/// relative to a `target` context. The local context is provided as `context`.
+	///
+	/// This will also re-sort the inner assets to preserve ordering guarantees.
+	pub fn reanchor(&mut self, target: &Location, context: &InteriorLocation) -> Result<(), ()> {
+		self.0.iter_mut().try_for_each(|i| i.reanchor(target, context))?;
+		self.0.sort();
+		Ok(())
+	}
+
+	/// Return a reference to an item at a specific index or `None` if it doesn't exist.
+	pub fn get(&self, index: usize) -> Option<&Asset> {
+		self.0.get(index)
+	}
+}
+
+/// A wildcard representing a set of assets.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[scale_info(replace_segment("staging_xcm", "xcm"))]
+pub enum WildAsset {
+	/// All assets in Holding.
+	All,
+	/// All assets in Holding of a given fungibility and ID.
+	AllOf { id: AssetId, fun: WildFungibility },
+	/// All assets in Holding, up to `u32` individual assets (different instances of non-fungibles
+	/// are separate assets).
+	AllCounted(#[codec(compact)] u32),
+	/// All assets in Holding of a given fungibility and ID up to `count` individual assets
+	/// (different instances of non-fungibles are separate assets).
+	AllOfCounted {
+		id: AssetId,
+		fun: WildFungibility,
+		#[codec(compact)]
+		count: u32,
+	},
+}
+
+impl TryFrom<OldWildAsset> for WildAsset {
+	type Error = ();
+	fn try_from(old: OldWildAsset) -> Result<WildAsset, ()> {
+		use OldWildAsset::*;
+		Ok(match old {
+			AllOf { id, fun } => Self::AllOf { id: id.try_into()?, fun },
+			All => Self::All,
+			AllOfCounted { id, fun, count } =>
+				Self::AllOfCounted { id: id.try_into()?, fun, count },
+			AllCounted(count) => Self::AllCounted(count),
+		})
+	}
+}
+
+impl WildAsset {
+	/// Returns true if `self` is a super-set of the given `inner` asset.
+	pub fn contains(&self, inner: &Asset) -> bool {
+		use WildAsset::*;
+		match self {
+			AllOfCounted { count: 0, .. } | AllCounted(0) => false,
+			AllOf { fun, id } | AllOfCounted { id, fun, .. } =>
+				inner.fun.is_kind(*fun) && &inner.id == id,
+			All | AllCounted(_) => true,
+		}
+	}
+
+	/// Mutate the asset to represent the same value from the perspective of a new `target`
+	/// location. The local chain's location is provided in `context`.
+	pub fn reanchor(&mut self, target: &Location, context: &InteriorLocation) -> Result<(), ()> {
+		use WildAsset::*;
+		match self {
+			AllOf { ref mut id, .. } | AllOfCounted { ref mut id, .. } =>
+				id.reanchor(target, context),
+			All | AllCounted(_) => Ok(()),
+		}
+	}
+
+	/// Maximum count of assets allowed to match, if any.
+	pub fn count(&self) -> Option<u32> {
+		use WildAsset::*;
+		match self {
+			AllOfCounted { count, .. } | AllCounted(count) => Some(*count),
+			All | AllOf { .. } => None,
+		}
+	}
+
+	/// Explicit limit on number of assets allowed to match, if any.
+	pub fn limit(&self) -> Option<u32> {
+		self.count()
+	}
+
+	/// Consume self and return the equivalent version but counted and with the `count` set to the
+	/// given parameter.
+	pub fn counted(self, count: u32) -> Self {
+		use WildAsset::*;
+		match self {
+			AllOfCounted { fun, id, .. } | AllOf { fun, id } => AllOfCounted { fun, id, count },
+			All | AllCounted(_) => AllCounted(count),
+		}
+	}
+}
+
+impl<A: Into<AssetId>, B: Into<WildFungibility>> From<(A, B)> for WildAsset {
+	fn from((id, fun): (A, B)) -> WildAsset {
+		WildAsset::AllOf { fun: fun.into(), id: id.into() }
+	}
+}
+
+/// `Asset` collection, defined either by a number of `Assets` or a single wildcard.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[scale_info(replace_segment("staging_xcm", "xcm"))]
+pub enum AssetFilter {
+	/// Specify the filter as being everything contained by the given `Assets` inner.
+	Definite(Assets),
+	/// Specify the filter as the given `WildAsset` wildcard.
+	Wild(WildAsset),
+}
+
+impl<T: Into<WildAsset>> From<T> for AssetFilter {
+	fn from(x: T) -> Self {
+		Self::Wild(x.into())
+	}
+}
+
+impl From<Asset> for AssetFilter {
+	fn from(x: Asset) -> Self {
+		Self::Definite(vec![x].into())
+	}
+}
+
+impl From<Vec<Asset>> for AssetFilter {
+	fn from(x: Vec<Asset>) -> Self {
+		Self::Definite(x.into())
+	}
+}
+
+impl From<Assets> for AssetFilter {
+	fn from(x: Assets) -> Self {
+		Self::Definite(x)
+	}
+}
+
+impl AssetFilter {
+	/// Returns true if `inner` would be matched by `self`.
+	///
+	/// Note that for `Counted` variants of wildcards, then it will disregard the count except for
+	/// always returning `false` when equal to 0.
+	pub fn matches(&self, inner: &Asset) -> bool {
+		match self {
+			AssetFilter::Definite(ref assets) => assets.contains(inner),
+			AssetFilter::Wild(ref wild) => wild.contains(inner),
+		}
+	}
+
+	/// Mutate the location of the asset identifier if concrete, giving it the same location
+	/// relative to a `target` context. The local context is provided as `context`.
+	pub fn reanchor(&mut self, target: &Location, context: &InteriorLocation) -> Result<(), ()> {
+		match self {
+			AssetFilter::Definite(ref mut assets) => assets.reanchor(target, context),
+			AssetFilter::Wild(ref mut wild) => wild.reanchor(target, context),
+		}
+	}
+
+	/// Maximum count of assets it is possible to match, if known.
+	pub fn count(&self) -> Option<u32> {
+		use AssetFilter::*;
+		match self {
+			Definite(x) => Some(x.len() as u32),
+			Wild(x) => x.count(),
+		}
+	}
+
+	/// Explicit limit placed on the number of items, if any.
+	pub fn limit(&self) -> Option<u32> {
+		use AssetFilter::*;
+		match self {
+			Definite(_) => None,
+			Wild(x) => x.limit(),
+		}
+	}
+}
+
+impl TryFrom<OldAssetFilter> for AssetFilter {
+	type Error = ();
+	fn try_from(old: OldAssetFilter) -> Result<AssetFilter, ()> {
+		Ok(match old {
+			OldAssetFilter::Definite(x) => Self::Definite(x.try_into()?),
+			OldAssetFilter::Wild(x) => Self::Wild(x.try_into()?),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::prelude::*;
+
+	#[test]
+	fn conversion_works() {
+		let _: Assets = (Here, 1u128).into();
+	}
+
+	#[test]
+	fn from_sorted_and_deduplicated_works() {
+		use super::*;
+		use alloc::vec;
+
+		let empty = vec![];
+		let r = Assets::from_sorted_and_deduplicated(empty);
+		assert_eq!(r, Ok(Assets(vec![])));
+
+		let dup_fun = vec![(Here, 100).into(), (Here, 10).into()];
+		let r = Assets::from_sorted_and_deduplicated(dup_fun);
+		assert!(r.is_err());
+
+		let dup_nft = vec![(Here, *b"notgood!").into(), (Here, *b"notgood!").into()];
+		let r = Assets::from_sorted_and_deduplicated(dup_nft);
+		assert!(r.is_err());
+
+		let good_fun = vec![(Here, 10).into(), (Parent, 10).into()];
+		let r = Assets::from_sorted_and_deduplicated(good_fun.clone());
+		assert_eq!(r, Ok(Assets(good_fun)));
+
+		let bad_fun = vec![(Parent, 10).into(), (Here, 10).into()];
+		let r = Assets::from_sorted_and_deduplicated(bad_fun);
+		assert!(r.is_err());
+
+		let good_nft = vec![(Here, ()).into(), (Here, *b"good").into()];
+		let r = Assets::from_sorted_and_deduplicated(good_nft.clone());
+		assert_eq!(r, Ok(Assets(good_nft)));
+
+		let bad_nft = vec![(Here, *b"bad!").into(), (Here, ()).into()];
+		let r = Assets::from_sorted_and_deduplicated(bad_nft);
+		assert!(r.is_err());
+
+		let mixed_good = vec![(Here, 10).into(), (Here, *b"good").into()];
+		let r = Assets::from_sorted_and_deduplicated(mixed_good.clone());
+		assert_eq!(r, Ok(Assets(mixed_good)));
+
+		let mixed_bad = vec![(Here, *b"bad!").into(), (Here, 10).into()];
+		let r = Assets::from_sorted_and_deduplicated(mixed_bad);
+		assert!(r.is_err());
+	}
+
+	#[test]
+	fn reanchor_preserves_sorting() {
+		use super::*;
+		use alloc::vec;
+
+		let reanchor_context: Junctions = Parachain(2000).into();
+		let dest = Location::new(1, Here);
+
+		let asset_1: Asset = (Location::new(0, [PalletInstance(50), GeneralIndex(1)]), 10).into();
+		let mut asset_1_reanchored = asset_1.clone();
+		assert!(asset_1_reanchored.reanchor(&dest, &reanchor_context).is_ok());
+		assert_eq!(
+			asset_1_reanchored,
+			(Location::new(0, [Parachain(2000), PalletInstance(50), GeneralIndex(1)]), 10).into()
+		);
+
+		let asset_2: Asset = (Location::new(1, Here), 10).into();
+		let mut asset_2_reanchored = asset_2.clone();
+		assert!(asset_2_reanchored.reanchor(&dest, &reanchor_context).is_ok());
+		assert_eq!(asset_2_reanchored, (Location::new(0, Here), 10).into());
+
+		let asset_3: Asset = (Location::new(1, [Parachain(1000)]), 10).into();
+		let mut asset_3_reanchored = asset_3.clone();
+		assert!(asset_3_reanchored.reanchor(&dest, &reanchor_context).is_ok());
+		assert_eq!(asset_3_reanchored, (Location::new(0, [Parachain(1000)]), 10).into());
+
+		let mut assets: Assets = vec![asset_1.clone(), asset_2.clone(), asset_3.clone()].into();
+		assert_eq!(assets.clone(), vec![asset_1.clone(), asset_2.clone(), asset_3.clone()].into());
+
+		assert!(assets.reanchor(&dest, &reanchor_context).is_ok());
+		assert_eq!(assets, vec![asset_2_reanchored, asset_3_reanchored, asset_1_reanchored].into());
+	}
+
+	#[test]
+	fn decoding_respects_limit() {
+		use super::*;
+
+		// Having lots of one asset will work since they are deduplicated
+		let lots_of_one_asset: Assets =
+			vec![(GeneralIndex(1), 1u128).into(); MAX_ITEMS_IN_ASSETS + 1].into();
+		let encoded = lots_of_one_asset.encode();
+		assert!(Assets::decode(&mut &encoded[..]).is_ok());
+
+		// Fewer assets than the limit works
+		let mut few_assets: Assets = Vec::new().into();
+		for i in 0..MAX_ITEMS_IN_ASSETS {
+			few_assets.push((GeneralIndex(i as u128), 1u128).into());
+		}
+		let encoded = few_assets.encode();
+		assert!(Assets::decode(&mut &encoded[..]).is_ok());
+
+		// Having lots of different assets will not work
+		let mut too_many_different_assets: Assets = Vec::new().into();
+		for i in 0..MAX_ITEMS_IN_ASSETS + 1 {
+			too_many_different_assets.push((GeneralIndex(i as u128), 1u128).into());
+		}
+		let encoded = too_many_different_assets.encode();
+		assert!(Assets::decode(&mut &encoded[..]).is_err());
+	}
+
+	#[test]
+	fn abstract_asset_ids_do_not_convert() {
+		use crate::v3::{AssetId as OldAssetId, MultiAsset as OldAsset};
+
+		let old: OldAsset = (OldAssetId::Abstract([1u8; 32]), 10u128).into();
+		assert!(Asset::try_from(old).is_err());
+
+		let old: OldAsset = (crate::v3::Parent, 10u128).into();
+		let new: Asset = old.clone().try_into().unwrap();
+		assert_eq!(new, (Parent, 10u128).into());
+		assert_eq!(OldAsset::try_from(new), Ok(old));
+	}
+}
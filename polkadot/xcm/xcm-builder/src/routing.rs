@@ -18,7 +18,7 @@
 
 use frame_system::unique;
 use parity_scale_codec::Encode;
-use sp_std::{marker::PhantomData, result::Result};
+use sp_std::{marker::PhantomData, result::Result, vec::Vec};
 use xcm::prelude::*;
 
 /// Wrapper router which, if the message does not already end with a `SetTopic` instruction,
@@ -60,6 +60,16 @@ impl<Inner: SendXcm> SendXcm for WithUniqueTopic<Inner> {
 	}
 }
 
+impl<Inner: InspectMessageQueues> InspectMessageQueues for WithUniqueTopic<Inner> {
+	fn clear_messages() {
+		Inner::clear_messages()
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		Inner::get_messages()
+	}
+}
+
 pub trait SourceTopic {
 	fn source_topic(entropy: impl Encode) -> XcmHash;
 }
@@ -104,3 +114,41 @@ impl<Inner: SendXcm, TopicSource: SourceTopic> SendXcm for WithTopicSource<Inner
 		Ok(unique_id)
 	}
 }
+
+impl<Inner: InspectMessageQueues, TopicSource> InspectMessageQueues
+	for WithTopicSource<Inner, TopicSource>
+{
+	fn clear_messages() {
+		Inner::clear_messages()
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		Inner::get_messages()
+	}
+}
+
+/// Inspection of the messages which a router has queued for delivery.
+///
+/// This is used to find out which messages are sent by a dry-run of a call or an XCM program,
+/// which is why routers only need to report messages that are still sitting in a queue of the
+/// local chain.
+pub trait InspectMessageQueues {
+	/// Remove all messages from the queues.
+	fn clear_messages();
+
+	/// Get all queued messages, grouped by their destination.
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl InspectMessageQueues for Tuple {
+	fn clear_messages() {
+		for_tuples!( #( Tuple::clear_messages(); )* );
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let mut messages = Vec::new();
+		for_tuples!( #( messages.append(&mut Tuple::get_messages()); )* );
+		messages
+	}
+}
@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
+use xcm_executor::{observe_execution, ExecutionObserver};
 
 #[test]
 fn basic_setup_works() {
@@ -104,3 +105,140 @@ fn code_registers_should_work() {
 	assert_eq!(asset_list(Here), vec![(Here, 1u128).into()]);
 	assert_eq!(sent_xcm(), vec![]);
 }
+
+#[derive(Debug, PartialEq)]
+enum ObservedStep {
+	Barrier(MultiLocation, XcmHash, Weight, Result<(), ProcessMessageError>),
+	Instruction(u32, Instruction<()>, Weight, XcmResult, MultiAssets),
+	FeesPaid(MultiAssets, FeeReason),
+	MessageSent(MultiLocation, XcmHash),
+	AssetsTrapped(MultiLocation, MultiAssets),
+	Outcome(Outcome),
+}
+
+#[derive(Default)]
+struct RecordingObserver(Vec<ObservedStep>);
+impl ExecutionObserver for RecordingObserver {
+	fn on_barrier(
+		&mut self,
+		origin: &MultiLocation,
+		message_id: &XcmHash,
+		weight: Weight,
+		result: &Result<(), ProcessMessageError>,
+	) {
+		self.0.push(ObservedStep::Barrier(*origin, *message_id, weight, *result));
+	}
+
+	fn on_instruction(
+		&mut self,
+		index: u32,
+		instruction: &[u8],
+		weight: Weight,
+		result: &XcmResult,
+		holding: &Assets,
+	) {
+		let instruction = Instruction::<()>::decode(&mut &instruction[..]).unwrap();
+		let holding = holding.clone().into();
+		self.0
+			.push(ObservedStep::Instruction(index, instruction, weight, *result, holding));
+	}
+
+	fn on_fees_paid(&mut self, fees: &MultiAssets, reason: FeeReason) {
+		self.0.push(ObservedStep::FeesPaid(fees.clone(), reason));
+	}
+
+	fn on_message_sent(&mut self, destination: &MultiLocation, message_id: &XcmHash) {
+		self.0.push(ObservedStep::MessageSent(*destination, *message_id));
+	}
+
+	fn on_assets_trapped(&mut self, origin: &MultiLocation, assets: &Assets) {
+		self.0.push(ObservedStep::AssetsTrapped(*origin, assets.clone().into()));
+	}
+
+	fn on_outcome(&mut self, outcome: &Outcome) {
+		self.0.push(ObservedStep::Outcome(outcome.clone()));
+	}
+}
+
+#[test]
+fn execution_observer_sees_every_step() {
+	AllowUnpaidFrom::set(vec![Here.into()]);
+	add_asset(Here, (Here, 10u128));
+	set_send_price((Here, 1u128));
+	let response_info =
+		QueryResponseInfo { destination: Parent.into(), query_id: 1, max_weight: Weight::zero() };
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 10u128).into()),
+		ReportHolding { response_info: response_info.clone(), assets: AllCounted(1).into() },
+		SetTopic([1; 32]),
+	]);
+	let hash = fake_message_hash(&message);
+	let limit = Weight::from_parts(30, 30);
+
+	let mut observer = RecordingObserver::default();
+	let r = observe_execution(&mut observer, || {
+		XcmExecutor::<TestConfig>::execute_xcm(Here, message, hash, limit)
+	});
+	// The trapped assets cost an additional 5 units of weight.
+	let weight_used = Weight::from_parts(35, 35);
+	assert_eq!(r, Outcome::Complete(weight_used));
+
+	let sent_id = sent_xcm()[0].2;
+	let holding: MultiAssets = (Here, 9u128).into();
+	assert_eq!(
+		observer.0,
+		vec![
+			ObservedStep::Barrier(Here.into(), [1; 32], limit, Ok(())),
+			ObservedStep::Instruction(
+				0,
+				WithdrawAsset((Here, 10u128).into()),
+				Weight::from_parts(10, 10),
+				Ok(()),
+				(Here, 10u128).into(),
+			),
+			ObservedStep::FeesPaid((Here, 1u128).into(), FeeReason::Report),
+			ObservedStep::MessageSent(Parent.into(), sent_id),
+			ObservedStep::Instruction(
+				1,
+				ReportHolding { response_info, assets: AllCounted(1).into() },
+				Weight::from_parts(10, 10),
+				Ok(()),
+				holding.clone(),
+			),
+			ObservedStep::Instruction(
+				2,
+				SetTopic([1; 32]),
+				Weight::from_parts(10, 10),
+				Ok(()),
+				holding.clone(),
+			),
+			ObservedStep::AssetsTrapped(Here.into(), holding),
+			ObservedStep::Outcome(Outcome::Complete(weight_used)),
+		]
+	);
+}
+
+#[test]
+fn execution_observer_sees_barrier_rejection() {
+	let message = Xcm::<TestCall>(vec![ClearOrigin, SetTopic([2; 32])]);
+	let hash = fake_message_hash(&message);
+	let limit = Weight::from_parts(20, 20);
+
+	let mut observer = RecordingObserver::default();
+	let r = observe_execution(&mut observer, || {
+		XcmExecutor::<TestConfig>::execute_xcm(Parachain(1), message, hash, limit)
+	});
+	assert_eq!(r, Outcome::Error(XcmError::Barrier));
+	assert_eq!(
+		observer.0,
+		vec![
+			ObservedStep::Barrier(
+				Parachain(1).into(),
+				[2; 32],
+				limit,
+				Err(ProcessMessageError::Unsupported)
+			),
+			ObservedStep::Outcome(Outcome::Error(XcmError::Barrier)),
+		]
+	);
+}
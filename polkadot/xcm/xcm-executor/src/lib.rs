@@ -39,6 +39,9 @@ mod assets;
 pub use assets::Assets;
 mod config;
 pub use config::Config;
+mod observer;
+use observer::with_observer;
+pub use observer::{note_message_sent, observe_execution, ExecutionObserver};
 
 /// A struct to specify how fees are being paid.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -205,12 +208,21 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 			weight_credit,
 		);
 		let mut properties = Properties { weight_credit, message_id: None };
-		if let Err(e) = Config::Barrier::should_execute(
+		let barrier_result = Config::Barrier::should_execute(
 			&origin,
 			message.inner_mut(),
 			xcm_weight,
 			&mut properties,
-		) {
+		);
+		with_observer(|observer| {
+			// A message refused by the barrier is still identified by its topic if it has one.
+			let message_id = properties.message_id.unwrap_or_else(|| match message.0.last() {
+				Some(SetTopic(topic)) => *topic,
+				_ => *id,
+			});
+			observer.on_barrier(&origin, &message_id, xcm_weight, &barrier_result);
+		});
+		if let Err(e) = barrier_result {
 			log::trace!(
 				target: "xcm::execute_xcm_in_credit",
 				"Barrier blocked execution! Error: {:?}. (origin: {:?}, message: {:?}, properties: {:?})",
@@ -219,7 +231,9 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 				message,
 				properties,
 			);
-			return Outcome::Error(XcmError::Barrier)
+			let outcome = Outcome::Error(XcmError::Barrier);
+			with_observer(|observer| observer.on_outcome(&outcome));
+			return outcome
 		}
 
 		*id = properties.message_id.unwrap_or(*id);
@@ -239,7 +253,9 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 			}
 		}
 
-		vm.post_process(xcm_weight)
+		let outcome = vm.post_process(xcm_weight);
+		with_observer(|observer| observer.on_outcome(&outcome));
+		outcome
 	}
 
 	fn charge_fees(origin: impl Into<MultiLocation>, fees: MultiAssets) -> XcmResult {
@@ -320,6 +336,9 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		for (i, instr) in xcm.0.into_iter().enumerate() {
 			match &mut result {
 				r @ Ok(()) => {
+					let observed = with_observer(|_| {
+						(instr.encode(), Config::Weigher::instr_weight(&instr).unwrap_or_default())
+					});
 					// Initialize the recursion count only the first time we hit this code in our
 					// potential recursive execution.
 					let inst_res = recursion_count::using_once(&mut 1, || {
@@ -343,6 +362,17 @@ impl<Config: config::Config> XcmExecutor<Config> {
 
 						self.process_instruction(instr)
 					});
+					if let Some((encoded, weight)) = observed {
+						with_observer(|observer| {
+							observer.on_instruction(
+								i as u32,
+								&encoded,
+								weight,
+								&inst_res,
+								&self.holding,
+							)
+						});
+					}
 					if let Err(e) = inst_res {
 						log::trace!(target: "xcm::execute", "!!! ERROR: {:?}", e);
 						*r = Err(ExecutorError {
@@ -379,6 +409,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				self.holding, self.context, self.original_origin,
 			);
 			let effective_origin = self.context.origin.as_ref().unwrap_or(&self.original_origin);
+			with_observer(|observer| observer.on_assets_trapped(effective_origin, &self.holding));
 			let trap_weight =
 				Config::AssetTrap::drop_assets(effective_origin, self.holding, &self.context);
 			weight_used.saturating_accrue(trap_weight);
@@ -412,7 +443,9 @@ impl<Config: config::Config> XcmExecutor<Config> {
 	) -> Result<XcmHash, XcmError> {
 		let (ticket, fee) = validate_send::<Config::XcmSender>(dest, msg)?;
 		self.take_fee(fee, reason)?;
-		Config::XcmSender::deliver(ticket).map_err(Into::into)
+		let message_id = Config::XcmSender::deliver(ticket)?;
+		with_observer(|observer| observer.on_message_sent(&dest, &message_id));
+		Ok(message_id)
 	}
 
 	/// Remove the registered error handler and return it. Do not refund its weight.
@@ -871,7 +904,8 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				let (ticket, price) = validate_send::<Config::XcmSender>(unlocker, msg)?;
 				self.take_fee(price, FeeReason::LockAsset)?;
 				lock_ticket.enact()?;
-				Config::XcmSender::deliver(ticket)?;
+				let message_id = Config::XcmSender::deliver(ticket)?;
+				with_observer(|observer| observer.on_message_sent(&unlocker, &message_id));
 				Ok(())
 			},
 			UnlockAsset { asset, target } => {
@@ -895,7 +929,8 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				let (ticket, price) = validate_send::<Config::XcmSender>(locker, msg)?;
 				self.take_fee(price, FeeReason::RequestUnlock)?;
 				reduce_ticket.enact()?;
-				Config::XcmSender::deliver(ticket)?;
+				let message_id = Config::XcmSender::deliver(ticket)?;
+				with_observer(|observer| observer.on_message_sent(&locker, &message_id));
 				Ok(())
 			},
 			ExchangeAsset { give, want, maximal } => {
@@ -968,6 +1003,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		} else {
 			self.holding.try_take(fee.into()).map_err(|_| XcmError::NotHoldingFees)?.into()
 		};
+		with_observer(|observer| observer.on_fees_paid(&paid, reason));
 		Config::FeeManager::handle_fee(paid, Some(&self.context), reason);
 		Ok(())
 	}
@@ -0,0 +1,87 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Hooks allowing tooling to observe the progress of the executor.
+
+use crate::{traits::FeeReason, Assets};
+use frame_support::traits::ProcessMessageError;
+use sp_weights::Weight;
+use xcm::latest::prelude::*;
+
+/// Observer of the steps taken by the `XcmExecutor` while executing a message.
+///
+/// The executor does not hold an observer itself; one is only notified while it is set through
+/// [`observe_execution`], which makes the hooks free when nobody is listening. This is meant for
+/// test tooling such as `xcm-emulator` and must not be relied upon by runtime logic.
+///
+/// Executions may nest (e.g. a `Transact` dispatching another XCM execution), in which case an
+/// `on_barrier` notification for the inner message arrives before the `on_outcome` of the outer.
+pub trait ExecutionObserver {
+	/// A message with the given `message_id` from `origin`, weighing `weight`, was passed to the
+	/// barrier, which returned `result`. Marks the start of an execution.
+	fn on_barrier(
+		&mut self,
+		_origin: &MultiLocation,
+		_message_id: &XcmHash,
+		_weight: Weight,
+		_result: &Result<(), ProcessMessageError>,
+	) {
+	}
+
+	/// The SCALE-encoded `instruction` at position `index` of the currently executing program was
+	/// processed with `result`. `weight` is the weight the instruction was charged for up front,
+	/// and `holding` the contents of the holding register afterwards.
+	fn on_instruction(
+		&mut self,
+		_index: u32,
+		_instruction: &[u8],
+		_weight: Weight,
+		_result: &XcmResult,
+		_holding: &Assets,
+	) {
+	}
+
+	/// `fees` were paid for the given `reason`.
+	fn on_fees_paid(&mut self, _fees: &MultiAssets, _reason: FeeReason) {}
+
+	/// A message with the given `message_id` was sent to `destination`, by the executor or
+	/// through [`note_message_sent`].
+	fn on_message_sent(&mut self, _destination: &MultiLocation, _message_id: &XcmHash) {}
+
+	/// `assets` left in the holding register were trapped on behalf of `origin`.
+	fn on_assets_trapped(&mut self, _origin: &MultiLocation, _assets: &Assets) {}
+
+	/// The execution started by the last unmatched `on_barrier` finished with `outcome`.
+	fn on_outcome(&mut self, _outcome: &Outcome) {}
+}
+
+environmental::environmental!(observer: trait ExecutionObserver);
+
+/// Execute `f`, notifying `observer` of every step taken by any `XcmExecutor` running within it.
+pub fn observe_execution<R>(observer: &mut dyn ExecutionObserver, f: impl FnOnce() -> R) -> R {
+	observer::using(observer, f)
+}
+
+/// Notify the currently set observer, if any, of a message with the given `message_id` sent to
+/// `destination` outside of the executor, e.g. by an extrinsic of `pallet-xcm`.
+pub fn note_message_sent(destination: &MultiLocation, message_id: &XcmHash) {
+	with_observer(|observer| observer.on_message_sent(destination, message_id));
+}
+
+/// Run `f` against the currently set observer, if any.
+pub(crate) fn with_observer<R>(f: impl FnOnce(&mut dyn ExecutionObserver) -> R) -> Option<R> {
+	observer::with(f)
+}
@@ -61,6 +61,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			future: PoolLimit { count: 100_000, total_bytes: 100 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(30 * 60),
+			pool_type: Default::default(),
 		},
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
@@ -91,6 +91,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the storage changes between two blocks.
+	ExportStateDiff(frame_state_diff_cli::ExportStateDiffCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
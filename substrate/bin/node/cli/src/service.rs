@@ -51,7 +51,7 @@ type FullGrandpaBlockImport =
 	grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
 
 /// The transaction pool type definition.
-pub type TransactionPool = sc_transaction_pool::FullPool<Block, FullClient>;
+pub type TransactionPool = sc_transaction_pool::TransactionPoolWrapper<Block, FullClient>;
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
@@ -144,7 +144,7 @@ pub fn new_partial(
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		TransactionPool,
 		(
 			impl Fn(
 				node_rpc::DenyUnsafe,
@@ -191,7 +191,7 @@ pub fn new_partial(
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::TransactionPoolWrapper::new_full(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -328,6 +328,8 @@ pub struct NewFullBase {
 	pub task_manager: TaskManager,
 	/// The client instance of the node.
 	pub client: Arc<FullClient>,
+	/// The database backend of the node.
+	pub backend: Arc<FullBackend>,
 	/// The networking service of the node.
 	pub network: Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
 	/// The syncing service of the node.
@@ -653,6 +655,7 @@ pub fn new_full_base(
 	Ok(NewFullBase {
 		task_manager,
 		client,
+		backend,
 		network,
 		sync: sync_service,
 		transaction_pool,
@@ -664,12 +667,14 @@ pub fn new_full_base(
 pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let mixnet_config = cli.mixnet_params.config(config.role.is_authority());
 	let database_source = config.database.clone();
-	let task_manager = new_full_base(config, mixnet_config, cli.no_hardware_benchmarks, |_, _| ())
-		.map(|NewFullBase { task_manager, .. }| task_manager)?;
+	let (task_manager, backend) =
+		new_full_base(config, mixnet_config, cli.no_hardware_benchmarks, |_, _| ())
+			.map(|NewFullBase { task_manager, backend, .. }| (task_manager, backend))?;
 
-	sc_storage_monitor::StorageMonitorService::try_spawn(
+	sc_storage_monitor::StorageMonitorService::try_spawn_with_pruning_budget(
 		cli.storage_monitor,
 		database_source,
+		backend.pruning_budget(),
 		&task_manager.spawn_essential_handle(),
 	)
 	.map_err(|e| ServiceError::Application(e.into()))?;
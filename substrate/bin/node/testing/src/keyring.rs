@@ -22,7 +22,7 @@ use codec::Encode;
 use kitchensink_runtime::{CheckedExtrinsic, SessionKeys, SignedExtra, UncheckedExtrinsic};
 use node_primitives::{AccountId, Balance, Nonce};
 use sp_keyring::{AccountKeyring, Ed25519Keyring, Sr25519Keyring};
-use sp_runtime::generic::Era;
+use sp_runtime::generic::{Era, ExtrinsicFormat, Preamble};
 
 /// Alice's account id.
 pub fn alice() -> AccountId {
@@ -91,8 +91,8 @@ pub fn sign(
 	tx_version: u32,
 	genesis_hash: [u8; 32],
 ) -> UncheckedExtrinsic {
-	match xt.signed {
-		Some((signed, extra)) => {
+	match xt.format {
+		ExtrinsicFormat::Signed(signed, extra) => {
 			let payload =
 				(xt.function, extra.clone(), spec_version, tx_version, genesis_hash, genesis_hash);
 			let key = AccountKeyring::from_account_id(&signed).unwrap();
@@ -106,10 +106,12 @@ pub fn sign(
 				})
 				.into();
 			UncheckedExtrinsic {
-				signature: Some((sp_runtime::MultiAddress::Id(signed), signature, extra)),
+				preamble: Preamble::Signed(sp_runtime::MultiAddress::Id(signed), signature, extra),
 				function: payload.0,
 			}
 		},
-		None => UncheckedExtrinsic { signature: None, function: xt.function },
+		ExtrinsicFormat::Bare => UncheckedExtrinsic::new_unsigned(xt.function),
+		ExtrinsicFormat::General(extension) =>
+			UncheckedExtrinsic::new_general(xt.function, extension),
 	}
 }
@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Proof utilities
-use crate::{CompactProof, StorageProof};
+use crate::{CompactProof, KeysAndPrefixes, StorageProof};
 use sp_runtime::traits::Block as BlockT;
 use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
 use sp_storage::ChildInfo;
@@ -40,6 +40,15 @@ pub trait ProofProvider<Block: BlockT> {
 		keys: &mut dyn Iterator<Item = &[u8]>,
 	) -> sp_blockchain::Result<StorageProof>;
 
+	/// Reads the given keys, and all the entries under the given prefixes, of the top trie and
+	/// of the given child tries at a given block, returning a compact read proof.
+	fn read_compact_proof(
+		&self,
+		hash: Block::Hash,
+		top: &KeysAndPrefixes,
+		children: &[(ChildInfo, KeysAndPrefixes)],
+	) -> sp_blockchain::Result<CompactProof>;
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
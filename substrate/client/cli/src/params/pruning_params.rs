@@ -54,6 +54,14 @@ pub struct PruningParams {
 		default_value = "archive-canonical"
 	)]
 	pub blocks_pruning: DatabasePruningMode,
+
+	/// Keep only as many recent finalized blocks as fit into the given budget, in MiB.
+	///
+	/// Block bodies, justifications and the state changes of the blocks are accounted. When the
+	/// state is pruned, the state pruning window is adjusted to the kept blocks. The budget is
+	/// lowered automatically by the storage monitor when the node is running out of disk space.
+	#[arg(long, value_name = "MiB", conflicts_with = "blocks_pruning")]
+	pub blocks_pruning_budget: Option<u64>,
 }
 
 impl PruningParams {
@@ -64,7 +72,10 @@ impl PruningParams {
 
 	/// Get the block pruning value from the parameters
 	pub fn blocks_pruning(&self) -> error::Result<BlocksPruning> {
-		Ok(self.blocks_pruning.into())
+		Ok(match self.blocks_pruning_budget {
+			Some(budget) => BlocksPruning::SizeBudget(budget.saturating_mul(1024 * 1024)),
+			None => self.blocks_pruning.into(),
+		})
 	}
 }
 
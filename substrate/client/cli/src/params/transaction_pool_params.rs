@@ -16,8 +16,27 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use clap::Args;
-use sc_service::config::TransactionPoolOptions;
+use clap::{Args, ValueEnum};
+use sc_service::config::{TransactionPoolOptions, TransactionPoolType};
+
+/// Type of transaction pool to be used
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum TransactionPoolTypeParam {
+	/// Uses a legacy, single-state transaction pool.
+	SingleState,
+	/// Uses a fork-aware transaction pool.
+	ForkAware,
+}
+
+impl Into<TransactionPoolType> for TransactionPoolTypeParam {
+	fn into(self) -> TransactionPoolType {
+		match self {
+			TransactionPoolTypeParam::SingleState => TransactionPoolType::SingleState,
+			TransactionPoolTypeParam::ForkAware => TransactionPoolType::ForkAware,
+		}
+	}
+}
 
 /// Parameters used to create the pool configuration.
 #[derive(Debug, Clone, Args)]
@@ -35,6 +54,13 @@ pub struct TransactionPoolParams {
 	/// If it is considered invalid. Defaults to 1800s.
 	#[arg(long, value_name = "SECONDS")]
 	pub tx_ban_seconds: Option<u64>,
+
+	/// The type of transaction pool to be instantiated.
+	///
+	/// Only taken into account by the nodes building their pool through
+	/// `sc_transaction_pool::TransactionPoolWrapper`.
+	#[arg(long, value_enum, default_value_t = TransactionPoolTypeParam::SingleState)]
+	pub pool_type: TransactionPoolTypeParam,
 }
 
 impl TransactionPoolParams {
@@ -59,6 +85,8 @@ impl TransactionPoolParams {
 			std::time::Duration::from_secs(30 * 60)
 		};
 
+		opts.pool_type = self.pool_type.into();
+
 		opts
 	}
 }
@@ -48,7 +48,10 @@ use std::{
 	collections::{HashMap, HashSet},
 	io,
 	path::{Path, PathBuf},
-	sync::Arc,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
 };
 
 use crate::{
@@ -318,6 +321,61 @@ pub enum BlocksPruning {
 	KeepFinalized,
 	/// Keep N recent finalized blocks.
 	Some(u32),
+	/// Keep as many recent finalized blocks as fit into the given number of bytes.
+	///
+	/// The block bodies, the justifications and the state changes kept by the state pruning
+	/// window are accounted, indexed transactions are not. The state pruning window is adjusted
+	/// to the kept blocks. The budget can be changed at runtime through
+	/// [`Backend::pruning_budget`].
+	SizeBudget(u64),
+}
+
+/// Shared handle to the byte budget of [`BlocksPruning::SizeBudget`].
+///
+/// Changes are picked up on the next block finalization. A raised budget keeps the following
+/// finalized blocks longer, the already pruned blocks are not restored.
+#[derive(Debug, Clone)]
+pub struct PruningBudget {
+	bytes: Arc<AtomicU64>,
+	kept_bytes: Arc<AtomicU64>,
+}
+
+impl PruningBudget {
+	/// Create a new budget of `bytes`.
+	pub fn new(bytes: u64) -> Self {
+		Self { bytes: Arc::new(AtomicU64::new(bytes)), kept_bytes: Default::default() }
+	}
+
+	/// Returns the current budget in bytes.
+	pub fn bytes(&self) -> u64 {
+		self.bytes.load(Ordering::Relaxed)
+	}
+
+	/// Set the budget to `bytes`.
+	pub fn set_bytes(&self, bytes: u64) {
+		self.bytes.store(bytes, Ordering::Relaxed)
+	}
+
+	/// Returns the size in bytes of the blocks that can still be pruned, as of the last block
+	/// finalization.
+	pub fn kept_bytes(&self) -> u64 {
+		self.kept_bytes.load(Ordering::Relaxed)
+	}
+
+	fn set_kept_bytes(&self, bytes: u64) {
+		self.kept_bytes.store(bytes, Ordering::Relaxed)
+	}
+}
+
+/// Size accounting of the finalized blocks kept by [`BlocksPruning::SizeBudget`].
+#[derive(Debug, Default, Clone, Copy, Encode, Decode)]
+struct BlocksSize<N> {
+	/// Oldest block that was not pruned yet.
+	oldest: N,
+	/// First block whose size was not added to `total` yet.
+	next_measured: N,
+	/// Total size of the blocks in `oldest..next_measured`.
+	total: u64,
 }
 
 /// Where to find the database..
@@ -1113,6 +1171,9 @@ pub struct Backend<Block: BlockT> {
 	import_lock: Arc<RwLock<()>>,
 	is_archive: bool,
 	blocks_pruning: BlocksPruning,
+	pruning_budget: Option<PruningBudget>,
+	blocks_size: Mutex<BlocksSize<NumberFor<Block>>>,
+	state_max_blocks: Option<u32>,
 	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
 	state_usage: Arc<StateUsageStats>,
 	genesis_state: RwLock<Option<Arc<DbGenesisStorage<Block>>>>,
@@ -1149,6 +1210,11 @@ impl<Block: BlockT> Backend<Block> {
 		}
 	}
 
+	/// Returns the byte budget of the blocks pruning, if [`BlocksPruning::SizeBudget`] is used.
+	pub fn pruning_budget(&self) -> Option<PruningBudget> {
+		self.pruning_budget.clone()
+	}
+
 	/// Create new memory-backed client backend for tests.
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test(blocks_pruning: u32, canonicalization_delay: u64) -> Self {
@@ -1165,7 +1231,8 @@ impl<Block: BlockT> Backend<Block> {
 		let db = sp_database::as_database(db);
 		let state_pruning = match blocks_pruning {
 			BlocksPruning::KeepAll => PruningMode::ArchiveAll,
-			BlocksPruning::KeepFinalized => PruningMode::ArchiveCanonical,
+			BlocksPruning::KeepFinalized | BlocksPruning::SizeBudget(_) =>
+				PruningMode::ArchiveCanonical,
 			BlocksPruning::Some(n) => PruningMode::blocks_pruning(n),
 		};
 		let db_setting = DatabaseSettings {
@@ -1219,7 +1286,26 @@ impl<Block: BlockT> Backend<Block> {
 
 		let state_pruning_used = state_db.pruning_mode();
 		let is_archive_pruning = state_pruning_used.is_archive();
+		let state_max_blocks = match state_pruning_used {
+			PruningMode::Constrained(constraints) =>
+				Some(constraints.max_blocks.unwrap_or(u32::MAX)),
+			_ => None,
+		};
 		let blockchain = BlockchainDb::new(db.clone())?;
+		let blocks_size: BlocksSize<_> = match db.get(columns::META, meta_keys::BLOCKS_SIZE) {
+			Some(encoded) => BlocksSize::decode(&mut &encoded[..]).map_err(|err| {
+				sp_blockchain::Error::Backend(format!("Error decoding blocks size: {}", err))
+			})?,
+			None => Default::default(),
+		};
+		let pruning_budget = match config.blocks_pruning {
+			BlocksPruning::SizeBudget(bytes) => {
+				let budget = PruningBudget::new(bytes);
+				budget.set_kept_bytes(blocks_size.total);
+				Some(budget)
+			},
+			_ => None,
+		};
 
 		let storage_db =
 			StorageDb { db: db.clone(), state_db, prefix_keys: !db.supports_ref_counting() };
@@ -1236,6 +1322,9 @@ impl<Block: BlockT> Backend<Block> {
 			io_stats: FrozenForDuration::new(std::time::Duration::from_secs(1)),
 			state_usage: Arc::new(StateUsageStats::new()),
 			blocks_pruning: config.blocks_pruning,
+			pruning_budget,
+			blocks_size: Mutex::new(blocks_size),
+			state_max_blocks,
 			genesis_state: RwLock::new(None),
 			shared_trie_cache: config.trie_cache_maximum_size.map(|maximum_size| {
 				SharedTrieCache::new(sp_trie::cache::CacheSize::new(maximum_size))
@@ -1492,14 +1581,19 @@ impl<Block: BlockT> Backend<Block> {
 			utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
 
 			transaction.set_from_vec(columns::HEADER, &lookup_key, pending_block.header.encode());
+			// Size of the block accounted by the size budget blocks pruning.
+			let mut block_size = 0;
 			if let Some(body) = pending_block.body {
 				// If we have any index operations we save block in the new format with indexed
 				// extrinsic headers Otherwise we save the body as a single blob.
 				if operation.index_ops.is_empty() {
-					transaction.set_from_vec(columns::BODY, &lookup_key, body.encode());
+					let body = body.encode();
+					block_size += body.len() as u64;
+					transaction.set_from_vec(columns::BODY, &lookup_key, body);
 				} else {
 					let body =
 						apply_index_ops::<Block>(&mut transaction, body, operation.index_ops);
+					block_size += body.len() as u64;
 					transaction.set_from_vec(columns::BODY_INDEX, &lookup_key, body);
 				}
 			}
@@ -1564,6 +1658,7 @@ impl<Block: BlockT> Backend<Block> {
 				}
 				self.state_usage.tally_writes_nodes(ops, bytes);
 				self.state_usage.tally_removed_nodes(removal, bytes_removal);
+				block_size += bytes;
 
 				let mut ops: u64 = 0;
 				let mut bytes: u64 = 0;
@@ -1612,6 +1707,13 @@ impl<Block: BlockT> Backend<Block> {
 				(number.is_zero() && last_finalized_num.is_zero()) ||
 					pending_block.leaf_state.is_final()
 			};
+			if matches!(self.blocks_pruning, BlocksPruning::SizeBudget(_)) {
+				transaction.set_from_vec(
+					columns::META,
+					&utils::block_size_key(hash),
+					block_size.encode(),
+				);
+			}
 
 			let header = &pending_block.header;
 			let is_best = pending_block.leaf_state.is_best();
@@ -1827,26 +1929,22 @@ impl<Block: BlockT> Backend<Block> {
 				let keep = std::cmp::max(blocks_pruning, 1);
 				if finalized_number >= keep.into() {
 					let number = finalized_number.saturating_sub(keep.into());
-
-					// Before we prune a block, check if it is pinned
-					if let Some(hash) = self.blockchain.hash(number)? {
-						self.blockchain.insert_persisted_body_if_pinned(hash)?;
-
-						// If the block was finalized in this transaction, it will not be in the db
-						// yet.
-						if let Some(justification) =
-							current_transaction_justifications.remove(&hash)
-						{
-							self.blockchain.insert_justifications_if_pinned(hash, justification);
-						} else {
-							self.blockchain.insert_persisted_justifications_if_pinned(hash)?;
-						}
-					};
-
-					self.prune_block(transaction, BlockId::<Block>::number(number))?;
+					self.prune_finalized_block(
+						transaction,
+						number,
+						current_transaction_justifications,
+					)?;
 				}
 				self.prune_displaced_branches(transaction, finalized_hash, displaced)?;
 			},
+			BlocksPruning::SizeBudget(_) => {
+				self.prune_blocks_by_size(
+					transaction,
+					finalized_number,
+					current_transaction_justifications,
+				)?;
+				self.prune_displaced_branches(transaction, finalized_hash, displaced)?;
+			},
 			BlocksPruning::KeepFinalized => {
 				self.prune_displaced_branches(transaction, finalized_hash, displaced)?;
 			},
@@ -1854,6 +1952,121 @@ impl<Block: BlockT> Backend<Block> {
 		Ok(())
 	}
 
+	/// Prune the oldest finalized blocks until the kept blocks fit into the pruning budget.
+	///
+	/// The size of each finalized block is accounted once, when one of its descendants is
+	/// finalized. The state pruning window is set to the kept blocks, so that the state of the
+	/// pruned blocks is pruned in the same transaction.
+	fn prune_blocks_by_size(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		finalized_number: NumberFor<Block>,
+		current_transaction_justifications: &mut HashMap<Block::Hash, Justification>,
+	) -> ClientResult<()> {
+		let budget = self.pruning_budget.as_ref().map_or(u64::MAX, |budget| budget.bytes());
+		let mut blocks_size = self.blocks_size.lock();
+
+		// Nothing was accounted yet while the finalized blocks jumped ahead, e.g. after a warp
+		// sync or when the size budget is enabled on an existing database. The blocks before are
+		// left alone.
+		if blocks_size.next_measured.is_zero() && finalized_number > One::one() {
+			blocks_size.oldest = finalized_number;
+			blocks_size.next_measured = finalized_number;
+		}
+
+		// Every finalized block is accounted once, except for the last finalized block which is
+		// always kept. This way every pruned block is subtracted from the total.
+		while blocks_size.next_measured < finalized_number {
+			let size = self.finalized_block_size(
+				blocks_size.next_measured,
+				current_transaction_justifications,
+			)?;
+			blocks_size.total = blocks_size.total.saturating_add(size);
+			blocks_size.next_measured += One::one();
+		}
+
+		while blocks_size.total > budget && blocks_size.oldest < blocks_size.next_measured {
+			let number = blocks_size.oldest;
+			let size = self.finalized_block_size(number, current_transaction_justifications)?;
+			self.prune_finalized_block(transaction, number, current_transaction_justifications)?;
+			blocks_size.total = blocks_size.total.saturating_sub(size);
+			blocks_size.oldest += One::one();
+		}
+
+		transaction.set_from_vec(columns::META, meta_keys::BLOCKS_SIZE, blocks_size.encode());
+		if let Some(budget) = &self.pruning_budget {
+			budget.set_kept_bytes(blocks_size.total);
+		}
+
+		if let Some(max_blocks) = self.state_max_blocks {
+			let kept: u32 =
+				finalized_number.saturating_sub(blocks_size.oldest).saturated_into::<u32>();
+			let commit = self
+				.storage
+				.state_db
+				.set_max_blocks(std::cmp::max(std::cmp::min(max_blocks, kept), 1))
+				.map_err(
+					sp_blockchain::Error::from_state_db::<
+						sc_state_db::Error<sp_database::error::DatabaseError>,
+					>,
+				)?;
+			apply_state_commit(transaction, commit);
+		}
+
+		Ok(())
+	}
+
+	/// Returns the size in bytes of the given finalized block, as recorded when it was imported,
+	/// along with its justifications.
+	fn finalized_block_size(
+		&self,
+		number: NumberFor<Block>,
+		current_transaction_justifications: &HashMap<Block::Hash, Justification>,
+	) -> ClientResult<u64> {
+		let Some(hash) = self.blockchain.hash(number)? else { return Ok(0) };
+		let imported = self
+			.storage
+			.db
+			.get(columns::META, &utils::block_size_key(hash))
+			.and_then(|encoded| u64::decode(&mut &encoded[..]).ok())
+			.unwrap_or_default();
+		// If the block was finalized in this transaction, its justification is not in the db yet.
+		let justifications = match current_transaction_justifications.get(&hash) {
+			Some(justification) => justification.encoded_size(),
+			None => read_db(
+				&*self.storage.db,
+				columns::KEY_LOOKUP,
+				columns::JUSTIFICATIONS,
+				BlockId::<Block>::hash(hash),
+			)?
+			.map_or(0, |justifications| justifications.len()),
+		};
+		Ok(imported + justifications as u64)
+	}
+
+	/// Prune a block from the canonical chain, keeping its body and justifications around if it
+	/// is pinned.
+	fn prune_finalized_block(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		number: NumberFor<Block>,
+		current_transaction_justifications: &mut HashMap<Block::Hash, Justification>,
+	) -> ClientResult<()> {
+		// Before we prune a block, check if it is pinned
+		if let Some(hash) = self.blockchain.hash(number)? {
+			self.blockchain.insert_persisted_body_if_pinned(hash)?;
+
+			// If the block was finalized in this transaction, it will not be in the db yet.
+			if let Some(justification) = current_transaction_justifications.remove(&hash) {
+				self.blockchain.insert_justifications_if_pinned(hash, justification);
+			} else {
+				self.blockchain.insert_persisted_justifications_if_pinned(hash)?;
+			}
+		};
+
+		self.prune_block(transaction, BlockId::<Block>::number(number))
+	}
+
 	fn prune_displaced_branches(
 		&self,
 		transaction: &mut Transaction<DbHash>,
@@ -1883,6 +2096,15 @@ impl<Block: BlockT> Backend<Block> {
 		id: BlockId<Block>,
 	) -> ClientResult<()> {
 		debug!(target: "db", "Removing block #{}", id);
+		if matches!(self.blocks_pruning, BlocksPruning::SizeBudget(_)) {
+			let hash = match id {
+				BlockId::Hash(hash) => Some(hash),
+				BlockId::Number(number) => self.blockchain.hash(number)?,
+			};
+			if let Some(hash) = hash {
+				transaction.remove(columns::META, &utils::block_size_key(hash));
+			}
+		}
 		utils::remove_from_db(
 			transaction,
 			&*self.storage.db,
@@ -3381,6 +3603,62 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn prune_blocks_by_size_on_finalize() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(BlocksPruning::SizeBudget(0), 0);
+		let budget = backend.pruning_budget().unwrap();
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		for i in 0..6 {
+			let hash = insert_block(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				None,
+			)
+			.unwrap();
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		// All blocks have the same size, keep two of them besides the last finalized one.
+		let block_size = backend.finalized_block_size(1, &HashMap::new()).unwrap();
+		assert!(block_size > 0);
+		budget.set_bytes(2 * block_size);
+		{
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, blocks[4]).unwrap();
+			for i in 1..5 {
+				op.mark_finalized(blocks[i], None).unwrap();
+			}
+			backend.commit_operation(op).unwrap();
+		}
+		let bc = backend.blockchain();
+		assert_eq!(None, bc.body(blocks[0]).unwrap());
+		assert_eq!(None, bc.body(blocks[1]).unwrap());
+		for i in 2..6 {
+			assert_eq!(Some(vec![(i as u64).into()]), bc.body(blocks[i]).unwrap());
+		}
+		assert_eq!(budget.kept_bytes(), 2 * block_size);
+
+		// Lowering the budget prunes everything but the last finalized block.
+		budget.set_bytes(0);
+		{
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, blocks[5]).unwrap();
+			op.mark_finalized(blocks[5], None).unwrap();
+			backend.commit_operation(op).unwrap();
+		}
+		for i in 0..5 {
+			assert_eq!(None, bc.body(blocks[i]).unwrap());
+		}
+		assert_eq!(Some(vec![5.into()]), bc.body(blocks[5]).unwrap());
+		assert_eq!(budget.kept_bytes(), 0);
+	}
+
 	#[test]
 	fn prune_blocks_on_finalize() {
 		let pruning_modes =
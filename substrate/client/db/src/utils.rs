@@ -56,6 +56,10 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Accounted size of the kept block bodies, used by the size budget blocks pruning.
+	pub const BLOCKS_SIZE: &[u8; 5] = b"bsize";
+	/// Size of a block recorded on import prefix, used by the size budget blocks pruning.
+	pub const BLOCK_SIZE_PREFIX: &[u8; 6] = b"blsize";
 }
 
 /// Database metadata.
@@ -112,6 +116,14 @@ where
 	Ok(lookup_key)
 }
 
+/// Convert hash into the key of the block size recorded on import, see
+/// [`meta_keys::BLOCK_SIZE_PREFIX`].
+pub fn block_size_key<H: AsRef<[u8]>>(hash: H) -> Vec<u8> {
+	let mut key = meta_keys::BLOCK_SIZE_PREFIX.to_vec();
+	key.extend_from_slice(hash.as_ref());
+	key
+}
+
 /// Delete number to hash mapping in DB transaction.
 pub fn remove_number_to_key_mapping<N: TryInto<u32>>(
 	transaction: &mut Transaction<DbHash>,
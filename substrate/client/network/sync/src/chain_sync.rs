@@ -32,7 +32,7 @@ use crate::{
 	blocks::BlockCollection,
 	extra_requests::ExtraRequests,
 	schema::v1::StateResponse,
-	state::{ImportResult, StateSync},
+	state::{clear_persisted_progress, ImportResult, StateSync},
 	types::{
 		BadPeer, Metrics, OpaqueStateRequest, OpaqueStateResponse, PeerInfo, SyncMode, SyncState,
 		SyncStatus,
@@ -47,7 +47,7 @@ use codec::Encode;
 use libp2p::PeerId;
 use log::{debug, error, info, trace, warn};
 
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{AuxStore, BlockBackend, ProofProvider};
 use sc_consensus::{BlockImportError, BlockImportStatus, IncomingBlock};
 use sc_network_common::sync::message::{
 	BlockAnnounce, BlockAttributes, BlockData, BlockRequest, BlockResponse, Direction, FromBlock,
@@ -314,8 +314,8 @@ pub(crate) enum PeerSyncState<B: BlockT> {
 	DownloadingStale(B::Hash),
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
-	/// Downloading state.
-	DownloadingState,
+	/// Downloading the given key range of the state.
+	DownloadingState(usize),
 	/// Downloading warp proof.
 	DownloadingWarpProof,
 	/// Downloading warp sync target block.
@@ -337,6 +337,7 @@ where
 		+ BlockBackend<B>
 		+ HeaderMetadata<B, Error = sp_blockchain::Error>
 		+ ProofProvider<B>
+		+ AuxStore
 		+ Send
 		+ Sync
 		+ 'static,
@@ -921,7 +922,7 @@ where
 					},
 					PeerSyncState::Available |
 					PeerSyncState::DownloadingJustification(..) |
-					PeerSyncState::DownloadingState |
+					PeerSyncState::DownloadingState(_) |
 					PeerSyncState::DownloadingWarpProof => Vec::new(),
 				}
 			} else {
@@ -1689,50 +1690,65 @@ where
 			.collect()
 	}
 
-	/// Get a state request scheduled by sync to be sent out (if any).
-	fn state_request(&mut self) -> Option<(PeerId, OpaqueStateRequest)> {
+	/// Get state requests scheduled by sync to be sent out.
+	///
+	/// Each key range of the state is requested from a different peer, with at most one pending
+	/// request per key range.
+	fn state_requests(&mut self) -> Vec<(PeerId, OpaqueStateRequest)> {
 		if self.allowed_requests.is_empty() {
-			return None
-		}
-		if (self.state_sync.is_some() || self.warp_sync.is_some()) &&
-			self.peers.iter().any(|(_, peer)| peer.state == PeerSyncState::DownloadingState)
-		{
-			// Only one pending state request is allowed.
-			return None
+			return Vec::new()
 		}
+		let pending_ranges = self
+			.peers
+			.values()
+			.filter_map(|peer| match peer.state {
+				PeerSyncState::DownloadingState(range) => Some(range),
+				_ => None,
+			})
+			.collect::<HashSet<_>>();
+
+		let mut requests = Vec::new();
 		if let Some(sync) = &self.state_sync {
 			if sync.is_complete() {
-				return None
+				return Vec::new()
 			}
 
+			let mut ranges =
+				sync.incomplete_ranges().filter(|range| !pending_ranges.contains(range));
 			for (id, peer) in self.peers.iter_mut() {
 				if peer.state.is_available() && peer.common_number >= sync.target_block_num() {
-					peer.state = PeerSyncState::DownloadingState;
-					let request = sync.next_request();
+					let Some(range) = ranges.next() else { break };
+					peer.state = PeerSyncState::DownloadingState(range);
+					let request = sync.next_request(range);
 					trace!(target: LOG_TARGET, "New StateRequest for {}: {:?}", id, request);
-					self.allowed_requests.clear();
-					return Some((*id, OpaqueStateRequest(Box::new(request))))
+					requests.push((*id, OpaqueStateRequest(Box::new(request))));
 				}
 			}
 		}
 		if let Some(sync) = &self.warp_sync {
 			if sync.is_complete() {
-				return None
+				return Vec::new()
 			}
-			if let (Some(request), Some(target)) =
-				(sync.next_state_request(), sync.target_block_number())
-			{
+			if let Some(target) = sync.target_block_number() {
+				let mut ranges = sync
+					.incomplete_state_ranges()
+					.into_iter()
+					.filter(|range| !pending_ranges.contains(range));
 				for (id, peer) in self.peers.iter_mut() {
 					if peer.state.is_available() && peer.best_number >= target {
+						let Some(range) = ranges.next() else { break };
+						let Some(request) = sync.next_state_request(range) else { break };
 						trace!(target: LOG_TARGET, "New StateRequest for {id}: {request:?}");
-						peer.state = PeerSyncState::DownloadingState;
-						self.allowed_requests.clear();
-						return Some((*id, OpaqueStateRequest(Box::new(request))))
+						peer.state = PeerSyncState::DownloadingState(range);
+						requests.push((*id, OpaqueStateRequest(Box::new(request))));
 					}
 				}
 			}
 		}
-		None
+		if !requests.is_empty() {
+			self.allowed_requests.clear();
+		}
+		requests
 	}
 
 	/// Get a warp proof request scheduled by sync to be sent out (if any).
@@ -1782,12 +1798,23 @@ where
 			BadPeer(*peer_id, rep::BAD_RESPONSE)
 		})?;
 
-		if let Some(peer) = self.peers.get_mut(peer_id) {
-			if let PeerSyncState::DownloadingState = peer.state {
-				peer.state = PeerSyncState::Available;
-				self.allowed_requests.set_all();
-			}
-		}
+		let range = match self.peers.get_mut(peer_id) {
+			Some(peer) => match peer.state {
+				PeerSyncState::DownloadingState(range) => {
+					peer.state = PeerSyncState::Available;
+					self.allowed_requests.set_all();
+					range
+				},
+				_ => {
+					debug!(target: LOG_TARGET, "Ignored obsolete state response from {peer_id}");
+					return Ok(())
+				},
+			},
+			None => {
+				debug!(target: LOG_TARGET, "Ignored state response from unknown peer {peer_id}");
+				return Ok(())
+			},
+		};
 		let import_result = if let Some(sync) = &mut self.state_sync {
 			debug!(
 				target: LOG_TARGET,
@@ -1796,7 +1823,7 @@ where
 				response.entries.len(),
 				response.proof.len(),
 			);
-			sync.import(*response)
+			sync.import(range, *response)
 		} else if let Some(sync) = &mut self.warp_sync {
 			debug!(
 				target: LOG_TARGET,
@@ -1805,7 +1832,7 @@ where
 				response.entries.len(),
 				response.proof.len(),
 			);
-			sync.import_state(*response)
+			sync.import_state(range, *response)
 		} else {
 			debug!(target: LOG_TARGET, "Ignored obsolete state response from {peer_id}");
 			return Err(BadPeer(*peer_id, rep::NOT_REQUESTED))
@@ -1941,6 +1968,7 @@ where
 							"State sync is complete ({} MiB), restarting block sync.",
 							self.state_sync.as_ref().map_or(0, |s| s.progress().size / (1024 * 1024)),
 						);
+						clear_persisted_progress::<B, _>(&*self.client);
 						self.state_sync = None;
 						self.mode = SyncMode::Full;
 						self.restart();
@@ -1955,6 +1983,7 @@ where
 							"Warp sync is complete ({} MiB), restarting block sync.",
 							self.warp_sync.as_ref().map_or(0, |s| s.progress().total_bytes / (1024 * 1024)),
 						);
+						clear_persisted_progress::<B, _>(&*self.client);
 						self.warp_sync = None;
 						self.mode = SyncMode::Full;
 						self.restart();
@@ -2038,7 +2067,7 @@ where
 		self.actions.extend(justification_requests);
 
 		let state_request = self
-			.state_request()
+			.state_requests()
 			.into_iter()
 			.map(|(peer_id, request)| ChainSyncAction::SendStateRequest { peer_id, request });
 		self.actions.extend(state_request);
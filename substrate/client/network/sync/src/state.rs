@@ -17,14 +17,23 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! State sync support.
+//!
+//! The verified state chunks are persisted to the auxiliary storage of the client along with the
+//! key cursors, so that an interrupted state sync resumes from the last downloaded keys after a
+//! restart, once the same target block is proven again. The persisted progress is only cleared
+//! when the imported state is committed, see [`clear_persisted_progress`].
+//!
+//! When the state proofs are verified, the key space of the top trie is split into
+//! [`PARALLEL_RANGES`] ranges, each downloaded and verified independently so that the ranges can
+//! be requested from different peers at the same time.
 
 use crate::{
 	schema::v1::{StateEntry, StateRequest, StateResponse},
 	types::StateDownloadProgress,
 };
 use codec::{Decode, Encode};
-use log::debug;
-use sc_client_api::{CompactProof, ProofProvider};
+use log::{debug, info, warn};
+use sc_client_api::{AuxStore, CompactProof, KeyValueStates, ProofProvider};
 use sc_consensus::ImportedState;
 use smallvec::SmallVec;
 use sp_core::storage::well_known_keys;
@@ -32,7 +41,149 @@ use sp_runtime::{
 	traits::{Block as BlockT, Header, NumberFor},
 	Justifications,
 };
-use std::{collections::HashMap, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+/// Number of key ranges downloaded in parallel when the state proofs are verified.
+///
+/// The ranges are bounded by single byte keys, so that all the child trie roots, prefixed with
+/// `:child_storage:`, belong to the same range.
+pub const PARALLEL_RANGES: usize = 16;
+
+/// Auxiliary storage key of the persisted state sync progress.
+const PROGRESS_KEY: &[u8] = b"state_sync_progress";
+
+/// Auxiliary storage key prefix of the persisted state chunks.
+const CHUNK_KEY_PREFIX: &[u8] = b"state_sync_chunk";
+
+/// Verified key values of a state response, along with the root of the trie they belong to, empty
+/// for the top trie.
+type Chunk = Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>;
+
+/// Persisted progress of a state sync verifying the state proofs.
+#[derive(Encode, Decode)]
+struct PersistedProgress<B: BlockT> {
+	/// Header of the target block, proven before the state sync is started.
+	target_header: B::Header,
+	target_justifications: Option<Justifications>,
+	/// Key cursor of each key range, and whether it is complete.
+	ranges: Vec<(Vec<Vec<u8>>, bool)>,
+	imported_bytes: u64,
+	/// Number of persisted chunks, see [`chunk_key`].
+	chunks: u32,
+}
+
+fn chunk_key(index: u32) -> Vec<u8> {
+	(CHUNK_KEY_PREFIX, index).encode()
+}
+
+fn load_progress<B: BlockT, C: AuxStore>(client: &C) -> Option<PersistedProgress<B>> {
+	match client.get_aux(PROGRESS_KEY) {
+		Ok(Some(encoded)) => match PersistedProgress::<B>::decode(&mut encoded.as_slice()) {
+			Ok(progress) => Some(progress),
+			Err(e) => {
+				warn!(target: "sync", "Failed to decode persisted state sync progress: {}", e);
+				clear_persisted_progress::<B, _>(client);
+				None
+			},
+		},
+		Ok(None) => None,
+		Err(e) => {
+			warn!(target: "sync", "Failed to read persisted state sync progress: {}", e);
+			None
+		},
+	}
+}
+
+/// Remove the persisted progress of a state sync.
+///
+/// Called once the state of the target block is imported, the progress being kept until then so
+/// that a failed import resumes from the persisted chunks.
+pub fn clear_persisted_progress<B: BlockT, C: AuxStore>(client: &C) {
+	let chunks = match client.get_aux(PROGRESS_KEY) {
+		Ok(Some(encoded)) => PersistedProgress::<B>::decode(&mut encoded.as_slice())
+			.map(|progress| progress.chunks)
+			.unwrap_or_default(),
+		_ => 0,
+	};
+	let chunk_keys = (0..chunks).map(chunk_key).collect::<Vec<_>>();
+	let mut delete = chunk_keys.iter().map(Vec::as_slice).collect::<Vec<_>>();
+	delete.push(PROGRESS_KEY);
+	if let Err(e) = client.insert_aux(&[], &delete) {
+		warn!(target: "sync", "Failed to clear persisted state sync progress: {}", e);
+	}
+}
+
+/// Range of the top trie keys downloaded by a sequence of state requests.
+struct KeyRange {
+	/// First byte of the keys of the range.
+	start: u8,
+	/// Inclusive end of the range, `None` for the last range.
+	end: Option<Vec<u8>>,
+	/// Last key imported from the range, starting at the exclusive start of the range.
+	last_key: SmallVec<[Vec<u8>; 2]>,
+	/// Whether all the keys of the range are imported.
+	complete: bool,
+}
+
+impl KeyRange {
+	/// Split the key space in `count` ranges, `count` being at most 256.
+	fn split(count: usize) -> Vec<Self> {
+		let bound = |index: usize| (index * 256 / count) as u8;
+		(0..count)
+			.map(|index| KeyRange {
+				start: bound(index),
+				end: (index + 1 < count).then(|| vec![bound(index + 1)]),
+				last_key: if index == 0 {
+					SmallVec::new()
+				} else {
+					SmallVec::from_elem(vec![bound(index)], 1)
+				},
+				complete: false,
+			})
+			.collect()
+	}
+
+	/// Number of the first key bytes of the range already imported.
+	fn progress(&self) -> u32 {
+		let end = self.end.as_ref().map_or(256, |end| end[0] as u32);
+		if self.complete {
+			return end - self.start as u32
+		}
+		let cursor = self.last_key.get(0).and_then(|last| last.get(0)).map_or(0, |c| *c as u32);
+		cursor.clamp(self.start as u32, end) - self.start as u32
+	}
+}
+
+/// Remove the top trie values after `end`, and the child tries they are the root of.
+///
+/// Returns true if any value is removed, meaning that all the values up to `end` are imported.
+fn truncate_range(values: &mut KeyValueStates, end: &[u8]) -> bool {
+	let Some(top) = values.0.first_mut() else { return false };
+	let split = top.key_values.partition_point(|(key, _)| key.as_slice() <= end);
+	if split == top.key_values.len() {
+		return false
+	}
+
+	let child_roots = |key_values: &[(Vec<u8>, Vec<u8>)]| {
+		key_values
+			.iter()
+			.filter(|(key, _)| well_known_keys::is_child_storage_key(key.as_slice()))
+			.map(|(_, root)| root.clone())
+			.collect::<HashSet<_>>()
+	};
+	let removed_roots = child_roots(&top.key_values[split..]);
+	top.key_values.truncate(split);
+	let kept_roots = child_roots(&top.key_values);
+	values.0.retain(|level| {
+		level.state_root.is_empty() ||
+			!removed_roots.contains(&level.state_root) ||
+			kept_roots.contains(&level.state_root)
+	});
+	true
+}
 
 /// State sync state machine. Accumulates partial state data until it
 /// is ready to be imported.
@@ -42,12 +193,15 @@ pub struct StateSync<B: BlockT, Client> {
 	target_root: B::Hash,
 	target_body: Option<Vec<B::Extrinsic>>,
 	target_justifications: Option<Justifications>,
-	last_key: SmallVec<[Vec<u8>; 2]>,
+	ranges: Vec<KeyRange>,
 	state: HashMap<Vec<u8>, (Vec<(Vec<u8>, Vec<u8>)>, Vec<Vec<u8>>)>,
 	complete: bool,
 	client: Arc<Client>,
 	imported_bytes: u64,
 	skip_proof: bool,
+	/// Number of chunks persisted so far, `None` if the state proofs are skipped or persisting
+	/// failed.
+	persisted_chunks: Option<u32>,
 }
 
 /// Import state chunk result.
@@ -63,9 +217,14 @@ pub enum ImportResult<B: BlockT> {
 impl<B, Client> StateSync<B, Client>
 where
 	B: BlockT,
-	Client: ProofProvider<B> + Send + Sync + 'static,
+	Client: ProofProvider<B> + AuxStore + Send + Sync + 'static,
 {
 	///  Create a new instance.
+	///
+	/// The state is downloaded as [`PARALLEL_RANGES`] key ranges, unless `skip_proof` is set.
+	///
+	/// Resumes from the persisted progress, if any, of a previous state sync of the same target,
+	/// unless `skip_proof` is set. The target header must be proven by the caller.
 	pub fn new(
 		client: Arc<Client>,
 		target_header: B::Header,
@@ -73,23 +232,168 @@ where
 		target_justifications: Option<Justifications>,
 		skip_proof: bool,
 	) -> Self {
-		Self {
+		let mut sync = Self {
 			client,
 			target_block: target_header.hash(),
 			target_root: *target_header.state_root(),
 			target_header,
 			target_body,
 			target_justifications,
-			last_key: SmallVec::default(),
+			ranges: KeyRange::split(if skip_proof { 1 } else { PARALLEL_RANGES }),
 			state: HashMap::default(),
 			complete: false,
 			imported_bytes: 0,
 			skip_proof,
+			persisted_chunks: (!skip_proof).then_some(0),
+		};
+		sync.resume();
+		sync
+	}
+
+	/// Load the persisted chunks and key cursors of the same target, discarding them if any of
+	/// them is missing.
+	///
+	/// The chunks are verified when downloaded, the state root of the whole state being checked
+	/// again on import.
+	fn resume(&mut self) {
+		if self.persisted_chunks.is_none() {
+			return
+		}
+		let Some(progress) = load_progress::<B, _>(&*self.client) else { return };
+		if progress.target_header.hash() != self.target_block ||
+			progress.ranges.len() != self.ranges.len()
+		{
+			debug!(
+				target: "sync",
+				"Discarding persisted state sync progress of block {:?}",
+				progress.target_header.hash(),
+			);
+			clear_persisted_progress::<B, _>(&*self.client);
+			return
 		}
+
+		for index in 0..progress.chunks {
+			let chunk = self
+				.client
+				.get_aux(&chunk_key(index))
+				.ok()
+				.flatten()
+				.and_then(|chunk| Chunk::decode(&mut chunk.as_slice()).ok());
+			let Some(chunk) = chunk else {
+				warn!(
+					target: "sync",
+					"Persisted state sync progress of block {:?} is invalid, restarting from scratch",
+					self.target_block,
+				);
+				self.state.clear();
+				clear_persisted_progress::<B, _>(&*self.client);
+				return
+			};
+			self.insert_chunk(chunk);
+		}
+
+		for (range, (last_key, complete)) in self.ranges.iter_mut().zip(progress.ranges) {
+			range.last_key = last_key.into();
+			range.complete = complete;
+		}
+		self.imported_bytes = progress.imported_bytes;
+		if self.target_justifications.is_none() {
+			self.target_justifications = progress.target_justifications;
+		}
+		if progress.chunks > 0 {
+			info!(
+				target: "sync",
+				"Resuming state sync of block {:?} from {} persisted chunks ({} bytes)",
+				self.target_block,
+				progress.chunks,
+				self.imported_bytes,
+			);
+		}
+		self.persisted_chunks = Some(progress.chunks);
 	}
 
-	///  Validate and import a state response.
-	pub fn import(&mut self, response: StateResponse) -> ImportResult<B> {
+	/// Persist a chunk of state imported with [`ImportResult::Continue`], along with the key
+	/// cursors following it.
+	fn persist_chunk(&mut self, chunk: Chunk) {
+		let Some(index) = self.persisted_chunks else { return };
+		let progress = PersistedProgress::<B> {
+			target_header: self.target_header.clone(),
+			target_justifications: self.target_justifications.clone(),
+			ranges: self
+				.ranges
+				.iter()
+				.map(|range| (range.last_key.to_vec(), range.complete))
+				.collect(),
+			imported_bytes: self.imported_bytes,
+			chunks: index + 1,
+		}
+		.encode();
+		let chunk = chunk.encode();
+		match self
+			.client
+			.insert_aux(&[(&chunk_key(index)[..], &chunk[..]), (PROGRESS_KEY, &progress[..])], &[])
+		{
+			Ok(()) => self.persisted_chunks = Some(index + 1),
+			Err(e) => {
+				warn!(target: "sync", "Failed to persist state sync progress: {}", e);
+				// The following chunks can't be resumed without this one.
+				clear_persisted_progress::<B, _>(&*self.client);
+				self.persisted_chunks = None;
+			},
+		}
+	}
+
+	/// Insert the verified key values of a chunk into the downloaded state.
+	fn insert_chunk(&mut self, chunk: Chunk) {
+		for (state_root, key_values) in chunk {
+			let key_values = if state_root.is_empty() {
+				// Read child trie roots.
+				key_values
+					.into_iter()
+					.filter(|key_value| {
+						if well_known_keys::is_child_storage_key(key_value.0.as_slice()) {
+							self.state
+								.entry(key_value.1.clone())
+								.or_default()
+								.1
+								.push(key_value.0.clone());
+							false
+						} else {
+							true
+						}
+					})
+					.collect()
+			} else {
+				key_values
+			};
+			let entry = self.state.entry(state_root).or_default();
+			if entry.0.len() > 0 && entry.1.len() > 1 {
+				// Already imported child_trie with same root.
+				// All the child tries belong to the same range, so that they are not
+				// downloaded in parallel.
+			} else if entry.0.is_empty() {
+				for (key, _value) in key_values.iter() {
+					self.imported_bytes += key.len() as u64;
+				}
+
+				entry.0 = key_values;
+			} else {
+				for (key, value) in key_values {
+					self.imported_bytes += key.len() as u64;
+					entry.0.push((key, value))
+				}
+			}
+		}
+	}
+
+	///  Validate and import a state response to a request of the given key range.
+	///
+	/// The verified chunks are persisted until the state is complete.
+	pub fn import(&mut self, range: usize, response: StateResponse) -> ImportResult<B> {
+		let Some(key_range) = self.ranges.get_mut(range).filter(|range| !range.complete) else {
+			debug!(target: "sync", "State response for unknown or complete range {}", range);
+			return ImportResult::BadResponse
+		};
 		if response.entries.is_empty() && response.proof.is_empty() {
 			debug!(target: "sync", "Bad state response");
 			return ImportResult::BadResponse
@@ -98,6 +402,7 @@ where
 			debug!(target: "sync", "Missing proof");
 			return ImportResult::BadResponse
 		}
+		let mut verified_chunk = None;
 		let complete = if !self.skip_proof {
 			debug!(target: "sync", "Importing state from {} trie nodes", response.proof.len());
 			let proof_size = response.proof.len() as u64;
@@ -108,10 +413,10 @@ where
 					return ImportResult::BadResponse
 				},
 			};
-			let (values, completed) = match self.client.verify_range_proof(
+			let (mut values, completed) = match self.client.verify_range_proof(
 				self.target_root,
 				proof,
-				self.last_key.as_slice(),
+				key_range.last_key.as_slice(),
 			) {
 				Err(e) => {
 					debug!(
@@ -125,50 +430,23 @@ where
 			};
 			debug!(target: "sync", "Imported with {} keys", values.len());
 
-			let complete = completed == 0;
-			if !complete && !values.update_last_key(completed, &mut self.last_key) {
+			// The values following the range are imported with the next range.
+			let truncated =
+				key_range.end.as_ref().map_or(false, |end| truncate_range(&mut values, end));
+			let complete = truncated || completed == 0;
+			if !complete && !values.update_last_key(completed, &mut key_range.last_key) {
 				debug!(target: "sync", "Error updating key cursor, depth: {}", completed);
 			};
 
-			for values in values.0 {
-				let key_values = if values.state_root.is_empty() {
-					// Read child trie roots.
-					values
-						.key_values
-						.into_iter()
-						.filter(|key_value| {
-							if well_known_keys::is_child_storage_key(key_value.0.as_slice()) {
-								self.state
-									.entry(key_value.1.clone())
-									.or_default()
-									.1
-									.push(key_value.0.clone());
-								false
-							} else {
-								true
-							}
-						})
-						.collect()
-				} else {
-					values.key_values
-				};
-				let entry = self.state.entry(values.state_root).or_default();
-				if entry.0.len() > 0 && entry.1.len() > 1 {
-					// Already imported child_trie with same root.
-					// Warning this will not work with parallel download.
-				} else if entry.0.is_empty() {
-					for (key, _value) in key_values.iter() {
-						self.imported_bytes += key.len() as u64;
-					}
-
-					entry.0 = key_values;
-				} else {
-					for (key, value) in key_values {
-						self.imported_bytes += key.len() as u64;
-						entry.0.push((key, value))
-					}
-				}
+			let chunk = values
+				.0
+				.into_iter()
+				.map(|values| (values.state_root, values.key_values))
+				.collect::<Chunk>();
+			if self.persisted_chunks.is_some() {
+				verified_chunk = Some(chunk.clone());
 			}
+			self.insert_chunk(chunk);
 			self.imported_bytes += proof_size;
 			complete
 		} else {
@@ -177,11 +455,11 @@ where
 			// the parent cursor stays valid.
 			// Empty parent trie content only happens when all the response content
 			// is part of a single child trie.
-			if self.last_key.len() == 2 && response.entries[0].entries.is_empty() {
+			if key_range.last_key.len() == 2 && response.entries[0].entries.is_empty() {
 				// Do not remove the parent trie position.
-				self.last_key.pop();
+				key_range.last_key.pop();
 			} else {
-				self.last_key.clear();
+				key_range.last_key.clear();
 			}
 			for state in response.entries {
 				debug!(
@@ -193,7 +471,7 @@ where
 
 				if !state.complete {
 					if let Some(e) = state.entries.last() {
-						self.last_key.push(e.key.clone());
+						key_range.last_key.push(e.key.clone());
 					}
 					complete = false;
 				}
@@ -219,7 +497,8 @@ where
 			}
 			complete
 		};
-		if complete {
+		self.ranges[range].complete = complete;
+		if self.ranges.iter().all(|range| range.complete) {
 			self.complete = true;
 			ImportResult::Import(
 				self.target_block,
@@ -232,19 +511,34 @@ where
 				self.target_justifications.clone(),
 			)
 		} else {
+			if let Some(chunk) = verified_chunk {
+				self.persist_chunk(chunk);
+			}
 			ImportResult::Continue
 		}
 	}
 
-	/// Produce next state request.
-	pub fn next_request(&self) -> StateRequest {
+	/// Produce next state request of the given key range.
+	pub fn next_request(&self, range: usize) -> StateRequest {
 		StateRequest {
 			block: self.target_block.encode(),
-			start: self.last_key.clone().into_vec(),
+			start: self
+				.ranges
+				.get(range)
+				.map(|range| range.last_key.clone().into_vec())
+				.unwrap_or_default(),
 			no_proof: self.skip_proof,
 		}
 	}
 
+	/// Returns the key ranges which are not downloaded yet, see [`Self::next_request`].
+	pub fn incomplete_ranges(&self) -> impl Iterator<Item = usize> + '_ {
+		self.ranges
+			.iter()
+			.enumerate()
+			.filter_map(|(index, range)| (!range.complete).then_some(index))
+	}
+
 	/// Check if the state is complete.
 	pub fn is_complete(&self) -> bool {
 		self.complete
@@ -262,8 +556,113 @@ where
 
 	/// Returns state sync estimated progress.
 	pub fn progress(&self) -> StateDownloadProgress {
-		let cursor = *self.last_key.get(0).and_then(|last| last.get(0)).unwrap_or(&0u8);
-		let percent_done = cursor as u32 * 100 / 256;
+		let done = self.ranges.iter().map(KeyRange::progress).sum::<u32>();
+		let percent_done = done * 100 / 256;
 		StateDownloadProgress { percentage: percent_done, size: self.imported_bytes }
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_api::StorageProvider;
+	use sp_blockchain::HeaderBackend;
+	use std::collections::BTreeMap;
+	use substrate_test_runtime_client::{
+		runtime::Block, DefaultTestClientBuilderExt, TestClient, TestClientBuilder,
+		TestClientBuilderExt,
+	};
+
+	/// Proof size limit small enough for the genesis state to be downloaded in many chunks.
+	const CHUNK_SIZE: usize = 1;
+
+	fn state_response(client: &TestClient, request: &StateRequest) -> StateResponse {
+		let hash = Decode::decode(&mut request.block.as_slice()).unwrap();
+		let (proof, _) = client
+			.read_proof_collection(hash, request.start.as_slice(), CHUNK_SIZE)
+			.unwrap();
+		StateResponse { proof: proof.encode(), ..Default::default() }
+	}
+
+	fn new_sync(client: &Arc<TestClient>) -> StateSync<Block, TestClient> {
+		let header = client.header(client.info().genesis_hash).unwrap().unwrap();
+		StateSync::new(client.clone(), header, None, None, false)
+	}
+
+	#[test]
+	fn stitches_ranges_downloaded_in_any_order() {
+		let client = Arc::new(TestClientBuilder::new().build());
+
+		// Download the last incomplete range first.
+		let mut sync = new_sync(&client);
+		let imported = loop {
+			let range = sync.incomplete_ranges().last().unwrap();
+			match sync.import(range, state_response(&client, &sync.next_request(range))) {
+				ImportResult::Continue => {},
+				ImportResult::Import(_, _, state, _, _) => break state,
+				ImportResult::BadResponse => panic!("Valid state response is rejected"),
+			}
+		};
+		assert!(sync.is_complete());
+		assert_eq!(sync.progress().percentage, 100);
+
+		let expected = client
+			.storage_pairs(client.info().genesis_hash, None, None)
+			.unwrap()
+			.map(|(key, value)| (key.0, value.0))
+			.filter(|(key, _)| !well_known_keys::is_child_storage_key(key))
+			.collect::<BTreeMap<_, _>>();
+		let top = imported.state.0.into_iter().find(|level| level.state_root.is_empty()).unwrap();
+		assert_eq!(top.key_values.len(), expected.len());
+		assert_eq!(top.key_values.into_iter().collect::<BTreeMap<_, _>>(), expected);
+	}
+
+	#[test]
+	fn resumes_persisted_state_sync() {
+		let client = Arc::new(TestClientBuilder::new().build());
+
+		let mut sync = new_sync(&client);
+		let response = state_response(&client, &sync.next_request(0));
+		assert!(matches!(sync.import(0, response), ImportResult::Continue));
+		let progress = sync.progress();
+		let request = sync.next_request(0);
+		drop(sync);
+
+		// The state sync of the same target resumes from the persisted chunk.
+		let mut sync = new_sync(&client);
+		assert_eq!(sync.progress(), progress);
+		assert_eq!(sync.next_request(0), request);
+
+		loop {
+			let range = sync.incomplete_ranges().next().unwrap();
+			match sync.import(range, state_response(&client, &sync.next_request(range))) {
+				ImportResult::Continue => {},
+				ImportResult::Import(..) => break,
+				ImportResult::BadResponse => panic!("Valid state response is rejected"),
+			}
+		}
+		// The persisted progress is kept until the imported state is committed.
+		assert!(client.get_aux(PROGRESS_KEY).unwrap().is_some());
+
+		clear_persisted_progress::<Block, _>(&*client);
+		assert!(client.get_aux(PROGRESS_KEY).unwrap().is_none());
+		assert!(client.get_aux(&chunk_key(0)).unwrap().is_none());
+	}
+
+	#[test]
+	fn discards_invalid_persisted_state_sync() {
+		let client = Arc::new(TestClientBuilder::new().build());
+
+		let mut sync = new_sync(&client);
+		let response = state_response(&client, &sync.next_request(0));
+		assert!(matches!(sync.import(0, response), ImportResult::Continue));
+		drop(sync);
+
+		client.insert_aux(&[(&chunk_key(0)[..], &b"invalid"[..])], &[]).unwrap();
+
+		let sync = new_sync(&client);
+		assert_eq!(sync.progress().size, 0);
+		assert!(sync.next_request(0).start.is_empty());
+		assert!(client.get_aux(PROGRESS_KEY).unwrap().is_none());
+	}
+}
@@ -27,7 +27,7 @@ use crate::{
 use codec::{Decode, Encode};
 use futures::channel::oneshot;
 use log::error;
-use sc_client_api::ProofProvider;
+use sc_client_api::{AuxStore, ProofProvider};
 use sc_network_common::sync::message::{
 	BlockAttributes, BlockData, BlockRequest, Direction, FromBlock,
 };
@@ -196,11 +196,14 @@ pub struct WarpSync<B: BlockT, Client> {
 impl<B, Client> WarpSync<B, Client>
 where
 	B: BlockT,
-	Client: HeaderBackend<B> + ProofProvider<B> + 'static,
+	Client: HeaderBackend<B> + ProofProvider<B> + AuxStore + 'static,
 {
 	/// Create a new instance. When passing a warp sync provider we will be checking for proof and
 	/// authorities. Alternatively we can pass a target block when we want to skip downloading
 	/// proofs, in this case we will continue polling until the target block is known.
+	///
+	/// The state download of a target block proven again by the warp proofs resumes from the
+	/// persisted progress of an interrupted download, see [`StateSync::new`].
 	pub fn new(client: Arc<Client>, warp_sync_config: WarpSyncConfig<B>) -> Self {
 		let last_hash = client.hash(Zero::zero()).unwrap().expect("Genesis header always exists");
 		match warp_sync_config {
@@ -232,14 +235,14 @@ where
 		self.phase = Phase::TargetBlock(header);
 	}
 
-	///  Validate and import a state response.
-	pub fn import_state(&mut self, response: StateResponse) -> ImportResult<B> {
+	///  Validate and import a state response to a request of the given key range.
+	pub fn import_state(&mut self, range: usize, response: StateResponse) -> ImportResult<B> {
 		match &mut self.phase {
 			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } => {
 				log::debug!(target: "sync", "Unexpected state response");
 				ImportResult::BadResponse
 			},
-			Phase::State(sync) => sync.import(response),
+			Phase::State(sync) => sync.import(range, response),
 		}
 	}
 
@@ -315,12 +318,21 @@ where
 		}
 	}
 
-	/// Produce next state request.
-	pub fn next_state_request(&self) -> Option<StateRequest> {
+	/// Produce next state request of the given key range.
+	pub fn next_state_request(&self, range: usize) -> Option<StateRequest> {
 		match &self.phase {
 			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } =>
 				None,
-			Phase::State(sync) => Some(sync.next_request()),
+			Phase::State(sync) => Some(sync.next_request(range)),
+		}
+	}
+
+	/// Returns the key ranges of the state which are not downloaded yet.
+	pub fn incomplete_state_ranges(&self) -> Vec<usize> {
+		match &self.phase {
+			Phase::WarpProof { .. } | Phase::TargetBlock(_) | Phase::PendingTargetBlock { .. } =>
+				Vec::new(),
+			Phase::State(sync) => sync.incomplete_ranges().collect(),
 		}
 	}
 
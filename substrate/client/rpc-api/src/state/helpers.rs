@@ -19,7 +19,10 @@
 //! Substrate state API helpers.
 
 use serde::{Deserialize, Serialize};
-use sp_core::Bytes;
+use sp_core::{
+	storage::{PrefixedStorageKey, StorageKey},
+	Bytes,
+};
 
 /// ReadProof struct returned by the RPC
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -30,3 +33,27 @@ pub struct ReadProof<Hash> {
 	/// A proof used to prove that storage entries are included in the storage trie
 	pub proof: Vec<Bytes>,
 }
+
+/// Keys and key prefixes of a trie whose entries are proven by `state_getCompactReadProof`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofKeys {
+	/// Keys whose value, or absence, is proven.
+	#[serde(default)]
+	pub keys: Vec<StorageKey>,
+	/// Prefixes whose whole content is proven.
+	#[serde(default)]
+	pub prefixes: Vec<StorageKey>,
+}
+
+/// Keys and key prefixes of a child trie whose entries are proven by
+/// `state_getCompactReadProof`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProofKeys {
+	/// The prefixed storage key of the child trie.
+	pub child_storage_key: PrefixedStorageKey,
+	/// The keys and key prefixes of the child trie.
+	#[serde(flatten)]
+	pub keys: ProofKeys,
+}
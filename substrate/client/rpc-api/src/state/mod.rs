@@ -28,7 +28,7 @@ use sp_version::RuntimeVersion;
 pub mod error;
 pub mod helpers;
 
-pub use self::helpers::ReadProof;
+pub use self::helpers::{ChildProofKeys, ProofKeys, ReadProof};
 
 /// Substrate state API
 #[rpc(client, server)]
@@ -111,6 +111,18 @@ pub trait StateApi<Hash> {
 	#[method(name = "state_getReadProof", blocking)]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> RpcResult<ReadProof<Hash>>;
 
+	/// Returns compact proof of storage entries at a specific block's state.
+	///
+	/// Proves the given keys and all the entries under the given prefixes, both of the top trie
+	/// and of the given child tries. The returned nodes are encoded as a `CompactProof`.
+	#[method(name = "state_getCompactReadProof", blocking)]
+	fn read_compact_proof(
+		&self,
+		top: ProofKeys,
+		children: Vec<ChildProofKeys>,
+		hash: Option<Hash>,
+	) -> RpcResult<ReadProof<Hash>>;
+
 	/// New runtime version subscription
 	#[subscription(
 		name = "state_subscribeRuntimeVersion" => "state_runtimeVersion",
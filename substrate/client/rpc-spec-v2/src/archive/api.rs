@@ -18,7 +18,13 @@
 
 //! API trait of the archive methods.
 
-use crate::MethodResult;
+use crate::{
+	common::events::{
+		ArchiveStorageDiffItem, ArchiveStorageDiffMethodResult, ArchiveStorageResult,
+		PaginatedStorageQuery,
+	},
+	MethodResult,
+};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 #[rpc(client, server)]
@@ -88,4 +94,37 @@ pub trait ArchiveApi<Hash> {
 		function: String,
 		call_parameters: String,
 	) -> RpcResult<MethodResult>;
+
+	/// Returns storage entries at a specific block's state.
+	///
+	/// Descendant queries report at most a fixed number of items per call. The next page is
+	/// fetched by providing the last reported key as `paginationStartKey`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_unstable_storage")]
+	fn archive_unstable_storage(
+		&self,
+		hash: Hash,
+		items: Vec<PaginatedStorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> RpcResult<ArchiveStorageResult>;
+
+	/// Returns the storage changes between two blocks.
+	///
+	/// Reports the keys that were added, modified or deleted under the provided key prefixes at
+	/// the `hash` block compared to the `previous_hash` block. If `previous_hash` is not
+	/// provided, the parent of the `hash` block is used.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_unstable_storageDiff")]
+	fn archive_unstable_storage_diff(
+		&self,
+		hash: Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+		previous_hash: Option<Hash>,
+	) -> RpcResult<ArchiveStorageDiffMethodResult>;
 }
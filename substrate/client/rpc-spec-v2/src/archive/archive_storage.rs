@@ -0,0 +1,269 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `archive_storage` and `archive_storageDiff` methods.
+
+use std::{cmp::Ordering, sync::Arc};
+
+use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{
+	chain_head::hex_string,
+	common::{
+		events::{
+			ArchiveStorageDiffItem, ArchiveStorageDiffMethodResult,
+			ArchiveStorageDiffOperationType, ArchiveStorageDiffResult, ArchiveStorageDiffType,
+			ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType, StorageResult,
+		},
+		storage::{is_key_queryable, IterQueryType, QueryIter, QueryResult, Storage},
+	},
+};
+
+/// Generates the results of the `archive_storage` and `archive_storageDiff` methods.
+pub struct ArchiveStorage<Client, Block, BE> {
+	/// Storage client.
+	client: Storage<Client, Block, BE>,
+	/// The maximum number of responses the API can return for a descendant query at a time.
+	storage_max_descendant_responses: usize,
+	/// The maximum number of queried items allowed for the `archive_storage` and
+	/// `archive_storageDiff` at a time.
+	storage_max_queried_items: usize,
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorage`].
+	pub fn new(
+		client: Arc<Client>,
+		storage_max_descendant_responses: usize,
+		storage_max_queried_items: usize,
+	) -> Self {
+		Self {
+			client: Storage::new(client),
+			storage_max_descendant_responses,
+			storage_max_queried_items,
+		}
+	}
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Generate the response of the `archive_storage` method.
+	pub fn handle_query(
+		&self,
+		hash: Block::Hash,
+		mut items: Vec<PaginatedStorageQuery<StorageKey>>,
+		child_key: Option<ChildInfo>,
+	) -> ArchiveStorageResult {
+		let discarded_items = items.len().saturating_sub(self.storage_max_queried_items);
+		items.truncate(self.storage_max_queried_items);
+
+		if let Some(child_key) = child_key.as_ref() {
+			if !is_key_queryable(child_key.storage_key()) {
+				return ArchiveStorageResult::ok(Vec::new(), discarded_items)
+			}
+		}
+
+		let mut storage_results = Vec::with_capacity(items.len());
+		let mut pagination = Vec::new();
+		for item in items {
+			if !is_key_queryable(&item.key.0) {
+				continue
+			}
+
+			let result = match item.query_type {
+				StorageQueryType::Value =>
+					self.client.query_value(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::Hash =>
+					self.client.query_hash(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::ClosestDescendantMerkleValue =>
+					self.client.query_merkle_value(hash, &item.key, child_key.as_ref()),
+				StorageQueryType::DescendantsValues | StorageQueryType::DescendantsHashes => {
+					let ty = match item.query_type {
+						StorageQueryType::DescendantsValues => IterQueryType::Value,
+						_ => IterQueryType::Hash,
+					};
+
+					match self.client.query_iter_pagination(
+						QueryIter {
+							query_key: item.key,
+							ty,
+							pagination_start_key: item.pagination_start_key,
+						},
+						hash,
+						child_key.as_ref(),
+						self.storage_max_descendant_responses,
+					) {
+						Ok((results, next_query)) => {
+							storage_results.extend(results);
+							if let Some(next_query) = next_query {
+								pagination.push(PaginatedStorageQuery {
+									key: hex_string(&next_query.query_key.0),
+									query_type: item.query_type,
+									pagination_start_key: next_query
+										.pagination_start_key
+										.map(|key| hex_string(&key.0)),
+								});
+							}
+						},
+						Err(error) => return ArchiveStorageResult::err(error),
+					}
+
+					continue
+				},
+			};
+
+			match result {
+				Ok(Some(value)) => storage_results.push(value),
+				Ok(None) => continue,
+				Err(error) => return ArchiveStorageResult::err(error),
+			}
+		}
+
+		ArchiveStorageResult::ok_with_pagination(storage_results, discarded_items, pagination)
+	}
+
+	/// Generate the response of the `archive_storageDiff` method.
+	///
+	/// The keys of `hash` are compared against the keys of `previous_hash`. At most
+	/// `storage_max_descendant_responses` keys are compared per item; items with keys left to
+	/// compare are returned in the `pagination` field, to be resumed by a subsequent call.
+	pub fn handle_diff(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		items: Vec<ArchiveStorageDiffItem<StorageKey>>,
+	) -> ArchiveStorageDiffMethodResult {
+		if items.len() > self.storage_max_queried_items {
+			return ArchiveStorageDiffMethodResult::err(format!(
+				"Too many items: {} provided, at most {} allowed",
+				items.len(),
+				self.storage_max_queried_items
+			))
+		}
+
+		let mut diff_results = Vec::new();
+		let mut pagination = Vec::new();
+		for item in items {
+			match self.diff_item(hash, previous_hash, item, &mut diff_results) {
+				Ok(Some(next_item)) => pagination.push(next_item),
+				Ok(None) => {},
+				Err(error) => return ArchiveStorageDiffMethodResult::err(error),
+			}
+		}
+
+		ArchiveStorageDiffMethodResult::ok_with_pagination(diff_results, pagination)
+	}
+
+	/// Compute the changes of the keys that start with the item's key.
+	///
+	/// Returns the item to resume from if the comparison stopped before exhausting the keys.
+	fn diff_item(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		item: ArchiveStorageDiffItem<StorageKey>,
+		diff_results: &mut Vec<ArchiveStorageDiffResult>,
+	) -> Result<Option<ArchiveStorageDiffItem<String>>, String> {
+		let child_trie_key = item.child_trie_key.map(|key| key.0);
+		if let Some(child_trie_key) = child_trie_key.as_ref() {
+			if !is_key_queryable(child_trie_key) {
+				return Ok(None)
+			}
+		}
+		let child_key = child_trie_key.clone().map(ChildInfo::new_default_from_vec);
+		let child_key = child_key.as_ref();
+
+		// Both iterators resume after the pagination key, which keeps their merge consistent.
+		let start_key = item.pagination_start_key.as_ref();
+		let mut current_keys = self.client.keys(hash, &item.key, start_key, child_key)?.peekable();
+		let mut previous_keys =
+			self.client.keys(previous_hash, &item.key, start_key, child_key)?.peekable();
+
+		let mut compared_keys = 0;
+		let mut last_key = None;
+		loop {
+			if compared_keys == self.storage_max_descendant_responses &&
+				(current_keys.peek().is_some() || previous_keys.peek().is_some())
+			{
+				return Ok(Some(ArchiveStorageDiffItem {
+					key: hex_string(&item.key.0),
+					return_type: item.return_type,
+					child_trie_key: child_trie_key.as_ref().map(hex_string),
+					pagination_start_key: last_key.map(|key: StorageKey| hex_string(&key.0)),
+				}))
+			}
+
+			// Both iterators produce keys in lexicographic order.
+			let (key, operation_type) = match (current_keys.peek(), previous_keys.peek()) {
+				(Some(current), Some(previous)) => match current.0.cmp(&previous.0) {
+					Ordering::Less => (current_keys.next(), ArchiveStorageDiffOperationType::Added),
+					Ordering::Greater =>
+						(previous_keys.next(), ArchiveStorageDiffOperationType::Deleted),
+					Ordering::Equal => {
+						previous_keys.next();
+						(current_keys.next(), ArchiveStorageDiffOperationType::Modified)
+					},
+				},
+				(Some(_), None) => (current_keys.next(), ArchiveStorageDiffOperationType::Added),
+				(None, Some(_)) => (previous_keys.next(), ArchiveStorageDiffOperationType::Deleted),
+				(None, None) => break,
+			};
+			let Some(key) = key else { break };
+			compared_keys += 1;
+			last_key = Some(key.clone());
+
+			if !is_key_queryable(&key.0) {
+				continue
+			}
+
+			if operation_type == ArchiveStorageDiffOperationType::Modified {
+				let current_value = self.client.query_hash(hash, &key, child_key)?;
+				let previous_value = self.client.query_hash(previous_hash, &key, child_key)?;
+				if current_value == previous_value {
+					continue
+				}
+			}
+
+			// Deleted keys report the content of the previous block.
+			let at = match operation_type {
+				ArchiveStorageDiffOperationType::Deleted => previous_hash,
+				_ => hash,
+			};
+			let result: QueryResult = match item.return_type {
+				ArchiveStorageDiffType::Value => self.client.query_value(at, &key, child_key),
+				ArchiveStorageDiffType::Hash => self.client.query_hash(at, &key, child_key),
+			};
+
+			if let Some(StorageResult { key, result }) = result? {
+				diff_results.push(ArchiveStorageDiffResult {
+					key,
+					result,
+					operation_type,
+					child_trie_key: child_trie_key.as_ref().map(hex_string),
+				});
+			}
+		}
+
+		Ok(None)
+	}
+}
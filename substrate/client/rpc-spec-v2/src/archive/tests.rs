@@ -16,7 +16,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{chain_head::hex_string, MethodResult};
+use crate::{
+	chain_head::hex_string,
+	common::events::{
+		ArchiveStorageDiffItem, ArchiveStorageDiffMethodOk, ArchiveStorageDiffMethodResult,
+		ArchiveStorageDiffOperationType, ArchiveStorageDiffType, ArchiveStorageMethodOk,
+		ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType, StorageResultType,
+	},
+	MethodResult,
+};
 
 use super::{archive::Archive, *};
 
@@ -24,12 +32,15 @@ use assert_matches::assert_matches;
 use codec::{Decode, Encode};
 use jsonrpsee::{
 	core::error::Error,
+	rpc_params,
 	types::{error::CallError, EmptyServerParams as EmptyParams},
 	RpcModule,
 };
 use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::ChildInfo;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
+use sp_core::{Blake2Hasher, Hasher};
 use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT},
 	SaturatedConversion,
@@ -42,23 +53,43 @@ use substrate_test_runtime_client::{
 
 const CHAIN_GENESIS: [u8; 32] = [0; 32];
 const INVALID_HASH: [u8; 32] = [1; 32];
+const MAX_PAGINATION_LIMIT: usize = 5;
+const MAX_QUERIED_LIMIT: usize = 5;
+const KEY: &[u8] = b":mock";
+const VALUE: &[u8] = b"hello world";
+const CHILD_STORAGE_KEY: &[u8] = b"child";
+const CHILD_VALUE: &[u8] = b"child value";
 
 type Header = substrate_test_runtime_client::runtime::Header;
 type Block = substrate_test_runtime_client::runtime::Block;
 
-fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
-	let builder = TestClientBuilder::new();
+fn setup_api(
+	max_descendant_responses: usize,
+	max_queried_items: usize,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let builder = TestClientBuilder::new().add_extra_child_storage(
+		&child_info,
+		KEY.to_vec(),
+		CHILD_VALUE.to_vec(),
+	);
 	let backend = builder.backend();
 	let client = Arc::new(builder.build());
 
-	let api = Archive::new(client.clone(), backend, CHAIN_GENESIS).into_rpc();
+	let api = Archive::new(
+		client.clone(),
+		backend,
+		CHAIN_GENESIS,
+		ArchiveConfig { max_descendant_responses, max_queried_items },
+	)
+	.into_rpc();
 
 	(client, api)
 }
 
 #[tokio::test]
 async fn archive_genesis() {
-	let (_client, api) = setup_api();
+	let (_client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	let genesis: String =
 		api.call("archive_unstable_genesisHash", EmptyParams::new()).await.unwrap();
@@ -67,7 +98,7 @@ async fn archive_genesis() {
 
 #[tokio::test]
 async fn archive_body() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Invalid block hash.
 	let invalid_hash = hex_string(&INVALID_HASH);
@@ -101,7 +132,7 @@ async fn archive_body() {
 
 #[tokio::test]
 async fn archive_header() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Invalid block hash.
 	let invalid_hash = hex_string(&INVALID_HASH);
@@ -135,7 +166,7 @@ async fn archive_header() {
 
 #[tokio::test]
 async fn archive_finalized_height() {
-	let (client, api) = setup_api();
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	let client_height: u32 = client.info().finalized_number.saturated_into();
 
@@ -147,7 +178,7 @@ async fn archive_finalized_height() {
 
 #[tokio::test]
 async fn archive_hash_by_height() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 
 	// Genesis height.
 	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [0]).await.unwrap();
@@ -253,7 +284,7 @@ async fn archive_hash_by_height() {
 
 #[tokio::test]
 async fn archive_call() {
-	let (mut client, api) = setup_api();
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
 	let invalid_hash = hex_string(&INVALID_HASH);
 
 	// Invalid parameter (non-hex).
@@ -309,3 +340,492 @@ async fn archive_call() {
 	let expected = MethodResult::ok("0x0000000000000000");
 	assert_eq!(result, expected);
 }
+
+#[tokio::test]
+async fn archive_storage_hashes_values() {
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let block = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let key = hex_string(&KEY);
+	let items: Vec<PaginatedStorageQuery<String>> = vec![
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::DescendantsHashes,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::DescendantsValues,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: hex_string(b":m"),
+			query_type: StorageQueryType::Value,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: hex_string(b":mo"),
+			query_type: StorageQueryType::Hash,
+			pagination_start_key: None,
+		},
+	];
+
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items.clone()])
+		.await
+		.unwrap();
+
+	let expected_hash = format!("{:?}", Blake2Hasher::hash(b"abcd"));
+	let expected_value = hex_string(b"abcd");
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items, .. }) => {
+			assert_eq!(result.len(), 4);
+			assert_eq!(discarded_items, 0);
+
+			assert_eq!(result[0].key, key);
+			assert_eq!(result[0].result, StorageResultType::Hash(expected_hash));
+			assert_eq!(result[1].key, key);
+			assert_eq!(result[1].result, StorageResultType::Value(expected_value));
+			assert_eq!(result[2].key, hex_string(b":m"));
+			assert_eq!(result[2].result, StorageResultType::Value(hex_string(b"a")));
+			assert_eq!(result[3].key, hex_string(b":mo"));
+			assert_eq!(
+				result[3].result,
+				StorageResultType::Hash(format!("{:?}", Blake2Hasher::hash(b"ab")))
+			);
+		},
+		_ => panic!("Unexpected result"),
+	};
+
+	// Child storage is queryable at the same block.
+	let items: Vec<PaginatedStorageQuery<String>> = vec![PaginatedStorageQuery {
+		key: key.clone(),
+		query_type: StorageQueryType::Value,
+		pagination_start_key: None,
+	}];
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![&block_hash, items, &hex_string(&CHILD_STORAGE_KEY)],
+		)
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items, .. }) => {
+			assert_eq!(result.len(), 1);
+			assert_eq!(discarded_items, 0);
+
+			assert_eq!(result[0].key, key);
+			assert_eq!(result[0].result, StorageResultType::Value(hex_string(&CHILD_VALUE)));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_closest_merkle_value() {
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let items = vec![PaginatedStorageQuery {
+		key: hex_string(&KEY),
+		query_type: StorageQueryType::ClosestDescendantMerkleValue,
+		pagination_start_key: None,
+	}];
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items, .. }) => {
+			assert_eq!(result.len(), 1);
+			assert_eq!(discarded_items, 0);
+			assert_eq!(result[0].key, hex_string(&KEY));
+			assert_matches!(result[0].result, StorageResultType::ClosestDescendantMerkleValue(_));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_paginate_iterations() {
+	// 1 iteration allowed before pagination kicks in.
+	let (mut client, api) = setup_api(1, MAX_QUERIED_LIMIT);
+
+	// Import a new block with storage changes.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let mut items = vec![PaginatedStorageQuery {
+		key: hex_string(b":m"),
+		query_type: StorageQueryType::DescendantsValues,
+		pagination_start_key: None,
+	}];
+	let mut reported = Vec::new();
+	for iteration in 0..3 {
+		let result: ArchiveStorageResult = api
+			.call("archive_unstable_storage", rpc_params![&block_hash, items.clone()])
+			.await
+			.unwrap();
+
+		match result {
+			ArchiveStorageResult::Ok(ArchiveStorageMethodOk {
+				result,
+				discarded_items,
+				pagination,
+			}) => {
+				assert_eq!(result.len(), 1);
+				assert_eq!(discarded_items, 0);
+				reported.push((result[0].key.clone(), result[0].result.clone()));
+
+				// The continuation resumes after the last reported key.
+				if iteration < 2 {
+					assert_eq!(
+						pagination,
+						vec![PaginatedStorageQuery {
+							key: hex_string(b":m"),
+							query_type: StorageQueryType::DescendantsValues,
+							pagination_start_key: Some(result[0].key.clone()),
+						}]
+					);
+					items = pagination;
+				} else {
+					assert!(pagination.is_empty());
+				}
+			},
+			_ => panic!("Unexpected result"),
+		};
+	}
+
+	assert_eq!(
+		reported,
+		vec![
+			(hex_string(b":m"), StorageResultType::Value(hex_string(b"a"))),
+			(hex_string(b":mo"), StorageResultType::Value(hex_string(b"ab"))),
+			(hex_string(b":moc"), StorageResultType::Value(hex_string(b"abc"))),
+		]
+	);
+
+	// No more items to report.
+	let items = vec![PaginatedStorageQuery {
+		key: hex_string(b":m"),
+		query_type: StorageQueryType::DescendantsValues,
+		pagination_start_key: Some(hex_string(b":moc")),
+	}];
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+	assert_eq!(result, ArchiveStorageResult::ok(Vec::new(), 0));
+
+	// Pagination start key is rejected for non-descendant queries.
+	let items = vec![PaginatedStorageQuery {
+		key: hex_string(b":m"),
+		query_type: StorageQueryType::Value,
+		pagination_start_key: Some(hex_string(b":m")),
+	}];
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_storage", rpc_params![&block_hash, items])
+		.await
+		.unwrap_err();
+	assert_matches!(err, Error::Call(CallError::Custom(ref err)) if err.code() == 3001 && err.message().contains("Invalid parameter"));
+}
+
+#[tokio::test]
+async fn archive_storage_discarded_items() {
+	// One query at a time
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, 1);
+
+	let block = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Valid call with storage at the key.
+	let key = hex_string(&KEY);
+	let items = vec![
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::Value,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::Hash,
+			pagination_start_key: None,
+		},
+		PaginatedStorageQuery {
+			key: key.clone(),
+			query_type: StorageQueryType::Hash,
+			pagination_start_key: None,
+		},
+	];
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![&block_hash, items, &hex_string(&CHILD_STORAGE_KEY)],
+		)
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageResult::Ok(ArchiveStorageMethodOk { result, discarded_items, .. }) => {
+			assert_eq!(result.len(), 1);
+			assert_eq!(discarded_items, 2);
+
+			assert_eq!(result[0].key, key);
+			assert_eq!(result[0].result, StorageResultType::Value(hex_string(&CHILD_VALUE)));
+		},
+		_ => panic!("Unexpected result"),
+	};
+}
+
+#[tokio::test]
+async fn archive_storage_diff() {
+	let (mut client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	let block_1 = builder.build().unwrap().block;
+	let block_1_hash = block_1.header.hash();
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	// Delete `:m`, modify `:mo`, keep `:moc` and add `:mock`.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_1_hash)
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), None).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"abx".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	let block_2 = builder.build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b":m"),
+		return_type: ArchiveStorageDiffType::Value,
+		child_trie_key: None,
+		pagination_start_key: None,
+	}];
+
+	// The parent block is used when the previous hash is not provided.
+	let result: ArchiveStorageDiffMethodResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![&format!("{:?}", block_2_hash), items.clone()],
+		)
+		.await
+		.unwrap();
+	let with_previous: ArchiveStorageDiffMethodResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![&format!("{:?}", block_2_hash), items, &format!("{:?}", block_1_hash)],
+		)
+		.await
+		.unwrap();
+	assert_eq!(result, with_previous);
+
+	let ArchiveStorageDiffMethodResult::Ok(ArchiveStorageDiffMethodOk { result, pagination }) =
+		result
+	else {
+		panic!("Unexpected result")
+	};
+	assert!(pagination.is_empty());
+	let changes: Vec<_> = result
+		.into_iter()
+		.map(|change| (change.key, change.result, change.operation_type))
+		.collect();
+	assert_eq!(
+		changes,
+		vec![
+			(
+				hex_string(b":m"),
+				StorageResultType::Value(hex_string(b"a")),
+				ArchiveStorageDiffOperationType::Deleted
+			),
+			(
+				hex_string(b":mo"),
+				StorageResultType::Value(hex_string(b"abx")),
+				ArchiveStorageDiffOperationType::Modified
+			),
+			(
+				hex_string(b":mock"),
+				StorageResultType::Value(hex_string(b"abcd")),
+				ArchiveStorageDiffOperationType::Added
+			),
+		]
+	);
+
+	// Unknown block.
+	let items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b":m"),
+		return_type: ArchiveStorageDiffType::Hash,
+		child_trie_key: None,
+		pagination_start_key: None,
+	}];
+	let result: ArchiveStorageDiffMethodResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&hex_string(&INVALID_HASH), items])
+		.await
+		.unwrap();
+	assert_matches!(result, ArchiveStorageDiffMethodResult::Err(_));
+}
+
+#[tokio::test]
+async fn archive_storage_diff_paginate_iterations() {
+	// 2 keys compared before pagination kicks in.
+	let (mut client, api) = setup_api(2, MAX_QUERIED_LIMIT);
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), Some(b"a".to_vec())).unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abc".to_vec())).unwrap();
+	let block_1 = builder.build().unwrap().block;
+	let block_1_hash = format!("{:?}", block_1.header.hash());
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	// Delete `:m`, keep `:mo`, modify `:moc` and add `:mock`.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_1.header.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":m".to_vec(), None).unwrap();
+	builder.push_storage_change(b":moc".to_vec(), Some(b"abx".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	let block_2 = builder.build().unwrap().block;
+	let block_2_hash = format!("{:?}", block_2.header.hash());
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b":m"),
+		return_type: ArchiveStorageDiffType::Hash,
+		child_trie_key: None,
+		pagination_start_key: None,
+	}];
+	let result: ArchiveStorageDiffMethodResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_2_hash, items, &block_1_hash])
+		.await
+		.unwrap();
+	let ArchiveStorageDiffMethodResult::Ok(ArchiveStorageDiffMethodOk { result, pagination }) =
+		result
+	else {
+		panic!("Unexpected result")
+	};
+	// `:m` and `:mo` are compared, only the former changed.
+	assert_eq!(result.len(), 1);
+	assert_eq!(result[0].key, hex_string(b":m"));
+	assert_eq!(result[0].operation_type, ArchiveStorageDiffOperationType::Deleted);
+	assert_eq!(
+		pagination,
+		vec![ArchiveStorageDiffItem {
+			key: hex_string(b":m"),
+			return_type: ArchiveStorageDiffType::Hash,
+			child_trie_key: None,
+			pagination_start_key: Some(hex_string(b":mo")),
+		}]
+	);
+
+	// Resume from the returned continuation.
+	let result: ArchiveStorageDiffMethodResult = api
+		.call("archive_unstable_storageDiff", rpc_params![&block_2_hash, pagination, &block_1_hash])
+		.await
+		.unwrap();
+	let ArchiveStorageDiffMethodResult::Ok(ArchiveStorageDiffMethodOk { result, pagination }) =
+		result
+	else {
+		panic!("Unexpected result")
+	};
+	let changes: Vec<_> =
+		result.into_iter().map(|change| (change.key, change.operation_type)).collect();
+	assert_eq!(
+		changes,
+		vec![
+			(hex_string(b":moc"), ArchiveStorageDiffOperationType::Modified),
+			(hex_string(b":mock"), ArchiveStorageDiffOperationType::Added),
+		]
+	);
+	assert!(pagination.is_empty());
+}
+
+#[tokio::test]
+async fn archive_storage_diff_too_many_items() {
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, 1);
+	let genesis_hash = format!("{:?}", client.chain_info().genesis_hash);
+
+	let item = ArchiveStorageDiffItem {
+		key: hex_string(b":m"),
+		return_type: ArchiveStorageDiffType::Value,
+		child_trie_key: None,
+		pagination_start_key: None,
+	};
+	let result: ArchiveStorageDiffMethodResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![&genesis_hash, vec![item.clone(), item], &genesis_hash],
+		)
+		.await
+		.unwrap();
+	assert_matches!(result, ArchiveStorageDiffMethodResult::Err(_));
+}
@@ -19,7 +19,7 @@
 //! API trait for transactions.
 
 use crate::transaction::event::TransactionEvent;
-use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sp_core::Bytes;
 
 #[rpc(client, server)]
@@ -35,3 +35,27 @@ pub trait TransactionApi<Hash: Clone> {
 	)]
 	fn submit_and_watch(&self, bytes: Bytes);
 }
+
+#[rpc(client, server)]
+pub trait TransactionBroadcastApi {
+	/// Broadcast an extrinsic to the chain.
+	///
+	/// The extrinsic is submitted to the transaction pool and broadcasted to the peers until
+	/// it is included in a finalized block or until the operation is stopped.
+	///
+	/// Returns the operation ID that can be used to stop the broadcast.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "transaction_unstable_broadcast")]
+	fn broadcast(&self, bytes: Bytes) -> RpcResult<Option<String>>;
+
+	/// Stop broadcasting an extrinsic previously submitted via `transaction_unstable_broadcast`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "transaction_unstable_stop")]
+	fn stop_broadcast(&self, operation_id: String) -> RpcResult<()>;
+}
@@ -0,0 +1,122 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::transaction::{
+	error::json_rpc_spec::INVALID_PARAM_ERROR, transaction_broadcast::MAX_ACTIVE_BROADCASTS,
+};
+use assert_matches::assert_matches;
+use codec::Encode;
+use jsonrpsee::{core::error::Error, types::error::CallError, RpcModule};
+use sc_transaction_pool::{BasicPool, FullChainApi};
+use sc_transaction_pool_api::TransactionPool;
+use sp_core::{testing::TaskExecutor, Bytes};
+use std::{sync::Arc, time::Duration};
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, Extrinsic, ExtrinsicBuilder, Transfer},
+	Backend, Client,
+};
+
+type FullTransactionPool = BasicPool<FullChainApi<Client<Backend>, Block>, Block>;
+
+fn uxt(sender: AccountKeyring, nonce: u64) -> Extrinsic {
+	let tx = Transfer {
+		amount: Default::default(),
+		nonce,
+		from: sender.into(),
+		to: AccountKeyring::Bob.into(),
+	};
+	ExtrinsicBuilder::new_transfer(tx).build()
+}
+
+fn setup_api(
+) -> (Arc<FullTransactionPool>, RpcModule<TransactionBroadcast<FullTransactionPool, Client<Backend>>>)
+{
+	let client = Arc::new(TestClientBuilder::new().build());
+	let spawner = TaskExecutor::new();
+	let pool =
+		BasicPool::new_full(Default::default(), true.into(), None, spawner.clone(), client.clone());
+
+	let api = TransactionBroadcast::new(client, pool.clone(), Arc::new(spawner)).into_rpc();
+
+	(pool, api)
+}
+
+/// Wait until the pool reports the expected number of ready transactions.
+async fn wait_for_ready(pool: &FullTransactionPool, expected: usize) {
+	tokio::time::timeout(Duration::from_secs(60), async {
+		while pool.status().ready != expected {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await
+	.unwrap();
+}
+
+#[tokio::test]
+async fn tx_broadcast_enters_pool() {
+	let (pool, api) = setup_api();
+
+	let xt: Bytes = uxt(AccountKeyring::Alice, 0).encode().into();
+	let operation_id: Option<String> =
+		api.call("transaction_unstable_broadcast", [xt]).await.unwrap();
+	let operation_id = operation_id.expect("Server is not overloaded; qed");
+
+	// The transaction is submitted to the pool in the background.
+	wait_for_ready(&pool, 1).await;
+
+	// Stop the broadcast.
+	let _: () = api.call("transaction_unstable_stop", [&operation_id]).await.unwrap();
+
+	// The operation ID is no longer valid.
+	let err = api
+		.call::<_, serde_json::Value>("transaction_unstable_stop", [&operation_id])
+		.await
+		.unwrap_err();
+	assert_matches!(err, Error::Call(CallError::Custom(ref err)) if err.code() == INVALID_PARAM_ERROR && err.message().contains("Invalid operation id"));
+}
+
+#[tokio::test]
+async fn tx_broadcast_invalid_operation_id() {
+	let (_pool, api) = setup_api();
+
+	let err = api
+		.call::<_, serde_json::Value>("transaction_unstable_stop", ["invalid_operation_id"])
+		.await
+		.unwrap_err();
+	assert_matches!(err, Error::Call(CallError::Custom(ref err)) if err.code() == INVALID_PARAM_ERROR && err.message().contains("Invalid operation id"));
+}
+
+#[tokio::test]
+async fn tx_broadcast_limits_active_operations() {
+	let (_pool, api) = setup_api();
+
+	for nonce in 0..MAX_ACTIVE_BROADCASTS {
+		let xt: Bytes = uxt(AccountKeyring::Alice, nonce as u64).encode().into();
+		let operation_id: Option<String> =
+			api.call("transaction_unstable_broadcast", [xt]).await.unwrap();
+		assert!(operation_id.is_some());
+	}
+
+	// The server is overloaded.
+	let xt: Bytes = uxt(AccountKeyring::Bob, 0).encode().into();
+	let operation_id: Option<String> =
+		api.call("transaction_unstable_broadcast", [xt]).await.unwrap();
+	assert_eq!(operation_id, None);
+}
@@ -148,6 +148,15 @@ where
 		keys: Vec<StorageKey>,
 	) -> Result<ReadProof<Block::Hash>, Error>;
 
+	/// Returns compact proof of the given keys, and of the entries under the given prefixes, of
+	/// the top trie and of the given child tries.
+	fn read_compact_proof(
+		&self,
+		block: Option<Block::Hash>,
+		top: ProofKeys,
+		children: Vec<ChildProofKeys>,
+	) -> Result<ReadProof<Block::Hash>, Error>;
+
 	/// Trace storage changes for block
 	fn trace_block(
 		&self,
@@ -307,6 +316,22 @@ where
 		self.backend.read_proof(block, keys).map_err(Into::into)
 	}
 
+	/// Proving the entries under a prefix requires iterating over them.
+	///
+	/// Note: requires the node to run with `--rpc-methods=Unsafe` when prefixes are given.
+	fn read_compact_proof(
+		&self,
+		top: ProofKeys,
+		children: Vec<ChildProofKeys>,
+		block: Option<Block::Hash>,
+	) -> RpcResult<ReadProof<Block::Hash>> {
+		if !top.prefixes.is_empty() || children.iter().any(|child| !child.keys.prefixes.is_empty())
+		{
+			self.deny_unsafe.check_if_safe()?;
+		}
+		self.backend.read_compact_proof(block, top, children).map_err(Into::into)
+	}
+
 	/// Re-execute the given block with the tracing targets given in `targets`
 	/// and capture all state changes.
 	///
@@ -33,10 +33,10 @@ use jsonrpsee::{
 	SubscriptionSink,
 };
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, ProofProvider,
-	StorageProvider,
+	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, KeysAndPrefixes,
+	ProofProvider, StorageProvider,
 };
-use sc_rpc_api::state::ReadProof;
+use sc_rpc_api::state::{ChildProofKeys, ProofKeys, ReadProof};
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
 use sp_blockchain::{
 	CachedHeaderMetadata, Error as ClientError, HeaderBackend, HeaderMetadata,
@@ -371,6 +371,37 @@ where
 			.map_err(client_err)
 	}
 
+	fn read_compact_proof(
+		&self,
+		block: Option<Block::Hash>,
+		top: ProofKeys,
+		children: Vec<ChildProofKeys>,
+	) -> std::result::Result<ReadProof<Block::Hash>, Error> {
+		let into_keys_and_prefixes = |keys: ProofKeys| KeysAndPrefixes {
+			keys: keys.keys.into_iter().map(|key| key.0).collect(),
+			prefixes: keys.prefixes.into_iter().map(|prefix| prefix.0).collect(),
+		};
+
+		self.block_or_best(block)
+			.and_then(|block| {
+				let children = children
+					.into_iter()
+					.map(|child| match ChildType::from_prefixed_key(&child.child_storage_key) {
+						Some((ChildType::ParentKeyId, storage_key)) => Ok((
+							ChildInfo::new_default(storage_key),
+							into_keys_and_prefixes(child.keys),
+						)),
+						None => Err(sp_blockchain::Error::InvalidChildStorageKey),
+					})
+					.collect::<ClientResult<Vec<_>>>()?;
+				self.client
+					.read_compact_proof(block, &into_keys_and_prefixes(top), &children)
+					.map(|proof| proof.encoded_nodes.into_iter().map(Into::into).collect())
+					.map(|proof| ReadProof { at: block, proof })
+			})
+			.map_err(client_err)
+	}
+
 	fn subscribe_runtime_version(&self, mut sink: SubscriptionSink) {
 		let client = self.client.clone();
 
@@ -26,9 +26,11 @@ use jsonrpsee::{
 	types::{error::CallError as RpcCallError, EmptyServerParams as EmptyParams, ErrorObject},
 };
 use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::{CompactProof, KeysAndPrefixes};
 use sc_rpc_api::DenyUnsafe;
 use sp_consensus::BlockOrigin;
 use sp_core::{hash::H256, storage::ChildInfo};
+use sp_runtime::traits::BlakeTwo256;
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	prelude::*,
@@ -519,6 +521,73 @@ async fn should_query_storage() {
 	run_tests(Arc::new(TestClientBuilder::new().build())).await;
 }
 
+#[tokio::test]
+async fn should_return_compact_read_proof() {
+	const CHILD_VALUE: &[u8] = b"hello world !";
+
+	let child_info = ChildInfo::new_default(STORAGE_KEY);
+	let client = Arc::new(
+		TestClientBuilder::new()
+			.add_extra_storage(b":map:acc1".to_vec(), vec![1, 2])
+			.add_extra_storage(b":map:acc2".to_vec(), vec![1, 2, 3])
+			.add_extra_child_storage(&child_info, b":mock".to_vec(), CHILD_VALUE.to_vec())
+			.build(),
+	);
+	let genesis_hash = client.genesis_hash();
+	let state_root = client.header(genesis_hash).unwrap().unwrap().state_root;
+	let (api, _child) = new_full(client.clone(), test_executor(), DenyUnsafe::No);
+
+	let top = ProofKeys {
+		keys: vec![StorageKey(b":soup".to_vec())],
+		prefixes: vec![StorageKey(b":map".to_vec())],
+	};
+	let children = vec![ChildProofKeys {
+		child_storage_key: prefixed_storage_key(),
+		keys: ProofKeys { keys: vec![StorageKey(b":mock".to_vec())], prefixes: vec![] },
+	}];
+	let proof = api.read_compact_proof(top.clone(), children.clone(), None).unwrap();
+	assert_eq!(proof.at, genesis_hash);
+
+	let proof =
+		CompactProof { encoded_nodes: proof.proof.into_iter().map(|node| node.0).collect() };
+	let values = sp_state_machine::read_compact_proof_check::<BlakeTwo256>(
+		state_root,
+		proof,
+		&KeysAndPrefixes { keys: vec![b":soup".to_vec()], prefixes: vec![b":map".to_vec()] },
+		&[(child_info, KeysAndPrefixes { keys: vec![b":mock".to_vec()], prefixes: vec![] })],
+	)
+	.unwrap();
+	assert_eq!(
+		values.top.into_iter().collect::<Vec<_>>(),
+		vec![
+			(b":map:acc1".to_vec(), Some(vec![1, 2])),
+			(b":map:acc2".to_vec(), Some(vec![1, 2, 3])),
+			(b":soup".to_vec(), None),
+		],
+	);
+	assert_eq!(
+		values
+			.children
+			.get(STORAGE_KEY)
+			.and_then(|child| child.get(&b":mock"[..]).cloned()),
+		Some(Some(CHILD_VALUE.to_vec())),
+	);
+
+	// Proving prefixes is unsafe, proving keys is not.
+	let (api, _child) = new_full(client, test_executor(), DenyUnsafe::Yes);
+	assert_matches!(
+		api.read_compact_proof(top, children.clone(), None),
+		Err(RpcError::Call(RpcCallError::Custom(e))) if e.message() == "RPC call is unsafe to be called externally"
+	);
+	assert!(api
+		.read_compact_proof(
+			ProofKeys { keys: vec![StorageKey(b":soup".to_vec())], prefixes: vec![] },
+			children,
+			None
+		)
+		.is_ok());
+}
+
 #[tokio::test]
 async fn should_return_runtime_version() {
 	let client = Arc::new(substrate_test_runtime_client::new());
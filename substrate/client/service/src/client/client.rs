@@ -72,10 +72,10 @@ use sp_runtime::{
 	Justification, Justifications, StateVersion,
 };
 use sp_state_machine::{
-	prove_child_read, prove_range_read_with_child_with_size, prove_read,
+	prove_child_read, prove_keys_and_prefixes, prove_range_read_with_child_with_size, prove_read,
 	read_range_proof_check_with_child_on_proving_backend, Backend as StateBackend,
-	ChildStorageCollection, KeyValueStates, KeyValueStorageLevel, StorageCollection,
-	MAX_NESTED_TRIE_DEPTH,
+	ChildStorageCollection, KeyValueStates, KeyValueStorageLevel, KeysAndPrefixes,
+	StorageCollection, MAX_NESTED_TRIE_DEPTH,
 };
 use sp_trie::{CompactProof, MerkleValue, StorageProof};
 use std::{
@@ -1237,6 +1237,22 @@ where
 			.and_then(|state| prove_child_read(state, child_info, keys).map_err(Into::into))
 	}
 
+	fn read_compact_proof(
+		&self,
+		hash: Block::Hash,
+		top: &KeysAndPrefixes,
+		children: &[(ChildInfo, KeysAndPrefixes)],
+	) -> sp_blockchain::Result<CompactProof> {
+		let state = self.state_at(hash)?;
+		// this is a read proof, using version V0 or V1 is equivalent.
+		let root = state.storage_root(std::iter::empty(), StateVersion::V0).0;
+
+		let proof = prove_keys_and_prefixes::<_, HashingFor<Block>>(state, top, children)?;
+		proof
+			.into_compact_proof::<HashingFor<Block>>(root)
+			.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))
+	}
+
 	fn execution_proof(
 		&self,
 		hash: Block::Hash,
@@ -18,7 +18,7 @@
 
 //! Service configuration.
 
-pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningMode};
+pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningBudget, PruningMode};
 pub use sc_executor::{WasmExecutionMethod, WasmtimeInstantiationStrategy};
 pub use sc_network::{
 	config::{
@@ -34,7 +34,7 @@ pub use sc_network::{
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_telemetry::TelemetryEndpoints;
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::{Options as TransactionPoolOptions, TransactionPoolType};
 use sp_core::crypto::SecretString;
 use std::{
 	io, iter,
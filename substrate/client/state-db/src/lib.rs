@@ -579,6 +579,21 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 		self.db.read().mode.clone()
 	}
 
+	/// Change the number of blocks whose state is kept by the constrained pruning mode.
+	///
+	/// A shrunk window prunes the blocks falling out of it right away, the returned changes must
+	/// be committed. A grown window keeps the state of the following canonicalized blocks longer.
+	/// The change is not persisted. Does nothing in the archive modes.
+	pub fn set_max_blocks(&self, max_blocks: u32) -> Result<CommitSet<Key>, Error<D::Error>> {
+		let mut db = self.db.write();
+		let mut commit = CommitSet::default();
+		if let PruningMode::Constrained(constraints) = &mut db.mode {
+			constraints.max_blocks = Some(max_blocks);
+			db.prune(&mut commit)?;
+		}
+		Ok(commit)
+	}
+
 	/// Add a new non-canonical block.
 	pub fn insert_block(
 		&self,
@@ -847,6 +862,20 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn set_max_blocks_shrinks_and_grows_window() {
+		let (mut db, sdb) =
+			make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(2) }));
+		db.commit(&sdb.set_max_blocks(1).unwrap());
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::Pruned);
+		assert!(db.data_eq(&make_db(&[21, 3, 922, 93, 94])));
+
+		db.commit(&sdb.set_max_blocks(2).unwrap());
+		db.commit(&sdb.canonicalize_block(&H256::from_low_u64_be(4)).unwrap());
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(3), 3), IsPruned::NotPruned);
+		assert!(db.data_eq(&make_db(&[21, 3, 4, 922, 93, 94])));
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
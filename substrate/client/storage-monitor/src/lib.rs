@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::Args;
-use sc_client_db::DatabaseSource;
+use sc_client_db::{DatabaseSource, PruningBudget};
 use sp_core::traits::SpawnEssentialNamed;
 use std::{
 	io,
@@ -45,7 +45,8 @@ pub struct StorageMonitorParams {
 	/// Required available space on database storage.
 	///
 	/// If available space for DB storage drops below the given threshold, node will
-	/// be gracefully terminated.
+	/// be gracefully terminated. When blocks are pruned by size budget, the budget is lowered
+	/// first and the node is only terminated once it is exhausted.
 	///
 	/// If `0` is given monitoring will be disabled.
 	#[arg(long = "db-storage-threshold", value_name = "MiB", default_value_t = 1024)]
@@ -64,6 +65,8 @@ pub struct StorageMonitorService {
 	threshold: u64,
 	/// storage space polling period
 	polling_period: Duration,
+	/// blocks pruning budget lowered when running out of space
+	pruning_budget: Option<PruningBudget>,
 }
 
 impl StorageMonitorService {
@@ -72,6 +75,17 @@ impl StorageMonitorService {
 		parameters: StorageMonitorParams,
 		database: DatabaseSource,
 		spawner: &impl SpawnEssentialNamed,
+	) -> Result<()> {
+		Self::try_spawn_with_pruning_budget(parameters, database, None, spawner)
+	}
+
+	/// Creates new StorageMonitorService for given client config, lowering the given blocks
+	/// pruning budget before terminating the node when running out of space.
+	pub fn try_spawn_with_pruning_budget(
+		parameters: StorageMonitorParams,
+		database: DatabaseSource,
+		pruning_budget: Option<PruningBudget>,
+		spawner: &impl SpawnEssentialNamed,
 	) -> Result<()> {
 		Ok(match (parameters.threshold, database.path()) {
 			(0, _) => {
@@ -98,6 +112,7 @@ impl StorageMonitorService {
 					path: path.to_path_buf(),
 					threshold,
 					polling_period: Duration::from_secs(parameters.polling_period.into()),
+					pruning_budget,
 				};
 
 				spawner.spawn_essential(
@@ -110,14 +125,44 @@ impl StorageMonitorService {
 	}
 
 	/// Main monitoring loop, intended to be spawned as essential task. Quits if free space drop
-	/// below threshold.
+	/// below threshold and the pruning budget can not be lowered anymore.
 	async fn run(self) {
 		loop {
 			tokio::time::sleep(self.polling_period).await;
-			if Self::check_free_space(&self.path, self.threshold).is_err() {
-				break
-			};
+			match Self::check_free_space(&self.path, self.threshold) {
+				Ok(()) => {},
+				Err(Error::StorageOutOfSpace(available, threshold))
+					if self.lower_pruning_budget(threshold - available) => {},
+				Err(_) => {
+					log::error!(target: LOG_TARGET, "Terminating due to insufficient storage space.");
+					break
+				},
+			}
+		}
+	}
+
+	/// Lowers the pruning budget so that `missing` MiB of the kept blocks are pruned. Returns
+	/// `false` if there are no blocks left to prune.
+	fn lower_pruning_budget(&self, missing: u64) -> bool {
+		let Some(budget) = &self.pruning_budget else { return false };
+		let kept = budget.kept_bytes();
+		if kept == 0 {
+			return false
+		}
+
+		// The blocks are pruned on the next finalization, the budget may already be lowered.
+		let bytes = budget.bytes();
+		let lowered = kept.saturating_sub(missing.saturating_mul(1024 * 1024));
+		if lowered < bytes {
+			log::warn!(
+				target: LOG_TARGET,
+				"Lowering blocks pruning budget from {}MiB to {}MiB to free storage space.",
+				bytes / 1024 / 1024,
+				lowered / 1024 / 1024,
+			);
+			budget.set_bytes(lowered);
 		}
+		true
 	}
 
 	/// Returns free space in MiB, or error if statvfs failed.
@@ -137,7 +182,7 @@ impl StorageMonitorService {
 				);
 
 				if available_space < threshold {
-					log::error!(target: LOG_TARGET, "Available space {available_space}MiB for path `{}` dropped below threshold: {threshold}MiB , terminating...", path.display());
+					log::error!(target: LOG_TARGET, "Available space {available_space}MiB for path `{}` dropped below threshold: {threshold}MiB.", path.display());
 					Err(Error::StorageOutOfSpace(available_space, threshold))
 				} else {
 					Ok(())
@@ -131,6 +131,49 @@ pub enum TransactionStatus<Hash, BlockHash> {
 	Invalid,
 }
 
+impl<Hash, BlockHash> TransactionStatus<Hash, BlockHash> {
+	/// Returns true if this is the last event emitted by [`TransactionStatusStream`].
+	pub fn is_final(&self) -> bool {
+		// The state must be kept in sync with `sc_transaction_pool::graph::watcher::Sender`.
+		match self {
+			Self::Usurped(_) |
+			Self::Finalized(_) |
+			Self::FinalityTimeout(_) |
+			Self::Invalid |
+			Self::Dropped => true,
+			Self::Future |
+			Self::Ready |
+			Self::Broadcast(_) |
+			Self::InBlock(_) |
+			Self::Retracted(_) => false,
+		}
+	}
+
+	/// Returns true if the transaction could be re-submitted to the pool in the future.
+	///
+	/// For example, `TransactionStatus::Dropped` is retriable, because the transaction
+	/// may enter the pool if there is space for it in the future.
+	pub fn is_retriable(&self) -> bool {
+		match self {
+			// The number of finality watchers has been reached.
+			Self::FinalityTimeout(_) |
+			// The transaction was dropped because of the limits of the pool.
+			// It can reenter the pool when other transactions are removed / finalized.
+			Self::Dropped => true,
+			// The runtime declared the transaction invalid, resubmitting it would only be
+			// rejected again.
+			Self::Invalid |
+			Self::Usurped(_) |
+			Self::Finalized(_) |
+			Self::Future |
+			Self::Ready |
+			Self::Broadcast(_) |
+			Self::InBlock(_) |
+			Self::Retracted(_) => false,
+		}
+	}
+}
+
 /// The stream of transaction events.
 pub type TransactionStatusStream<Hash, BlockHash> =
 	dyn Stream<Item = TransactionStatus<Hash, BlockHash>> + Send;
@@ -298,6 +341,11 @@ pub enum ChainEvent<B: BlockT> {
 		/// If `None`, no re-org happened on import.
 		tree_route: Option<Arc<sp_blockchain::TreeRoute<B>>>,
 	},
+	/// New block have been added to the chain, without becoming the best block.
+	NewBlock {
+		/// Hash of the block.
+		hash: B::Hash,
+	},
 	/// An existing block has been finalized.
 	Finalized {
 		/// Hash of just finalized block.
@@ -311,7 +359,9 @@ impl<B: BlockT> ChainEvent<B> {
 	/// Returns the block hash associated to the event.
 	pub fn hash(&self) -> B::Hash {
 		match self {
-			Self::NewBestBlock { hash, .. } | Self::Finalized { hash, .. } => *hash,
+			Self::NewBestBlock { hash, .. } |
+			Self::NewBlock { hash } |
+			Self::Finalized { hash, .. } => *hash,
 		}
 	}
 
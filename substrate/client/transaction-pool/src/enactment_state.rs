@@ -179,6 +179,7 @@ where
 		match event {
 			ChainEvent::NewBestBlock { hash, .. } => self.recent_best_block = *hash,
 			ChainEvent::Finalized { hash, .. } => self.recent_finalized_block = *hash,
+			ChainEvent::NewBlock { .. } => {},
 		};
 		log::debug!(
 			target: LOG_TARGET,
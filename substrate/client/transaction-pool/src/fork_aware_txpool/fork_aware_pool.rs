@@ -0,0 +1,891 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The fork-aware transaction pool implementation.
+
+use super::{
+	multi_view_listener::MultiViewListener, tx_mem_pool::TxMemPool, view::View,
+	view_store::ViewStore,
+};
+use crate::{
+	api::FullChainApi,
+	error,
+	graph::{self, ExtrinsicHash, IsValidator, ValidatedTransaction},
+	metrics::MetricsLink as PrometheusMetrics,
+	PolledIterator, ReadyIteratorFor, LOG_TARGET,
+};
+use async_trait::async_trait;
+use futures::{
+	channel::{
+		mpsc::{channel, Sender},
+		oneshot,
+	},
+	future,
+	prelude::*,
+};
+use parking_lot::Mutex;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
+	PoolFuture, PoolStatus, TransactionFor, TransactionPool, TransactionSource,
+	TransactionStatusStreamFor, TxHash,
+};
+use sp_blockchain::HashAndNumber;
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+};
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+	sync::Arc,
+};
+
+/// Pending requests for the ready set at the heights the most recent view has not reached yet.
+struct ReadyPoll<T, Block: BlockT> {
+	pollers: Vec<(NumberFor<Block>, oneshot::Sender<T>)>,
+}
+
+impl<T, Block: BlockT> ReadyPoll<T, Block> {
+	fn new() -> Self {
+		Self { pollers: Default::default() }
+	}
+
+	fn add(&mut self, at: NumberFor<Block>) -> oneshot::Receiver<T> {
+		let (sender, receiver) = oneshot::channel();
+		self.pollers.push((at, sender));
+		receiver
+	}
+
+	/// Resolves the requests at `number` and below.
+	fn trigger(&mut self, number: NumberFor<Block>, iterator_factory: impl Fn() -> T) {
+		let mut idx = 0;
+		while idx < self.pollers.len() {
+			if self.pollers[idx].0 <= number {
+				let poller_sender = self.pollers.swap_remove(idx);
+				log::debug!(target: LOG_TARGET, "Sending ready signal at block {}", number);
+				let _ = poller_sender.1.send(iterator_factory());
+			} else {
+				idx += 1;
+			}
+		}
+	}
+
+	/// Removes the requests which are not awaited anymore.
+	fn remove_cancelled(&mut self) {
+		self.pollers.retain(|(_, poller)| !poller.is_canceled());
+	}
+}
+
+type ImportNotificationSinks<ChainApi> = Mutex<Vec<Sender<ExtrinsicHash<ChainApi>>>>;
+
+/// Fork-aware transaction pool.
+///
+/// Keeps a view of the pool for every leaf of the tracked block tree that is not older than the
+/// last finalized block, see the [module documentation](super) for details.
+pub struct ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	api: Arc<ChainApi>,
+	mempool: Arc<TxMemPool<ChainApi>>,
+	view_store: Arc<ViewStore<ChainApi>>,
+	listener: Arc<MultiViewListener<ChainApi>>,
+	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<ChainApi>, Block>>>,
+	import_notification_sinks: Arc<ImportNotificationSinks<ChainApi>>,
+	metrics: PrometheusMetrics,
+	finalized_hash: Mutex<Block::Hash>,
+	options: graph::Options,
+	is_validator: IsValidator,
+}
+
+impl<ChainApi, Block> ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	/// Create new fork-aware transaction pool with provided api, for tests.
+	pub fn new_test(
+		pool_api: Arc<ChainApi>,
+		best_block_hash: Block::Hash,
+		finalized_hash: Block::Hash,
+	) -> Self {
+		Self::new_with_options(
+			Default::default(),
+			true.into(),
+			pool_api,
+			None,
+			best_block_hash,
+			finalized_hash,
+		)
+	}
+
+	/// Create new fork-aware transaction pool with provided api and options.
+	///
+	/// The initial view is created at the best block.
+	pub fn new_with_options(
+		options: graph::Options,
+		is_validator: IsValidator,
+		pool_api: Arc<ChainApi>,
+		prometheus: Option<&PrometheusRegistry>,
+		best_block_hash: Block::Hash,
+		finalized_hash: Block::Hash,
+	) -> Self {
+		let metrics = PrometheusMetrics::new(prometheus);
+		let view_store = Arc::new(ViewStore::new(pool_api.clone()));
+		match pool_api.block_id_to_number(&BlockId::Hash(best_block_hash)) {
+			Ok(Some(number)) => {
+				view_store.insert_new_view(
+					Arc::new(View::new(
+						pool_api.clone(),
+						HashAndNumber { hash: best_block_hash, number },
+						options.clone(),
+						is_validator.clone(),
+						metrics.clone(),
+					)),
+					true,
+				);
+			},
+			_ => log::warn!(
+				target: LOG_TARGET,
+				"Unknown best block {:?}, no initial view created",
+				best_block_hash
+			),
+		}
+
+		Self {
+			api: pool_api,
+			mempool: Arc::new(TxMemPool::new(options.ready.count + options.future.count)),
+			view_store,
+			listener: Arc::new(MultiViewListener::new()),
+			ready_poll: Arc::new(Mutex::new(ReadyPoll::new())),
+			import_notification_sinks: Default::default(),
+			metrics,
+			finalized_hash: Mutex::new(finalized_hash),
+			options,
+			is_validator,
+		}
+	}
+
+	/// Get access to the underlying api
+	pub fn api(&self) -> &ChainApi {
+		&self.api
+	}
+
+	/// Returns the number of views currently kept by the pool.
+	pub fn active_views_count(&self) -> usize {
+		self.view_store.len()
+	}
+
+	/// Returns the view at the given block.
+	///
+	/// If the pool keeps no view at the block, a temporary view is created to validate the
+	/// transactions against it. Fails if the block is unknown.
+	fn view_at(&self, at: Block::Hash) -> Result<Arc<View<ChainApi>>, ChainApi::Error> {
+		if let Some(view) = self.view_store.get_view_at(&at) {
+			return Ok(view)
+		}
+
+		let number = self
+			.api
+			.block_id_to_number(&BlockId::Hash(at))?
+			.ok_or_else(|| error::Error::BlockIdConversion(format!("{:?}", at)))?;
+		Ok(Arc::new(View::new(
+			self.api.clone(),
+			HashAndNumber { hash: at, number },
+			self.options.clone(),
+			self.is_validator.clone(),
+			Default::default(),
+		)))
+	}
+
+	/// Inserts a new view, notifying the watchers about the views it supersedes.
+	fn insert_new_view(&self, view: Arc<View<ChainApi>>, make_most_recent: bool) {
+		for superseded in self.view_store.insert_new_view(view, make_most_recent) {
+			self.listener.remove_view(superseded);
+		}
+	}
+
+	/// Resolves the pending ready set requests up to the height of the most recent view.
+	fn trigger_ready_poll(&self, ready_poll: &mut ReadyPoll<ReadyIteratorFor<ChainApi>, Block>) {
+		if let Some(view) = self.view_store.most_recent_view() {
+			let number = view.at.number;
+			ready_poll.trigger(number, move || Box::new(view.pool.validated_pool().ready()));
+		}
+	}
+
+	/// Returns the hashes of the extrinsics of the given block, in order.
+	async fn block_transactions(&self, hash: Block::Hash) -> Vec<ExtrinsicHash<ChainApi>> {
+		self.api
+			.block_body(hash)
+			.await
+			.unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Error fetching body of block {:?}: {}", hash, e);
+				None
+			})
+			.unwrap_or_default()
+			.iter()
+			.map(|xt| self.api.hash_and_length(xt).0)
+			.collect()
+	}
+
+	/// Returns the hashes of the transactions included in the blocks enacted on the way from
+	/// `from` to `to`.
+	async fn included_transactions(
+		&self,
+		from: Block::Hash,
+		to: Block::Hash,
+	) -> HashSet<ExtrinsicHash<ChainApi>> {
+		let tree_route = match self.api.tree_route(from, to) {
+			Ok(tree_route) => tree_route,
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Error computing tree route from {:?} to {:?}: {}",
+					from,
+					to,
+					e
+				);
+				return Default::default()
+			},
+		};
+
+		let mut included = HashSet::new();
+		for block in tree_route.enacted() {
+			included.extend(self.block_transactions(block.hash).await);
+		}
+		included
+	}
+
+	/// Creates a view at the given block.
+	///
+	/// The view is populated with the transactions of the memory pool which were not included
+	/// in the blocks between the last finalized block and the view's block.
+	async fn build_view(&self, at: HashAndNumber<Block>) -> View<ChainApi> {
+		let view = View::new(
+			self.api.clone(),
+			at.clone(),
+			self.options.clone(),
+			self.is_validator.clone(),
+			self.metrics.clone(),
+		);
+
+		let finalized_hash = *self.finalized_hash.lock();
+		let included = self.included_transactions(finalized_hash, at.hash).await;
+
+		let mut unwatched: Vec<(TransactionSource, Vec<_>)> = Vec::new();
+		let mut watched = Vec::new();
+		for (hash, source, xt, is_watched) in
+			self.mempool.clone_transactions(|hash| !included.contains(hash))
+		{
+			if is_watched {
+				watched.push((hash, source, xt));
+			} else if let Some((_, xts)) = unwatched.iter_mut().find(|(s, _)| *s == source) {
+				xts.push(xt);
+			} else {
+				unwatched.push((source, vec![xt]));
+			}
+		}
+
+		for (source, xts) in unwatched {
+			if let Err(e) = view.submit_many(source, xts).await {
+				log::debug!(target: LOG_TARGET, "Error populating view {:?}: {}", at.hash, e);
+			}
+		}
+
+		for (hash, source, xt) in watched {
+			match view.submit_and_watch(source, xt).await {
+				Ok(watcher) => self.listener.add_view_watcher_for_tx(
+					hash,
+					at.hash,
+					watcher.into_stream().boxed(),
+				),
+				Err(e) => log::trace!(
+					target: LOG_TARGET,
+					"[{:?}] not imported into view {:?}: {}",
+					hash,
+					at.hash,
+					e
+				),
+			}
+		}
+
+		view
+	}
+
+	/// Notifies the watchers about the blocks retracted and enacted when the best block switches
+	/// from `from` to `to`.
+	async fn update_watchers(&self, from: Block::Hash, to: Block::Hash) {
+		let tree_route = match self.api.tree_route(from, to) {
+			Ok(tree_route) => tree_route,
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Error computing tree route from {:?} to {:?}: {}",
+					from,
+					to,
+					e
+				);
+				return
+			},
+		};
+
+		for retracted in tree_route.retracted() {
+			let hashes = self.block_transactions(retracted.hash).await;
+			self.listener.transactions_retracted(retracted.hash, &hashes);
+		}
+
+		let mut pruned = 0;
+		for enacted in tree_route.enacted() {
+			let hashes = self.block_transactions(enacted.hash).await;
+			pruned += hashes.len();
+			self.listener.transactions_included(enacted.hash, &hashes);
+		}
+		self.metrics
+			.report(|metrics| metrics.block_transactions_pruned.inc_by(pruned as u64));
+	}
+
+	/// Makes the view at the new best block the most recent one, creating it if needed.
+	async fn handle_new_best_block(&self, hash: Block::Hash) {
+		let previous_best = self.view_store.most_recent_view().map(|view| view.at.hash);
+		if previous_best == Some(hash) {
+			return
+		}
+
+		if let Some(view) = self.view_store.get_view_at(&hash) {
+			// The view may have been created while the block was on a fork, in which case the
+			// view of the previous best block is superseded only now.
+			let mut ready_poll = self.ready_poll.lock();
+			self.insert_new_view(view, true);
+			self.trigger_ready_poll(&mut ready_poll);
+		} else {
+			let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+				Ok(Some(number)) => number,
+				Ok(None) => {
+					log::debug!(target: LOG_TARGET, "Skipping view for unknown block {:?}", hash);
+					return
+				},
+				Err(e) => {
+					log::debug!(target: LOG_TARGET, "Skipping view for block {:?}: {}", hash, e);
+					return
+				},
+			};
+
+			let view = Arc::new(self.build_view(HashAndNumber { hash, number }).await);
+			let mut ready_poll = self.ready_poll.lock();
+			self.insert_new_view(view, true);
+			self.trigger_ready_poll(&mut ready_poll);
+		}
+
+		if let Some(previous_best) = previous_best {
+			self.update_watchers(previous_best, hash).await;
+		}
+	}
+
+	/// Creates a view at a block imported on a fork, without making it the most recent one.
+	///
+	/// No view is created at or below the finalized height, nor at a block which already has a
+	/// descendant with a view.
+	async fn handle_new_block(&self, hash: Block::Hash) {
+		if self.view_store.get_view_at(&hash).is_some() {
+			return
+		}
+
+		let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+			Ok(Some(number)) => number,
+			Ok(None) => {
+				log::debug!(target: LOG_TARGET, "Skipping view for unknown block {:?}", hash);
+				return
+			},
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Skipping view for block {:?}: {}", hash, e);
+				return
+			},
+		};
+
+		let finalized_hash = *self.finalized_hash.lock();
+		let finalized_number = self.api.block_id_to_number(&BlockId::Hash(finalized_hash));
+		if matches!(finalized_number, Ok(Some(finalized_number)) if number <= finalized_number) {
+			log::debug!(target: LOG_TARGET, "Skipping view for finalized height {:?}", hash);
+			return
+		}
+
+		let at = HashAndNumber { hash, number };
+		if !self.view_store.is_leaf(&at) {
+			log::debug!(target: LOG_TARGET, "Skipping view for non-leaf block {:?}", hash);
+			return
+		}
+
+		let view = Arc::new(self.build_view(at).await);
+		self.insert_new_view(view, false);
+	}
+
+	/// Notifies the watchers about the finalized transactions and cleans up the memory pool and
+	/// the views which are not descendants of the finalized block anymore.
+	async fn handle_finalized(&self, finalized_hash: Block::Hash, tree_route: &[Block::Hash]) {
+		let finalized_number = match self.api.block_id_to_number(&BlockId::Hash(finalized_hash)) {
+			Ok(Some(number)) => number,
+			Ok(None) => {
+				log::debug!(target: LOG_TARGET, "Unknown finalized block {:?}", finalized_hash);
+				return
+			},
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Unknown finalized block {:?}: {}", finalized_hash, e);
+				return
+			},
+		};
+
+		for block in tree_route.iter().chain(std::iter::once(&finalized_hash)) {
+			let hashes = self.block_transactions(*block).await;
+			self.listener.transactions_finalized(*block, &hashes);
+			self.mempool.remove(&hashes);
+		}
+
+		*self.finalized_hash.lock() = finalized_hash;
+		let removed = self
+			.view_store
+			.handle_finalized(&HashAndNumber { hash: finalized_hash, number: finalized_number });
+		for view_hash in removed {
+			self.listener.remove_view(view_hash);
+		}
+
+		// The finalized block may never have been reported as the best block.
+		if self.view_store.is_empty() {
+			self.handle_new_best_block(finalized_hash).await;
+		}
+
+		// The remaining views are revalidated, so that the transactions which became invalid on
+		// their forks are dropped from them.
+		self.view_store.revalidate().await;
+
+		// The requests below the finalized height will never get a view of their own. They are
+		// served by the most recent view, or by an empty set if the pool keeps no view at all.
+		{
+			let mut ready_poll = self.ready_poll.lock();
+			self.trigger_ready_poll(&mut ready_poll);
+			ready_poll.trigger(finalized_number, || Box::new(std::iter::empty()));
+		}
+
+		let view_store = &self.view_store;
+		let stale = self.mempool.remove_stale(|hash| !view_store.is_imported(hash));
+		self.listener.invalidate_transactions(&stale);
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(stale.len() as u64));
+
+		self.ready_poll.lock().remove_cancelled();
+	}
+}
+
+/// Notifies the import notification stream subscribers about the transaction.
+fn notify_imported<ChainApi: graph::ChainApi>(
+	sinks: &ImportNotificationSinks<ChainApi>,
+	hash: ExtrinsicHash<ChainApi>,
+) {
+	sinks.lock().retain_mut(|sink| match sink.try_send(hash) {
+		Ok(()) => true,
+		Err(e) =>
+			if e.is_full() {
+				log::warn!(
+					target: LOG_TARGET,
+					"[{:?}] Trying to notify an import but the channel is full",
+					hash,
+				);
+				true
+			} else {
+				false
+			},
+	});
+}
+
+impl<ChainApi, Block> TransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	type Block = ChainApi::Block;
+	type Hash = ExtrinsicHash<ChainApi>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = ChainApi::Error;
+
+	fn submit_at(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		self.metrics
+			.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
+
+		// The transactions are validated at `at`, even if the pool keeps no view there.
+		let at_view = match self.view_at(at) {
+			Ok(view) => view,
+			Err(e) => return future::ready(Err(e)).boxed(),
+		};
+
+		// Transactions rejected by the memory pool are not submitted to the views.
+		let mut results = Vec::with_capacity(xts.len());
+		let mut to_submit = Vec::new();
+		for xt in xts {
+			let hash = self.hash_of(&xt);
+			match self.mempool.push(source, hash, xt.clone(), false) {
+				Ok(()) => {
+					results.push(None);
+					to_submit.push((hash, xt));
+				},
+				Err(e) => results.push(Some(Err(e))),
+			}
+		}
+
+		let mempool = self.mempool.clone();
+		let view_store = self.view_store.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		async move {
+			let (hashes, xts): (Vec<_>, Vec<_>) = to_submit.into_iter().unzip();
+			let submitted = match view_store.submit_at(&at_view, source, xts).await {
+				Ok(submitted) => submitted,
+				Err(e) => {
+					mempool.remove(&hashes);
+					return Err(e)
+				},
+			};
+
+			let rejected = hashes
+				.iter()
+				.zip(submitted.iter())
+				.filter_map(|(hash, result)| result.is_err().then_some(*hash))
+				.collect::<Vec<_>>();
+			mempool.remove(&rejected);
+			mempool.mark_validated(&hashes);
+
+			for (hash, _) in hashes.iter().zip(submitted.iter()).filter(|(_, r)| r.is_ok()) {
+				if view_store.is_ready(hash) {
+					notify_imported(&sinks, *hash);
+				}
+			}
+
+			let mut submitted = submitted.into_iter();
+			Ok(results
+				.into_iter()
+				.map(|result| {
+					result.unwrap_or_else(|| {
+						submitted.next().expect("One result per submitted transaction; qed")
+					})
+				})
+				.collect())
+		}
+		.boxed()
+	}
+
+	fn submit_one(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		let results = self.submit_at(at, source, vec![xt]);
+
+		async move {
+			let mut results = results.await?;
+			results.pop().expect("One extrinsic passed; one result returned; qed")
+		}
+		.boxed()
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
+
+		// The transaction is validated at `at`, even if the pool keeps no view there.
+		let at_view = match self.view_at(at) {
+			Ok(view) => view,
+			Err(e) => return future::ready(Err(e)).boxed(),
+		};
+
+		let hash = self.hash_of(&xt);
+		if let Err(e) = self.mempool.push(source, hash, xt.clone(), true) {
+			return future::ready(Err(e)).boxed()
+		}
+
+		let mempool = self.mempool.clone();
+		let view_store = self.view_store.clone();
+		let listener = self.listener.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		async move {
+			let external_watcher = listener.create_external_watcher_for_tx(hash);
+
+			match view_store.submit_and_watch(&at_view, source, xt).await {
+				Ok(watchers) => {
+					for (view_hash, watcher) in watchers {
+						listener.add_view_watcher_for_tx(
+							hash,
+							view_hash,
+							watcher.into_stream().boxed(),
+						);
+					}
+					mempool.mark_validated(&[hash]);
+					if view_store.is_ready(&hash) {
+						notify_imported(&sinks, hash);
+					}
+					Ok(external_watcher)
+				},
+				Err(e) => {
+					mempool.remove(&[hash]);
+					listener.remove_watcher(&hash);
+					Err(e)
+				},
+			}
+		}
+		.boxed()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		let removed = self.view_store.remove_invalid(hashes);
+		self.mempool.remove(hashes);
+		self.listener.invalidate_transactions(hashes);
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+		removed
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.view_store
+			.most_recent_view()
+			.map(|view| view.status())
+			.unwrap_or(PoolStatus { ready: 0, ready_bytes: 0, future: 0, future_bytes: 0 })
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		const CHANNEL_BUFFER_SIZE: usize = 1024;
+
+		let (sink, stream) = channel(CHANNEL_BUFFER_SIZE);
+		self.import_notification_sinks.lock().push(sink);
+		stream
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.api.hash_and_length(xt).0
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		self.view_store.on_broadcasted(propagations)
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.view_store
+			.most_recent_view()
+			.and_then(|view| view.pool.validated_pool().ready_by_hash(hash))
+	}
+
+	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<ChainApi> {
+		// The lock is held while checking the view store, so a view inserted concurrently
+		// always triggers the request added below.
+		let mut ready_poll = self.ready_poll.lock();
+
+		if let Some(view) = self.view_store.most_recent_view().filter(|view| view.at.number >= at) {
+			let iterator: ReadyIteratorFor<ChainApi> = Box::new(view.pool.validated_pool().ready());
+			return async move { iterator }.boxed()
+		}
+
+		// Nothing at or below the finalized height can become the most recent view anymore.
+		let finalized_hash = *self.finalized_hash.lock();
+		if let Ok(Some(finalized_number)) =
+			self.api.block_id_to_number(&BlockId::Hash(finalized_hash))
+		{
+			if at <= finalized_number {
+				log::debug!(target: LOG_TARGET, "Ready set requested at finalized height {}", at);
+				return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+			}
+		}
+
+		ready_poll
+			.add(at)
+			.map(|received| {
+				received.unwrap_or_else(|e| {
+					log::warn!(target: LOG_TARGET, "Error receiving ready set: {:?}", e);
+					Box::new(std::iter::empty())
+				})
+			})
+			.boxed()
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<ChainApi> {
+		match self.view_store.most_recent_view() {
+			Some(view) => Box::new(view.pool.validated_pool().ready()),
+			None => Box::new(std::iter::empty()),
+		}
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		self.view_store
+			.most_recent_view()
+			.map(|view| view.pool.validated_pool().pool.read().futures().cloned().collect())
+			.unwrap_or_default()
+	}
+}
+
+#[async_trait]
+impl<ChainApi, Block> MaintainedTransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		match event {
+			ChainEvent::NewBestBlock { hash, .. } => self.handle_new_best_block(hash).await,
+			ChainEvent::NewBlock { hash } => self.handle_new_block(hash).await,
+			ChainEvent::Finalized { hash, tree_route } =>
+				self.handle_finalized(hash, &tree_route).await,
+		}
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Maintained: mempool: {}, status: {:?}",
+			self.mempool.len(),
+			self.status()
+		);
+	}
+}
+
+impl<Block, Client> ForkAwareTxPool<FullChainApi<Client, Block>, Block>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new fork-aware transaction pool for a full node with the provided api.
+	pub fn new_full(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
+		Arc::new(Self::new_with_options(
+			options,
+			is_validator,
+			pool_api,
+			prometheus,
+			client.usage_info().chain.best_hash,
+			client.usage_info().chain.finalized_hash,
+		))
+	}
+}
+
+impl<Block, Client> sc_transaction_pool_api::LocalTransactionPool
+	for ForkAwareTxPool<FullChainApi<Client, Block>, Block>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: sc_transaction_pool_api::LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		use graph::ChainApi;
+		use sp_runtime::{
+			traits::SaturatedConversion, transaction_validity::TransactionValidityError,
+		};
+
+		let (hash, bytes) = self.api.hash_and_length(&xt);
+		let validate_at = |at: Block::Hash, number: NumberFor<Block>| {
+			let validity = self
+				.api
+				.validate_transaction_blocking(at, TransactionSource::Local, xt.clone())?
+				.map_err(|e| {
+					Self::Error::Pool(match e {
+						TransactionValidityError::Invalid(i) => TxPoolError::InvalidTransaction(i),
+						TransactionValidityError::Unknown(u) => TxPoolError::UnknownTransaction(u),
+					})
+				})?;
+			Ok::<_, Self::Error>(ValidatedTransaction::valid_at(
+				number.saturated_into::<u64>(),
+				hash,
+				TransactionSource::Local,
+				xt.clone(),
+				bytes,
+				validity,
+			))
+		};
+
+		// The transaction must be valid at `at`, even if the pool keeps no view there.
+		let block_number = self
+			.api
+			.block_id_to_number(&BlockId::hash(at))?
+			.ok_or_else(|| error::Error::BlockIdConversion(format!("{:?}", at)))?;
+		let mut validated_at = Some(validate_at(at, block_number)?);
+
+		self.mempool.push(TransactionSource::Local, hash, xt.clone(), false)?;
+
+		// The transaction is imported into every view, validated at the view's block. Only the
+		// failure of the view at `at` is returned, the other views may reject the transaction.
+		let mut result = Ok(hash);
+		for view in self.view_store.all_views() {
+			let validated = if view.at.hash == at { validated_at.take() } else { None };
+			let view_result = validated
+				.map_or_else(|| validate_at(view.at.hash, view.at.number), Ok)
+				.and_then(|validated| view.pool.validated_pool().submit(vec![validated]).remove(0));
+			match view_result {
+				Err(e) if view.at.hash == at => result = Err(e),
+				Err(e) => log::trace!(
+					target: LOG_TARGET,
+					"[{:?}] not imported into view {:?}: {}",
+					hash,
+					view.at.hash,
+					e
+				),
+				Ok(_) => {},
+			}
+		}
+
+		match result {
+			Ok(_) => self.mempool.mark_validated(&[hash]),
+			Err(_) => self.mempool.remove(&[hash]),
+		}
+
+		result
+	}
+}
@@ -0,0 +1,49 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fork-aware transaction pool.
+//!
+//! Instead of keeping a single set of transactions revalidated against the best block, the
+//! fork-aware pool keeps a *view* for every leaf of the tracked block tree that is not older than
+//! the last finalized block. A view is a separate [`graph::Pool`](crate::graph::Pool) containing
+//! the transactions validated at the view's block, so a transaction that is only valid on a
+//! sibling fork is kept in that fork's view and can be used by block production on top of it.
+//! Views are created for the new best blocks, for the blocks imported on other forks and for the
+//! finalized block if no view follows it. The view of a block is dropped as soon as a view is
+//! created for one of its descendants, except for the view of the best block which is only
+//! replaced by the view of the next best block.
+//!
+//! Transactions are validated at the block they are submitted at, using a temporary view if the
+//! pool keeps no view there, and then imported into all the views.
+//!
+//! All submitted transactions are kept in the memory pool, which is used to populate the views
+//! of newly imported blocks. Transactions already included in the blocks between the last
+//! finalized block and the view's block never enter the view. On finalization, the remaining views
+//! are revalidated and the memory pool is cleaned up: finalized transactions are removed, as well
+//! as the transactions which are not valid in any view anymore.
+//!
+//! The events of watched transactions reported by the individual views are merged into a single
+//! stream by the multi-view listener.
+
+mod fork_aware_pool;
+mod multi_view_listener;
+mod tx_mem_pool;
+mod view;
+mod view_store;
+
+pub use fork_aware_pool::ForkAwareTxPool;
@@ -0,0 +1,337 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Multi-view listener.
+//!
+//! Every view reports the progress of a watched transaction through its own watcher. The
+//! listener merges the streams of all views into the single stream returned to the submitter,
+//! and extends it with the block related events (`InBlock`, `Retracted`, `Finalized`) which are
+//! driven by the fork-aware pool itself.
+
+use crate::{
+	graph::{self, BlockHash, ExtrinsicHash},
+	LOG_TARGET,
+};
+use futures::{
+	future::Either,
+	select_biased,
+	stream::{self, SelectAll},
+	Stream, StreamExt,
+};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{TransactionStatus, TransactionStatusStream};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+};
+
+/// The stream of transaction events returned to the submitter.
+pub(super) type TxStatusStream<ChainApi> =
+	Pin<Box<TransactionStatusStream<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>>>;
+
+/// The stream of transaction events coming from a single view.
+pub(super) type ViewStatusStream<ChainApi> = Pin<
+	Box<dyn Stream<Item = TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>> + Send>,
+>;
+
+/// Commands sent to the external watcher of a single transaction.
+enum ControllerCommand<ChainApi: graph::ChainApi> {
+	/// Start listening to the events of the transaction in a new view.
+	AddView(BlockHash<ChainApi>, ViewStatusStream<ChainApi>),
+	/// The view was removed, it does not keep the transaction valid anymore.
+	RemoveView(BlockHash<ChainApi>),
+	/// The transaction was included in the block at the given index.
+	InBlock(BlockHash<ChainApi>, usize),
+	/// The block including the transaction was retracted.
+	Retracted(BlockHash<ChainApi>),
+	/// The block including the transaction at the given index was finalized.
+	Finalized(BlockHash<ChainApi>, usize),
+	/// The transaction is not valid in any view anymore.
+	Invalidate,
+}
+
+type Controller<ChainApi> = TracingUnboundedSender<ControllerCommand<ChainApi>>;
+
+/// The state of the external watcher of a single transaction.
+struct ExternalWatcherContext<ChainApi: graph::ChainApi> {
+	/// The hash of the watched transaction.
+	tx_hash: ExtrinsicHash<ChainApi>,
+	/// The merged streams of all the views the transaction was submitted to.
+	fused: SelectAll<Pin<Box<dyn Stream<Item = ViewEvent<ChainApi>> + Send>>>,
+	/// The receiver of the commands sent by the fork-aware pool.
+	controller: TracingUnboundedReceiver<ControllerCommand<ChainApi>>,
+	/// The views in which the transaction was not reported as invalid or dropped.
+	views_keeping_tx_valid: HashSet<BlockHash<ChainApi>>,
+	/// The block in which the transaction was most recently reported as included.
+	in_block: Option<BlockHash<ChainApi>>,
+	/// The event to be emitted before any other.
+	pending: Option<TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>>,
+	ready_seen: bool,
+	future_seen: bool,
+	terminate: bool,
+}
+
+type ViewEvent<ChainApi> =
+	(BlockHash<ChainApi>, TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>);
+
+impl<ChainApi> ExternalWatcherContext<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	fn new(
+		tx_hash: ExtrinsicHash<ChainApi>,
+		controller: TracingUnboundedReceiver<ControllerCommand<ChainApi>>,
+	) -> Self {
+		Self {
+			tx_hash,
+			fused: SelectAll::new(),
+			controller,
+			views_keeping_tx_valid: Default::default(),
+			in_block: None,
+			pending: None,
+			ready_seen: false,
+			future_seen: false,
+			terminate: false,
+		}
+	}
+
+	fn add_view(&mut self, view_hash: BlockHash<ChainApi>, stream: ViewStatusStream<ChainApi>) {
+		self.views_keeping_tx_valid.insert(view_hash);
+		self.fused.push(stream.map(move |status| (view_hash, status)).boxed());
+	}
+
+	/// Handles the event reported by a single view, returning the event that shall be
+	/// forwarded to the submitter, if any.
+	fn handle_view_event(
+		&mut self,
+		view_hash: BlockHash<ChainApi>,
+		status: TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>,
+	) -> Option<TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>> {
+		log::trace!(
+			target: LOG_TARGET,
+			"[{:?}] view {:?} reported {:?}",
+			self.tx_hash,
+			view_hash,
+			status
+		);
+		match status {
+			TransactionStatus::Future if !self.ready_seen && !self.future_seen => {
+				self.future_seen = true;
+				Some(status)
+			},
+			TransactionStatus::Ready if !self.ready_seen => {
+				self.ready_seen = true;
+				Some(status)
+			},
+			TransactionStatus::Broadcast(_) => Some(status),
+			TransactionStatus::Invalid |
+			TransactionStatus::Dropped |
+			TransactionStatus::Usurped(_) => {
+				self.views_keeping_tx_valid.remove(&view_hash);
+				if self.views_keeping_tx_valid.is_empty() {
+					self.terminate = true;
+					Some(status)
+				} else {
+					None
+				}
+			},
+			// Block related events are driven by the fork-aware pool, views never see them.
+			_ => None,
+		}
+	}
+
+	/// Handles the command sent by the fork-aware pool, returning the event that shall be
+	/// forwarded to the submitter, if any.
+	fn handle_command(
+		&mut self,
+		command: ControllerCommand<ChainApi>,
+	) -> Option<TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>> {
+		match command {
+			ControllerCommand::AddView(view_hash, stream) => {
+				self.add_view(view_hash, stream);
+				None
+			},
+			ControllerCommand::RemoveView(view_hash) => {
+				self.views_keeping_tx_valid.remove(&view_hash);
+				None
+			},
+			ControllerCommand::InBlock(block_hash, index) => {
+				self.in_block = Some(block_hash);
+				Some(TransactionStatus::InBlock((block_hash, index)))
+			},
+			ControllerCommand::Retracted(block_hash) => {
+				if self.in_block == Some(block_hash) {
+					self.in_block = None;
+				}
+				Some(TransactionStatus::Retracted(block_hash))
+			},
+			ControllerCommand::Finalized(block_hash, index) => {
+				self.terminate = true;
+				let finalized = TransactionStatus::Finalized((block_hash, index));
+				// The block may be finalized without ever becoming the best block.
+				if self.in_block == Some(block_hash) {
+					Some(finalized)
+				} else {
+					self.pending = Some(finalized);
+					Some(TransactionStatus::InBlock((block_hash, index)))
+				}
+			},
+			ControllerCommand::Invalidate => {
+				self.terminate = true;
+				Some(TransactionStatus::Invalid)
+			},
+		}
+	}
+}
+
+/// Merges the events of the transactions watched in multiple views.
+pub(super) struct MultiViewListener<ChainApi: graph::ChainApi> {
+	controllers: RwLock<HashMap<ExtrinsicHash<ChainApi>, Controller<ChainApi>>>,
+}
+
+impl<ChainApi> MultiViewListener<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// Creates a new listener.
+	pub(super) fn new() -> Self {
+		Self { controllers: Default::default() }
+	}
+
+	/// Creates the stream returned to the submitter of the watched transaction.
+	///
+	/// The stream is fed by the views added with [`Self::add_view_watcher_for_tx`] and by the
+	/// block related notifications.
+	pub(super) fn create_external_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+	) -> TxStatusStream<ChainApi> {
+		let (sender, receiver) = tracing_unbounded("mpsc_txpool_multi_view_listener", 100_000);
+		self.controllers.write().insert(tx_hash, sender);
+
+		let context = ExternalWatcherContext::new(tx_hash, receiver);
+		stream::unfold(context, |mut ctx| async move {
+			if let Some(status) = ctx.pending.take() {
+				return Some((status, ctx))
+			}
+			if ctx.terminate {
+				return None
+			}
+			loop {
+				// The events already reported by the views precede the commands of the pool.
+				let next = select_biased! {
+					event = ctx.fused.next() => Either::Right(event),
+					command = ctx.controller.next() => Either::Left(command),
+					complete => return None,
+				};
+				let status = match next {
+					Either::Left(Some(command)) => ctx.handle_command(command),
+					// The fork-aware pool is gone, no more events will arrive.
+					Either::Left(None) => return None,
+					Either::Right(Some((view_hash, status))) =>
+						ctx.handle_view_event(view_hash, status),
+					// All the views were dropped, wait for new views or commands.
+					Either::Right(None) => None,
+				};
+				if let Some(status) = status {
+					return Some((status, ctx))
+				}
+			}
+		})
+		.boxed()
+	}
+
+	/// Starts forwarding the events of the transaction reported by the given view.
+	pub(super) fn add_view_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+		view_hash: BlockHash<ChainApi>,
+		stream: ViewStatusStream<ChainApi>,
+	) {
+		self.send(&tx_hash, ControllerCommand::AddView(view_hash, stream));
+	}
+
+	/// Notifies all the watchers that the view at the given block was removed.
+	pub(super) fn remove_view(&self, view_hash: BlockHash<ChainApi>) {
+		for controller in self.controllers.read().values() {
+			let _ = controller.unbounded_send(ControllerCommand::RemoveView(view_hash));
+		}
+	}
+
+	/// Stops watching the given transaction.
+	pub(super) fn remove_watcher(&self, tx_hash: &ExtrinsicHash<ChainApi>) {
+		self.controllers.write().remove(tx_hash);
+	}
+
+	/// Notifies the watchers about the transactions included in the block.
+	///
+	/// `tx_hashes` are the hashes of all the extrinsics of the block, in order.
+	pub(super) fn transactions_included(
+		&self,
+		block_hash: BlockHash<ChainApi>,
+		tx_hashes: &[ExtrinsicHash<ChainApi>],
+	) {
+		for (index, tx_hash) in tx_hashes.iter().enumerate() {
+			self.send(tx_hash, ControllerCommand::InBlock(block_hash, index));
+		}
+	}
+
+	/// Notifies the watchers about the retraction of the block including their transactions.
+	pub(super) fn transactions_retracted(
+		&self,
+		block_hash: BlockHash<ChainApi>,
+		tx_hashes: &[ExtrinsicHash<ChainApi>],
+	) {
+		for tx_hash in tx_hashes {
+			self.send(tx_hash, ControllerCommand::Retracted(block_hash));
+		}
+	}
+
+	/// Notifies the watchers about the finalization of the block including their transactions.
+	///
+	/// `tx_hashes` are the hashes of all the extrinsics of the block, in order.
+	pub(super) fn transactions_finalized(
+		&self,
+		block_hash: BlockHash<ChainApi>,
+		tx_hashes: &[ExtrinsicHash<ChainApi>],
+	) {
+		for (index, tx_hash) in tx_hashes.iter().enumerate() {
+			self.send(tx_hash, ControllerCommand::Finalized(block_hash, index));
+			self.remove_watcher(tx_hash);
+		}
+	}
+
+	/// Notifies the watchers that their transactions are not valid in any view.
+	pub(super) fn invalidate_transactions(&self, tx_hashes: &[ExtrinsicHash<ChainApi>]) {
+		for tx_hash in tx_hashes {
+			self.send(tx_hash, ControllerCommand::Invalidate);
+			self.remove_watcher(tx_hash);
+		}
+	}
+
+	fn send(&self, tx_hash: &ExtrinsicHash<ChainApi>, command: ControllerCommand<ChainApi>) {
+		let mut controllers = self.controllers.write();
+		if let Some(controller) = controllers.get(tx_hash) {
+			if controller.unbounded_send(command).is_err() {
+				log::trace!(target: LOG_TARGET, "[{:?}] external watcher dropped", tx_hash);
+				controllers.remove(tx_hash);
+			}
+		}
+	}
+}
@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction memory pool.
+//!
+//! The memory pool holds every transaction submitted to the fork-aware pool, regardless of the
+//! fork it is valid on. It is the source of transactions for newly created views. Transactions
+//! leave the memory pool when they are finalized or when no view considers them valid anymore.
+
+use crate::graph::{self, ExtrinsicFor, ExtrinsicHash};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{error, TransactionSource};
+use std::collections::HashMap;
+
+/// A transaction kept in the memory pool.
+struct TxInMemPool<ChainApi: graph::ChainApi> {
+	/// Is the progress of the transaction watched by the submitter.
+	watched: bool,
+	/// The source of the transaction.
+	source: TransactionSource,
+	/// The extrinsic.
+	tx: ExtrinsicFor<ChainApi>,
+	/// Was the transaction already submitted to the views existing at the time it was received.
+	///
+	/// Transactions that are not yet validated are never considered stale.
+	validated: bool,
+}
+
+/// Pool of all transactions known to the fork-aware pool.
+pub(super) struct TxMemPool<ChainApi: graph::ChainApi> {
+	transactions: RwLock<HashMap<ExtrinsicHash<ChainApi>, TxInMemPool<ChainApi>>>,
+	max_transactions_count: usize,
+}
+
+impl<ChainApi> TxMemPool<ChainApi>
+where
+	ChainApi: graph::ChainApi,
+{
+	/// Creates a new memory pool holding at most `max_transactions_count` transactions.
+	pub(super) fn new(max_transactions_count: usize) -> Self {
+		Self { transactions: Default::default(), max_transactions_count }
+	}
+
+	/// Adds a transaction to the memory pool.
+	///
+	/// Fails if the transaction is already known or if the memory pool is full.
+	pub(super) fn push(
+		&self,
+		source: TransactionSource,
+		hash: ExtrinsicHash<ChainApi>,
+		tx: ExtrinsicFor<ChainApi>,
+		watched: bool,
+	) -> Result<(), ChainApi::Error> {
+		let mut transactions = self.transactions.write();
+		if transactions.contains_key(&hash) {
+			return Err(error::Error::AlreadyImported(Box::new(hash)).into())
+		}
+		if transactions.len() >= self.max_transactions_count {
+			return Err(error::Error::ImmediatelyDropped.into())
+		}
+		transactions.insert(hash, TxInMemPool { watched, source, tx, validated: false });
+		Ok(())
+	}
+
+	/// Marks the given transactions as submitted to the views.
+	pub(super) fn mark_validated(&self, hashes: &[ExtrinsicHash<ChainApi>]) {
+		let mut transactions = self.transactions.write();
+		for hash in hashes {
+			if let Some(tx) = transactions.get_mut(hash) {
+				tx.validated = true;
+			}
+		}
+	}
+
+	/// Removes the given transactions from the memory pool.
+	pub(super) fn remove(&self, hashes: &[ExtrinsicHash<ChainApi>]) {
+		let mut transactions = self.transactions.write();
+		for hash in hashes {
+			transactions.remove(hash);
+		}
+	}
+
+	/// Removes all validated transactions for which `is_stale` returns true.
+	///
+	/// Returns the hashes of the removed transactions.
+	pub(super) fn remove_stale(
+		&self,
+		is_stale: impl Fn(&ExtrinsicHash<ChainApi>) -> bool,
+	) -> Vec<ExtrinsicHash<ChainApi>> {
+		let mut removed = Vec::new();
+		self.transactions.write().retain(|hash, tx| {
+			let stale = tx.validated && is_stale(hash);
+			if stale {
+				removed.push(*hash);
+			}
+			!stale
+		});
+		removed
+	}
+
+	/// Returns a copy of all transactions, except the ones rejected by `filter`.
+	pub(super) fn clone_transactions(
+		&self,
+		filter: impl Fn(&ExtrinsicHash<ChainApi>) -> bool,
+	) -> Vec<(ExtrinsicHash<ChainApi>, TransactionSource, ExtrinsicFor<ChainApi>, bool)> {
+		self.transactions
+			.read()
+			.iter()
+			.filter(|(hash, _)| filter(hash))
+			.map(|(hash, tx)| (*hash, tx.source, tx.tx.clone(), tx.watched))
+			.collect()
+	}
+
+	/// Returns the number of transactions in the memory pool.
+	pub(super) fn len(&self) -> usize {
+		self.transactions.read().len()
+	}
+}
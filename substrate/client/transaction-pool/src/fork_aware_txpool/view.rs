@@ -0,0 +1,95 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction pool view.
+//!
+//! A view represents the state of the transaction pool at the given block. It owns a separate
+//! [`graph::Pool`] which contains the mempool transactions validated against the view's block.
+
+use crate::{
+	graph::{self, watcher::Watcher, ExtrinsicFor, ExtrinsicHash, IsValidator},
+	metrics::MetricsLink,
+	revalidation,
+};
+use sc_transaction_pool_api::{PoolStatus, TransactionSource};
+use sp_blockchain::HashAndNumber;
+use std::sync::Arc;
+
+/// The state of the transaction pool at the given block.
+pub(super) struct View<ChainApi: graph::ChainApi> {
+	/// The transactions validated at the view's block.
+	pub(super) pool: graph::Pool<ChainApi>,
+	/// The block at which the view was created.
+	pub(super) at: HashAndNumber<ChainApi::Block>,
+}
+
+impl<ChainApi> View<ChainApi>
+where
+	ChainApi: graph::ChainApi,
+{
+	/// Creates a new, empty view at the given block.
+	///
+	/// The transactions evicted from the view's pool are reported to `metrics`.
+	pub(super) fn new(
+		api: Arc<ChainApi>,
+		at: HashAndNumber<ChainApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+		metrics: MetricsLink,
+	) -> Self {
+		Self { pool: graph::Pool::new_with_metrics(options, is_validator, api, metrics), at }
+	}
+
+	/// Imports a bunch of unverified extrinsics into the view.
+	pub(super) async fn submit_many(
+		&self,
+		source: TransactionSource,
+		xts: impl IntoIterator<Item = ExtrinsicFor<ChainApi>>,
+	) -> Result<Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>>, ChainApi::Error> {
+		self.pool.submit_at(self.at.hash, source, xts).await
+	}
+
+	/// Imports a single extrinsic into the view and starts watching its progress.
+	pub(super) async fn submit_and_watch(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> Result<Watcher<ExtrinsicHash<ChainApi>, ExtrinsicHash<ChainApi>>, ChainApi::Error> {
+		self.pool.submit_and_watch(self.at.hash, source, xt).await
+	}
+
+	/// Revalidates the ready transactions of the view at the view's block.
+	///
+	/// The transactions which became invalid are removed from the view, the others are
+	/// resubmitted with their refreshed validity.
+	pub(super) async fn revalidate(&self) {
+		let validated_pool = self.pool.validated_pool();
+		let hashes = validated_pool.ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		revalidation::batch_revalidate(&self.pool, validated_pool.api(), self.at.hash, hashes).await
+	}
+
+	/// Returns true if the transaction is in the ready or future queue of the view.
+	pub(super) fn is_imported(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.pool.validated_pool().pool.read().is_imported(hash)
+	}
+
+	/// Returns the status of the view.
+	pub(super) fn status(&self) -> PoolStatus {
+		self.pool.validated_pool().status()
+	}
+}
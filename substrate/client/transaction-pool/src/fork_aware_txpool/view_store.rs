@@ -0,0 +1,317 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction pool view store.
+//!
+//! Keeps the views of the fork-aware pool, one for every leaf of the tracked block tree that is
+//! not older than the last finalized block, and dispatches the requests to all of them.
+
+use super::view::View;
+use crate::{
+	graph::{self, watcher::Watcher, BlockHash, ExtrinsicFor, ExtrinsicHash, TransactionFor},
+	LOG_TARGET,
+};
+use futures::future;
+use parking_lot::RwLock;
+use sc_transaction_pool_api::TransactionSource;
+use sp_blockchain::HashAndNumber;
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+/// The views of the fork-aware pool.
+pub(super) struct ViewStore<ChainApi: graph::ChainApi> {
+	api: Arc<ChainApi>,
+	views: RwLock<HashMap<BlockHash<ChainApi>, Arc<View<ChainApi>>>>,
+	most_recent_view: RwLock<Option<BlockHash<ChainApi>>>,
+}
+
+impl<ChainApi> ViewStore<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// Creates an empty view store.
+	pub(super) fn new(api: Arc<ChainApi>) -> Self {
+		Self { api, views: Default::default(), most_recent_view: Default::default() }
+	}
+
+	/// Returns all the views, the most recent one first.
+	pub(super) fn all_views(&self) -> Vec<Arc<View<ChainApi>>> {
+		let most_recent = *self.most_recent_view.read();
+		let mut views = self.views.read().values().cloned().collect::<Vec<_>>();
+		views.sort_by_key(|view| Some(view.at.hash) != most_recent);
+		views
+	}
+
+	/// Returns all the views except the given one, the most recent one first.
+	fn other_views(&self, view: &Arc<View<ChainApi>>) -> Vec<Arc<View<ChainApi>>> {
+		let mut views = self.all_views();
+		views.retain(|other| !Arc::ptr_eq(other, view));
+		views
+	}
+
+	/// Returns true if the given view is kept in the store.
+	fn contains(&self, view: &Arc<View<ChainApi>>) -> bool {
+		self.views
+			.read()
+			.get(&view.at.hash)
+			.map_or(false, |stored| Arc::ptr_eq(stored, view))
+	}
+
+	/// Imports a bunch of unverified extrinsics into every view.
+	///
+	/// The extrinsics are validated in `at_view` first, which may be a temporary view outside of
+	/// the store. Its results are returned, and only the extrinsics it accepted are imported into
+	/// the other views; the failures reported by the other views are not reported to the caller.
+	pub(super) async fn submit_at(
+		&self,
+		at_view: &Arc<View<ChainApi>>,
+		source: TransactionSource,
+		xts: Vec<ExtrinsicFor<ChainApi>>,
+	) -> Result<Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>>, ChainApi::Error> {
+		let results = at_view.submit_many(source, xts.iter().cloned()).await?;
+		let accepted = xts
+			.into_iter()
+			.zip(results.iter())
+			.filter_map(|(xt, result)| result.is_ok().then_some(xt))
+			.collect::<Vec<_>>();
+
+		let views = self.other_views(at_view);
+		let view_results = future::join_all(
+			views.iter().map(|view| view.submit_many(source, accepted.iter().cloned())),
+		)
+		.await;
+		for (view, result) in views.iter().zip(view_results) {
+			if let Err(e) = result {
+				log::debug!(
+					target: LOG_TARGET,
+					"Submission to view {:?} failed: {}",
+					view.at.hash,
+					e
+				);
+			}
+		}
+
+		Ok(results)
+	}
+
+	/// Imports a single extrinsic into every view and starts watching its progress.
+	///
+	/// The extrinsic is validated in `at_view` first, which may be a temporary view outside of
+	/// the store, and its failure is returned. Otherwise the watchers of all the views which
+	/// imported the extrinsic are returned.
+	pub(super) async fn submit_and_watch(
+		&self,
+		at_view: &Arc<View<ChainApi>>,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> Result<
+		Vec<(BlockHash<ChainApi>, Watcher<ExtrinsicHash<ChainApi>, ExtrinsicHash<ChainApi>>)>,
+		ChainApi::Error,
+	> {
+		let mut watchers = Vec::new();
+		if self.contains(at_view) {
+			let watcher = at_view.submit_and_watch(source, xt.clone()).await?;
+			watchers.push((at_view.at.hash, watcher));
+		} else {
+			at_view.submit_many(source, std::iter::once(xt.clone())).await?.remove(0)?;
+		}
+
+		let views = self.other_views(at_view);
+		let results =
+			future::join_all(views.iter().map(|view| view.submit_and_watch(source, xt.clone())))
+				.await;
+		for (view, result) in views.iter().zip(results) {
+			match result {
+				Ok(watcher) => watchers.push((view.at.hash, watcher)),
+				Err(e) => log::trace!(
+					target: LOG_TARGET,
+					"Submission to view {:?} failed: {}",
+					view.at.hash,
+					e
+				),
+			}
+		}
+
+		Ok(watchers)
+	}
+
+	/// Returns the view at the given block, if any.
+	pub(super) fn get_view_at(&self, hash: &BlockHash<ChainApi>) -> Option<Arc<View<ChainApi>>> {
+		self.views.read().get(hash).cloned()
+	}
+
+	/// Returns the view at the most recent best block, if any.
+	pub(super) fn most_recent_view(&self) -> Option<Arc<View<ChainApi>>> {
+		let most_recent = (*self.most_recent_view.read())?;
+		self.get_view_at(&most_recent)
+	}
+
+	/// Marks the view at the given block as the most recent one.
+	fn set_most_recent_view(&self, hash: BlockHash<ChainApi>) {
+		*self.most_recent_view.write() = Some(hash);
+	}
+
+	/// Inserts a new view, marking it as the most recent one if `make_most_recent` is set.
+	///
+	/// The views at the ancestors of the new view's block are not leaves anymore and get removed,
+	/// except for the most recent view which is only replaced by a new most recent view. Returns
+	/// the blocks of the removed views.
+	pub(super) fn insert_new_view(
+		&self,
+		view: Arc<View<ChainApi>>,
+		make_most_recent: bool,
+	) -> Vec<BlockHash<ChainApi>> {
+		let at = view.at.clone();
+		let most_recent = *self.most_recent_view.read();
+		let mut views = self.views.write();
+		let superseded = views
+			.values()
+			.filter(|other| make_most_recent || Some(other.at.hash) != most_recent)
+			.filter(|other| other.at.number < at.number && self.is_ancestor(other.at.hash, at.hash))
+			.map(|other| other.at.hash)
+			.collect::<Vec<_>>();
+		for hash in &superseded {
+			views.remove(hash);
+		}
+		views.insert(at.hash, view);
+		if make_most_recent {
+			self.set_most_recent_view(at.hash);
+		}
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Inserted view {:?}, superseded views: {:?}",
+			at.hash,
+			superseded
+		);
+		superseded
+	}
+
+	/// Returns true if no view is kept at a descendant of the given block.
+	pub(super) fn is_leaf(&self, at: &HashAndNumber<ChainApi::Block>) -> bool {
+		!self
+			.views
+			.read()
+			.values()
+			.any(|view| view.at.number > at.number && self.is_ancestor(at.hash, view.at.hash))
+	}
+
+	/// Revalidates the transactions of every view.
+	pub(super) async fn revalidate(&self) {
+		future::join_all(self.all_views().iter().map(|view| view.revalidate())).await;
+	}
+
+	/// Returns true if `ancestor` is an ancestor of `hash`, i.e. no block is retracted on the way
+	/// from `ancestor` to `hash`.
+	fn is_ancestor(&self, ancestor: BlockHash<ChainApi>, hash: BlockHash<ChainApi>) -> bool {
+		match self.api.tree_route(ancestor, hash) {
+			Ok(tree_route) => tree_route.retracted().is_empty(),
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Error computing tree route from {:?} to {:?}: {}",
+					ancestor,
+					hash,
+					e
+				);
+				false
+			},
+		}
+	}
+
+	/// Returns true if there are no views.
+	pub(super) fn is_empty(&self) -> bool {
+		self.views.read().is_empty()
+	}
+
+	/// Returns the number of views.
+	pub(super) fn len(&self) -> usize {
+		self.views.read().len()
+	}
+
+	/// Returns true if the transaction is in the ready or future queue of any view.
+	pub(super) fn is_imported(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.views.read().values().any(|view| view.is_imported(hash))
+	}
+
+	/// Returns true if the transaction is in the ready queue of any view.
+	pub(super) fn is_ready(&self, hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.views
+			.read()
+			.values()
+			.any(|view| view.pool.validated_pool().ready_by_hash(hash).is_some())
+	}
+
+	/// Removes the given transactions and their dependencies from every view.
+	///
+	/// Returns the removed transactions, without duplicates.
+	pub(super) fn remove_invalid(
+		&self,
+		hashes: &[ExtrinsicHash<ChainApi>],
+	) -> Vec<TransactionFor<ChainApi>> {
+		let mut seen = HashSet::new();
+		self.views
+			.read()
+			.values()
+			.flat_map(|view| view.pool.validated_pool().remove_invalid(hashes))
+			.filter(|tx| seen.insert(tx.hash))
+			.collect()
+	}
+
+	/// Notifies every view about the broadcasted transactions.
+	pub(super) fn on_broadcasted(&self, propagated: HashMap<ExtrinsicHash<ChainApi>, Vec<String>>) {
+		for view in self.views.read().values() {
+			view.pool.validated_pool().on_broadcasted(propagated.clone());
+		}
+	}
+
+	/// Removes the views which are not descendants of the finalized block.
+	///
+	/// The view at the finalized block itself is kept. If the most recent view gets removed,
+	/// the remaining view at the highest block becomes the most recent one. Returns the blocks of
+	/// the removed views.
+	pub(super) fn handle_finalized(
+		&self,
+		finalized: &HashAndNumber<ChainApi::Block>,
+	) -> Vec<BlockHash<ChainApi>> {
+		let mut views = self.views.write();
+		let mut removed = Vec::new();
+		views.retain(|hash, view| {
+			let keep = if view.at.number <= finalized.number {
+				*hash == finalized.hash
+			} else {
+				self.is_ancestor(finalized.hash, *hash)
+			};
+			if !keep {
+				removed.push(*hash);
+			}
+			keep
+		});
+
+		let mut most_recent_view = self.most_recent_view.write();
+		if !matches!(*most_recent_view, Some(hash) if views.contains_key(&hash)) {
+			*most_recent_view =
+				views.values().max_by_key(|view| view.at.number).map(|view| view.at.hash);
+		}
+
+		log::debug!(target: LOG_TARGET, "Views after finalization: {}", views.len());
+		removed
+	}
+}
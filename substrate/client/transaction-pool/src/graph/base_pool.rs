@@ -385,7 +385,10 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	/// Removes and returns worst transactions from the queues and all transactions that depend on
 	/// them. Technically the worst transaction should be evaluated by computing the entire pending
 	/// set. We use a simplified approach to remove transactions with the lowest priority first or
-	/// those that occupy the pool for the longest time in case priority is the same.
+	/// those that occupy the pool for the longest time in case priority is the same. This applies
+	/// to both queues, so a transaction with a higher priority entering a full pool evicts the
+	/// lowest priority transactions (along with their dependencies), while a transaction with a
+	/// lower priority than everything else in the queue is evicted itself.
 	pub fn enforce_limits(
 		&mut self,
 		ready: &Limit,
@@ -428,7 +431,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			// find the worst transaction
 			let worst = self.future.fold(|worst, current| match worst {
 				None => Some(current.clone()),
-				Some(ref tx) if tx.imported_at > current.imported_at => Some(current.clone()),
+				Some(ref tx)
+					if tx.transaction.priority > current.transaction.priority ||
+						(tx.transaction.priority == current.transaction.priority &&
+							tx.imported_at > current.imported_at) =>
+					Some(current.clone()),
 				other => other,
 			});
 
@@ -442,6 +449,96 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Makes room in the full queue `tx` would be imported to, by evicting transactions with a
+	/// strictly lower priority, no matter which account sent them.
+	///
+	/// Transactions are evicted starting from the lowest priority (the oldest one first in case
+	/// priority is the same) along with all transactions that depend on them, until `tx` fits
+	/// within the limit. Nothing is evicted if `tx` replaces a ready transaction providing the
+	/// same tag, as such a replacement does not grow the queue. If even evicting all the lower
+	/// priority transactions would not make enough room, nothing is evicted and the transaction is
+	/// left to [`Self::enforce_limits`] after the import.
+	///
+	/// Returns the evicted transactions.
+	pub fn make_room_for(
+		&mut self,
+		tx: &Transaction<Hash, Ex>,
+		ready: &Limit,
+		future: &Limit,
+	) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		if self.is_imported(&tx.hash) {
+			return vec![]
+		}
+
+		let provided = self.ready.provided_tags();
+		let to_ready = tx.requires.iter().all(|tag| {
+			provided.contains_key(tag) || self.recently_pruned.iter().any(|x| x.contains(tag))
+		});
+		if to_ready && tx.provides.iter().any(|tag| provided.contains_key(tag)) {
+			return vec![]
+		}
+
+		let (limit, count, bytes) = if to_ready {
+			(ready, self.ready.len(), self.ready.bytes())
+		} else {
+			if self.reject_future_transactions {
+				return vec![]
+			}
+			(future, self.future.len(), self.future.bytes())
+		};
+		let (mut count, mut bytes) = (count + 1, bytes + tx.bytes);
+		if !limit.is_exceeded(count, bytes) {
+			return vec![]
+		}
+
+		// Transactions that could be evicted, in eviction order. They are only collected once the
+		// queue is known to be full.
+		let candidates = if to_ready {
+			let mut candidates = self
+				.ready
+				.fold::<Vec<TransactionRef<Hash, Ex>>, _>(|acc, current| {
+					let mut acc = acc.unwrap_or_default();
+					if current.transaction.transaction.priority < tx.priority {
+						acc.push(current.transaction.clone());
+					}
+					Some(acc)
+				})
+				.unwrap_or_default();
+			candidates.sort_by_key(|tx| (tx.transaction.priority, tx.insertion_id));
+			candidates.into_iter().map(|tx| tx.transaction).collect::<Vec<_>>()
+		} else {
+			let mut candidates = self
+				.future
+				.fold::<Vec<WaitingTransaction<Hash, Ex>>, _>(|acc, current| {
+					let mut acc = acc.unwrap_or_default();
+					if current.transaction.priority < tx.priority {
+						acc.push(current.clone());
+					}
+					Some(acc)
+				})
+				.unwrap_or_default();
+			candidates.sort_by_key(|tx| (tx.transaction.priority, tx.imported_at));
+			candidates.into_iter().map(|tx| tx.transaction).collect::<Vec<_>>()
+		};
+
+		// Evicting a transaction also evicts its dependencies, so the room made by evicting the
+		// candidates alone is the lower bound of what is actually freed.
+		let mut to_evict = vec![];
+		for candidate in candidates {
+			if !limit.is_exceeded(count, bytes) {
+				break
+			}
+			count -= 1;
+			bytes = bytes.saturating_sub(candidate.bytes);
+			to_evict.push(candidate.hash.clone());
+		}
+		if limit.is_exceeded(count, bytes) {
+			return vec![]
+		}
+
+		self.remove_subtree(&to_evict)
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -1042,4 +1139,182 @@ source: TransactionSource::External, requires: [03, 02], provides: [04], data: [
 		assert_eq!(pool.reject_future_transactions, true);
 		assert_eq!(pool.future.len(), 1);
 	}
+
+	#[test]
+	fn should_evict_lowest_priority_future_transaction() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 2, total_bytes: 1000 };
+		pool.import(Transaction {
+			data: vec![1u8],
+			hash: 1,
+			priority: 10u64,
+			requires: vec![vec![0]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		pool.import(Transaction {
+			data: vec![2u8],
+			hash: 2,
+			priority: 1u64,
+			requires: vec![vec![0]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+
+		// when
+		pool.import(Transaction {
+			data: vec![3u8],
+			hash: 3,
+			priority: 5u64,
+			requires: vec![vec![0]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.future.len(), 2);
+		assert!(pool.future.contains(&1));
+		assert!(pool.future.contains(&3));
+	}
+
+	#[test]
+	fn should_evict_lowest_priority_ready_transaction_with_dependencies() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 2, total_bytes: 1000 };
+		pool.import(Transaction {
+			data: vec![1u8],
+			hash: 1,
+			priority: 1u64,
+			provides: vec![vec![1]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		pool.import(Transaction {
+			data: vec![2u8],
+			hash: 2,
+			priority: 10u64,
+			requires: vec![vec![1]],
+			provides: vec![vec![2]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+
+		// when
+		pool.import(Transaction {
+			data: vec![3u8],
+			hash: 3,
+			priority: 5u64,
+			provides: vec![vec![3]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		let mut removed = removed.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![1, 2]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+	}
+
+	#[test]
+	fn should_make_room_by_evicting_lower_priority_transactions_of_other_senders() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 2, total_bytes: 1000 };
+		pool.import(Transaction {
+			data: vec![1u8],
+			hash: 1,
+			priority: 1u64,
+			provides: vec![vec![1]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		pool.import(Transaction {
+			data: vec![2u8],
+			hash: 2,
+			priority: 3u64,
+			provides: vec![vec![2]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let tx = Transaction {
+			data: vec![3u8],
+			hash: 3,
+			priority: 5u64,
+			provides: vec![vec![3]],
+			..DEFAULT_TX.clone()
+		};
+
+		// when
+		let removed = pool.make_room_for(&tx, &limit, &limit);
+		pool.import(tx).unwrap();
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3, 2]);
+	}
+
+	#[test]
+	fn should_not_make_room_for_transaction_without_higher_priority() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 1, total_bytes: 1000 };
+		pool.import(Transaction {
+			data: vec![1u8],
+			hash: 1,
+			priority: 5u64,
+			provides: vec![vec![1]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let tx = Transaction {
+			data: vec![2u8],
+			hash: 2,
+			priority: 5u64,
+			provides: vec![vec![2]],
+			..DEFAULT_TX.clone()
+		};
+
+		// when
+		let removed = pool.make_room_for(&tx, &limit, &limit);
+
+		// then
+		assert!(removed.is_empty());
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+	}
+
+	#[test]
+	fn should_not_make_room_for_replacement_of_same_tag() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 1, total_bytes: 1000 };
+		pool.import(Transaction {
+			data: vec![1u8],
+			hash: 1,
+			priority: 1u64,
+			provides: vec![vec![1]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let tx = Transaction {
+			data: vec![2u8],
+			hash: 2,
+			priority: 5u64,
+			provides: vec![vec![1]],
+			..DEFAULT_TX.clone()
+		};
+
+		// when
+		let removed = pool.make_room_for(&tx, &limit, &limit);
+		pool.import(tx).unwrap();
+
+		// then
+		assert!(removed.is_empty());
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+	}
 }
@@ -18,7 +18,7 @@
 
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::LOG_TARGET;
+use crate::{metrics::MetricsLink, TransactionPoolType, LOG_TARGET};
 use futures::{channel::mpsc::Receiver, Future};
 use sc_transaction_pool_api::error;
 use sp_blockchain::TreeRoute;
@@ -119,6 +119,8 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// The type of the pool built by [`TransactionPoolWrapper`](crate::TransactionPoolWrapper).
+	pub pool_type: TransactionPoolType,
 }
 
 impl Default for Options {
@@ -128,6 +130,7 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			pool_type: Default::default(),
 		}
 	}
 }
@@ -151,6 +154,23 @@ impl<B: ChainApi> Pool<B> {
 		Self { validated_pool: Arc::new(ValidatedPool::new(options, is_validator, api)) }
 	}
 
+	/// Create a new transaction pool reporting the evicted transactions to the given metrics.
+	pub(crate) fn new_with_metrics(
+		options: Options,
+		is_validator: IsValidator,
+		api: Arc<B>,
+		metrics: MetricsLink,
+	) -> Self {
+		Self {
+			validated_pool: Arc::new(ValidatedPool::new_with_metrics(
+				options,
+				is_validator,
+				api,
+				metrics,
+			)),
+		}
+	}
+
 	/// Imports a bunch of unverified extrinsics to the pool
 	pub async fn submit_at(
 		&self,
@@ -22,7 +22,7 @@ use std::{
 	sync::Arc,
 };
 
-use crate::LOG_TARGET;
+use crate::{metrics::MetricsLink, LOG_TARGET};
 use futures::channel::mpsc::{channel, Sender};
 use parking_lot::{Mutex, RwLock};
 use sc_transaction_pool_api::{error, PoolStatus, ReadyTransactions};
@@ -86,17 +86,18 @@ pub type ValidatedTransactionFor<B> =
 	ValidatedTransaction<ExtrinsicHash<B>, ExtrinsicFor<B>, <B as ChainApi>::Error>;
 
 /// A closure that returns true if the local node is a validator that can author blocks.
-pub struct IsValidator(Box<dyn Fn() -> bool + Send + Sync>);
+#[derive(Clone)]
+pub struct IsValidator(Arc<dyn Fn() -> bool + Send + Sync>);
 
 impl From<bool> for IsValidator {
 	fn from(is_validator: bool) -> Self {
-		Self(Box::new(move || is_validator))
+		Self(Arc::new(move || is_validator))
 	}
 }
 
 impl From<Box<dyn Fn() -> bool + Send + Sync>> for IsValidator {
 	fn from(is_validator: Box<dyn Fn() -> bool + Send + Sync>) -> Self {
-		Self(is_validator)
+		Self(is_validator.into())
 	}
 }
 
@@ -109,11 +110,22 @@ pub struct ValidatedPool<B: ChainApi> {
 	pub(crate) pool: RwLock<base::BasePool<ExtrinsicHash<B>, ExtrinsicFor<B>>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
 	rotator: PoolRotator<ExtrinsicHash<B>>,
+	metrics: MetricsLink,
 }
 
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, is_validator: IsValidator, api: Arc<B>) -> Self {
+		Self::new_with_metrics(options, is_validator, api, Default::default())
+	}
+
+	/// Create a new transaction pool reporting the evicted transactions to the given metrics.
+	pub(crate) fn new_with_metrics(
+		options: Options,
+		is_validator: IsValidator,
+		api: Arc<B>,
+		metrics: MetricsLink,
+	) -> Self {
 		let base_pool = base::BasePool::new(options.reject_future_transactions);
 		let ban_time = options.ban_time;
 		Self {
@@ -124,6 +136,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
 			rotator: PoolRotator::new(ban_time),
+			metrics,
 		}
 	}
 
@@ -191,8 +204,44 @@ impl<B: ChainApi> ValidatedPool<B> {
 					return Err(error::Error::Unactionable.into())
 				}
 
-				let imported = self.pool.write().import(tx)?;
+				let hash = tx.hash;
+				let (imported, evicted) = {
+					let mut pool = self.pool.write();
+					let evicted =
+						pool.make_room_for(&tx, &self.options.ready, &self.options.future);
+					match pool.import(tx) {
+						Ok(imported) => (Ok(imported), evicted),
+						// The room was made in vain, the evicted transactions are put back. Those
+						// which cannot be are reported as evicted.
+						Err(e) => {
+							let lost = evicted
+								.into_iter()
+								.filter(|evicted| pool.import(evicted.duplicate()).is_err())
+								.collect::<Vec<_>>();
+							(Err(e), lost)
+						},
+					}
+				};
+
+				if !evicted.is_empty() {
+					let evicted = evicted.into_iter().map(|x| x.hash).collect::<Vec<_>>();
+					log::debug!(
+						target: LOG_TARGET,
+						"[{:?}] Evicted {} lower priority transactions",
+						hash,
+						evicted.len(),
+					);
+					self.rotator.ban(&Instant::now(), evicted.iter().copied());
+					self.metrics.report(|metrics| {
+						metrics.transactions_evicted.inc_by(evicted.len() as u64)
+					});
+					let mut listener = self.listener.write();
+					for h in &evicted {
+						listener.dropped(h, None);
+					}
+				}
 
+				let imported = imported?;
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					let sinks = &mut self.import_notification_sinks.lock();
 					sinks.retain_mut(|sink| match sink.try_send(*hash) {
@@ -258,6 +307,8 @@ impl<B: ChainApi> ValidatedPool<B> {
 			};
 			if !removed.is_empty() {
 				log::debug!(target: LOG_TARGET, "Enforcing limits: {} dropped", removed.len());
+				self.metrics
+					.report(|metrics| metrics.transactions_evicted.inc_by(removed.len() as u64));
 			}
 
 			// run notifications
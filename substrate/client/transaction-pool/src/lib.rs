@@ -25,15 +25,18 @@
 mod api;
 mod enactment_state;
 pub mod error;
+mod fork_aware_txpool;
 mod graph;
 mod metrics;
 mod revalidation;
 #[cfg(test)]
 mod tests;
+mod transaction_pool_wrapper;
 
 pub use crate::api::FullChainApi;
 use async_trait::async_trait;
 use enactment_state::{EnactmentAction, EnactmentState};
+pub use fork_aware_txpool::ForkAwareTxPool;
 use futures::{
 	channel::oneshot,
 	future::{self, ready},
@@ -48,6 +51,7 @@ use std::{
 	pin::Pin,
 	sync::Arc,
 };
+pub use transaction_pool_wrapper::{TransactionPoolType, TransactionPoolWrapper};
 
 use graph::{ExtrinsicHash, IsValidator};
 use sc_transaction_pool_api::{
@@ -201,7 +205,13 @@ where
 		best_block_hash: Block::Hash,
 		finalized_hash: Block::Hash,
 	) -> Self {
-		let pool = Arc::new(graph::Pool::new(options, is_validator, pool_api.clone()));
+		let metrics = PrometheusMetrics::new(prometheus);
+		let pool = Arc::new(graph::Pool::new_with_metrics(
+			options,
+			is_validator,
+			pool_api.clone(),
+			metrics.clone(),
+		));
 		let (revalidation_queue, background_task) = match revalidation_type {
 			RevalidationType::Light =>
 				(revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone()), None),
@@ -229,7 +239,7 @@ where
 				RevalidationType::Full => RevalidationStrategy::Always,
 			})),
 			ready_poll: Arc::new(Mutex::new(ReadyPoll::new(best_block_number))),
-			metrics: PrometheusMetrics::new(prometheus),
+			metrics,
 			enactment_state: Arc::new(Mutex::new(EnactmentState::new(
 				best_block_hash,
 				finalized_hash,
@@ -727,6 +737,11 @@ where
 	PoolApi: 'static + graph::ChainApi<Block = Block>,
 {
 	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		// The single state pool only follows the best chain.
+		if let ChainEvent::NewBlock { .. } = event {
+			return
+		}
+
 		let prev_finalized_block = self.enactment_state.lock().recent_finalized_block();
 		let compute_tree_route = |from, to| -> Result<TreeRoute<Block>, String> {
 			match self.api.tree_route(from, to) {
@@ -49,6 +49,7 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub transactions_evicted: Counter<U64>,
 }
 
 impl Metrics {
@@ -82,6 +83,13 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			transactions_evicted: register(
+				Counter::new(
+					"substrate_sub_txpool_transactions_evicted",
+					"Total number of transactions evicted from the pool to make room for higher priority ones",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
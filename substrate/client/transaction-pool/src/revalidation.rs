@@ -63,9 +63,9 @@ impl<Api: ChainApi> Unpin for RevalidationWorker<Api> {}
 ///
 /// Each transaction is validated  against chain, and invalid are
 /// removed from the `pool`, while valid are resubmitted.
-async fn batch_revalidate<Api: ChainApi>(
-	pool: Arc<Pool<Api>>,
-	api: Arc<Api>,
+pub(crate) async fn batch_revalidate<Api: ChainApi>(
+	pool: &Pool<Api>,
+	api: &Api,
 	at: BlockHash<Api>,
 	batch: impl IntoIterator<Item = ExtrinsicHash<Api>>,
 ) {
@@ -253,7 +253,7 @@ impl<Api: ChainApi> RevalidationWorker<Api> {
 					let next_batch = this.prepare_batch();
 					let batch_len = next_batch.len();
 
-					batch_revalidate(this.pool.clone(), this.api.clone(), this.best_block, next_batch).await;
+					batch_revalidate(&this.pool, &this.api, this.best_block, next_batch).await;
 
 					if batch_len > 0 || this.len() > 0 {
 						log::debug!(
@@ -359,9 +359,7 @@ where
 				log::warn!(target: LOG_TARGET, "Failed to update background worker: {:?}", e);
 			}
 		} else {
-			let pool = self.pool.clone();
-			let api = self.api.clone();
-			batch_revalidate(pool, api, at, transactions).await
+			batch_revalidate(&self.pool, &self.api, at, transactions).await
 		}
 	}
 }
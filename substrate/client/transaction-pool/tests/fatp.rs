@@ -0,0 +1,292 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for the fork-aware transaction pool.
+
+use futures::{executor::block_on, prelude::*, task::Poll};
+use sc_transaction_pool::ForkAwareTxPool;
+use sc_transaction_pool_api::{
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionStatus,
+};
+use sp_runtime::transaction_validity::TransactionSource;
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	runtime::{Block, Header},
+	AccountKeyring::*,
+};
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
+
+const SOURCE: TransactionSource = TransactionSource::External;
+
+fn pool() -> (ForkAwareTxPool<TestApi, Block>, Arc<TestApi>) {
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+	let genesis_hash = api.expect_hash_from_number(0);
+	(ForkAwareTxPool::new_test(api.clone(), genesis_hash, genesis_hash), api)
+}
+
+fn block_event(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::NewBestBlock { hash: header.hash(), tree_route: None }
+}
+
+fn new_block_event(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::NewBlock { hash: header.hash() }
+}
+
+fn finalization_event(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::Finalized { hash: header.hash(), tree_route: Arc::from(vec![]) }
+}
+
+#[test]
+fn fatp_keeps_transactions_valid_on_sibling_fork() {
+	sp_tracing::try_init_simple();
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let xt = uxt(Alice, 209);
+	block_on(pool.submit_one(genesis_hash, SOURCE, xt.clone())).expect("1. Imported");
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![xt], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+	assert_eq!(pool.status().ready, 0);
+
+	let b1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&b1_header)));
+	assert_eq!(pool.status().ready, 1);
+
+	assert_eq!(block_on(pool.ready_at(1)).count(), 1);
+
+	// Switching back to the sibling fork uses its view.
+	block_on(pool.maintain(block_event(&a1_header)));
+	assert_eq!(pool.status().ready, 0);
+	assert_eq!(block_on(pool.ready_at(1)).count(), 0);
+	assert_eq!(pool.active_views_count(), 2);
+}
+
+#[test]
+fn fatp_drops_superseded_views() {
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+	assert_eq!(pool.active_views_count(), 1);
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+	assert_eq!(pool.active_views_count(), 1);
+
+	let a2_header = api.push_block_with_parent(a1_header.hash(), vec![], true);
+	block_on(pool.maintain(block_event(&a2_header)));
+	assert_eq!(pool.active_views_count(), 1);
+
+	// The sibling fork is a new leaf.
+	let b1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&b1_header)));
+	assert_eq!(pool.active_views_count(), 2);
+}
+
+#[test]
+fn fatp_submit_at_honours_block() {
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&header)));
+
+	// The block is unknown.
+	assert!(block_on(pool.submit_one(Default::default(), SOURCE, uxt(Alice, 209))).is_err());
+	assert_eq!(pool.status().ready, 0);
+
+	// There is no view at the genesis block anymore, the transaction is validated there and
+	// imported into the view of its descendant.
+	block_on(pool.submit_one(genesis_hash, SOURCE, uxt(Alice, 209))).expect("1. Imported");
+	assert_eq!(pool.status().ready, 1);
+}
+
+#[test]
+fn fatp_ready_at_resolves_when_view_is_created() {
+	let (pool, api) = pool();
+	let header = api.push_block(1, vec![], true);
+
+	block_on(pool.submit_one(api.expect_hash_from_number(0), SOURCE, uxt(Alice, 209)))
+		.expect("1. Imported");
+
+	let noop_waker = futures::task::noop_waker();
+	let mut context = futures::task::Context::from_waker(&noop_waker);
+
+	let mut ready_set_future = pool.ready_at(1);
+	if ready_set_future.poll_unpin(&mut context).is_ready() {
+		panic!("Ready set should not be ready before the view is created!");
+	}
+
+	block_on(pool.maintain(block_event(&header)));
+
+	match ready_set_future.poll_unpin(&mut context) {
+		Poll::Pending => {
+			panic!("Ready set should become ready after the view is created!");
+		},
+		Poll::Ready(iterator) => {
+			assert_eq!(iterator.count(), 1);
+		},
+	}
+}
+
+#[test]
+fn fatp_watcher_reports_in_block_and_finalized() {
+	sp_tracing::try_init_simple();
+	let (pool, api) = pool();
+
+	let xt = uxt(Alice, 209);
+	let watcher =
+		block_on(pool.submit_and_watch(api.expect_hash_from_number(0), SOURCE, xt.clone()))
+			.expect("1. Imported");
+	assert_eq!(pool.status().ready, 1);
+
+	let header = api.push_block(1, vec![xt], true);
+	block_on(pool.maintain(block_event(&header)));
+	block_on(pool.maintain(finalization_event(&header)));
+	assert_eq!(pool.status().ready, 0);
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((header.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((header.hash(), 0))));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn fatp_finalization_of_sibling_fork_drops_stale_views() {
+	sp_tracing::try_init_simple();
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let xt = uxt(Alice, 209);
+	let watcher =
+		block_on(pool.submit_and_watch(genesis_hash, SOURCE, xt.clone())).expect("1. Imported");
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![xt], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+	let b1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&b1_header)));
+
+	block_on(pool.maintain(finalization_event(&a1_header)));
+	assert_eq!(pool.status().ready, 0);
+	assert_eq!(pool.active_views_count(), 1);
+	assert_eq!(pool.ready_at(1).now_or_never().map(|ready| ready.count()), Some(0));
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((a1_header.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Retracted(a1_header.hash())));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((a1_header.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((a1_header.hash(), 0))));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn fatp_ready_at_resolves_on_finalization() {
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	let a2_header = api.push_block_with_parent(a1_header.hash(), vec![], false);
+	block_on(pool.maintain(block_event(&a1_header)));
+
+	let noop_waker = futures::task::noop_waker();
+	let mut context = futures::task::Context::from_waker(&noop_waker);
+
+	let mut ready_set_future = pool.ready_at(2);
+	if ready_set_future.poll_unpin(&mut context).is_ready() {
+		panic!("Ready set should not be ready before the height is reached!");
+	}
+
+	// The block is finalized without ever becoming the best block.
+	block_on(pool.maintain(finalization_event(&a2_header)));
+	assert!(ready_set_future.poll_unpin(&mut context).is_ready());
+
+	// Nothing below the finalized height is awaited.
+	assert!(pool.ready_at(1).now_or_never().is_some());
+}
+
+#[test]
+fn fatp_creates_view_for_block_imported_on_fork() {
+	sp_tracing::try_init_simple();
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let xt = uxt(Alice, 209);
+	block_on(pool.submit_one(genesis_hash, SOURCE, xt.clone())).expect("1. Imported");
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![xt], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+	assert_eq!(pool.status().ready, 0);
+
+	// The sibling block is not the best block, the transaction it does not include is only
+	// ready in its own view.
+	let b1_header = api.push_block_with_parent(genesis_hash, vec![], false);
+	block_on(pool.maintain(new_block_event(&b1_header)));
+	assert_eq!(pool.active_views_count(), 2);
+	assert_eq!(pool.status().ready, 0);
+
+	// The sibling block is finalized without ever becoming the best block, its view is kept.
+	block_on(pool.maintain(finalization_event(&b1_header)));
+	assert_eq!(pool.active_views_count(), 1);
+	assert_eq!(pool.status().ready, 1);
+	assert_eq!(block_on(pool.ready_at(1)).count(), 1);
+}
+
+#[test]
+fn fatp_keeps_best_view_when_its_child_is_imported() {
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	block_on(pool.submit_one(genesis_hash, SOURCE, uxt(Alice, 209))).expect("1. Imported");
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+
+	// The child of the best block does not supersede the best view until it becomes the best.
+	let a2_header = api.push_block_with_parent(a1_header.hash(), vec![], false);
+	block_on(pool.maintain(new_block_event(&a2_header)));
+	assert_eq!(pool.active_views_count(), 2);
+	assert_eq!(pool.status().ready, 1);
+
+	block_on(pool.maintain(block_event(&a2_header)));
+	assert_eq!(pool.active_views_count(), 1);
+	assert_eq!(pool.status().ready, 1);
+}
+
+#[test]
+fn fatp_revalidates_views_on_finalization() {
+	sp_tracing::try_init_simple();
+	let (pool, api) = pool();
+	let genesis_hash = api.expect_hash_from_number(0);
+
+	let xt = uxt(Alice, 209);
+	block_on(pool.submit_one(genesis_hash, SOURCE, xt.clone())).expect("1. Imported");
+
+	let a1_header = api.push_block_with_parent(genesis_hash, vec![], true);
+	block_on(pool.maintain(block_event(&a1_header)));
+	let a2_header = api.push_block_with_parent(a1_header.hash(), vec![], true);
+	block_on(pool.maintain(block_event(&a2_header)));
+	assert_eq!(pool.status().ready, 1);
+
+	// The transaction becomes invalid, which the view of the best block only notices when it
+	// is revalidated.
+	api.add_invalid(&xt);
+	block_on(pool.maintain(finalization_event(&a1_header)));
+	assert_eq!(pool.active_views_count(), 1);
+	assert_eq!(pool.status().ready, 0);
+}
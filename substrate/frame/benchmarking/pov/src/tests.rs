@@ -182,6 +182,7 @@ mod mock {
 		type RuntimeOrigin = RuntimeOrigin;
 		type Nonce = u32;
 		type RuntimeCall = RuntimeCall;
+		type RuntimeTask = RuntimeTask;
 		type Hash = H256;
 		type Hashing = ::sp_runtime::traits::BlakeTwo256;
 		type AccountId = u32;
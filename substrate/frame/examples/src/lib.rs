@@ -40,6 +40,8 @@
 //! - [`pallet_example_split`]: A simple example of a FRAME pallet demonstrating the ability to
 //!   split sections across multiple files.
 //!
+//! - [`pallet_example_tasks`]: This pallet demonstrates the use of `Tasks` to execute service work.
+//!
 //! - [`pallet_example_frame_crate`]: Example pallet showcasing how one can be
 //! built using only the `frame` umbrella crate.
 //!
@@ -0,0 +1,103 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Tasks Example Pallet
+//!
+//! **This pallet serves as an example and is not meant to be used in production.**
+//!
+//! A FRAME pallet demonstrating how to declare tasks with `#[pallet::tasks]`.
+//!
+//! Anyone can put numbers into the [`Numbers`] map. Each of them is a piece of work that is
+//! discovered by enumerating the map: adding the number into [`Total`] and removing it from the
+//! map. The pallet's offchain worker submits all valid tasks as unsigned
+//! `frame_system::Call::do_task` transactions, so nobody has to submit them by hand.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The maximum number of tasks the offchain worker submits per block.
+pub const MAX_SUBMITTED_TASKS: usize = 16;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::{offchain::SendTransactionTypes, pallet_prelude::*};
+
+	#[pallet::config]
+	pub trait Config:
+		SendTransactionTypes<frame_system::Call<Self>> + frame_system::Config
+	{
+		/// The overarching task type.
+		type RuntimeTask: frame_support::traits::Task
+			+ IsType<<Self as frame_system::Config>::RuntimeTask>
+			+ From<Task<Self>>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Numbers which still have to be added into the [`Total`].
+	#[pallet::storage]
+	pub type Numbers<T: Config> = StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	/// The sum of all keys and values that were added from [`Numbers`].
+	#[pallet::storage]
+	pub type Total<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The number to add was not found.
+		NotFound,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(_block_number: BlockNumberFor<T>) {
+			let tasks = <Task<T> as frame_support::traits::Task>::iter()
+				.map(|task| <T as Config>::RuntimeTask::from(task).into());
+			let submitted =
+				frame_system::offchain::submit_valid_tasks::<T>(tasks, MAX_SUBMITTED_TASKS);
+			log::debug!(target: "runtime::tasks-example", "Submitted {} tasks", submitted);
+		}
+	}
+
+	#[pallet::tasks]
+	impl<T: Config> Pallet<T> {
+		/// Add a pair of numbers into the totals and remove them.
+		#[pallet::task_index(0)]
+		#[pallet::task_list(Numbers::<T>::iter_keys())]
+		#[pallet::task_condition(|i| Numbers::<T>::contains_key(i))]
+		// One read for the `task_condition` checked by `do_task`, two reads and writes for the
+		// task.
+		#[pallet::task_weight(T::DbWeight::get().reads_writes(3, 2))]
+		fn add_number_into_total(i: u32) -> DispatchResult {
+			let v = Numbers::<T>::take(i).ok_or(Error::<T>::NotFound)?;
+			Total::<T>::mutate(|(total_keys, total_values)| {
+				*total_keys = total_keys.saturating_add(i);
+				*total_values = total_values.saturating_add(v);
+			});
+			Ok(())
+		}
+	}
+}
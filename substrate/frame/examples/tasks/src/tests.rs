@@ -0,0 +1,161 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for `pallet-example-tasks`.
+
+use crate::{mock::*, Numbers, Total};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::Task};
+use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+use sp_runtime::traits::OffchainWorker;
+
+#[test]
+fn task_enumerate_works() {
+	new_test_ext().execute_with(|| {
+		Numbers::<Runtime>::insert(0, 1);
+		assert_eq!(crate::pallet::Task::<Runtime>::iter().collect::<Vec<_>>().len(), 1);
+	});
+}
+
+#[test]
+fn runtime_task_enumerate_works_via_frame_system_config() {
+	new_test_ext().execute_with(|| {
+		Numbers::<Runtime>::insert(0, 1);
+		Numbers::<Runtime>::insert(1, 4);
+		assert_eq!(
+			<Runtime as frame_system::Config>::RuntimeTask::iter().collect::<Vec<_>>().len(),
+			2
+		);
+	});
+}
+
+#[test]
+fn task_index_works() {
+	new_test_ext().execute_with(|| {
+		let task = crate::pallet::Task::<Runtime>::add_number_into_total { i: 1u32 };
+		assert_eq!(task.task_index(), 0);
+		assert_eq!(RuntimeTask::from(task).task_index(), 0);
+	});
+}
+
+#[test]
+fn task_execution_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Numbers::<Runtime>::insert(0, 1);
+		Numbers::<Runtime>::insert(1, 4);
+
+		let task =
+			RuntimeTask::TasksExample(crate::pallet::Task::<Runtime>::add_number_into_total {
+				i: 1u32,
+			});
+		assert!(task.is_valid());
+		assert_ok!(System::do_task(RuntimeOrigin::signed(1), task.clone()));
+		assert_eq!(Numbers::<Runtime>::get(0), Some(1));
+		assert_eq!(Numbers::<Runtime>::get(1), None);
+		assert_eq!(Total::<Runtime>::get(), (1, 4));
+		System::assert_last_event(frame_system::Event::<Runtime>::TaskCompleted { task }.into());
+	});
+}
+
+#[test]
+fn task_execution_fails_for_invalid_task() {
+	new_test_ext().execute_with(|| {
+		Numbers::<Runtime>::insert(1, 4);
+		let task =
+			RuntimeTask::TasksExample(crate::pallet::Task::<Runtime>::add_number_into_total {
+				i: 0u32,
+			});
+		assert!(!task.is_valid());
+		assert_noop!(
+			System::do_task(RuntimeOrigin::signed(1), task),
+			frame_system::Error::<Runtime>::InvalidTask
+		);
+		assert_eq!(Total::<Runtime>::get(), (0, 0));
+	});
+}
+
+#[test]
+fn offchain_worker_submits_valid_tasks() {
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+
+	t.execute_with(|| {
+		Numbers::<Runtime>::insert(0, 10);
+
+		<TasksExample as OffchainWorker<u64>>::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		assert_eq!(
+			tx.call,
+			RuntimeCall::System(frame_system::Call::do_task {
+				task: RuntimeTask::TasksExample(
+					crate::pallet::Task::<Runtime>::add_number_into_total { i: 0 }
+				),
+			})
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_at_most_max_submitted_tasks() {
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+
+	t.execute_with(|| {
+		for i in 0..(crate::MAX_SUBMITTED_TASKS as u32 * 2) {
+			Numbers::<Runtime>::insert(i, i);
+		}
+
+		<TasksExample as OffchainWorker<u64>>::offchain_worker(1);
+
+		assert_eq!(pool_state.read().transactions.len(), crate::MAX_SUBMITTED_TASKS);
+	});
+}
+
+#[test]
+fn do_task_unsigned_validity_is_bounded() {
+	use frame_support::pallet_prelude::{InvalidTransaction, TransactionSource};
+	use sp_runtime::traits::ValidateUnsigned;
+
+	new_test_ext().execute_with(|| {
+		let task = crate::pallet::Task::<Runtime>::add_number_into_total { i: 0 };
+		let call = frame_system::Call::<Runtime>::do_task { task: task.into() };
+
+		assert_eq!(
+			System::validate_unsigned(TransactionSource::External, &call),
+			Err(InvalidTransaction::Call.into()),
+		);
+
+		Numbers::<Runtime>::insert(0, 1);
+		let valid = System::validate_unsigned(TransactionSource::External, &call).unwrap();
+		assert_eq!(valid.priority, frame_system::DO_TASK_PRIORITY);
+		assert_eq!(valid.longevity, frame_system::DO_TASK_LONGEVITY);
+		assert_eq!(valid.provides.len(), 1);
+	});
+}
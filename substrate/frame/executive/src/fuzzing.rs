@@ -0,0 +1,282 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzing of a whole runtime with arbitrary sequences of calls.
+//!
+//! [`Executive::fuzz`] executes blocks full of `RuntimeCall`s that are generated from the input of
+//! a fuzzer with [`frame_support::fuzzing::EncodingGenerator`], dispatched from arbitrary origins:
+//! the configured signers, root, none and, generated like the calls, the custom origins of the
+//! runtime's pallets.
+//! After every block, the `try_state` hooks of all pallets are run. Panics, calls that consume more
+//! weight than they declared, blocks that exceed the maximum block weight and failing `try_state`
+//! checks are reported as a [`Finding`].
+//!
+//! A fuzz target for a runtime, here using `honggfuzz`, looks like this:
+//!
+//! ```ignore
+//! fn main() {
+//! 	let config = FuzzConfig {
+//! 		blocks: 8,
+//! 		calls_per_block: 16,
+//! 		signers: vec![ALICE, BOB],
+//! 		allow_root: true,
+//! 		allow_none: true,
+//! 		allow_custom: true,
+//! 	};
+//! 	loop {
+//! 		honggfuzz::fuzz!(|data: &[u8]| {
+//! 			new_test_ext().execute_with(|| {
+//! 				if let Err(finding) = Executive::fuzz(data, &config) {
+//! 					panic!("{finding:?}");
+//! 				}
+//! 			})
+//! 		});
+//! 	}
+//! }
+//! ```
+
+use super::*;
+use codec::Decode;
+use frame_support::{
+	dispatch::RawOrigin,
+	fuzzing::{EncodingGenerator, FuzzInput},
+	traits::{CallerTrait, Get, OriginTrait},
+	DebugNoBound,
+};
+use frame_system::CheckWeight;
+use scale_info::TypeInfo;
+use sp_runtime::traits::SignedExtension;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Configuration of [`Executive::fuzz`].
+pub struct FuzzConfig<AccountId> {
+	/// The maximum number of blocks to execute.
+	pub blocks: u32,
+	/// The maximum number of calls to dispatch in a block.
+	pub calls_per_block: u32,
+	/// The accounts that signed origins are chosen from.
+	pub signers: Vec<AccountId>,
+	/// Whether calls may be dispatched from the root origin.
+	pub allow_root: bool,
+	/// Whether calls may be dispatched from the none origin.
+	pub allow_none: bool,
+	/// Whether calls may be dispatched from generated custom origins of the runtime's pallets.
+	pub allow_custom: bool,
+}
+
+type PalletsOriginOf<System> =
+	<<System as frame_system::Config>::RuntimeOrigin as OriginTrait>::PalletsOrigin;
+
+/// A problem found by [`Executive::fuzz`].
+#[derive(DebugNoBound)]
+pub enum Finding<System: frame_system::Config> {
+	/// Block execution panicked, in the given call or otherwise in a hook.
+	Panic { block: BlockNumberFor<System>, call: Option<System::RuntimeCall>, message: String },
+	/// A call consumed more weight than it declared.
+	WeightOverrun {
+		block: BlockNumberFor<System>,
+		call: System::RuntimeCall,
+		declared: Weight,
+		actual: Weight,
+	},
+	/// The weight consumed by a block exceeds the maximum block weight.
+	BlockWeightExceeded { block: BlockNumberFor<System>, consumed: Weight, max: Weight },
+	/// A `try_state` check failed after the block.
+	InvariantViolated { block: BlockNumberFor<System>, error: TryRuntimeError },
+}
+
+impl<
+		System: frame_system::Config + EnsureInherentsAreFirst<Block> + Send + Sync,
+		Block: traits::Block<
+			Header = frame_system::pallet_prelude::HeaderFor<System>,
+			Hash = System::Hash,
+		>,
+		Context: Default,
+		UnsignedValidator,
+		AllPalletsWithSystem: OnRuntimeUpgrade
+			+ BeforeAllRuntimeMigrations
+			+ OnInitialize<BlockNumberFor<System>>
+			+ OnIdle<BlockNumberFor<System>>
+			+ OnPollPallets<BlockNumberFor<System>>
+			+ OnFinalize<BlockNumberFor<System>>
+			+ OffchainWorker<BlockNumberFor<System>>
+			+ TryState<BlockNumberFor<System>>,
+		COnRuntimeUpgrade: OnRuntimeUpgrade,
+		CMultiStepMigrator: MultiStepMigrator,
+		COnPollBudget: Get<Weight>,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+		COnPollBudget,
+	>
+where
+	Block::Extrinsic: Checkable<Context> + Codec,
+	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
+	CallOf<Block::Extrinsic, Context>:
+		Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
+	UnsignedValidator: ValidateUnsigned<Call = CallOf<Block::Extrinsic, Context>>,
+	System::RuntimeCall:
+		GetDispatchInfo + Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	PalletsOriginOf<System>: TypeInfo + Decode + 'static,
+{
+	/// Execute blocks with calls generated from the fuzzer `input`, on top of the current state.
+	///
+	/// Calls are dispatched directly, without any transaction extension except for the block
+	/// weight and length checks of [`CheckWeight`]. Calls that would not fit into the block and
+	/// mandatory calls are skipped. Execution stops at the first [`Finding`], or once `input` is
+	/// exhausted or `config.blocks` blocks were executed.
+	pub fn fuzz(
+		input: &[u8],
+		config: &FuzzConfig<System::AccountId>,
+	) -> Result<(), Finding<System>> {
+		let generator = EncodingGenerator::new::<System::RuntimeCall>();
+		let mut input = FuzzInput::new(input);
+
+		let mut origins: Vec<System::RuntimeOrigin> =
+			config.signers.iter().map(|who| RawOrigin::Signed(who.clone()).into()).collect();
+		if config.allow_root {
+			origins.push(RawOrigin::Root.into());
+		}
+		if config.allow_none {
+			origins.push(RawOrigin::None.into());
+		}
+		let custom_origins =
+			config.allow_custom.then(EncodingGenerator::new::<PalletsOriginOf<System>>);
+
+		let mut block = <frame_system::Pallet<System>>::block_number();
+		let mut parent_hash = <frame_system::Pallet<System>>::block_hash(block);
+		for _ in 0..config.blocks {
+			if input.is_empty() {
+				break
+			}
+			block += One::one();
+
+			let header = frame_system::pallet_prelude::HeaderFor::<System>::new(
+				block,
+				Default::default(),
+				Default::default(),
+				parent_hash,
+				Default::default(),
+			);
+			catch_panic(block, None, || Self::initialize_block(&header))?;
+
+			let calls = input.u32() % config.calls_per_block.saturating_add(1);
+			for _ in 0..calls {
+				let call = generator.arbitrary::<System::RuntimeCall>(&mut input);
+				let origin = Self::fuzz_origin(&mut input, &origins, custom_origins.as_ref());
+				if let (Some(call), Some(origin)) = (call, origin) {
+					Self::fuzz_dispatch(block, call, origin)?;
+				}
+			}
+
+			let header = catch_panic(block, None, Self::finalize_block)?;
+
+			let consumed = <frame_system::Pallet<System>>::block_weight().total();
+			let max = System::BlockWeights::get().max_block;
+			if consumed.any_gt(max) {
+				return Err(Finding::BlockWeightExceeded { block, consumed, max })
+			}
+
+			AllPalletsWithSystem::try_state(block, TryStateSelect::All)
+				.map_err(|error| Finding::InvariantViolated { block, error })?;
+
+			parent_hash = header.hash();
+		}
+
+		Ok(())
+	}
+
+	/// Choose one of `origins` or, if `custom_origins` is given, generate a custom origin.
+	fn fuzz_origin(
+		input: &mut FuzzInput,
+		origins: &[System::RuntimeOrigin],
+		custom_origins: Option<&EncodingGenerator>,
+	) -> Option<System::RuntimeOrigin> {
+		let choices = origins.len() + custom_origins.map_or(0, |_| 1);
+		if choices == 0 {
+			return None
+		}
+		match origins.get(input.u32() as usize % choices) {
+			Some(origin) => Some(origin.clone()),
+			// The system origins are only chosen from the configured ones.
+			None => custom_origins?
+				.arbitrary::<PalletsOriginOf<System>>(input)
+				.filter(|origin| origin.as_system_ref().is_none())
+				.map(Into::into),
+		}
+	}
+
+	/// Dispatch `call` like an extrinsic of the current block.
+	fn fuzz_dispatch(
+		block: BlockNumberFor<System>,
+		call: System::RuntimeCall,
+		origin: System::RuntimeOrigin,
+	) -> Result<(), Finding<System>> {
+		let info = call.get_dispatch_info();
+		let encoded = call.encode();
+		let len = encoded.len();
+		if info.class == DispatchClass::Mandatory ||
+			CheckWeight::<System>::do_pre_dispatch(&info, len).is_err()
+		{
+			return Ok(())
+		}
+
+		<frame_system::Pallet<System>>::note_extrinsic(encoded);
+		let result = catch_panic(block, Some(call.clone()), || call.clone().dispatch(origin))?;
+		let post_info = match &result {
+			Ok(post_info) => *post_info,
+			Err(error) => error.post_info,
+		};
+
+		if let Some(actual) = post_info.actual_weight.filter(|actual| actual.any_gt(info.weight)) {
+			return Err(Finding::WeightOverrun { block, call, declared: info.weight, actual })
+		}
+
+		let _ = CheckWeight::<System>::post_dispatch(
+			None,
+			&info,
+			&post_info,
+			len,
+			&result.as_ref().map(|_| ()).map_err(|error| error.error),
+		);
+		<frame_system::Pallet<System>>::note_applied_extrinsic(&result, info);
+		Ok(())
+	}
+}
+
+/// Run `f`, turning a panic into a [`Finding::Panic`].
+fn catch_panic<System: frame_system::Config, R>(
+	block: BlockNumberFor<System>,
+	call: Option<System::RuntimeCall>,
+	f: impl FnOnce() -> R,
+) -> Result<R, Finding<System>> {
+	catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+		let message = payload
+			.downcast_ref::<&str>()
+			.map(|s| s.to_string())
+			.or_else(|| payload.downcast_ref::<String>().cloned())
+			.unwrap_or_else(|| "<non-string panic payload>".into());
+		Finding::Panic { block, call, message }
+	})
+}
@@ -113,25 +113,44 @@
 //!
 //! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllPalletsWithSystem, CustomOnRuntimeUpgrade>;
 //! ```
+//!
+//! ### Multi-block migrations
+//!
+//! Migrations that do not fit into a single block can be driven by a
+//! [`MultiStepMigrator`](frame_support::migrations::MultiStepMigrator), like `pallet-migrations`,
+//! which is passed as the last generic parameter. It is stepped in every block right after
+//! `on_initialize`. While it reports ongoing migrations, only inherents are applied and all
+//! other extrinsics are rejected.
+//!
+//! ### Polling
+//!
+//! Once all inherents of a block are applied, and before the first transaction, the `on_poll`
+//! hooks of all pallets are called with the weight that is left in the block, capped by the
+//! `OnPollBudget` of the executive so that transactions are not starved. Every pallet gets an
+//! equal share of what is left when its turn comes, so weight that a pallet does not use is passed
+//! on to the pallets after it. The pallet that is polled first rotates with the block number. The
+//! weight consumed by each pallet is reported through `frame_system::Event::PalletPolled`. Pallets
+//! are not polled while multi-block migrations are ongoing.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Codec, Encode};
 use frame_support::{
 	dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+	migrations::MultiStepMigrator,
 	pallet_prelude::InvalidTransaction,
 	traits::{
 		BeforeAllRuntimeMigrations, EnsureInherentsAreFirst, ExecuteBlock, OffchainWorker,
-		OnFinalize, OnIdle, OnInitialize, OnRuntimeUpgrade,
+		OnFinalize, OnIdle, OnInitialize, OnPollPallets, OnRuntimeUpgrade,
 	},
-	weights::Weight,
+	weights::{Weight, WeightMeter},
 };
 use frame_system::pallet_prelude::BlockNumberFor;
 use sp_runtime::{
 	generic::Digest,
 	traits::{
 		self, Applyable, CheckEqual, Checkable, Dispatchable, Header, NumberFor, One,
-		ValidateUnsigned, Zero,
+		SaturatedConversion, ValidateUnsigned, Zero,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult,
@@ -144,14 +163,21 @@ use ::{
 		traits::{TryDecodeEntireStorage, TryDecodeEntireStorageError, TryState},
 		StorageNoopGuard,
 	},
-	frame_try_runtime::{TryStateSelect, UpgradeCheckSelect},
+	frame_try_runtime::{BlockHook, TryStateSelect, UpgradeCheckSelect},
 	log,
 	sp_runtime::TryRuntimeError,
 };
 
+#[cfg(all(feature = "std", feature = "try-runtime"))]
+pub mod fuzzing;
+
 #[allow(dead_code)]
 const LOG_TARGET: &str = "runtime::executive";
 
+/// Maximum number of steps of the multi-block migrations run by the try-runtime upgrade checks.
+#[cfg(feature = "try-runtime")]
+pub const MAX_TRY_RUNTIME_MIGRATION_STEPS: u32 = 100_000;
+
 pub type CheckedOf<E, C> = <E as Checkable<C>>::Checked;
 pub type CallOf<E, C> = <CheckedOf<E, C> as Applyable>::Call;
 pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
@@ -167,6 +193,10 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
 ///   used to call hooks e.g. `on_initialize`.
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///   already called by `AllPalletsWithSystem`. It will be called before all modules will be called.
+/// - `MultiStepMigrator`: Drives migrations that span multiple blocks. Only inherents are applied
+///   while it has ongoing migrations.
+/// - `OnPollBudget`: The maximum weight that the `on_poll` hooks of all pallets may consume in a
+///   block. Defaults to [`DefaultPollBudget`].
 pub struct Executive<
 	System,
 	Block,
@@ -174,6 +204,8 @@ pub struct Executive<
 	UnsignedValidator,
 	AllPalletsWithSystem,
 	OnRuntimeUpgrade = (),
+	MultiStepMigrator = (),
+	OnPollBudget = DefaultPollBudget<System>,
 >(
 	PhantomData<(
 		System,
@@ -182,9 +214,25 @@ pub struct Executive<
 		UnsignedValidator,
 		AllPalletsWithSystem,
 		OnRuntimeUpgrade,
+		MultiStepMigrator,
+		OnPollBudget,
 	)>,
 );
 
+/// The default budget of the `on_poll` hooks of a block: a tenth of the maximum weight of the
+/// normal dispatch class, or of the maximum block weight if the normal class is not limited.
+pub struct DefaultPollBudget<System>(PhantomData<System>);
+
+impl<System: frame_system::Config> frame_support::traits::Get<Weight>
+	for DefaultPollBudget<System>
+{
+	fn get() -> Weight {
+		let weights = <System::BlockWeights as frame_support::traits::Get<_>>::get();
+		let normal = weights.get(DispatchClass::Normal).max_total.unwrap_or(weights.max_block);
+		normal.saturating_div(10)
+	}
+}
+
 impl<
 		System: frame_system::Config + EnsureInherentsAreFirst<Block>,
 		Block: traits::Block<
@@ -197,11 +245,23 @@ impl<
 			+ BeforeAllRuntimeMigrations
 			+ OnInitialize<BlockNumberFor<System>>
 			+ OnIdle<BlockNumberFor<System>>
+			+ OnPollPallets<BlockNumberFor<System>>
 			+ OnFinalize<BlockNumberFor<System>>
 			+ OffchainWorker<BlockNumberFor<System>>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
+		CMultiStepMigrator: MultiStepMigrator,
+		COnPollBudget: frame_support::traits::Get<Weight>,
 	> ExecuteBlock<Block>
-	for Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+	for Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+		COnPollBudget,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -218,6 +278,8 @@ where
 			UnsignedValidator,
 			AllPalletsWithSystem,
 			COnRuntimeUpgrade,
+			CMultiStepMigrator,
+			COnPollBudget,
 		>::execute_block(block);
 	}
 }
@@ -235,12 +297,25 @@ impl<
 			+ BeforeAllRuntimeMigrations
 			+ OnInitialize<BlockNumberFor<System>>
 			+ OnIdle<BlockNumberFor<System>>
+			+ OnPollPallets<BlockNumberFor<System>>
 			+ OnFinalize<BlockNumberFor<System>>
 			+ OffchainWorker<BlockNumberFor<System>>
 			+ TryState<BlockNumberFor<System>>
 			+ TryDecodeEntireStorage,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		CMultiStepMigrator: MultiStepMigrator,
+		COnPollBudget: frame_support::traits::Get<Weight>,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+		COnPollBudget,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -288,9 +363,14 @@ where
 			} else {
 				uxt.unchecked_into_checked_i_know_what_i_am_doing(&Default::default())
 			}?;
+			let dispatch_info = xt.get_dispatch_info();
+			if dispatch_info.class != DispatchClass::Mandatory {
+				Self::inherents_applied();
+			}
+			Self::ensure_allowed_during_migrations(&dispatch_info)?;
+
 			<frame_system::Pallet<System>>::note_extrinsic(encoded);
 
-			let dispatch_info = xt.get_dispatch_info();
 			let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 			<frame_system::Pallet<System>>::note_applied_extrinsic(&r, dispatch_info);
@@ -310,6 +390,7 @@ where
 		}
 
 		// post-extrinsics book-keeping
+		Self::inherents_applied();
 		<frame_system::Pallet<System>>::note_finished_extrinsics();
 		Self::idle_and_finalize_hook(*header.number());
 
@@ -362,6 +443,51 @@ where
 		Ok(frame_system::Pallet::<System>::block_weight().total())
 	}
 
+	/// Execute the given `hook` of the block with the given `header` on top of the current state,
+	/// returning the weight declared by the hook.
+	///
+	/// Unlike [`Self::initialize_block`], the weight of the runtime upgrade is registered before
+	/// `on_initialize` runs. `on_finalize` declares no weight of its own, it is expected to be
+	/// accounted for by `on_initialize`.
+	pub fn try_execute_block_hook(
+		header: &frame_system::pallet_prelude::HeaderFor<System>,
+		hook: BlockHook,
+	) -> Weight {
+		sp_io::init_tracing();
+		match hook {
+			BlockHook::OnRuntimeUpgrade => {
+				let digests = Self::extract_pre_digest(header);
+				let weight =
+					Self::runtime_upgrade_hook(header.number(), header.parent_hash(), &digests);
+				<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+					weight,
+					DispatchClass::Mandatory,
+				);
+				weight
+			},
+			BlockHook::OnInitialize => {
+				let weight = Self::initialize_hook(*header.number());
+				Self::note_finished_initialize(weight);
+				weight
+			},
+			BlockHook::OnPoll => {
+				let weight = <frame_system::Pallet<System>>::block_weight().total();
+				Self::inherents_applied();
+				<frame_system::Pallet<System>>::block_weight().total().saturating_sub(weight)
+			},
+			BlockHook::OnIdle => {
+				<frame_system::Pallet<System>>::note_finished_extrinsics();
+				Self::idle_hook(*header.number())
+			},
+			BlockHook::OnFinalize => {
+				<AllPalletsWithSystem as OnFinalize<BlockNumberFor<System>>>::on_finalize(
+					*header.number(),
+				);
+				Weight::zero()
+			},
+		}
+	}
+
 	/// Execute all `OnRuntimeUpgrade` of this runtime.
 	///
 	/// The `checks` param determines whether to execute `pre/post_upgrade` and `try_state` hooks.
@@ -372,6 +498,7 @@ where
 			<(COnRuntimeUpgrade, AllPalletsWithSystem) as OnRuntimeUpgrade>::try_on_runtime_upgrade(
 				checks.pre_and_post(),
 			)?;
+		let multi_step_weight = Self::try_step_migrations_to_completion(checks)?;
 		// Nothing should modify the state after the migrations ran:
 		let _guard = StorageNoopGuard::default();
 
@@ -389,7 +516,47 @@ where
 			)?;
 		}
 
-		Ok(before_all_weight.saturating_add(try_on_runtime_upgrade_weight))
+		Ok(before_all_weight
+			.saturating_add(try_on_runtime_upgrade_weight)
+			.saturating_add(multi_step_weight))
+	}
+
+	/// Step the multi-block migrations started by the runtime upgrade to completion, each step
+	/// in its own block.
+	///
+	/// The `checks` param determines whether to execute the `pre/post_upgrade` hooks of the
+	/// migrations. Fails if the migrations don't complete within
+	/// [`MAX_TRY_RUNTIME_MIGRATION_STEPS`] steps, e.g. because one of them failed.
+	fn try_step_migrations_to_completion(
+		checks: UpgradeCheckSelect,
+	) -> Result<Weight, TryRuntimeError> {
+		let step_to_completion = || {
+			let mut weight = Weight::zero();
+			let mut steps = 0;
+			while CMultiStepMigrator::ongoing() {
+				if steps == MAX_TRY_RUNTIME_MIGRATION_STEPS {
+					return Err("Multi-block migrations did not complete".into())
+				}
+				let block_number = frame_system::Pallet::<System>::block_number();
+				frame_system::Pallet::<System>::set_block_number(block_number + One::one());
+				weight = weight.saturating_add(CMultiStepMigrator::step());
+				steps += 1;
+			}
+			if steps > 0 {
+				log::info!(
+					target: LOG_TARGET,
+					"try-runtime: multi-block migrations completed in {} steps",
+					steps,
+				);
+			}
+			Ok(weight)
+		};
+
+		if checks.pre_and_post() {
+			CMultiStepMigrator::with_upgrade_checks(step_to_completion)
+		} else {
+			step_to_completion()
+		}
 	}
 
 	/// Logs the result of trying to decode the entire state.
@@ -436,10 +603,23 @@ impl<
 			+ BeforeAllRuntimeMigrations
 			+ OnInitialize<BlockNumberFor<System>>
 			+ OnIdle<BlockNumberFor<System>>
+			+ OnPollPallets<BlockNumberFor<System>>
 			+ OnFinalize<BlockNumberFor<System>>
 			+ OffchainWorker<BlockNumberFor<System>>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		CMultiStepMigrator: MultiStepMigrator,
+		COnPollBudget: frame_support::traits::Get<Weight>,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+		COnPollBudget,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -479,6 +659,18 @@ where
 		parent_hash: &System::Hash,
 		digest: &Digest,
 	) {
+		let weight = Self::runtime_upgrade_hook(block_number, parent_hash, digest)
+			.saturating_add(Self::initialize_hook(*block_number));
+		Self::note_finished_initialize(weight);
+	}
+
+	/// Run `on_runtime_upgrade` if the runtime was upgraded and initialize `frame_system` for the
+	/// block, returning the weight of the upgrade.
+	fn runtime_upgrade_hook(
+		block_number: &BlockNumberFor<System>,
+		parent_hash: &System::Hash,
+		digest: &Digest,
+	) -> Weight {
 		// Reset events before apply runtime upgrade hook.
 		// This is required to preserve events from runtime upgrade hook.
 		// This means the format of all the event related storages must always be compatible.
@@ -495,14 +687,20 @@ where
 			);
 		}
 		<frame_system::Pallet<System>>::initialize(block_number, parent_hash, digest);
-		weight = weight.saturating_add(<AllPalletsWithSystem as OnInitialize<
-			BlockNumberFor<System>,
-		>>::on_initialize(*block_number));
-		weight = weight.saturating_add(
-			<System::BlockWeights as frame_support::traits::Get<_>>::get().base_block,
-		);
+		weight
+	}
+
+	/// Run `on_initialize`, returning its weight.
+	fn initialize_hook(block_number: BlockNumberFor<System>) -> Weight {
+		<AllPalletsWithSystem as OnInitialize<BlockNumberFor<System>>>::on_initialize(block_number)
+	}
+
+	/// Register the `weight` of the initialization along with the base weight of the block.
+	fn note_finished_initialize(weight: Weight) {
 		<frame_system::Pallet<System>>::register_extra_weight_unchecked(
-			weight,
+			weight.saturating_add(
+				<System::BlockWeights as frame_support::traits::Get<_>>::get().base_block,
+			),
 			DispatchClass::Mandatory,
 		);
 
@@ -568,6 +766,7 @@ where
 		});
 
 		// post-extrinsics book-keeping
+		Self::inherents_applied();
 		<frame_system::Pallet<System>>::note_finished_extrinsics();
 
 		Self::idle_and_finalize_hook(block_number);
@@ -578,6 +777,7 @@ where
 	pub fn finalize_block() -> frame_system::pallet_prelude::HeaderFor<System> {
 		sp_io::init_tracing();
 		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "finalize_block");
+		Self::inherents_applied();
 		<frame_system::Pallet<System>>::note_finished_extrinsics();
 		let block_number = <frame_system::Pallet<System>>::block_number();
 
@@ -586,25 +786,94 @@ where
 		<frame_system::Pallet<System>>::finalize()
 	}
 
-	fn idle_and_finalize_hook(block_number: NumberFor<Block>) {
-		let weight = <frame_system::Pallet<System>>::block_weight();
-		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
-		let remaining_weight = max_weight.saturating_sub(weight.total());
+	/// Note that all inherents of the block have been applied and step the multi-block
+	/// migrations if they are ongoing, or poll all pallets otherwise.
+	///
+	/// The migrations are stepped after the inherents, so that they see the state of the block
+	/// that the inherents set, e.g. the timestamp or the parachain validation data.
+	///
+	/// Does nothing if it was already called in the current block.
+	fn inherents_applied() {
+		if <frame_system::Pallet<System>>::inherents_applied() {
+			return
+		}
+		<frame_system::Pallet<System>>::note_inherents_applied();
 
-		if remaining_weight.all_gt(Weight::zero()) {
-			let used_weight = <AllPalletsWithSystem as OnIdle<BlockNumberFor<System>>>::on_idle(
-				block_number,
-				remaining_weight,
-			);
+		if CMultiStepMigrator::ongoing() {
+			let used_weight = CMultiStepMigrator::step();
 			<frame_system::Pallet<System>>::register_extra_weight_unchecked(
 				used_weight,
 				DispatchClass::Mandatory,
 			);
+		} else {
+			Self::poll_pallets(<frame_system::Pallet<System>>::block_number());
 		}
+	}
+
+	/// Call the `on_poll` hook of every pallet with its share of the poll budget.
+	///
+	/// The budget is the remaining block weight, capped by `COnPollBudget`. Pallets are polled in
+	/// round-robin order, starting at the pallet indexed by the block number. Each pallet is
+	/// limited to an equal share of the budget that is left when it is polled, so weight that is
+	/// not used by a pallet is handed to the pallets after it.
+	fn poll_pallets(block_number: BlockNumberFor<System>) {
+		let count = <AllPalletsWithSystem as OnPollPallets<BlockNumberFor<System>>>::pallet_count();
+		if count == 0 {
+			return
+		}
+
+		let weight = <frame_system::Pallet<System>>::block_weight();
+		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let mut remaining_weight =
+			max_weight.saturating_sub(weight.total()).min(COnPollBudget::get());
+
+		let start: u32 = (block_number % count.into()).saturated_into();
+		for offset in 0..count {
+			let position = (start + offset) % count;
+			let share = remaining_weight.saturating_div((count - offset) as u64);
+			let mut meter = WeightMeter::with_limit(share);
+			<AllPalletsWithSystem as OnPollPallets<BlockNumberFor<System>>>::on_poll_pallet(
+				position,
+				block_number,
+				&mut meter,
+			);
 
+			let consumed = meter.consumed();
+			remaining_weight.saturating_reduce(consumed);
+			if let Some(pallet_index) = <AllPalletsWithSystem as OnPollPallets<
+				BlockNumberFor<System>,
+			>>::pallet_index(position)
+			{
+				<frame_system::Pallet<System>>::note_pallet_polled(pallet_index, consumed);
+			}
+		}
+	}
+
+	fn idle_and_finalize_hook(block_number: NumberFor<Block>) {
+		Self::idle_hook(block_number);
 		<AllPalletsWithSystem as OnFinalize<BlockNumberFor<System>>>::on_finalize(block_number);
 	}
 
+	/// Run `on_idle` with the remaining weight of the block, returning the weight it used.
+	fn idle_hook(block_number: NumberFor<Block>) -> Weight {
+		let weight = <frame_system::Pallet<System>>::block_weight();
+		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let remaining_weight = max_weight.saturating_sub(weight.total());
+
+		if !remaining_weight.all_gt(Weight::zero()) {
+			return Weight::zero()
+		}
+		let used_weight = <AllPalletsWithSystem as OnIdle<BlockNumberFor<System>>>::on_idle(
+			block_number,
+			remaining_weight,
+		);
+		<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+			used_weight,
+			DispatchClass::Mandatory,
+		);
+		used_weight
+	}
+
 	/// Apply extrinsic outside of the block execution function.
 	///
 	/// This doesn't attempt to validate anything regarding the block, but it builds a list of uxt
@@ -618,6 +887,13 @@ where
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default())?;
 
+		let dispatch_info = xt.get_dispatch_info();
+		// The first extrinsic that is not an inherent marks the end of the inherents.
+		if dispatch_info.class != DispatchClass::Mandatory {
+			Self::inherents_applied();
+		}
+		Self::ensure_allowed_during_migrations(&dispatch_info)?;
+
 		// We don't need to make sure to `note_extrinsic` only after we know it's going to be
 		// executed to prevent it from leaking in storage since at this point, it will either
 		// execute or panic (and revert storage changes).
@@ -626,7 +902,6 @@ where
 		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// Decode parameters and dispatch
-		let dispatch_info = xt.get_dispatch_info();
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 		// Mandatory(inherents) are not allowed to fail.
@@ -642,6 +917,19 @@ where
 		Ok(r.map(|_| ()).map_err(|e| e.error))
 	}
 
+	/// Only inherents may be applied while multi-block migrations are ongoing.
+	///
+	/// Other extrinsics are rejected as exhausting the block resources, so that block authors
+	/// skip them without dropping them from the transaction pool.
+	fn ensure_allowed_during_migrations(
+		dispatch_info: &DispatchInfo,
+	) -> Result<(), InvalidTransaction> {
+		if dispatch_info.class != DispatchClass::Mandatory && CMultiStepMigrator::ongoing() {
+			return Err(InvalidTransaction::ExhaustsResources)
+		}
+		Ok(())
+	}
+
 	fn final_checks(header: &frame_system::pallet_prelude::HeaderFor<System>) {
 		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "final_checks");
 		// remove temporaries
@@ -751,7 +1039,7 @@ mod tests {
 
 	use frame_support::{
 		assert_err, parameter_types,
-		traits::{fungible, ConstU32, ConstU64, ConstU8, Currency},
+		traits::{fungible, ConstU32, ConstU64, ConstU8, Currency, Get},
 		weights::{ConstantMultiplier, IdentityFee, RuntimeDbWeight, Weight, WeightToFee},
 	};
 	use frame_system::{ChainContext, LastRuntimeUpgrade, LastRuntimeUpgradeInfo};
@@ -785,6 +1073,12 @@ mod tests {
 				Weight::from_parts(175, 0)
 			}
 
+			fn on_poll(n: BlockNumberFor<T>, weight: &mut frame_support::weights::WeightMeter) {
+				println!("on_poll({}, {})", n, weight.remaining());
+				super::PollLimits::mutate(|limits| limits.push(weight.limit()));
+				let _ = weight.try_consume(super::PollWeight::get());
+			}
+
 			fn on_finalize(n: BlockNumberFor<T>) {
 				println!("on_finalize({})", n);
 			}
@@ -839,6 +1133,10 @@ mod tests {
 				sp_io::storage::set("storage_root".as_bytes(), &root);
 				Ok(())
 			}
+
+			pub fn overweight(_origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+				Ok(Some(Weight::from_parts(1_000, 0)).into())
+			}
 		}
 
 		#[pallet::inherent]
@@ -914,6 +1212,7 @@ mod tests {
 		type RuntimeOrigin = RuntimeOrigin;
 		type Nonce = u64;
 		type RuntimeCall = RuntimeCall;
+		type RuntimeTask = RuntimeTask;
 		type Hash = sp_core::H256;
 		type Hashing = BlakeTwo256;
 		type AccountId = u64;
@@ -1000,6 +1299,36 @@ mod tests {
 		}
 	}
 
+	parameter_types! {
+		/// Whether the mocked multi-block migrations are ongoing.
+		pub static MbmActive: bool = false;
+		/// How often the mocked multi-block migrations were stepped.
+		pub static MbmSteps: u32 = 0;
+		/// The number of steps after which the mocked multi-block migrations complete.
+		pub static MbmCompletesAfter: u32 = u32::MAX;
+	}
+
+	parameter_types! {
+		/// The weight limit that `on_poll` of the custom pallet was called with in each poll.
+		pub static PollLimits: Vec<Weight> = vec![];
+		/// The weight that `on_poll` of the custom pallet consumes.
+		pub static PollWeight: Weight = Weight::zero();
+		/// The budget of the `on_poll` hooks of a block.
+		pub static PollBudget: Weight = Weight::MAX;
+	}
+
+	struct MockedMigrator;
+	impl MultiStepMigrator for MockedMigrator {
+		fn ongoing() -> bool {
+			MbmActive::get() && MbmSteps::get() < MbmCompletesAfter::get()
+		}
+
+		fn step() -> Weight {
+			MbmSteps::mutate(|s| *s += 1);
+			Weight::from_parts(10, 0)
+		}
+	}
+
 	type Executive = super::Executive<
 		Runtime,
 		Block<TestXt>,
@@ -1007,6 +1336,7 @@ mod tests {
 		Runtime,
 		AllPalletsWithSystem,
 		CustomOnRuntimeUpgrade,
+		MockedMigrator,
 	>;
 
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
@@ -1686,4 +2016,317 @@ mod tests {
 			);
 		})
 	}
+
+	#[test]
+	fn only_inherents_are_applied_during_migrations() {
+		let xt1 = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let xt2 = TestXt::new(call_transfer(33, 0), sign_extra(1, 0, 0));
+
+		new_test_ext(1).execute_with(|| {
+			MbmActive::set(true);
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			// The migrations are stepped once the inherents are applied.
+			Executive::apply_extrinsic(xt1).unwrap().unwrap();
+			assert_eq!(MbmSteps::get(), 0);
+			assert_eq!(
+				Executive::apply_extrinsic(xt2.clone()).unwrap_err(),
+				InvalidTransaction::ExhaustsResources.into()
+			);
+
+			assert_eq!(MbmSteps::get(), 1);
+
+			// Transactions are applied again once the migrations completed.
+			MbmActive::set(false);
+			Executive::apply_extrinsic(xt2).unwrap().unwrap();
+		});
+	}
+
+	#[test]
+	fn on_poll_runs_after_inherents_and_before_transactions() {
+		let xt1 = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let xt2 = TestXt::new(call_transfer(33, 0), sign_extra(1, 0, 0));
+
+		new_test_ext(1).execute_with(|| {
+			PollWeight::set(Weight::from_parts(7, 0));
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+
+			Executive::apply_extrinsic(xt1).unwrap().unwrap();
+			assert!(PollLimits::get().is_empty());
+			let mandatory_weight = *System::block_weight().get(DispatchClass::Mandatory);
+
+			Executive::apply_extrinsic(xt2).unwrap().unwrap();
+			assert_eq!(PollLimits::get().len(), 1);
+			assert_eq!(
+				System::block_weight().get(DispatchClass::Mandatory),
+				&(mandatory_weight + Weight::from_parts(7, 0))
+			);
+			let custom_index = <Custom as frame_support::traits::PalletInfoAccess>::index();
+			assert!(System::events().contains(&frame_system::EventRecord {
+				phase: frame_system::Phase::ApplyExtrinsic(1),
+				event: frame_system::Event::<Runtime>::PalletPolled {
+					pallet_index: custom_index as u32,
+					consumed: Weight::from_parts(7, 0),
+				}
+				.into(),
+				topics: vec![],
+			}));
+
+			// Pallets are polled only once per block.
+			Executive::finalize_block();
+			assert_eq!(PollLimits::get().len(), 1);
+		});
+	}
+
+	#[test]
+	fn on_poll_runs_in_blocks_without_transactions() {
+		let xt = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let header = new_test_ext(1).execute_with(|| {
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			Executive::apply_extrinsic(xt.clone()).unwrap().unwrap();
+			Executive::finalize_block()
+		});
+		assert_eq!(PollLimits::take().len(), 1);
+
+		new_test_ext(1).execute_with(|| {
+			Executive::execute_block(Block::new(header, vec![xt]));
+		});
+		assert_eq!(PollLimits::take().len(), 1);
+	}
+
+	#[test]
+	fn on_poll_budget_is_shared_round_robin() {
+		let pallets = <AllPalletsWithSystem as OnPollPallets<u64>>::pallet_count() as u64;
+		let custom_position = 3;
+		assert_eq!(
+			<AllPalletsWithSystem as OnPollPallets<u64>>::pallet_index(custom_position),
+			Some(<Custom as frame_support::traits::PalletInfoAccess>::index() as u32)
+		);
+
+		for block_number in 1..=pallets {
+			new_test_ext(1).execute_with(|| {
+				Executive::initialize_block(&Header::new(
+					block_number,
+					H256::default(),
+					H256::default(),
+					[69u8; 32].into(),
+					Digest::default(),
+				));
+				let max_block = <Runtime as frame_system::Config>::BlockWeights::get().max_block;
+				let remaining = max_block - System::block_weight().total();
+				let budget = remaining.min(DefaultPollBudget::<Runtime>::get());
+
+				Executive::finalize_block();
+
+				// The other pallets do not consume anything, so the custom pallet gets an equal
+				// share of the whole budget with the pallets polled after it.
+				let polled_before =
+					(custom_position as u64 + pallets - block_number % pallets) % pallets;
+				assert_eq!(
+					PollLimits::take(),
+					vec![budget.saturating_div(pallets - polled_before)]
+				);
+			});
+		}
+	}
+
+	#[test]
+	fn on_poll_budget_is_configurable() {
+		type Executive = super::Executive<
+			Runtime,
+			Block<TestXt>,
+			ChainContext<Runtime>,
+			Runtime,
+			AllPalletsWithSystem,
+			CustomOnRuntimeUpgrade,
+			MockedMigrator,
+			PollBudget,
+		>;
+
+		// A tenth of the `max_total` of the normal class.
+		assert_eq!(DefaultPollBudget::<Runtime>::get(), Weight::from_parts(102, u64::MAX / 10));
+
+		let pallets = <AllPalletsWithSystem as OnPollPallets<u64>>::pallet_count() as u64;
+		new_test_ext(1).execute_with(|| {
+			PollBudget::set(Weight::from_parts(pallets * 10, 0));
+			// The custom pallet is polled first in block 3.
+			Executive::initialize_block(&Header::new(
+				3,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			Executive::finalize_block();
+
+			assert_eq!(PollLimits::take(), vec![Weight::from_parts(10, 0)]);
+		});
+	}
+
+	#[test]
+	fn on_poll_is_skipped_during_migrations() {
+		let xt1 = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let xt2 = TestXt::new(call_transfer(33, 0), sign_extra(1, 0, 0));
+
+		new_test_ext(1).execute_with(|| {
+			MbmActive::set(true);
+			Executive::initialize_block(&Header::new(
+				1,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			Executive::apply_extrinsic(xt1).unwrap().unwrap();
+			assert!(Executive::apply_extrinsic(xt2).is_err());
+			Executive::finalize_block();
+
+			assert!(PollLimits::get().is_empty());
+			MbmActive::set(false);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn try_runtime_upgrade_steps_migrations_to_completion() {
+		new_test_ext(1).execute_with(|| {
+			MbmActive::set(true);
+			MbmCompletesAfter::set(3);
+			let block_number = System::block_number();
+
+			Executive::try_runtime_upgrade(UpgradeCheckSelect::PreAndPost).unwrap();
+			assert_eq!(MbmSteps::get(), 3);
+			assert_eq!(System::block_number(), block_number + 3);
+
+			MbmActive::set(false);
+			MbmCompletesAfter::set(u32::MAX);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn try_execute_block_hook_returns_the_weight_of_each_hook() {
+		new_test_ext(1).execute_with(|| {
+			let header = Header::new_from_number(1);
+			let hooks = [
+				(BlockHook::OnRuntimeUpgrade, Weight::zero()),
+				(BlockHook::OnInitialize, Weight::from_parts(175, 0)),
+				(BlockHook::OnPoll, Weight::zero()),
+				(BlockHook::OnIdle, Weight::from_parts(175, 0)),
+				(BlockHook::OnFinalize, Weight::zero()),
+			];
+			for (hook, weight) in hooks {
+				assert_eq!(Executive::try_execute_block_hook(&header, hook), weight, "{hook:?}");
+			}
+
+			// Same as executing the block, see `block_hooks_weight_is_stored`.
+			assert_eq!(
+				<frame_system::Pallet<Runtime>>::block_weight().total(),
+				Weight::from_parts(175 + 175 + 10, 0)
+			);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn fuzz_executes_generated_calls() {
+		use fuzzing::FuzzConfig;
+
+		let config = FuzzConfig {
+			blocks: 3,
+			calls_per_block: 2,
+			signers: vec![1],
+			allow_root: false,
+			allow_none: false,
+			allow_custom: false,
+		};
+		// Every choice is a little endian `u32`: the number of calls in the block, then for every
+		// call the pallet, the call and the origin.
+		let block = [
+			[2, 0, 0, 0],
+			// `Custom::some_function` from account 1.
+			[2, 0, 0, 0],
+			[0, 0, 0, 0],
+			[0, 0, 0, 0],
+			// `Custom::inherent_call` is mandatory and therefore skipped.
+			[2, 0, 0, 0],
+			[5, 0, 0, 0],
+			[0, 0, 0, 0],
+		]
+		.concat();
+
+		new_test_ext(1).execute_with(|| {
+			assert!(Executive::fuzz(&block.repeat(2), &config).is_ok());
+			assert_eq!(System::block_number(), 2);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn fuzz_dispatches_from_none_origin() {
+		use fuzzing::FuzzConfig;
+
+		let config = FuzzConfig {
+			blocks: 1,
+			calls_per_block: 1,
+			signers: vec![],
+			allow_root: false,
+			allow_none: true,
+			allow_custom: false,
+		};
+		// One call to `Custom::some_unsigned_message` from the none origin.
+		let input = [[1, 0, 0, 0], [2, 0, 0, 0], [2, 0, 0, 0], [0, 0, 0, 0]].concat();
+
+		new_test_ext(1).execute_with(|| {
+			assert!(Executive::fuzz(&input, &config).is_ok());
+			assert!(System::events().iter().any(|record| matches!(
+				record.event,
+				RuntimeEvent::System(frame_system::Event::ExtrinsicSuccess { .. })
+			)));
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn fuzz_reports_weight_overrun() {
+		use fuzzing::{Finding, FuzzConfig};
+
+		let config = FuzzConfig {
+			blocks: 1,
+			calls_per_block: 1,
+			signers: vec![1],
+			allow_root: false,
+			allow_none: false,
+			allow_custom: false,
+		};
+		// One call to `Custom::overweight` from account 1.
+		let input = [[1, 0, 0, 0], [2, 0, 0, 0], [7, 0, 0, 0], [0, 0, 0, 0]].concat();
+
+		new_test_ext(1).execute_with(|| match Executive::fuzz(&input, &config) {
+			Err(Finding::WeightOverrun { block, call, declared, actual }) => {
+				assert_eq!(block, 1);
+				assert_eq!(call, RuntimeCall::Custom(custom::Call::overweight {}));
+				assert_eq!(declared, Weight::zero());
+				assert_eq!(actual, Weight::from_parts(1_000, 0));
+			},
+			other => panic!("unexpected result: {other:?}"),
+		});
+	}
 }
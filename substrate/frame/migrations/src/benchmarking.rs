@@ -0,0 +1,208 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_runtime::traits::One;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+/// A cursor with the maximal proof size, since its inner cursor is bounded by its MEL.
+fn cursor<T: Config>() -> CursorOf<T> {
+	MigrationCursor::Active(ActiveCursor {
+		index: u32::MAX,
+		inner_cursor: None,
+		started_at: 0u32.into(),
+	})
+}
+
+#[benchmarks]
+mod benches {
+	use super::*;
+
+	#[benchmark]
+	fn onboard_new_mbms() {
+		T::Migrations::set_success_after(0); // Happy path
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::onboard_new_mbms();
+		}
+
+		assert_last_event::<T>(Event::UpgradeStarted { migrations: 1 }.into());
+	}
+
+	#[benchmark]
+	fn progress_mbms_none() {
+		T::Migrations::set_success_after(0);
+		assert!(!Cursor::<T>::exists());
+
+		#[block]
+		{
+			Pallet::<T>::progress_mbms(One::one());
+		}
+	}
+
+	/// All migrations completed.
+	#[benchmark]
+	fn exec_migration_completed() {
+		T::Migrations::set_success_after(0);
+		assert_eq!(T::Migrations::len(), 1, "Setup failed");
+		let c = ActiveCursor { index: 1, inner_cursor: None, started_at: 0u32.into() };
+		let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::exec_migration(c, false, &mut meter);
+		}
+
+		assert_last_event::<T>(Event::UpgradeCompleted.into());
+	}
+
+	/// No migration runs since it is skipped as historic.
+	#[benchmark]
+	fn exec_migration_skipped_historic() {
+		T::Migrations::set_success_after(0);
+		assert_eq!(T::Migrations::len(), 1, "Setup failed");
+		let c = ActiveCursor { index: 0, inner_cursor: None, started_at: 0u32.into() };
+
+		let id: IdentifierOf<T> = T::Migrations::nth_id(0).unwrap().try_into().unwrap();
+		Historic::<T>::insert(id, ());
+
+		let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::exec_migration(c, false, &mut meter);
+		}
+
+		assert_last_event::<T>(Event::MigrationSkipped { index: 0 }.into());
+	}
+
+	/// Advance a migration by one step.
+	#[benchmark]
+	fn exec_migration_advance() {
+		T::Migrations::set_success_after(1);
+		assert_eq!(T::Migrations::len(), 1, "Setup failed");
+		let c = ActiveCursor { index: 0, inner_cursor: None, started_at: 0u32.into() };
+		let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::exec_migration(c, false, &mut meter);
+		}
+
+		assert_last_event::<T>(Event::MigrationAdvanced { index: 0, took: One::one() }.into());
+	}
+
+	/// Successfully complete a migration.
+	#[benchmark]
+	fn exec_migration_complete() {
+		T::Migrations::set_success_after(0);
+		assert_eq!(T::Migrations::len(), 1, "Setup failed");
+		let c = ActiveCursor { index: 0, inner_cursor: None, started_at: 0u32.into() };
+		let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::exec_migration(c, false, &mut meter);
+		}
+
+		assert_last_event::<T>(Event::MigrationCompleted { index: 0, took: One::one() }.into());
+	}
+
+	/// A migration fails and the chain gets stuck.
+	#[benchmark]
+	fn exec_migration_fail() {
+		T::Migrations::set_fail_after(0);
+		assert_eq!(T::Migrations::len(), 1, "Setup failed");
+		let c = ActiveCursor { index: 0, inner_cursor: None, started_at: 0u32.into() };
+		let mut meter = WeightMeter::with_limit(T::MaxServiceWeight::get());
+		System::<T>::set_block_number(One::one());
+
+		#[block]
+		{
+			Pallet::<T>::exec_migration(c, false, &mut meter);
+		}
+
+		assert_last_event::<T>(Event::UpgradeFailed.into());
+	}
+
+	#[benchmark]
+	fn force_set_cursor() {
+		let c = Some(cursor::<T>());
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, c.clone());
+
+		assert_eq!(Cursor::<T>::get(), c);
+	}
+
+	#[benchmark]
+	fn force_set_active_cursor() {
+		#[extrinsic_call]
+		_(RawOrigin::Root, 0, None, None);
+
+		assert!(Cursor::<T>::exists());
+	}
+
+	#[benchmark]
+	fn force_onboard_mbms() {
+		T::Migrations::set_success_after(0);
+		System::<T>::set_block_number(One::one());
+
+		#[extrinsic_call]
+		_(RawOrigin::Root);
+
+		assert_last_event::<T>(Event::UpgradeStarted { migrations: 1 }.into());
+	}
+
+	#[benchmark]
+	fn clear_historic(n: Linear<0, { DEFAULT_HISTORIC_BATCH_CLEAR_SIZE * 2 }>) {
+		let id_max_len = <T as Config>::IdentifierMaxLen::get();
+		assert!(id_max_len >= 4, "Precondition violated");
+
+		for i in 0..DEFAULT_HISTORIC_BATCH_CLEAR_SIZE * 2 {
+			let id = IdentifierOf::<T>::truncate_from(
+				i.encode().into_iter().cycle().take(id_max_len as usize).collect::<Vec<_>>(),
+			);
+
+			Historic::<T>::insert(&id, ());
+		}
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Root,
+			HistoricCleanupSelector::Wildcard { limit: n.into(), previous_cursor: None },
+		);
+	}
+
+	// Implements a test for each benchmark. Execute with:
+	// `cargo test -p pallet-migrations --features runtime-benchmarks`.
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
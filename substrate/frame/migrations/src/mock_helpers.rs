@@ -0,0 +1,154 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test helpers for internal and external usage.
+
+#![allow(missing_docs)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	migrations::*,
+	weights::{Weight, WeightMeter},
+};
+use sp_std::vec::Vec;
+
+/// Different kinds of migrations that can be mocked.
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub enum MockedMigrationKind {
+	/// Succeed after its number of steps elapsed.
+	SucceedAfter,
+	/// Fail after its number of steps elapsed.
+	FailAfter,
+	/// Never terminate.
+	TimeoutAfter,
+	/// Cause an [`SteppedMigrationError::InsufficientWeight`] error after its number of steps
+	/// elapsed.
+	HighWeightAfter(Weight),
+}
+use MockedMigrationKind::*; // C style
+
+/// Creates a migration identifier with a specific `kind` and `steps`.
+pub fn mocked_id(kind: MockedMigrationKind, steps: u32) -> Vec<u8> {
+	(b"MockedMigration", kind, steps).encode()
+}
+
+frame_support::parameter_types! {
+	/// The configs for the migrations to run.
+	storage MockedMigrationList: Vec<(MockedMigrationKind, u32)> = Vec::new();
+}
+
+/// Allows to set the migrations to run at runtime instead of compile-time.
+///
+/// It achieves this by using the storage to store the migrations to run.
+pub struct MockedMigrations;
+impl SteppedMigrations for MockedMigrations {
+	fn len() -> u32 {
+		MockedMigrationList::get().len() as u32
+	}
+
+	fn nth_id(n: u32) -> Option<Vec<u8>> {
+		let k = MockedMigrationList::get().get(n as usize).copied();
+		k.map(|(kind, steps)| mocked_id(kind, steps))
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		MockedMigrationList::get().get(n as usize).map(|(kind, steps)| match kind {
+			TimeoutAfter => Some(*steps),
+			_ => None,
+		})
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		_meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		let (kind, steps) = *MockedMigrationList::get().get(n as usize)?;
+
+		let mut count: u32 =
+			cursor.as_ref().and_then(|c| Decode::decode(&mut &c[..]).ok()).unwrap_or(0);
+		log::debug!("MockedMigration: Step {}", count);
+		if count != steps || matches!(kind, TimeoutAfter) {
+			count += 1;
+			return Some(Ok(Some(count.encode())))
+		}
+
+		Some(match kind {
+			SucceedAfter => {
+				log::debug!("MockedMigration: Succeeded after {} steps", count);
+				Ok(None)
+			},
+			HighWeightAfter(required) => {
+				log::debug!("MockedMigration: Not enough weight after {} steps", count);
+				Err(SteppedMigrationError::InsufficientWeight { required })
+			},
+			FailAfter => {
+				log::debug!("MockedMigration: Failed after {} steps", count);
+				Err(SteppedMigrationError::Failed)
+			},
+			TimeoutAfter => unreachable!(),
+		})
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		// This is a hack but should be fine. We don't need it in testing.
+		Self::nth_step(n, cursor, meter)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>> {
+		MockedMigrationList::get().get(n as usize).map(|_| Ok(Vec::new()))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		n: u32,
+		_state: Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		MockedMigrationList::get().get(n as usize).map(|_| Ok(()))
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		65_536
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		256
+	}
+}
+
+impl MockedMigrations {
+	/// Set the migrations to run.
+	pub fn set(migrations: Vec<(MockedMigrationKind, u32)>) {
+		MockedMigrationList::set(&migrations);
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::MockedMigrations for MockedMigrations {
+	fn set_fail_after(steps: u32) {
+		MockedMigrationList::set(&sp_std::vec![(FailAfter, steps)]);
+	}
+
+	fn set_success_after(steps: u32) {
+		MockedMigrationList::set(&sp_std::vec![(SucceedAfter, steps)]);
+	}
+}
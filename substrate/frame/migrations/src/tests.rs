@@ -0,0 +1,320 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use crate::{
+	mock::{Test as T, *},
+	mock_helpers::{MockedMigrationKind::*, *},
+	Cursor, Error, Event, HistoricCleanupSelector, MigrationCursor,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	migrations::{FailedMigrationHandling, MultiStepMigrator},
+	traits::OnRuntimeUpgrade,
+	weights::Weight,
+};
+use sp_runtime::traits::BadOrigin;
+
+/// Returns the identifiers of the given migrations, sorted like [`historic`].
+fn ids(migrations: Vec<(MockedMigrationKind, u32)>) -> Vec<Vec<u8>> {
+	let mut ids: Vec<_> = migrations.into_iter().map(|(k, s)| mocked_id(k, s)).collect();
+	ids.sort();
+	ids
+}
+
+/// The historic migrations as plain vectors.
+fn historic_ids() -> Vec<Vec<u8>> {
+	historic().into_iter().map(|id| id.into_inner()).collect()
+}
+
+#[test]
+fn basic_works() {
+	test_closure(|| {
+		// Add three migrations, each taking one block longer than the previous.
+		MockedMigrations::set(vec![(SucceedAfter, 0), (SucceedAfter, 1), (SucceedAfter, 2)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		// Check that the executed migrations are recorded in `Historical`.
+		assert_eq!(
+			historic_ids(),
+			ids(vec![(SucceedAfter, 0), (SucceedAfter, 1), (SucceedAfter, 2)])
+		);
+
+		// Check that we got all events.
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 3 },
+			Event::MigrationCompleted { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 1, took: 0 },
+			Event::MigrationCompleted { index: 1, took: 1 },
+			Event::MigrationAdvanced { index: 2, took: 0 },
+			Event::MigrationAdvanced { index: 2, took: 1 },
+			Event::MigrationCompleted { index: 2, took: 2 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+		assert!(!Migrations::ongoing());
+	});
+}
+
+#[test]
+fn no_migrations_does_nothing() {
+	test_closure(|| {
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		assert!(!Migrations::ongoing());
+		assert!(historic().is_empty());
+		assert_events(vec![]);
+		assert_eq!(upgrades_started_completed_failed(), (0, 0, 0));
+	});
+}
+
+#[test]
+fn failing_migration_sets_cursor_to_stuck() {
+	test_closure(|| {
+		FailedUpgradeResponse::set(FailedMigrationHandling::KeepStuck);
+		MockedMigrations::set(vec![(FailAfter, 2)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		// Failed migrations are not recorded in `Historical`.
+		assert!(historic().is_empty());
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationFailed { index: 0, took: 3 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+
+		// The cursor stays stuck and keeps blocking transactions.
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+		assert!(Migrations::ongoing());
+	});
+}
+
+#[test]
+fn failing_migration_force_unstuck_works() {
+	test_closure(|| {
+		FailedUpgradeResponse::set(FailedMigrationHandling::ForceUnstuck);
+		MockedMigrations::set(vec![(FailAfter, 0)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationFailed { index: 0, took: 1 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert!(Cursor::<T>::get().is_none());
+		assert!(!Migrations::ongoing());
+	});
+}
+
+#[test]
+fn high_weight_migration_fails() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(HighWeightAfter(Weight::MAX), 2)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		assert!(historic().is_empty());
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationFailed { index: 0, took: 3 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+	});
+}
+
+#[test]
+fn timeout_migration_fails() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(TimeoutAfter, 3)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		assert!(historic().is_empty());
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::MigrationAdvanced { index: 0, took: 2 },
+			Event::MigrationAdvanced { index: 0, took: 3 },
+			Event::MigrationAdvanced { index: 0, took: 4 },
+			Event::MigrationFailed { index: 0, took: 4 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 0, 1));
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+	});
+}
+
+#[test]
+fn historic_skipping_works() {
+	test_closure(|| {
+		MockedMigrations::set(vec![
+			(SucceedAfter, 0),
+			(SucceedAfter, 0), // Will be skipped
+			(SucceedAfter, 1),
+		]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+
+		assert_eq!(historic_ids(), ids(vec![(SucceedAfter, 0), (SucceedAfter, 1)]));
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 3 },
+			Event::MigrationCompleted { index: 0, took: 1 },
+			Event::MigrationSkipped { index: 1 },
+			Event::MigrationAdvanced { index: 2, took: 0 },
+			Event::MigrationCompleted { index: 2, took: 1 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+
+		// Now go for another upgrade; just to make sure that it wont execute again.
+		Migrations::on_runtime_upgrade();
+		run_to_block(20);
+
+		// Everything got skipped.
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 3 },
+			Event::MigrationSkipped { index: 0 },
+			Event::MigrationSkipped { index: 1 },
+			Event::MigrationSkipped { index: 2 },
+			Event::UpgradeCompleted,
+		]);
+		assert_eq!(upgrades_started_completed_failed(), (1, 1, 0));
+	});
+}
+
+#[test]
+fn upgrade_while_ongoing_gets_stuck() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(SucceedAfter, 5)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(2);
+
+		// Another upgrade interrupts the ongoing migrations.
+		Migrations::on_runtime_upgrade();
+		assert_events(vec![
+			Event::UpgradeStarted { migrations: 1 },
+			Event::MigrationAdvanced { index: 0, took: 1 },
+			Event::UpgradeFailed,
+		]);
+		assert_eq!(UpgradesFailed::take(), vec![Some(0)]);
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+	});
+}
+
+#[test]
+fn force_onboard_mbms_works() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(SucceedAfter, 1)]);
+		System::set_block_number(1);
+
+		assert_noop!(Migrations::force_onboard_mbms(RuntimeOrigin::signed(1)), BadOrigin);
+		assert_ok!(Migrations::force_onboard_mbms(RuntimeOrigin::root()));
+		assert!(Migrations::ongoing());
+
+		// Cannot onboard while migrations are ongoing.
+		assert_noop!(Migrations::force_onboard_mbms(RuntimeOrigin::root()), Error::<T>::Ongoing);
+
+		run_to_block(10);
+		assert!(!Migrations::ongoing());
+		assert_eq!(historic_ids(), ids(vec![(SucceedAfter, 1)]));
+	});
+}
+
+#[test]
+fn force_set_cursor_works() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(SucceedAfter, 1)]);
+		System::set_block_number(1);
+
+		assert_noop!(Migrations::force_set_cursor(RuntimeOrigin::signed(1), None), BadOrigin);
+		assert_ok!(Migrations::force_set_cursor(
+			RuntimeOrigin::root(),
+			Some(MigrationCursor::Stuck)
+		));
+		assert!(Migrations::ongoing());
+
+		// Stuck migrations do not make progress.
+		run_to_block(5);
+		assert_eq!(Cursor::<T>::get(), Some(MigrationCursor::Stuck));
+
+		// Restart the migration process in the next block.
+		assert_ok!(Migrations::force_set_active_cursor(RuntimeOrigin::root(), 0, None, None));
+		run_to_block(10);
+		assert!(!Migrations::ongoing());
+		assert_eq!(historic_ids(), ids(vec![(SucceedAfter, 1)]));
+	});
+}
+
+#[test]
+fn clear_historic_works() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(SucceedAfter, 0), (SucceedAfter, 1), (SucceedAfter, 2)]);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(10);
+		assert_eq!(historic().len(), 3);
+		events();
+
+		// Clear a specific migration.
+		let id = mocked_id(SucceedAfter, 1).try_into().unwrap();
+		assert_ok!(Migrations::clear_historic(
+			RuntimeOrigin::root(),
+			HistoricCleanupSelector::Specific(vec![id])
+		));
+		assert_eq!(historic_ids(), ids(vec![(SucceedAfter, 0), (SucceedAfter, 2)]));
+
+		// Clear the rest with a wildcard.
+		assert_ok!(Migrations::clear_historic(
+			RuntimeOrigin::root(),
+			HistoricCleanupSelector::Wildcard { limit: None, previous_cursor: None }
+		));
+		assert!(historic().is_empty());
+		assert_events(vec![
+			Event::HistoricCleared { next_cursor: None },
+			Event::HistoricCleared { next_cursor: None },
+		]);
+	});
+}
@@ -50,6 +50,7 @@ pub fn expand_runtime_metadata(
 			let constants = expand_pallet_metadata_constants(runtime, decl);
 			let errors = expand_pallet_metadata_errors(runtime, decl);
 			let docs = expand_pallet_metadata_docs(runtime, decl);
+			let view_functions = expand_pallet_metadata_view_functions(runtime, decl);
 			let attr = decl.cfg_pattern.iter().fold(TokenStream::new(), |acc, pattern| {
 				let attr = TokenStream::from_str(&format!("#[cfg({})]", pattern.original()))
 					.expect("was successfully parsed before; qed");
@@ -69,6 +70,7 @@ pub fn expand_runtime_metadata(
 					event: #event,
 					constants: #constants,
 					error: #errors,
+					view_functions: #view_functions,
 					docs: #docs,
 				}
 			}
@@ -115,6 +117,7 @@ pub fn expand_runtime_metadata(
 					extrinsic: #scrate::__private::metadata_ir::ExtrinsicMetadataIR {
 						ty,
 						version: <#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
+						versions: <#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata>::VERSIONS.into(),
 						address_ty,
 						call_ty,
 						signature_ty,
@@ -131,6 +134,20 @@ pub fn expand_runtime_metadata(
 									additional_signed: meta.additional_signed,
 								})
 								.collect(),
+						transaction_extensions: <
+								<
+									#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata
+								>::TransactionExtensions as #scrate::sp_runtime::traits::TransactionExtension<
+									<#extrinsic as #scrate::sp_runtime::traits::Extrinsic>::Call
+								>
+							>::metadata()
+								.into_iter()
+								.map(|meta| #scrate::__private::metadata_ir::TransactionExtensionMetadataIR {
+									identifier: meta.identifier,
+									ty: meta.ty,
+									implicit: meta.additional_signed,
+								})
+								.collect(),
 					},
 					ty: #scrate::__private::scale_info::meta_type::<#runtime>(),
 					apis: (&rt).runtime_metadata(),
@@ -256,3 +273,12 @@ fn expand_pallet_metadata_docs(runtime: &Ident, decl: &Pallet) -> TokenStream {
 		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_documentation_metadata()
 	}
 }
+
+fn expand_pallet_metadata_view_functions(runtime: &Ident, decl: &Pallet) -> TokenStream {
+	let path = &decl.path;
+	let instance = decl.instance.as_ref().into_iter();
+
+	quote! {
+		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_view_functions_metadata()
+	}
+}
@@ -0,0 +1,168 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License
+
+use crate::construct_runtime::Pallet;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+use std::str::FromStr;
+
+/// Expands aggregate `RuntimeTask` enum.
+pub fn expand_outer_task(
+	runtime_name: &Ident,
+	pallet_decls: &[Pallet],
+	scrate: &TokenStream2,
+) -> TokenStream2 {
+	let mut from_impls = Vec::new();
+	let mut task_variants = Vec::new();
+	let mut variant_names = Vec::new();
+	let mut task_types = Vec::new();
+	let mut pallet_attrs = Vec::new();
+	let mut query_task_part_macros = Vec::new();
+
+	for decl in pallet_decls.iter().filter(|decl| decl.exists_part("Task")) {
+		let variant_name = &decl.name;
+		let path = &decl.path;
+		let index = decl.index;
+		let attr = decl.cfg_pattern.iter().fold(TokenStream2::new(), |acc, pattern| {
+			let attr = TokenStream2::from_str(&format!("#[cfg({})]", pattern.original()))
+				.expect("was successfully parsed before; qed");
+			quote! {
+				#acc
+				#attr
+			}
+		});
+		let task_type = match decl.instance.as_ref() {
+			Some(inst) => quote!(#path::Task<#runtime_name, #path::#inst>),
+			None => quote!(#path::Task<#runtime_name>),
+		};
+
+		task_variants.push(quote! {
+			#attr
+			#[codec(index = #index)]
+			#variant_name(#task_type),
+		});
+
+		from_impls.push(quote! {
+			#attr
+			impl From<#task_type> for RuntimeTask {
+				fn from(task: #task_type) -> Self {
+					RuntimeTask::#variant_name(task)
+				}
+			}
+
+			#attr
+			impl TryInto<#task_type> for RuntimeTask {
+				type Error = ();
+
+				#[allow(unreachable_patterns)]
+				fn try_into(self) -> Result<#task_type, Self::Error> {
+					match self {
+						RuntimeTask::#variant_name(task) => Ok(task),
+						_ => Err(()),
+					}
+				}
+			}
+		});
+
+		query_task_part_macros.push(quote! {
+			#path::__substrate_task_check::is_task_part_defined!(#variant_name);
+		});
+
+		variant_names.push(variant_name);
+		task_types.push(task_type);
+		pallet_attrs.push(attr);
+	}
+
+	quote! {
+		#( #query_task_part_macros )*
+
+		/// An aggregation of all `Task` enums across all pallets included in the current runtime.
+		#[derive(
+			Clone, Eq, PartialEq,
+			#scrate::__private::codec::Encode,
+			#scrate::__private::codec::Decode,
+			#scrate::__private::scale_info::TypeInfo,
+			#scrate::__private::RuntimeDebug,
+		)]
+		pub enum RuntimeTask {
+			#( #task_variants )*
+		}
+
+		impl #scrate::traits::Task for RuntimeTask {
+			type Enumeration = #scrate::__private::sp_std::boxed::Box<
+				dyn #scrate::__private::sp_std::iter::Iterator<Item = RuntimeTask>
+			>;
+
+			fn iter() -> Self::Enumeration {
+				// The tasks of each pallet are chained lazily, so that callers stopping early
+				// neither enumerate nor allocate the tasks of the remaining pallets.
+				let all_tasks: Self::Enumeration =
+					#scrate::__private::sp_std::boxed::Box::new(
+						#scrate::__private::sp_std::iter::empty()
+					);
+				#(
+					#pallet_attrs
+					let all_tasks: Self::Enumeration =
+						#scrate::__private::sp_std::boxed::Box::new(all_tasks.chain(
+							<#task_types as #scrate::traits::Task>::iter().map(RuntimeTask::from)
+						));
+				)*
+				all_tasks
+			}
+
+			fn is_valid(&self) -> bool {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#variant_names(ref task) =>
+							#scrate::traits::Task::is_valid(task),
+					)*
+				}
+			}
+
+			fn run(&self) -> Result<(), #scrate::sp_runtime::DispatchError> {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#variant_names(ref task) => #scrate::traits::Task::run(task),
+					)*
+				}
+			}
+
+			fn weight(&self) -> #scrate::pallet_prelude::Weight {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#variant_names(ref task) => #scrate::traits::Task::weight(task),
+					)*
+				}
+			}
+
+			fn task_index(&self) -> u32 {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#variant_names(ref task) =>
+							#scrate::traits::Task::task_index(task),
+					)*
+				}
+			}
+		}
+
+		#( #from_impls )*
+	}
+}
@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License
+
+use crate::construct_runtime::Pallet;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+use std::str::FromStr;
+
+/// Expands the aggregate `RuntimeViewFunction` dispatcher.
+pub fn expand_outer_view_function(
+	runtime_name: &Ident,
+	pallet_decls: &[Pallet],
+	scrate: &TokenStream2,
+) -> TokenStream2 {
+	let mut pallet_attrs = Vec::new();
+	let mut pallet_types = Vec::new();
+
+	for decl in pallet_decls.iter().filter(|decl| decl.exists_part("Pallet")) {
+		let path = &decl.path;
+		let instance = decl.instance.as_ref().into_iter();
+		let attr = decl.cfg_pattern.iter().fold(TokenStream2::new(), |acc, pattern| {
+			let attr = TokenStream2::from_str(&format!("#[cfg({})]", pattern.original()))
+				.expect("was successfully parsed before; qed");
+			quote! {
+				#acc
+				#attr
+			}
+		});
+
+		pallet_attrs.push(attr);
+		pallet_types.push(quote!(#path::Pallet<#runtime_name #(, #path::#instance)*>));
+	}
+
+	quote! {
+		/// The aggregated view functions of all pallets of the runtime.
+		///
+		/// Dispatches a view function to the pallet whose prefix matches the id.
+		#[derive(Clone, Copy, PartialEq, Eq, #scrate::sp_runtime::RuntimeDebug)]
+		pub struct RuntimeViewFunction;
+
+		impl #scrate::view_functions::DispatchViewFunction for RuntimeViewFunction {
+			fn dispatch_view_function<O: #scrate::__private::codec::Output>(
+				id: &#scrate::view_functions::ViewFunctionId,
+				input: &mut &[u8],
+				output: &mut O,
+			) -> Result<(), #scrate::view_functions::ViewFunctionDispatchError> {
+				#(
+					#pallet_attrs
+					if id.prefix ==
+						<#pallet_types as #scrate::view_functions::ViewFunctionIdPrefix>::prefix()
+					{
+						return <
+							#pallet_types as #scrate::view_functions::DispatchViewFunction
+						>::dispatch_view_function(id, input, output)
+					}
+				)*
+
+				Err(#scrate::view_functions::ViewFunctionDispatchError::NotFound(*id))
+			}
+		}
+
+		impl #runtime_name {
+			/// Execute the view function identified by `id` with the SCALE encoded arguments
+			/// `input`, returning the SCALE encoded result.
+			///
+			/// Convenience function to implement the `RuntimeViewFunction` runtime API.
+			pub fn execute_view_function(
+				id: #scrate::view_functions::ViewFunctionId,
+				input: #scrate::__private::sp_std::vec::Vec<u8>,
+			) -> Result<
+				#scrate::__private::sp_std::vec::Vec<u8>,
+				#scrate::view_functions::ViewFunctionDispatchError,
+			> {
+				let mut output = #scrate::__private::sp_std::vec::Vec::new();
+				<RuntimeViewFunction as #scrate::view_functions::DispatchViewFunction>::dispatch_view_function(
+					&id,
+					&mut &input[..],
+					&mut output,
+				)?;
+				Ok(output)
+			}
+		}
+	}
+}
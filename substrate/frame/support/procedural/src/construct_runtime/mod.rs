@@ -41,6 +41,11 @@
 //!  - `enum RuntimeError`: While this cannot be used directly to decode `sp_runtime::DispatchError`
 //!    from the chain, it contains the information needed to decode the
 //!    `sp_runtime::DispatchError::Module`.
+//!  - `enum RuntimeTask`: This type contains the tasks declared by the pallets, which can be
+//!    executed through `frame_system::Call::do_task`.
+//!
+//! It also generates `struct RuntimeViewFunction`, which dispatches the view functions declared
+//! by the pallets and is used to implement the `RuntimeViewFunction` runtime API.
 //!
 //! # State Transitions
 //!
@@ -386,6 +391,8 @@ fn construct_runtime_final_expansion(
 	let pallet_to_index = decl_pallet_runtime_setup(&name, &pallets, &scrate);
 
 	let dispatch = expand::expand_outer_dispatch(&name, system_pallet, &pallets, &scrate);
+	let tasks = expand::expand_outer_task(&name, &pallets, &scrate);
+	let view_functions = expand::expand_outer_view_function(&name, &pallets, &scrate);
 	let metadata = expand::expand_runtime_metadata(
 		&name,
 		&pallets,
@@ -475,6 +482,10 @@ fn construct_runtime_final_expansion(
 
 		#dispatch
 
+		#tasks
+
+		#view_functions
+
 		#metadata
 
 		#outer_config
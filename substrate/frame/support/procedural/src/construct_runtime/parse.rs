@@ -33,6 +33,7 @@ mod keyword {
 	syn::custom_keyword!(UncheckedExtrinsic);
 	syn::custom_keyword!(Pallet);
 	syn::custom_keyword!(Call);
+	syn::custom_keyword!(Task);
 	syn::custom_keyword!(Storage);
 	syn::custom_keyword!(Event);
 	syn::custom_keyword!(Error);
@@ -395,6 +396,7 @@ fn parse_pallet_parts(input: ParseStream) -> Result<Vec<PalletPart>> {
 pub enum PalletPartKeyword {
 	Pallet(keyword::Pallet),
 	Call(keyword::Call),
+	Task(keyword::Task),
 	Storage(keyword::Storage),
 	Event(keyword::Event),
 	Error(keyword::Error),
@@ -416,6 +418,8 @@ impl Parse for PalletPartKeyword {
 			Ok(Self::Pallet(input.parse()?))
 		} else if lookahead.peek(keyword::Call) {
 			Ok(Self::Call(input.parse()?))
+		} else if lookahead.peek(keyword::Task) {
+			Ok(Self::Task(input.parse()?))
 		} else if lookahead.peek(keyword::Storage) {
 			Ok(Self::Storage(input.parse()?))
 		} else if lookahead.peek(keyword::Event) {
@@ -450,6 +454,7 @@ impl PalletPartKeyword {
 		match self {
 			Self::Pallet(_) => "Pallet",
 			Self::Call(_) => "Call",
+			Self::Task(_) => "Task",
 			Self::Storage(_) => "Storage",
 			Self::Event(_) => "Event",
 			Self::Error(_) => "Error",
@@ -480,6 +485,7 @@ impl ToTokens for PalletPartKeyword {
 		match self {
 			Self::Pallet(inner) => inner.to_tokens(tokens),
 			Self::Call(inner) => inner.to_tokens(tokens),
+			Self::Task(inner) => inner.to_tokens(tokens),
 			Self::Storage(inner) => inner.to_tokens(tokens),
 			Self::Event(inner) => inner.to_tokens(tokens),
 			Self::Error(inner) => inner.to_tokens(tokens),
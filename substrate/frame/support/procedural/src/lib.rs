@@ -139,6 +139,7 @@ fn counter_prefix(prefix: &str) -> String {
 ///     storage with `GenesisConfig`
 ///   - `Inherent` - If the pallet provides/can check inherents.
 ///   - `ValidateUnsigned` - If the pallet validates unsigned extrinsics.
+///   - `Task` - If the pallet declares tasks with `#[pallet::tasks]`.
 ///
 ///   It is important to list these parts here to export them correctly in the metadata or to make
 /// the pallet usable in the runtime.
@@ -882,13 +883,14 @@ pub fn inject_runtime_type(_: TokenStream, tokens: TokenStream) -> TokenStream {
 	if item.ident != "RuntimeCall" &&
 		item.ident != "RuntimeEvent" &&
 		item.ident != "RuntimeOrigin" &&
+		item.ident != "RuntimeTask" &&
 		item.ident != "RuntimeHoldReason" &&
 		item.ident != "RuntimeFreezeReason" &&
 		item.ident != "PalletInfo"
 	{
 		return syn::Error::new_spanned(
 			item,
-			"`#[inject_runtime_type]` can only be attached to `RuntimeCall`, `RuntimeEvent`, `RuntimeOrigin` or `PalletInfo`",
+			"`#[inject_runtime_type]` can only be attached to `RuntimeCall`, `RuntimeEvent`, `RuntimeOrigin`, `RuntimeTask` or `PalletInfo`",
 		)
 		.to_compile_error()
 		.into();
@@ -1054,6 +1056,31 @@ pub fn storage_version(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+/// The `pallet::track_storage_usage` attribute makes the storage maps of the pallet account for
+/// the bytes they add to and remove from the state in a counter per pallet:
+///
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::track_storage_usage]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// The accounted usage is exposed through `StorageUsageTrait` and checked against the state and
+/// the quota of the pallet in `try_state`. Dispatchables of the pallet growing the usage of any
+/// pallet beyond its quota fail.
+///
+/// The weight of every dispatchable of the pallet includes the overhead of accounting for one
+/// changed entry. Pallets whose dispatchables change more entries can declare the number as an
+/// argument, e.g. `#[pallet::track_storage_usage(4)]`. The overhead beyond that is charged to the
+/// block.
+///
+/// NOTE: A pallet with existing storage starting to track its usage must initialize its counter
+/// with `frame_support::storage::usage::initialize`.
+#[proc_macro_attribute]
+pub fn track_storage_usage(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 /// The `#[pallet::hooks]` attribute allows you to specify a `Hooks` implementation for
 /// `Pallet` that specifies pallet-specific logic.
 ///
@@ -1187,6 +1214,72 @@ pub fn feeless_if(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+/// Allows a pallet to declare a set of functions as *tasks*: work that can be discovered by
+/// inspecting the pallet's storage (typically in an offchain worker) and then be executed
+/// on-chain by anyone through `frame_system::Call::do_task`.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::tasks]
+/// impl<T: Config> Pallet<T> {
+/// 	/// Add a pair of numbers into the totals and remove them.
+/// 	#[pallet::task_index(0)]
+/// 	#[pallet::task_list(Numbers::<T>::iter_keys())]
+/// 	#[pallet::task_condition(|i| Numbers::<T>::contains_key(i))]
+/// 	#[pallet::task_weight(T::WeightInfo::add_number_into_total())]
+/// 	fn add_number_into_total(i: u32) -> DispatchResult {
+/// 		...
+/// 	}
+/// }
+/// ```
+///
+/// Each task function must be annotated with:
+/// * `#[pallet::task_index($idx)]`: a unique index of the task within the pallet.
+/// * `#[pallet::task_list($expr)]`: an iterator over the arguments of all tasks of this kind,
+///   yielding the argument itself for single argument tasks and a tuple of the arguments otherwise.
+///   It is consumed lazily, so it should not collect the arguments up front.
+/// * `#[pallet::task_condition($closure)]`: a closure taking references to the arguments and
+///   returning whether the task is valid.
+/// * `#[pallet::task_weight($expr)]`: the weight of the task, the arguments are in scope as
+///   references. It must include the cost of the `task_condition`, which is checked again when the
+///   task is executed.
+///
+/// ### Macro expansion
+///
+/// The macro generates an enum `Task<T>` with one variant per task function and implements
+/// `frame_support::traits::Task` on it. `construct_runtime!` aggregates the `Task` enums of all
+/// pallets into a `RuntimeTask` enum.
+#[proc_macro_attribute]
+pub fn tasks(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Defines the index of a task within a `#[pallet::tasks]` block. See [`macro@tasks`].
+#[proc_macro_attribute]
+pub fn task_index(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Defines the enumeration of a task within a `#[pallet::tasks]` block. See [`macro@tasks`].
+#[proc_macro_attribute]
+pub fn task_list(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Defines the validity condition of a task within a `#[pallet::tasks]` block. See
+/// [`macro@tasks`].
+#[proc_macro_attribute]
+pub fn task_condition(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Defines the weight of a task within a `#[pallet::tasks]` block. See [`macro@tasks`].
+#[proc_macro_attribute]
+pub fn task_weight(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 /// Allows you to define some extra constants to be added into constant metadata.
 ///
 /// Item must be defined as:
@@ -1498,6 +1591,39 @@ pub fn validate_unsigned(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+/// The `#[pallet::view_functions]` attribute allows the pallet to declare read-only queries,
+/// which can be called from outside the runtime without any node-side code.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::view_functions]
+/// impl<T: Config> Pallet<T> {
+/// 	/// Get the value stored under `key`.
+/// 	pub fn get_value(key: u32) -> Option<u32> {
+/// 		SomeMap::<T>::get(key)
+/// 	}
+/// }
+/// ```
+///
+/// I.e. a regular impl block of `Pallet<T>` with bound `T: Config` and some optional where
+/// clause. Functions must not take `self`, must not be generic and must return a value.
+///
+/// ## Macro expansion
+///
+/// The macro generates one type per function, named after the function in `PascalCase` (e.g.
+/// `GetValue<T>`) and holding its arguments, which implements
+/// `frame_support::view_functions::ViewFunction`. A view function is identified by a
+/// `ViewFunctionId` made of the `twox_128` hash of the pallet name and the `twox_128` hash of the
+/// function signature.
+///
+/// `construct_runtime!` aggregates the view functions of all pallets into `RuntimeViewFunction`
+/// and includes their signatures in the pallet metadata.
+#[proc_macro_attribute]
+pub fn view_functions(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 /// The `#[pallet::origin]` attribute allows you to define some origin for the pallet.
 ///
 /// Item must be either a type alias, an enum, or a struct. It needs to be public.
@@ -211,6 +211,34 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 
 	let capture_docs = if cfg!(feature = "no-metadata-docs") { "never" } else { "always" };
 
+	// The accounting overhead included in the weight of the dispatchables of a pallet tracking its
+	// storage usage.
+	let storage_usage_overhead = def.pallet_struct.track_storage_usage.map(|_| {
+		let changes = def
+			.pallet_struct
+			.storage_usage_changes
+			.as_ref()
+			.map_or_else(|| quote::quote!(1), |changes| quote::quote!(#changes));
+		quote::quote!(#frame_support::storage::usage::StorageUsageOverhead::of_changes(#changes))
+	});
+	let storage_usage_weight = storage_usage_overhead.as_ref().map(|overhead| {
+		quote::quote!(
+			#overhead.weight(<
+				<T as #frame_system::Config>::DbWeight as
+				#frame_support::traits::Get<#frame_support::weights::RuntimeDbWeight>
+			>::get())
+		)
+	});
+	let maybe_add_storage_usage_weight = storage_usage_weight.as_ref().map(
+		|weight| quote::quote!(let __pallet_weight = __pallet_weight.saturating_add(#weight);),
+	);
+	let dispatch_result = match &storage_usage_weight {
+		Some(weight) => quote::quote!(
+			#frame_support::storage::usage::with_declared_overhead(__pallet_result, #weight)
+		),
+		None => quote::quote!(__pallet_result),
+	};
+
 	// Wrap all calls inside of storage layers
 	if let Some(syn::Item::Impl(item_impl)) = def
 		.call
@@ -220,11 +248,28 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 		item_impl.items.iter_mut().for_each(|i| {
 			if let syn::ImplItem::Fn(method) = i {
 				let block = &method.block;
-				method.block = syn::parse_quote! {{
-					// We execute all dispatchable in a new storage layer, allowing them
-					// to return an error at any point, and undoing any storage changes.
-					#frame_support::storage::with_storage_layer(|| #block)
-				}};
+				method.block = if let Some(declared) = &storage_usage_overhead {
+					syn::parse_quote! {{
+						// We execute all dispatchable in a new storage layer, allowing them
+						// to return an error at any point, and undoing any storage changes.
+						// Storage quotas are enforced within the layer, and the overhead of
+						// accounting for the storage usage beyond the declared one is charged
+						// to the block.
+						let (result, overhead) =
+							#frame_support::storage::usage::dispatch_within_quota(|| #block);
+						#frame_system::Pallet::<T>::register_storage_usage_overhead(
+							overhead,
+							#declared,
+						);
+						result
+					}}
+				} else {
+					syn::parse_quote! {{
+						// We execute all dispatchable in a new storage layer, allowing them
+						// to return an error at any point, and undoing any storage changes.
+						#frame_support::storage::with_storage_layer(|| #block)
+					}}
+				};
 			}
 		});
 	}
@@ -334,6 +379,7 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 							let __pallet_weight = <
 								dyn #frame_support::dispatch::WeighData<( #( & #args_type, )* )>
 							>::weigh_data(&__pallet_base_weight, ( #( #args_name, )* ));
+							#maybe_add_storage_usage_weight
 
 							let __pallet_class = <
 								dyn #frame_support::dispatch::ClassifyDispatch<
@@ -421,8 +467,10 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 									#frame_support::__private::sp_tracing::trace_span!(stringify!(#fn_name))
 								);
 								#maybe_allow_attrs
-								<#pallet_ident<#type_use_gen>>::#fn_name(origin, #( #args_name, )* )
-									.map(Into::into).map_err(Into::into)
+								let __pallet_result =
+									<#pallet_ident<#type_use_gen>>::#fn_name(origin, #( #args_name, )* )
+										.map(Into::into).map_err(Into::into);
+								#dispatch_result
 							},
 						)*
 						Self::__Ignore(_, _) => {
@@ -90,6 +90,16 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 		}
 	};
 
+	// If the pallet tracks its storage usage, check that the accounted usage matches the state and
+	// that the pallet stays within its quota.
+	let storage_usage_check = if def.pallet_struct.track_storage_usage.is_some() {
+		quote::quote! {
+			#frame_support::storage::usage::try_state::<Self>()?;
+		}
+	} else {
+		proc_macro2::TokenStream::new()
+	};
+
 	let hooks_impl = if def.hooks.is_none() {
 		let frame_system = &def.frame_system;
 		quote::quote! {
@@ -175,6 +185,22 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 		}
 
+		impl<#type_impl_gen>
+			#frame_support::traits::OnPoll<#frame_system::pallet_prelude::BlockNumberFor::<T>>
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn on_poll(
+				n: #frame_system::pallet_prelude::BlockNumberFor::<T>,
+				weight: &mut #frame_support::weights::WeightMeter
+			) {
+				<
+					Self as #frame_support::traits::Hooks<
+						#frame_system::pallet_prelude::BlockNumberFor::<T>
+					>
+				>::on_poll(n, weight);
+			}
+		}
+
 		impl<#type_impl_gen>
 			#frame_support::traits::OnInitialize<#frame_system::pallet_prelude::BlockNumberFor::<T>>
 			for #pallet_ident<#type_use_gen> #where_clause
@@ -304,6 +330,7 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 					"🩺 Running {:?} try-state checks",
 					#pallet_name,
 				);
+				#storage_usage_check
 				<
 					Self as #frame_support::traits::Hooks<
 						#frame_system::pallet_prelude::BlockNumberFor::<T>
@@ -729,6 +729,14 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 			_ => proc_macro2::TokenStream::default(),
 		};
 
+		let maybe_track_storage_usage = if def.pallet_struct.track_storage_usage.is_some() {
+			quote::quote_spanned!(storage_def.attr_span =>
+				const TRACK_STORAGE_USAGE: bool = true;
+			)
+		} else {
+			proc_macro2::TokenStream::default()
+		};
+
 		let storage_prefix_hash = two128_str(&prefix_struct_const);
 		quote::quote_spanned!(storage_def.attr_span =>
 			#maybe_counter
@@ -763,6 +771,8 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 				fn storage_prefix_hash() -> [u8; 16] {
 					#storage_prefix_hash
 				}
+
+				#maybe_track_storage_usage
 			}
 		)
 	});
@@ -885,6 +895,88 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		)
 	};
 
+	let storage_usage = {
+		let config_where_clause = &def.config.where_clause;
+		let (storage_usage, computed_storage_usage) =
+			if def.pallet_struct.track_storage_usage.is_some() {
+				let tracked_prefixes = def
+					.storages
+					.iter()
+					.filter(|storage| !matches!(storage.metadata, Metadata::Value { .. }))
+					.map(|storage| {
+						let prefix_struct_ident = prefix_ident(storage);
+						let gen = &def.type_use_generics(storage.attr_span);
+						let cfg_attrs = &storage.cfg_attrs;
+						quote::quote_spanned!(storage.attr_span =>
+							#(#cfg_attrs)*
+							{
+								bytes = bytes.saturating_add(
+									#frame_support::storage::usage::prefix_size(
+										&<
+											#prefix_struct_ident<#gen> as
+											#frame_support::traits::StorageInstance
+										>::prefix_hash()
+									)
+								);
+							}
+						)
+					});
+
+				(
+					quote::quote!(
+						#frame_support::__private::sp_std::vec![
+							#frame_support::traits::PalletStorageUsage {
+								pallet_name: pallet_name.as_bytes().to_vec(),
+								bytes: #frame_support::storage::usage::usage(pallet_name.as_bytes()),
+								quota: #frame_support::storage::usage::quota(pallet_name.as_bytes()),
+							}
+						]
+					),
+					quote::quote!(
+						#[allow(unused_mut)]
+						let mut bytes = 0u64;
+						#( #tracked_prefixes )*
+
+						#frame_support::__private::sp_std::vec![
+							#frame_support::traits::PalletStorageUsage {
+								pallet_name: pallet_name.as_bytes().to_vec(),
+								bytes,
+								quota: #frame_support::storage::usage::quota(pallet_name.as_bytes()),
+							}
+						]
+					),
+				)
+			} else {
+				(
+					quote::quote!(#frame_support::__private::sp_std::vec![]),
+					quote::quote!(#frame_support::__private::sp_std::vec![]),
+				)
+			};
+
+		quote::quote!(
+			impl<#type_impl_gen> #frame_support::traits::StorageUsageTrait
+				for #pallet_ident<#type_use_gen>
+				#config_where_clause
+			{
+				#[allow(unused_variables)]
+				fn storage_usage()
+					-> #frame_support::__private::sp_std::vec::Vec<#frame_support::traits::PalletStorageUsage>
+				{
+					let pallet_name = <Self as #frame_support::traits::PalletInfoAccess>::name();
+					#storage_usage
+				}
+
+				#[allow(unused_variables)]
+				fn computed_storage_usage()
+					-> #frame_support::__private::sp_std::vec::Vec<#frame_support::traits::PalletStorageUsage>
+				{
+					let pallet_name = <Self as #frame_support::traits::PalletInfoAccess>::name();
+					#computed_storage_usage
+				}
+			}
+		)
+	};
+
 	quote::quote!(
 		impl<#type_impl_gen> #pallet_ident<#type_use_gen>
 			#completed_where_clause
@@ -911,6 +1003,8 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		#( #prefix_structs )*
 		#( #on_empty_structs )*
 
+		#storage_usage
+
 		#try_decode_entire_state
 	)
 }
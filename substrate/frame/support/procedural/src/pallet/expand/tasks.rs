@@ -0,0 +1,198 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{pallet::Def, COUNTER};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+/// * Generate the `Task` enum with one variant per task and implement `Task` on it.
+/// * Generate the macro used by `construct_runtime` to check the `Task` part is defined.
+pub fn expand_tasks(def: &mut Def) -> TokenStream {
+	let count = COUNTER.with(|counter| counter.borrow_mut().inc());
+	let macro_ident = Ident::new(&format!("__is_task_part_defined_{}", count), def.item.span());
+
+	let maybe_compile_error = if def.tasks.is_none() {
+		quote! {
+			compile_error!(concat!(
+				"`",
+				stringify!($pallet_name),
+				"` does not have #[pallet::tasks] defined, perhaps you should \
+				remove `Task` from construct_runtime?",
+			));
+		}
+	} else {
+		TokenStream::new()
+	};
+
+	let task_check = quote! {
+		#[doc(hidden)]
+		pub mod __substrate_task_check {
+			#[macro_export]
+			#[doc(hidden)]
+			macro_rules! #macro_ident {
+				($pallet_name:ident) => {
+					#maybe_compile_error
+				}
+			}
+
+			#[doc(hidden)]
+			pub use #macro_ident as is_task_part_defined;
+		}
+	};
+
+	let tasks = match def.tasks.as_ref() {
+		Some(tasks) => tasks,
+		None => return task_check,
+	};
+
+	let span = tasks.attr_span;
+	let frame_support = &def.frame_support;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_decl_bounded_gen = &def.type_decl_bounded_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let pallet_ident = &def.pallet_struct.pallet;
+	let where_clause = &tasks.where_clause;
+	let capture_docs = if cfg!(feature = "no-metadata-docs") { "never" } else { "always" };
+
+	let task_name = tasks.tasks.iter().map(|task| &task.name).collect::<Vec<_>>();
+	let task_index = tasks.tasks.iter().map(|task| task.task_index as u32).collect::<Vec<_>>();
+	let codec_index = tasks.tasks.iter().map(|task| task.task_index).collect::<Vec<_>>();
+	let task_doc = tasks.tasks.iter().map(|task| &task.docs).collect::<Vec<_>>();
+	let task_list = tasks.tasks.iter().map(|task| &task.list).collect::<Vec<_>>();
+	let task_condition = tasks.tasks.iter().map(|task| &task.condition).collect::<Vec<_>>();
+	let task_weight = tasks.tasks.iter().map(|task| &task.weight).collect::<Vec<_>>();
+	let args_name = tasks
+		.tasks
+		.iter()
+		.map(|task| task.args.iter().map(|(name, _)| name).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let args_type = tasks
+		.tasks
+		.iter()
+		.map(|task| task.args.iter().map(|(_, type_)| type_).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	// The items yielded by `task_list` are the argument itself for single argument tasks and a
+	// tuple of the arguments otherwise.
+	let list_item_pattern = args_name
+		.iter()
+		.map(|names| match &names[..] {
+			[name] => quote!(#name),
+			names => quote!( ( #( #names ),* ) ),
+		})
+		.collect::<Vec<_>>();
+
+	quote::quote_spanned!(span =>
+		#task_check
+
+		/// The tasks of this pallet, which can be executed through `frame_system::Call::do_task`.
+		///
+		/// Each variant corresponds to one function of the `#[pallet::tasks]` block.
+		#[derive(
+			#frame_support::RuntimeDebugNoBound,
+			#frame_support::CloneNoBound,
+			#frame_support::EqNoBound,
+			#frame_support::PartialEqNoBound,
+			#frame_support::__private::codec::Encode,
+			#frame_support::__private::codec::Decode,
+			#frame_support::__private::scale_info::TypeInfo,
+		)]
+		#[codec(encode_bound())]
+		#[codec(decode_bound())]
+		#[scale_info(skip_type_params(#type_use_gen), capture_docs = #capture_docs)]
+		#[allow(non_camel_case_types)]
+		pub enum Task<#type_decl_bounded_gen> #where_clause {
+			#[doc(hidden)]
+			#[codec(skip)]
+			__Ignore(
+				#frame_support::__private::sp_std::marker::PhantomData<(#type_use_gen,)>,
+				#frame_support::Never,
+			),
+			#(
+				#( #[doc = #task_doc] )*
+				#[codec(index = #codec_index)]
+				#task_name {
+					#(
+						#[allow(missing_docs)]
+						#args_name: #args_type
+					),*
+				},
+			)*
+		}
+
+		impl<#type_impl_gen> #frame_support::traits::Task for Task<#type_use_gen> #where_clause {
+			type Enumeration = #frame_support::__private::sp_std::boxed::Box<
+				dyn #frame_support::__private::sp_std::iter::Iterator<Item = Self>
+			>;
+
+			fn iter() -> Self::Enumeration {
+				// Chained lazily, so that callers stopping early do not enumerate all tasks.
+				#frame_support::__private::sp_std::boxed::Box::new(
+					#frame_support::__private::sp_std::iter::empty()
+					#(
+						.chain(
+							(#task_list)
+								.map(|#list_item_pattern| Self::#task_name { #( #args_name ),* })
+						)
+					)*
+				)
+			}
+
+			fn is_valid(&self) -> bool {
+				match self {
+					#(
+						Self::#task_name { #( #args_name ),* } => (#task_condition)(#( #args_name ),*),
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			fn run(&self) -> Result<(), #frame_support::pallet_prelude::DispatchError> {
+				match self.clone() {
+					#(
+						Self::#task_name { #( #args_name ),* } => {
+							#frame_support::__private::sp_tracing::enter_span!(
+								#frame_support::__private::sp_tracing::trace_span!(stringify!(#task_name))
+							);
+							#frame_support::storage::with_storage_layer(|| {
+								<#pallet_ident<#type_use_gen>>::#task_name(#( #args_name ),*)
+							})
+						},
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			#[allow(unused_variables)]
+			fn weight(&self) -> #frame_support::pallet_prelude::Weight {
+				match self {
+					#(
+						Self::#task_name { #( #args_name ),* } => #task_weight,
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			fn task_index(&self) -> u32 {
+				match self {
+					#( Self::#task_name { .. } => #task_index, )*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+		}
+	)
+}
@@ -0,0 +1,207 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+/// * Generate one type per view function and implement `ViewFunction` on it.
+/// * Implement `ViewFunctionIdPrefix` and `DispatchViewFunction` on the pallet.
+/// * Implement `fn pallet_view_functions_metadata` on the pallet.
+///
+/// The implementations on the pallet are always generated, as `construct_runtime!` dispatches
+/// to every pallet.
+pub fn expand_view_functions(def: &mut Def) -> TokenStream {
+	let (span, view_functions_where_clause, view_functions) = match def.view_functions.as_ref() {
+		Some(view_functions) => (
+			view_functions.attr_span,
+			&view_functions.where_clause,
+			&view_functions.view_functions[..],
+		),
+		None => (def.item.span(), &None, &[][..]),
+	};
+
+	let frame_support = &def.frame_support;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_decl_bounded_gen = &def.type_decl_bounded_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let pallet_ident = &def.pallet_struct.pallet;
+	let where_clause =
+		super::merge_where_clauses(&[&def.config.where_clause, view_functions_where_clause]);
+	let capture_docs = if cfg!(feature = "no-metadata-docs") { "never" } else { "always" };
+
+	let name = view_functions.iter().map(|f| &f.name).collect::<Vec<_>>();
+	let name_str = name.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+	let type_ident = view_functions.iter().map(|f| f.type_ident()).collect::<Vec<_>>();
+	let return_type = view_functions.iter().map(|f| &f.return_type).collect::<Vec<_>>();
+	let suffix = view_functions
+		.iter()
+		.map(|f| {
+			let bytes = f.id_suffix();
+			quote!( [ #( #bytes ),* ] )
+		})
+		.collect::<Vec<_>>();
+	let args_name = view_functions
+		.iter()
+		.map(|f| f.args.iter().map(|(name, _)| name).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let args_name_str = args_name
+		.iter()
+		.map(|names| names.iter().map(|name| name.to_string()).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let args_type = view_functions
+		.iter()
+		.map(|f| f.args.iter().map(|(_, type_)| type_).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let type_doc = view_functions
+		.iter()
+		.map(|f| {
+			if f.docs.is_empty() {
+				let doc = format!("Arguments of the view function [`Pallet::{}`].", f.name);
+				vec![syn::parse_quote!(#doc)]
+			} else {
+				f.docs.clone()
+			}
+		})
+		.collect::<Vec<_>>();
+	let metadata_doc = view_functions
+		.iter()
+		.map(|f| if cfg!(feature = "no-metadata-docs") { vec![] } else { f.docs.clone() })
+		.collect::<Vec<_>>();
+
+	quote::quote_spanned!(span =>
+		#(
+			#( #[doc = #type_doc] )*
+			#[derive(
+				#frame_support::RuntimeDebugNoBound,
+				#frame_support::CloneNoBound,
+				#frame_support::EqNoBound,
+				#frame_support::PartialEqNoBound,
+				#frame_support::__private::codec::Encode,
+				#frame_support::__private::codec::Decode,
+				#frame_support::__private::scale_info::TypeInfo,
+			)]
+			#[codec(encode_bound())]
+			#[codec(decode_bound())]
+			#[scale_info(skip_type_params(#type_use_gen), capture_docs = #capture_docs)]
+			pub struct #type_ident<#type_decl_bounded_gen> #view_functions_where_clause {
+				#(
+					#[allow(missing_docs)]
+					pub #args_name: #args_type,
+				)*
+				#[codec(skip)]
+				_phantom: #frame_support::__private::sp_std::marker::PhantomData<(#type_use_gen,)>,
+			}
+
+			impl<#type_impl_gen> #type_ident<#type_use_gen> #view_functions_where_clause {
+				/// Create the arguments of the view function.
+				#[allow(clippy::too_many_arguments)]
+				pub fn new( #( #args_name: #args_type ),* ) -> Self {
+					Self { #( #args_name, )* _phantom: #frame_support::__private::sp_std::marker::PhantomData }
+				}
+			}
+
+			impl<#type_impl_gen> #frame_support::view_functions::ViewFunctionIdSuffix
+				for #type_ident<#type_use_gen> #view_functions_where_clause
+			{
+				const SUFFIX: [u8; 16] = #suffix;
+			}
+
+			impl<#type_impl_gen> #frame_support::view_functions::ViewFunction
+				for #type_ident<#type_use_gen> #where_clause
+			{
+				type ReturnType = #return_type;
+
+				fn id() -> #frame_support::view_functions::ViewFunctionId {
+					#frame_support::view_functions::ViewFunctionId {
+						prefix: <
+							#pallet_ident<#type_use_gen> as
+							#frame_support::view_functions::ViewFunctionIdPrefix
+						>::prefix(),
+						suffix: <Self as #frame_support::view_functions::ViewFunctionIdSuffix>::SUFFIX,
+					}
+				}
+
+				fn invoke(self) -> Self::ReturnType {
+					let Self { #( #args_name, )* .. } = self;
+					<#pallet_ident<#type_use_gen>>::#name( #( #args_name, )* )
+				}
+			}
+		)*
+
+		impl<#type_impl_gen> #frame_support::view_functions::ViewFunctionIdPrefix
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn prefix() -> [u8; 16] {
+				#frame_support::__private::hashing::twox_128(
+					<Self as #frame_support::traits::PalletInfoAccess>::name().as_bytes()
+				)
+			}
+		}
+
+		impl<#type_impl_gen> #frame_support::view_functions::DispatchViewFunction
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			/// Dispatch to the view function of this pallet matching the suffix of `id`, the
+			/// prefix is expected to be checked by the caller.
+			#[allow(unused_variables)]
+			fn dispatch_view_function<O: #frame_support::__private::codec::Output>(
+				id: &#frame_support::view_functions::ViewFunctionId,
+				input: &mut &[u8],
+				output: &mut O,
+			) -> Result<(), #frame_support::view_functions::ViewFunctionDispatchError> {
+				match id.suffix {
+					#(
+						#suffix => <
+							#type_ident<#type_use_gen> as
+							#frame_support::view_functions::ViewFunction
+						>::execute(input, output),
+					)*
+					_ => Err(#frame_support::view_functions::ViewFunctionDispatchError::NotFound(*id)),
+				}
+			}
+		}
+
+		impl<#type_impl_gen> #pallet_ident<#type_use_gen> #where_clause {
+			#[doc(hidden)]
+			pub fn pallet_view_functions_metadata()
+				-> #frame_support::__private::sp_std::vec::Vec<
+					#frame_support::__private::metadata_ir::PalletViewFunctionMetadataIR
+				>
+			{
+				#frame_support::__private::sp_std::vec![ #(
+					#frame_support::__private::metadata_ir::PalletViewFunctionMetadataIR {
+						name: #name_str,
+						id: <
+							#type_ident<#type_use_gen> as
+							#frame_support::view_functions::ViewFunction
+						>::id().into(),
+						inputs: #frame_support::__private::sp_std::vec![ #(
+							#frame_support::__private::metadata_ir::PalletViewFunctionParamMetadataIR {
+								name: #args_name_str,
+								ty: #frame_support::__private::scale_info::meta_type::<#args_type>(),
+							}
+						),* ],
+						output: #frame_support::__private::scale_info::meta_type::<#return_type>(),
+						docs: #frame_support::__private::sp_std::vec![ #( #metadata_doc ),* ],
+					}
+				),* ]
+			}
+		}
+	)
+}
@@ -33,8 +33,10 @@ pub mod inherent;
 pub mod origin;
 pub mod pallet_struct;
 pub mod storage;
+pub mod tasks;
 pub mod type_value;
 pub mod validate_unsigned;
+pub mod view_functions;
 
 use composite::{keyword::CompositeKeyword, CompositeDef};
 use frame_support_procedural_tools::generate_access_from_frame_or_crate;
@@ -49,6 +51,7 @@ pub struct Def {
 	pub pallet_struct: pallet_struct::PalletStructDef,
 	pub hooks: Option<hooks::HooksDef>,
 	pub call: Option<call::CallDef>,
+	pub tasks: Option<tasks::TasksDef>,
 	pub storages: Vec<storage::StorageDef>,
 	pub error: Option<error::ErrorDef>,
 	pub event: Option<event::EventDef>,
@@ -57,6 +60,7 @@ pub struct Def {
 	pub genesis_config: Option<genesis_config::GenesisConfigDef>,
 	pub genesis_build: Option<genesis_build::GenesisBuildDef>,
 	pub validate_unsigned: Option<validate_unsigned::ValidateUnsignedDef>,
+	pub view_functions: Option<view_functions::ViewFunctionsDef>,
 	pub extra_constants: Option<extra_constants::ExtraConstantsDef>,
 	pub composites: Vec<composite::CompositeDef>,
 	pub type_values: Vec<type_value::TypeValueDef>,
@@ -84,6 +88,7 @@ impl Def {
 		let mut pallet_struct = None;
 		let mut hooks = None;
 		let mut call = None;
+		let mut tasks = None;
 		let mut error = None;
 		let mut event = None;
 		let mut origin = None;
@@ -91,6 +96,7 @@ impl Def {
 		let mut genesis_config = None;
 		let mut genesis_build = None;
 		let mut validate_unsigned = None;
+		let mut view_functions = None;
 		let mut extra_constants = None;
 		let mut storages = vec![];
 		let mut type_values = vec![];
@@ -118,6 +124,8 @@ impl Def {
 				},
 				Some(PalletAttr::RuntimeCall(cw, span)) if call.is_none() =>
 					call = Some(call::CallDef::try_from(span, index, item, dev_mode, cw)?),
+				Some(PalletAttr::Tasks(span)) if tasks.is_none() =>
+					tasks = Some(tasks::TasksDef::try_from(span, index, item)?),
 				Some(PalletAttr::Error(span)) if error.is_none() =>
 					error = Some(error::ErrorDef::try_from(span, index, item)?),
 				Some(PalletAttr::RuntimeEvent(span)) if event.is_none() =>
@@ -140,6 +148,10 @@ impl Def {
 					let v = validate_unsigned::ValidateUnsignedDef::try_from(index, item)?;
 					validate_unsigned = Some(v);
 				},
+				Some(PalletAttr::ViewFunctions(span)) if view_functions.is_none() => {
+					let v = view_functions::ViewFunctionsDef::try_from(span, index, item)?;
+					view_functions = Some(v);
+				},
 				Some(PalletAttr::TypeValue(span)) =>
 					type_values.push(type_value::TypeValueDef::try_from(span, index, item)?),
 				Some(PalletAttr::ExtraConstants(_)) =>
@@ -198,10 +210,12 @@ impl Def {
 				.ok_or_else(|| syn::Error::new(item_span, "Missing `#[pallet::pallet]`"))?,
 			hooks,
 			call,
+			tasks,
 			extra_constants,
 			genesis_config,
 			genesis_build,
 			validate_unsigned,
+			view_functions,
 			error,
 			event,
 			origin,
@@ -249,6 +263,12 @@ impl Def {
 		if let Some(call) = &self.call {
 			instances.extend_from_slice(&call.instances[..]);
 		}
+		if let Some(tasks) = &self.tasks {
+			instances.extend_from_slice(&tasks.instances[..]);
+		}
+		if let Some(view_functions) = &self.view_functions {
+			instances.extend_from_slice(&view_functions.instances[..]);
+		}
 		if let Some(hooks) = &self.hooks {
 			instances.extend_from_slice(&hooks.instances[..]);
 		}
@@ -408,6 +428,7 @@ impl GenericKind {
 mod keyword {
 	syn::custom_keyword!(origin);
 	syn::custom_keyword!(call);
+	syn::custom_keyword!(tasks);
 	syn::custom_keyword!(weight);
 	syn::custom_keyword!(event);
 	syn::custom_keyword!(config);
@@ -419,6 +440,7 @@ mod keyword {
 	syn::custom_keyword!(genesis_build);
 	syn::custom_keyword!(genesis_config);
 	syn::custom_keyword!(validate_unsigned);
+	syn::custom_keyword!(view_functions);
 	syn::custom_keyword!(type_value);
 	syn::custom_keyword!(pallet);
 	syn::custom_keyword!(generate_store);
@@ -471,6 +493,12 @@ enum PalletAttr {
 	/// to zero. Now when there is a `weight` attribute on the `#[pallet::call]`, then that is used
 	/// instead of the zero weight. So to say: it works together with `dev_mode`.
 	RuntimeCall(Option<InheritedCallWeightAttr>, proc_macro2::Span),
+	/// A `#[pallet::tasks]` impl block declaring the tasks of the pallet.
+	///
+	/// Each function of the block declares a task and must be annotated with
+	/// `#[pallet::task_index(..)]`, `#[pallet::task_list(..)]`, `#[pallet::task_condition(..)]`
+	/// and `#[pallet::task_weight(..)]`.
+	Tasks(proc_macro2::Span),
 	Error(proc_macro2::Span),
 	RuntimeEvent(proc_macro2::Span),
 	RuntimeOrigin(proc_macro2::Span),
@@ -479,6 +507,11 @@ enum PalletAttr {
 	GenesisConfig(proc_macro2::Span),
 	GenesisBuild(proc_macro2::Span),
 	ValidateUnsigned(proc_macro2::Span),
+	/// A `#[pallet::view_functions]` impl block declaring the view functions of the pallet.
+	///
+	/// Each function of the block is a read-only query which can be called from outside the
+	/// runtime through the `RuntimeViewFunction` runtime API.
+	ViewFunctions(proc_macro2::Span),
 	TypeValue(proc_macro2::Span),
 	ExtraConstants(proc_macro2::Span),
 	Composite(proc_macro2::Span),
@@ -491,6 +524,7 @@ impl PalletAttr {
 			Self::Pallet(span) => *span,
 			Self::Hooks(span) => *span,
 			Self::RuntimeCall(_, span) => *span,
+			Self::Tasks(span) => *span,
 			Self::Error(span) => *span,
 			Self::RuntimeEvent(span) => *span,
 			Self::RuntimeOrigin(span) => *span,
@@ -499,6 +533,7 @@ impl PalletAttr {
 			Self::GenesisConfig(span) => *span,
 			Self::GenesisBuild(span) => *span,
 			Self::ValidateUnsigned(span) => *span,
+			Self::ViewFunctions(span) => *span,
 			Self::TypeValue(span) => *span,
 			Self::ExtraConstants(span) => *span,
 			Self::Composite(span) => *span,
@@ -535,6 +570,8 @@ impl syn::parse::Parse for PalletAttr {
 				false => Some(InheritedCallWeightAttr::parse(&content)?),
 			};
 			Ok(PalletAttr::RuntimeCall(attr, span))
+		} else if lookahead.peek(keyword::tasks) {
+			Ok(PalletAttr::Tasks(content.parse::<keyword::tasks>()?.span()))
 		} else if lookahead.peek(keyword::error) {
 			Ok(PalletAttr::Error(content.parse::<keyword::error>()?.span()))
 		} else if lookahead.peek(keyword::event) {
@@ -551,6 +588,8 @@ impl syn::parse::Parse for PalletAttr {
 			Ok(PalletAttr::GenesisBuild(content.parse::<keyword::genesis_build>()?.span()))
 		} else if lookahead.peek(keyword::validate_unsigned) {
 			Ok(PalletAttr::ValidateUnsigned(content.parse::<keyword::validate_unsigned>()?.span()))
+		} else if lookahead.peek(keyword::view_functions) {
+			Ok(PalletAttr::ViewFunctions(content.parse::<keyword::view_functions>()?.span()))
 		} else if lookahead.peek(keyword::type_value) {
 			Ok(PalletAttr::TypeValue(content.parse::<keyword::type_value>()?.span()))
 		} else if lookahead.peek(keyword::extra_constants) {
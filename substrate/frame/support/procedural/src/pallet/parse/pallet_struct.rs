@@ -26,6 +26,7 @@ mod keyword {
 	syn::custom_keyword!(generate_store);
 	syn::custom_keyword!(without_storage_info);
 	syn::custom_keyword!(storage_version);
+	syn::custom_keyword!(track_storage_usage);
 	syn::custom_keyword!(Store);
 }
 
@@ -46,16 +47,24 @@ pub struct PalletStructDef {
 	pub without_storage_info: Option<proc_macro2::Span>,
 	/// The current storage version of the pallet.
 	pub storage_version: Option<syn::Path>,
+	/// Whether the storage maps of the pallet account for the bytes they use.
+	/// Contains the span of the attribute.
+	pub track_storage_usage: Option<proc_macro2::Span>,
+	/// The number of entries a dispatchable of the pallet is expected to change at most, whose
+	/// accounting is included in its weight.
+	pub storage_usage_changes: Option<syn::Expr>,
 }
 
 /// Parse for one variant of:
 /// * `#[pallet::generate_store($vis trait Store)]`
 /// * `#[pallet::without_storage_info]`
 /// * `#[pallet::storage_version(STORAGE_VERSION)]`
+/// * `#[pallet::track_storage_usage]` or `#[pallet::track_storage_usage(CHANGES)]`
 pub enum PalletStructAttr {
 	GenerateStore { span: proc_macro2::Span, vis: syn::Visibility, keyword: keyword::Store },
 	WithoutStorageInfoTrait(proc_macro2::Span),
 	StorageVersion { storage_version: syn::Path, span: proc_macro2::Span },
+	TrackStorageUsage { changes: Option<syn::Expr>, span: proc_macro2::Span },
 }
 
 impl PalletStructAttr {
@@ -63,7 +72,8 @@ impl PalletStructAttr {
 		match self {
 			Self::GenerateStore { span, .. } |
 			Self::WithoutStorageInfoTrait(span) |
-			Self::StorageVersion { span, .. } => *span,
+			Self::StorageVersion { span, .. } |
+			Self::TrackStorageUsage { span, .. } => *span,
 		}
 	}
 }
@@ -97,6 +107,18 @@ impl syn::parse::Parse for PalletStructAttr {
 			let storage_version = version_content.parse::<syn::Path>()?;
 
 			Ok(Self::StorageVersion { storage_version, span })
+		} else if lookahead.peek(keyword::track_storage_usage) {
+			let span = content.parse::<keyword::track_storage_usage>()?.span();
+
+			let changes = if content.peek(syn::token::Paren) {
+				let changes_content;
+				syn::parenthesized!(changes_content in content);
+				Some(changes_content.parse::<syn::Expr>()?)
+			} else {
+				None
+			};
+
+			Ok(Self::TrackStorageUsage { changes, span })
 		} else {
 			Err(lookahead.error())
 		}
@@ -119,6 +141,8 @@ impl PalletStructDef {
 		let mut store = None;
 		let mut without_storage_info = None;
 		let mut storage_version_found = None;
+		let mut track_storage_usage = None;
+		let mut storage_usage_changes = None;
 
 		let struct_attrs: Vec<PalletStructAttr> = helper::take_item_pallet_attrs(&mut item.attrs)?;
 		for attr in struct_attrs {
@@ -136,6 +160,12 @@ impl PalletStructDef {
 				{
 					storage_version_found = Some(storage_version);
 				},
+				PalletStructAttr::TrackStorageUsage { changes, span }
+					if track_storage_usage.is_none() =>
+				{
+					track_storage_usage = Some(span);
+					storage_usage_changes = changes;
+				},
 				attr => {
 					let msg = "Unexpected duplicated attribute";
 					return Err(syn::Error::new(attr.span(), msg))
@@ -166,6 +196,8 @@ impl PalletStructDef {
 			attr_span,
 			without_storage_info,
 			storage_version: storage_version_found,
+			track_storage_usage,
+			storage_usage_changes,
 		})
 	}
 }
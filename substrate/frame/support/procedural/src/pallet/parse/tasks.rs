@@ -0,0 +1,261 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(pallet);
+	syn::custom_keyword!(task_index);
+	syn::custom_keyword!(task_list);
+	syn::custom_keyword!(task_condition);
+	syn::custom_keyword!(task_weight);
+}
+
+/// Definition of the tasks of a pallet, typically `impl<T: Config> Pallet<T> { ... }`
+/// annotated with `#[pallet::tasks]`.
+pub struct TasksDef {
+	/// The index of the tasks item in pallet module.
+	pub index: usize,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// The tasks declared in the impl block.
+	pub tasks: Vec<TaskDef>,
+	/// The span of the `pallet::tasks` attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Definition of a single task, typically:
+/// `#[pallet::task_index(..)] #[pallet::task_list(..)] .. fn foo(param1: ...) -> DispatchResult`
+pub struct TaskDef {
+	/// Function name.
+	pub name: syn::Ident,
+	/// Information on args: `(name, type)`.
+	pub args: Vec<(syn::Ident, Box<syn::Type>)>,
+	/// The task index, unique within the pallet.
+	pub task_index: u8,
+	/// Expression evaluating to an iterator over the arguments of all tasks of this kind.
+	pub list: syn::Expr,
+	/// Closure taking references to the arguments and returning whether the task is valid.
+	pub condition: syn::ExprClosure,
+	/// Expression of the weight of the task, may use the task arguments by reference.
+	pub weight: syn::Expr,
+	/// Docs, used for the variant of the generated `Task` enum.
+	pub docs: Vec<syn::Expr>,
+}
+
+/// Attributes for functions in tasks impl block.
+pub enum TaskAttr {
+	/// Parse for `#[pallet::task_index(expr)]`
+	TaskIndex(proc_macro2::Span, u8),
+	/// Parse for `#[pallet::task_list(expr)]`
+	TaskList(proc_macro2::Span, syn::Expr),
+	/// Parse for `#[pallet::task_condition(closure)]`
+	TaskCondition(proc_macro2::Span, syn::ExprClosure),
+	/// Parse for `#[pallet::task_weight(expr)]`
+	TaskWeight(proc_macro2::Span, syn::Expr),
+}
+
+impl TaskAttr {
+	fn span(&self) -> proc_macro2::Span {
+		match self {
+			Self::TaskIndex(span, _) |
+			Self::TaskList(span, _) |
+			Self::TaskCondition(span, _) |
+			Self::TaskWeight(span, _) => *span,
+		}
+	}
+}
+
+impl syn::parse::Parse for TaskAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![#]>()?;
+		let content;
+		syn::bracketed!(content in input);
+		content.parse::<keyword::pallet>()?;
+		content.parse::<syn::Token![::]>()?;
+
+		let lookahead = content.lookahead1();
+		if lookahead.peek(keyword::task_index) {
+			let span = content.parse::<keyword::task_index>()?.span();
+			let index_content;
+			syn::parenthesized!(index_content in content);
+			let index = index_content.parse::<syn::LitInt>()?;
+			if !index.suffix().is_empty() {
+				let msg = "Number literal must not have a suffix";
+				return Err(syn::Error::new(index.span(), msg))
+			}
+			Ok(TaskAttr::TaskIndex(span, index.base10_parse()?))
+		} else if lookahead.peek(keyword::task_list) {
+			let span = content.parse::<keyword::task_list>()?.span();
+			let list_content;
+			syn::parenthesized!(list_content in content);
+			Ok(TaskAttr::TaskList(span, list_content.parse()?))
+		} else if lookahead.peek(keyword::task_condition) {
+			let span = content.parse::<keyword::task_condition>()?.span();
+			let closure_content;
+			syn::parenthesized!(closure_content in content);
+			let closure = closure_content.parse::<syn::ExprClosure>().map_err(|e| {
+				let msg = "Invalid task_condition attribute: expected a closure";
+				let mut err = syn::Error::new(closure_content.span(), msg);
+				err.combine(e);
+				err
+			})?;
+			Ok(TaskAttr::TaskCondition(span, closure))
+		} else if lookahead.peek(keyword::task_weight) {
+			let span = content.parse::<keyword::task_weight>()?.span();
+			let weight_content;
+			syn::parenthesized!(weight_content in content);
+			Ok(TaskAttr::TaskWeight(span, weight_content.parse()?))
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
+impl TasksDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item_impl = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(item.span(), "Invalid pallet::tasks, expected item impl"))
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item_impl.generics, item_impl.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item_impl.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item_impl.trait_ {
+			let msg = "Invalid pallet::tasks, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg))
+		}
+
+		let mut tasks = vec![];
+		let mut indices = HashMap::new();
+		for item in &mut item_impl.items {
+			let method = if let syn::ImplItem::Fn(method) = item { method } else { continue };
+
+			let mut task_index = None;
+			let mut list = None;
+			let mut condition = None;
+			let mut weight = None;
+			for attr in helper::take_item_pallet_attrs::<TaskAttr>(&mut method.attrs)? {
+				let span = attr.span();
+				let duplicate = match attr {
+					TaskAttr::TaskIndex(_, i) => task_index.replace(i).is_some(),
+					TaskAttr::TaskList(_, l) => list.replace(l).is_some(),
+					TaskAttr::TaskCondition(_, c) => condition.replace(c).is_some(),
+					TaskAttr::TaskWeight(_, w) => weight.replace(w).is_some(),
+				};
+				if duplicate {
+					let msg = "Invalid pallet::tasks, duplicated task attribute";
+					return Err(syn::Error::new(span, msg))
+				}
+			}
+
+			let missing = |attr: &str| {
+				let msg = format!(
+					"Invalid pallet::tasks, every task requires a `#[pallet::{}(..)]` attribute",
+					attr,
+				);
+				syn::Error::new(method.sig.span(), msg)
+			};
+			let task_index = task_index.ok_or_else(|| missing("task_index"))?;
+			let list = list.ok_or_else(|| missing("task_list"))?;
+			let condition = condition.ok_or_else(|| missing("task_condition"))?;
+			let weight = weight.ok_or_else(|| missing("task_weight"))?;
+
+			if let Some(used_fn) = indices.insert(task_index, method.sig.ident.clone()) {
+				let msg = format!(
+					"Task indices are conflicting: Both functions {} and {} are at index {}",
+					used_fn, method.sig.ident, task_index,
+				);
+				let mut err = syn::Error::new(used_fn.span(), &msg);
+				err.combine(syn::Error::new(method.sig.ident.span(), msg));
+				return Err(err)
+			}
+
+			let returns_dispatch_result = match &method.sig.output {
+				syn::ReturnType::Type(_, type_) => match &**type_ {
+					syn::Type::Path(syn::TypePath { path, .. }) =>
+						path.segments.last().map_or(false, |s| s.ident == "DispatchResult"),
+					_ => false,
+				},
+				syn::ReturnType::Default => false,
+			};
+			if !returns_dispatch_result {
+				let msg = "Invalid pallet::tasks, require return type DispatchResult";
+				return Err(syn::Error::new(method.sig.span(), msg))
+			}
+
+			let mut args = vec![];
+			for arg in method.sig.inputs.iter() {
+				let arg = match arg {
+					syn::FnArg::Typed(arg) => arg,
+					syn::FnArg::Receiver(_) => {
+						let msg = "Invalid pallet::tasks, task functions can not take `self`";
+						return Err(syn::Error::new(arg.span(), msg))
+					},
+				};
+				let arg_ident = if let syn::Pat::Ident(pat) = &*arg.pat {
+					pat.ident.clone()
+				} else {
+					let msg = "Invalid pallet::tasks, argument must be ident";
+					return Err(syn::Error::new(arg.pat.span(), msg))
+				};
+				args.push((arg_ident, arg.ty.clone()));
+			}
+
+			if condition.inputs.len() != args.len() {
+				let msg = "Invalid pallet::tasks, task_condition closure must take the same \
+					number of arguments as the task function";
+				return Err(syn::Error::new(condition.span(), msg))
+			}
+
+			let docs = get_doc_literals(&method.attrs);
+
+			tasks.push(TaskDef {
+				name: method.sig.ident.clone(),
+				args,
+				task_index,
+				list,
+				condition,
+				weight,
+				docs,
+			});
+		}
+
+		Ok(Self {
+			index,
+			attr_span,
+			instances,
+			where_clause: item_impl.generics.where_clause.clone(),
+			tasks,
+		})
+	}
+}
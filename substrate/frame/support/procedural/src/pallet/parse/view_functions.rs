@@ -0,0 +1,167 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use inflector::Inflector;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+/// Definition of the view functions of a pallet, typically `impl<T: Config> Pallet<T> { ... }`
+/// annotated with `#[pallet::view_functions]`.
+pub struct ViewFunctionsDef {
+	/// The index of the view functions item in pallet module.
+	pub index: usize,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// The view functions declared in the impl block.
+	pub view_functions: Vec<ViewFunctionDef>,
+	/// The span of the `pallet::view_functions` attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Definition of a single view function, typically `pub fn foo(param1: ...) -> ReturnType`.
+pub struct ViewFunctionDef {
+	/// Function name.
+	pub name: syn::Ident,
+	/// Information on args: `(name, type)`.
+	pub args: Vec<(syn::Ident, Box<syn::Type>)>,
+	/// The return type of the function.
+	pub return_type: syn::Type,
+	/// Docs, used for the generated type and the metadata.
+	pub docs: Vec<syn::Expr>,
+}
+
+impl ViewFunctionDef {
+	/// The ident of the type generated for this view function, e.g. `GetValue` for `get_value`.
+	pub fn type_ident(&self) -> syn::Ident {
+		syn::Ident::new(&self.name.to_string().to_pascal_case(), self.name.span())
+	}
+
+	/// The signature the id suffix of this view function is derived from, without any
+	/// whitespace, e.g. `get_value(u32,T::AccountId)->Option<u32>`.
+	pub fn signature(&self) -> String {
+		let args = self
+			.args
+			.iter()
+			.map(|(_, ty)| ty.to_token_stream().to_string())
+			.collect::<Vec<_>>()
+			.join(",");
+		let signature = format!("{}({})->{}", self.name, args, self.return_type.to_token_stream());
+		signature.split_whitespace().collect()
+	}
+
+	/// The suffix of the `ViewFunctionId` of this view function.
+	pub fn id_suffix(&self) -> [u8; 16] {
+		sp_core_hashing::twox_128(self.signature().as_bytes())
+	}
+}
+
+impl ViewFunctionsDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item_impl = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::view_functions, expected item impl";
+			return Err(syn::Error::new(item.span(), msg))
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item_impl.generics, item_impl.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item_impl.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item_impl.trait_ {
+			let msg = "Invalid pallet::view_functions, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg))
+		}
+
+		let mut view_functions = vec![];
+		for item in &item_impl.items {
+			let method = if let syn::ImplItem::Fn(method) = item { method } else { continue };
+
+			if !method.sig.generics.params.is_empty() {
+				let msg = "Invalid pallet::view_functions, view functions can not be generic";
+				return Err(syn::Error::new(method.sig.generics.span(), msg))
+			}
+
+			let return_type = match &method.sig.output {
+				syn::ReturnType::Type(_, type_) => (**type_).clone(),
+				syn::ReturnType::Default => {
+					let msg = "Invalid pallet::view_functions, view functions must return a value";
+					return Err(syn::Error::new(method.sig.span(), msg))
+				},
+			};
+
+			let mut args = vec![];
+			for arg in method.sig.inputs.iter() {
+				let arg = match arg {
+					syn::FnArg::Typed(arg) => arg,
+					syn::FnArg::Receiver(_) => {
+						let msg = "Invalid pallet::view_functions, view functions can not take \
+							`self`";
+						return Err(syn::Error::new(arg.span(), msg))
+					},
+				};
+				let arg_ident = if let syn::Pat::Ident(pat) = &*arg.pat {
+					pat.ident.clone()
+				} else {
+					let msg = "Invalid pallet::view_functions, argument must be ident";
+					return Err(syn::Error::new(arg.pat.span(), msg))
+				};
+				args.push((arg_ident, arg.ty.clone()));
+			}
+
+			let view_function = ViewFunctionDef {
+				name: method.sig.ident.clone(),
+				args,
+				return_type,
+				docs: get_doc_literals(&method.attrs),
+			};
+
+			if let Some(other) = view_functions
+				.iter()
+				.find(|other: &&ViewFunctionDef| other.type_ident() == view_function.type_ident())
+			{
+				let msg = format!(
+					"Invalid pallet::view_functions, functions {} and {} map to the same type {}",
+					other.name,
+					view_function.name,
+					view_function.type_ident(),
+				);
+				return Err(syn::Error::new(method.sig.ident.span(), msg))
+			}
+
+			view_functions.push(view_function);
+		}
+
+		Ok(Self {
+			index,
+			attr_span,
+			instances,
+			where_clause: item_impl.generics.where_clause.clone(),
+			view_functions,
+		})
+	}
+}
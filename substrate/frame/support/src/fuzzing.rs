@@ -0,0 +1,309 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of arbitrary values from fuzzer input.
+//!
+//! [`EncodingGenerator`] turns the raw bytes of a fuzzer into valid SCALE encodings of any type
+//! with a `scale-info` type description, like the `RuntimeCall` of a runtime. Every choice that is
+//! made while walking the type description, like the variant of an enum or the length of a
+//! vector, is taken from a [`FuzzInput`]. The same input therefore always generates the same
+//! value, which makes every finding of a fuzzer reproducible.
+
+use codec::{Compact, Decode, Encode};
+use scale_info::{
+	form::PortableForm, PortableRegistry, Registry, Type, TypeDef, TypeDefPrimitive, TypeInfo,
+};
+
+/// The bytes provided by a fuzzer, consumed from the front.
+///
+/// Once all bytes are consumed, only zeros are returned.
+pub struct FuzzInput<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> FuzzInput<'a> {
+	/// Create a new instance from the bytes of a fuzzer.
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data }
+	}
+
+	/// Whether all bytes are consumed.
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	/// Take the next `N` bytes, padded with zeros if not enough are left.
+	pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+		let mut bytes = [0u8; N];
+		let len = N.min(self.data.len());
+		bytes[..len].copy_from_slice(&self.data[..len]);
+		self.data = &self.data[len..];
+		bytes
+	}
+
+	/// Take the next byte.
+	pub fn u8(&mut self) -> u8 {
+		self.bytes::<1>()[0]
+	}
+
+	/// Take the next four bytes as a little endian `u32`.
+	pub fn u32(&mut self) -> u32 {
+		u32::from_le_bytes(self.bytes())
+	}
+
+	/// Choose one of `items`, or `None` if there are none.
+	pub fn choose<'b, T>(&mut self, items: &'b [T]) -> Option<&'b T> {
+		if items.is_empty() {
+			return None
+		}
+		items.get(self.u32() as usize % items.len())
+	}
+}
+
+/// Generates SCALE encodings of a type from its `scale-info` type description.
+///
+/// The generated encodings are structurally valid, but may still be rejected by the `Decode`
+/// implementation of the type, e.g. if a `BoundedVec` is longer than its bound. Use
+/// [`Self::arbitrary`] to only get values that decode.
+pub struct EncodingGenerator {
+	registry: PortableRegistry,
+	ty: u32,
+	max_len: u32,
+	max_depth: u32,
+}
+
+impl EncodingGenerator {
+	/// Create a generator for values of type `T`.
+	pub fn new<T: TypeInfo + 'static>() -> Self {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&scale_info::meta_type::<T>()).id;
+		Self { registry: registry.into(), ty, max_len: 16, max_depth: 32 }
+	}
+
+	/// Limit the length of generated sequences and strings. Defaults to 16.
+	pub fn max_len(mut self, max_len: u32) -> Self {
+		self.max_len = max_len;
+		self
+	}
+
+	/// Limit the nesting of generated values. Defaults to 32.
+	///
+	/// No value is generated if the limit is reached, which only happens for recursive types.
+	pub fn max_depth(mut self, max_depth: u32) -> Self {
+		self.max_depth = max_depth;
+		self
+	}
+
+	/// Generate an encoding from `input`.
+	///
+	/// Returns `None` if the maximum depth is exceeded.
+	pub fn generate(&self, input: &mut FuzzInput) -> Option<Vec<u8>> {
+		let mut encoded = Vec::new();
+		self.generate_type(self.ty, input, &mut encoded, 0)?;
+		Some(encoded)
+	}
+
+	/// Generate a value of type `T` from `input`.
+	///
+	/// `T` must be the type this generator was created for. Returns `None` if the generated
+	/// encoding is not accepted by the `Decode` implementation of `T`.
+	pub fn arbitrary<T: Decode>(&self, input: &mut FuzzInput) -> Option<T> {
+		let encoded = self.generate(input)?;
+		T::decode(&mut &encoded[..]).ok()
+	}
+
+	fn generate_type(
+		&self,
+		ty: u32,
+		input: &mut FuzzInput,
+		out: &mut Vec<u8>,
+		depth: u32,
+	) -> Option<()> {
+		if depth > self.max_depth {
+			return None
+		}
+		let depth = depth + 1;
+
+		match &self.resolve(ty)?.type_def {
+			TypeDef::Composite(composite) => composite
+				.fields
+				.iter()
+				.try_for_each(|field| self.generate_type(field.ty.id, input, out, depth)),
+			TypeDef::Variant(variant) => {
+				let variant = input.choose(&variant.variants)?;
+				out.push(variant.index);
+				variant
+					.fields
+					.iter()
+					.try_for_each(|field| self.generate_type(field.ty.id, input, out, depth))
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = self.len(input);
+				Compact(len).encode_to(out);
+				(0..len)
+					.try_for_each(|_| self.generate_type(sequence.type_param.id, input, out, depth))
+			},
+			TypeDef::Array(array) => (0..array.len)
+				.try_for_each(|_| self.generate_type(array.type_param.id, input, out, depth)),
+			TypeDef::Tuple(tuple) => tuple
+				.fields
+				.iter()
+				.try_for_each(|ty| self.generate_type(ty.id, input, out, depth)),
+			TypeDef::Primitive(primitive) => {
+				self.generate_primitive(primitive, input, out);
+				Some(())
+			},
+			TypeDef::Compact(compact) => {
+				let value = u128::from_le_bytes(input.bytes());
+				let value = match self.compact_width(compact.type_param.id) {
+					1 => value as u8 as u128,
+					2 => value as u16 as u128,
+					4 => value as u32 as u128,
+					8 => value as u64 as u128,
+					_ => value,
+				};
+				Compact(value).encode_to(out);
+				Some(())
+			},
+			TypeDef::BitSequence(_) => {
+				// Bits are stored in whole bytes, which is valid for every store type if the number
+				// of bits is a multiple of 64.
+				let words = self.len(input);
+				Compact(words.saturating_mul(64)).encode_to(out);
+				(0..words.saturating_mul(8)).for_each(|_| out.push(input.u8()));
+				Some(())
+			},
+		}
+	}
+
+	fn generate_primitive(
+		&self,
+		primitive: &TypeDefPrimitive,
+		input: &mut FuzzInput,
+		out: &mut Vec<u8>,
+	) {
+		let len = match primitive {
+			TypeDefPrimitive::Bool => return (input.u8() % 2 == 1).encode_to(out),
+			TypeDefPrimitive::Char => {
+				let c = char::from_u32(input.u32() % 0x11_0000).unwrap_or_default();
+				return (c as u32).encode_to(out)
+			},
+			TypeDefPrimitive::Str => {
+				let len = self.len(input);
+				let s: String = (0..len).map(|_| char::from(input.u8() % 95 + 32)).collect();
+				return s.encode_to(out)
+			},
+			TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+			TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+			TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+			TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+			TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+		};
+		(0..len).for_each(|_| out.push(input.u8()));
+	}
+
+	/// The width in bytes of the integer behind a compact encoded type.
+	fn compact_width(&self, ty: u32) -> usize {
+		match self.resolve(ty).map(|ty| &ty.type_def) {
+			Some(TypeDef::Primitive(TypeDefPrimitive::U8)) => 1,
+			Some(TypeDef::Primitive(TypeDefPrimitive::U16)) => 2,
+			Some(TypeDef::Primitive(TypeDefPrimitive::U32)) => 4,
+			Some(TypeDef::Primitive(TypeDefPrimitive::U64)) => 8,
+			// Types like `Perbill` are compact encoded as their single field.
+			Some(TypeDef::Composite(composite)) if composite.fields.len() == 1 =>
+				self.compact_width(composite.fields[0].ty.id),
+			_ => 16,
+		}
+	}
+
+	fn len(&self, input: &mut FuzzInput) -> u32 {
+		input.u32() % (self.max_len + 1)
+	}
+
+	fn resolve(&self, ty: u32) -> Option<&Type<PortableForm>> {
+		self.registry.resolve(ty)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{traits::ConstU32, BoundedVec};
+
+	#[derive(Encode, Decode, TypeInfo, Debug, PartialEq)]
+	enum Call {
+		Transfer {
+			to: [u8; 4],
+			#[codec(compact)]
+			amount: u64,
+		},
+		Batch(Vec<Call>),
+		Remark(String, Option<(bool, i16)>),
+		Bounded(BoundedVec<u8, ConstU32<2>>),
+	}
+
+	#[test]
+	fn fuzz_input_works() {
+		let mut input = FuzzInput::new(&[1, 2, 0, 0, 0, 5]);
+		assert_eq!(input.u8(), 1);
+		assert_eq!(input.u32(), 2);
+		assert_eq!(input.choose(&["a", "b", "c"]), Some(&"c"));
+		assert!(input.is_empty());
+		assert_eq!(input.u32(), 0);
+		assert_eq!(input.choose::<u8>(&[]), None);
+	}
+
+	#[test]
+	fn generated_encodings_decode() {
+		let generator = EncodingGenerator::new::<Call>();
+		let data: Vec<u8> =
+			(0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+		let mut input = FuzzInput::new(&data);
+
+		let mut generated = 0;
+		while !input.is_empty() {
+			let Some(encoded) = generator.generate(&mut input) else { continue };
+			generated += 1;
+			match Call::decode(&mut &encoded[..]) {
+				Ok(call) => assert_eq!(call.encode(), encoded),
+				// Only the bound of a `BoundedVec` is not part of its type description.
+				Err(_) => assert!(encoded.starts_with(&[3])),
+			}
+		}
+		assert!(generated > 1);
+	}
+
+	#[test]
+	fn generation_is_deterministic() {
+		let generator = EncodingGenerator::new::<Call>();
+		let data = [0, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0, 0];
+
+		let first = generator.arbitrary::<Call>(&mut FuzzInput::new(&data));
+		assert_eq!(first, Some(Call::Transfer { to: [1, 2, 3, 4], amount: 5 }),);
+		assert_eq!(generator.arbitrary::<Call>(&mut FuzzInput::new(&data)), first);
+	}
+
+	#[test]
+	fn max_depth_stops_recursion() {
+		let generator = EncodingGenerator::new::<Call>().max_depth(4);
+		// Always choose `Batch` with a single call.
+		let data = [1, 0, 0, 0, 1, 0, 0, 0].repeat(8);
+
+		assert_eq!(generator.generate(&mut FuzzInput::new(&data)), None);
+	}
+}
@@ -65,6 +65,8 @@ pub mod __private {
 pub mod dispatch;
 pub mod crypto;
 pub mod dispatch_context;
+#[cfg(feature = "std")]
+pub mod fuzzing;
 mod hash;
 pub mod inherent;
 pub mod instances;
@@ -73,6 +75,7 @@ pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod traits;
+pub mod view_functions;
 pub mod weights;
 #[doc(hidden)]
 pub mod unsigned {
@@ -921,6 +924,7 @@ pub mod pallet_prelude {
 /// * [`pallet::disable_frame_system_supertrait_check`](#disable_supertrait_check)
 /// * [`pallet::generate_store($vis trait Store)`](#palletgenerate_storevis-trait-store)
 /// * [`pallet::storage_version`](#palletstorage_version)
+/// * [`pallet::track_storage_usage`](#pallettrack_storage_usage)
 /// * [`pallet::hooks`](#hooks-pallethooks-optional)
 /// * [`pallet::call`](#call-palletcall-optional)
 /// * [`pallet::weight($expr)`](#palletweightexpr)
@@ -1147,6 +1151,34 @@ pub mod pallet_prelude {
 ///
 /// Also see [`pallet::storage_version`](`frame_support::pallet_macros::storage_version`)
 ///
+/// # `pallet::track_storage_usage`
+///
+/// The storage maps of a pallet can account for the bytes they add to and remove from the
+/// state in a counter per pallet by using the `pallet::track_storage_usage` attribute:
+///
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::track_storage_usage]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// The accounted usage is exposed through [`traits::StorageUsageTrait`] and checked against
+/// the state and the quota of the pallet in `try_state`. Dispatchables of the pallet growing
+/// the usage of any pallet beyond its quota fail.
+///
+/// The weight of every dispatchable of the pallet includes the overhead of accounting for one
+/// changed entry, or for the number of entries given as argument:
+///
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::track_storage_usage(4)]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// See [`storage::usage`] for details.
+///
+/// Also see [`pallet::track_storage_usage`](`frame_support::pallet_macros::track_storage_usage`)
+///
 /// # Hooks: `#[pallet::hooks]` (optional)
 ///
 /// The `pallet::hooks` attribute allows you to specify a `Hooks` implementation for `Pallet`
@@ -2229,7 +2261,8 @@ pub mod pallet_macros {
 		call_index, compact, composite_enum, config, disable_frame_system_supertrait_check, error,
 		event, extra_constants, feeless_if, generate_deposit, generate_store, getter, hooks,
 		import_section, inherent, no_default, no_default_bounds, origin, pallet_section,
-		storage_prefix, storage_version, type_value, unbounded, validate_unsigned, weight,
+		storage_prefix, storage_version, task_condition, task_index, task_list, task_weight, tasks,
+		track_storage_usage, type_value, unbounded, validate_unsigned, view_functions, weight,
 		whitelist_storage,
 	};
 
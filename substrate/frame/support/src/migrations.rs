@@ -16,13 +16,20 @@
 // limitations under the License.
 
 use crate::{
-	traits::{GetStorageVersion, NoStorageVersionSet, PalletInfoAccess, StorageVersion},
-	weights::{RuntimeDbWeight, Weight},
+	defensive,
+	storage::transactional::with_transaction,
+	traits::{Defensive, GetStorageVersion, NoStorageVersionSet, PalletInfoAccess, StorageVersion},
+	weights::{RuntimeDbWeight, Weight, WeightMeter},
 };
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
 use impl_trait_for_tuples::impl_for_tuples;
 use sp_core::Get;
 use sp_io::{hashing::twox_128, storage::clear_prefix, KillStorageResult};
-use sp_std::marker::PhantomData;
+use sp_runtime::{DispatchError, TransactionOutcome};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(feature = "std")]
+pub mod verification;
 
 /// Handles storage migration pallet versioning.
 ///
@@ -345,3 +352,533 @@ impl<P: Get<&'static str>, DbWeight: Get<RuntimeDbWeight>> frame_support::traits
 		Ok(())
 	}
 }
+
+/// A migration that can proceed in multiple steps.
+///
+/// Each step is bounded by a [`WeightMeter`] and hands back a cursor that is passed to the next
+/// step. This allows migrations over large storage maps to be spread over multiple blocks.
+/// Stepped migrations are driven by a [`MultiStepMigrator`] like `pallet-migrations`.
+pub trait SteppedMigration {
+	/// The cursor type that stores the progress (aka. state) of this migration.
+	type Cursor: FullCodec + MaxEncodedLen;
+
+	/// The unique identifier type of this migration.
+	type Identifier: FullCodec + MaxEncodedLen;
+
+	/// The unique identifier of this migration.
+	///
+	/// If two migrations have the same identifier, then they are assumed to be identical.
+	fn id() -> Self::Identifier;
+
+	/// The maximum number of steps that this migration can take.
+	///
+	/// This can be used to enforce progress and prevent migrations becoming stuck forever. A
+	/// migration that exceeds its max steps is treated as failed. `None` means that there is no
+	/// limit.
+	fn max_steps() -> Option<u32> {
+		None
+	}
+
+	/// Try to migrate as much as possible with the given weight.
+	///
+	/// **ANY STORAGE CHANGES MUST BE ROLLED-BACK BY THE CALLER UPON ERROR.** This is necessary
+	/// since the caller cannot return a cursor in the error case. [`Self::transactional_step`] is
+	/// provided as convenience for a caller. A cursor of `None` implies that the migration is at
+	/// its end.
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError>;
+
+	/// Same as [`Self::step`], but rolls back pending changes in the error case.
+	fn transactional_step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		with_transaction(|| match Self::step(cursor, meter) {
+			Ok(cursor) => TransactionOutcome::Commit(Ok(Ok(cursor))),
+			Err(err) => TransactionOutcome::Rollback(Ok(Err(err))),
+		})
+		// Only fails if the transactional layer limit is reached.
+		.map_err(|_: DispatchError| SteppedMigrationError::Failed)?
+	}
+
+	/// Hook for testing that is run before the first step of the migration.
+	///
+	/// The returned state is passed to [`Self::post_upgrade`] once the migration completed.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+		Ok(Vec::new())
+	}
+
+	/// Hook for testing that is run after the last step of the migration.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		Ok(())
+	}
+}
+
+/// Error that can occur during a [`SteppedMigration`].
+#[derive(Debug, Encode, Decode, MaxEncodedLen, scale_info::TypeInfo, PartialEq, Eq, Clone)]
+pub enum SteppedMigrationError {
+	// Transient errors:
+	/// The remaining weight is not enough to do anything.
+	///
+	/// Can be resolved by calling with at least `required` weight. Note that calling it with
+	/// exactly `required` weight could cause it to not make any progress.
+	InsufficientWeight {
+		/// Amount of weight required to make progress.
+		required: Weight,
+	},
+	// Permanent errors:
+	/// The migration cannot decode its cursor and therefore not proceed.
+	///
+	/// This should not happen unless (1) the migration itself returned an invalid cursor in a
+	/// previous iteration, (2) the storage got corrupted or (3) there is a bug in the caller's
+	/// code.
+	InvalidCursor,
+	/// The migration encountered a permanent error and cannot continue.
+	Failed,
+}
+
+/// Notification handler for status updates regarding multi-block migrations.
+#[impl_for_tuples(8)]
+pub trait MigrationStatusHandler {
+	/// Notifies of the start of a runtime migration.
+	fn started() {}
+
+	/// Notifies of the completion of a runtime migration.
+	fn completed() {}
+}
+
+/// Handles a failed runtime migration.
+///
+/// This should never happen, but is here for completeness.
+pub trait FailedMigrationHandler {
+	/// Infallibly handle a failed runtime migration.
+	///
+	/// Gets passed in the optional index of the migration in the batch that caused the failure.
+	/// The returned [`FailedMigrationHandling`] decides how the caller proceeds.
+	fn failed(migration: Option<u32>) -> FailedMigrationHandling;
+}
+
+/// Do not allow any transactions to be processed after a runtime upgrade failed.
+///
+/// This is **not a sane default**, since it prevents governance intervention.
+pub struct FreezeChainOnFailedMigration;
+
+impl FailedMigrationHandler for FreezeChainOnFailedMigration {
+	fn failed(_migration: Option<u32>) -> FailedMigrationHandling {
+		FailedMigrationHandling::KeepStuck
+	}
+}
+
+/// Enter safe mode on a failed runtime upgrade.
+///
+/// This can be very useful to manually intervene and fix the chain state. `Else` is used in case
+/// that the safe mode could not be entered.
+pub struct EnterSafeModeOnFailedMigration<SM, Else: FailedMigrationHandler>(
+	PhantomData<(SM, Else)>,
+);
+
+impl<Else: FailedMigrationHandler, SM: crate::traits::SafeMode> FailedMigrationHandler
+	for EnterSafeModeOnFailedMigration<SM, Else>
+where
+	<SM as crate::traits::SafeMode>::BlockNumber: sp_runtime::traits::Bounded,
+{
+	fn failed(migration: Option<u32>) -> FailedMigrationHandling {
+		let entered = if SM::is_entered() {
+			SM::extend(sp_runtime::traits::Bounded::max_value())
+		} else {
+			SM::enter(sp_runtime::traits::Bounded::max_value())
+		};
+
+		// If we could not enter or extend safe mode (for whatever reason), then we try the next.
+		if entered.is_err() {
+			Else::failed(migration)
+		} else {
+			FailedMigrationHandling::KeepStuck
+		}
+	}
+}
+
+/// How to proceed after a runtime upgrade failed.
+///
+/// There is NO SANE DEFAULT HERE. All options are very dangerous and should be used with care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailedMigrationHandling {
+	/// Resume extrinsic processing of the chain. This will not resume the upgrade.
+	///
+	/// This should be supplemented with additional measures to ensure that the broken chain state
+	/// does not get further messed up by user extrinsics.
+	ForceUnstuck,
+	/// Set the cursor to `Stuck` and keep blocking extrinsics.
+	KeepStuck,
+	/// Don't do anything with the cursor and let the handler decide.
+	///
+	/// This can be useful in cases where the other two options would overwrite any changes that
+	/// were done by the handler to the cursor.
+	Ignore,
+}
+
+/// Something that can do multi step migrations.
+pub trait MultiStepMigrator {
+	/// Hint for whether [`Self::step`] should be called.
+	fn ongoing() -> bool;
+
+	/// Do the next step in the MBM process.
+	///
+	/// Must gracefully handle the case that it is currently not upgrading.
+	fn step() -> Weight;
+
+	/// Run `f`, which steps the migrations to completion within a single runtime call, checking
+	/// every migration with its `pre_upgrade` and `post_upgrade` hooks.
+	///
+	/// The states returned by the `pre_upgrade` hooks are only kept in memory while `f` runs. Used
+	/// by the try-runtime upgrade checks of `frame_executive`.
+	#[cfg(feature = "try-runtime")]
+	fn with_upgrade_checks<R>(f: impl FnOnce() -> R) -> R {
+		f()
+	}
+}
+
+impl MultiStepMigrator for () {
+	fn ongoing() -> bool {
+		false
+	}
+
+	fn step() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Multiple [`SteppedMigration`]s.
+pub trait SteppedMigrations {
+	/// The number of migrations that `Self` aggregates.
+	fn len() -> u32;
+
+	/// The `n`th [`SteppedMigration::id`].
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_id(n: u32) -> Option<Vec<u8>>;
+
+	/// The [`SteppedMigration::max_steps`] of the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_max_steps(n: u32) -> Option<Option<u32>>;
+
+	/// Do a [`SteppedMigration::step`] on the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>>;
+
+	/// Do a [`SteppedMigration::transactional_step`] on the `n`th migration.
+	///
+	/// Is guaranteed to return `Some` if `n < Self::len()`.
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>>;
+
+	/// Call the pre-upgrade hooks of the `n`th migration.
+	///
+	/// Returns `None` if the index is out of bounds.
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>>;
+
+	/// Call the post-upgrade hooks of the `n`th migration.
+	///
+	/// Returns `None` if the index is out of bounds.
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, _state: Vec<u8>)
+		-> Option<Result<(), sp_runtime::TryRuntimeError>>;
+
+	/// The maximal encoded length across all cursors.
+	fn cursor_max_encoded_len() -> usize;
+
+	/// The maximal encoded length across all identifiers.
+	fn identifier_max_encoded_len() -> usize;
+
+	/// Assert the integrity of the migrations.
+	///
+	/// Should be executed as part of a test prior to runtime usage.
+	#[cfg(feature = "std")]
+	fn integrity_test() -> Result<(), &'static str> {
+		use crate::ensure;
+
+		for n in 0..Self::len() {
+			ensure!(Self::nth_id(n).is_some(), "id is None");
+			ensure!(Self::nth_max_steps(n).is_some(), "max steps is None");
+		}
+
+		Ok(())
+	}
+}
+
+impl SteppedMigrations for () {
+	fn len() -> u32 {
+		0
+	}
+
+	fn nth_id(_n: u32) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn nth_max_steps(_n: u32) -> Option<Option<u32>> {
+		None
+	}
+
+	fn nth_step(
+		_n: u32,
+		_cursor: Option<Vec<u8>>,
+		_meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		None
+	}
+
+	fn nth_transactional_step(
+		_n: u32,
+		_cursor: Option<Vec<u8>>,
+		_meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(_n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>> {
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(
+		_n: u32,
+		_state: Vec<u8>,
+	) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		None
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		0
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		0
+	}
+}
+
+// A collection consisting of only a single migration.
+impl<T: SteppedMigration> SteppedMigrations for T {
+	fn len() -> u32 {
+		1
+	}
+
+	fn nth_id(n: u32) -> Option<Vec<u8>> {
+		if n != 0 {
+			defensive!("nth_id should only be called with n==0");
+		}
+
+		Some(T::id().encode())
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		// It should be generally fine to call with n>0, but the code should not attempt to.
+		(n == 0)
+			.then_some(T::max_steps())
+			.defensive_proof("nth_max_steps should only be called with n==0")
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		if n != 0 {
+			defensive!("nth_step should only be called with n==0");
+			return None
+		}
+
+		let cursor = match cursor {
+			Some(cursor) => match T::Cursor::decode(&mut &cursor[..]) {
+				Ok(cursor) => Some(cursor),
+				Err(_) => return Some(Err(SteppedMigrationError::InvalidCursor)),
+			},
+			None => None,
+		};
+
+		Some(T::step(cursor, meter).map(|cursor| cursor.map(|cursor| cursor.encode())))
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		if n != 0 {
+			defensive!("nth_transactional_step should only be called with n==0");
+			return None
+		}
+
+		let cursor = match cursor {
+			Some(cursor) => match T::Cursor::decode(&mut &cursor[..]) {
+				Ok(cursor) => Some(cursor),
+				Err(_) => return Some(Err(SteppedMigrationError::InvalidCursor)),
+			},
+			None => None,
+		};
+
+		Some(
+			T::transactional_step(cursor, meter).map(|cursor| cursor.map(|cursor| cursor.encode())),
+		)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>> {
+		if n != 0 {
+			defensive!("nth_pre_upgrade should only be called with n==0");
+		}
+
+		Some(T::pre_upgrade())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		if n != 0 {
+			defensive!("nth_post_upgrade should only be called with n==0");
+		}
+		Some(T::post_upgrade(state))
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		T::Cursor::max_encoded_len()
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		T::Identifier::max_encoded_len()
+	}
+}
+
+#[impl_for_tuples(1, 30)]
+impl SteppedMigrations for Tuple {
+	fn len() -> u32 {
+		for_tuples!( #( Tuple::len() )+* )
+	}
+
+	fn nth_id(n: u32) -> Option<Vec<u8>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_id(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_max_steps(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+
+		for_tuples!( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_step(n - i, cursor, meter)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_transactional_step(n - i, cursor, meter)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_pre_upgrade(n - i)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		let mut i = 0;
+
+		for_tuples! ( #(
+			if (i + Tuple::len()) > n {
+				return Tuple::nth_post_upgrade(n - i, state)
+			}
+
+			i += Tuple::len();
+		)* );
+
+		None
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		let mut max_len = 0;
+
+		for_tuples!( #(
+			max_len = max_len.max(Tuple::cursor_max_encoded_len());
+		)* );
+
+		max_len
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		let mut max_len = 0;
+
+		for_tuples!( #(
+			max_len = max_len.max(Tuple::identifier_max_encoded_len());
+		)* );
+
+		max_len
+	}
+}
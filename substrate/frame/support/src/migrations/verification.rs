@@ -0,0 +1,674 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of storage migrations against existing state.
+//!
+//! [`MigrationCheck`] runs a migration and diffs all storage under the prefix of a pallet before
+//! and after it. Both sides are decoded with the storage metadata of the pallet before and after
+//! the migration, so no `pre_upgrade` or `post_upgrade` hooks have to be written to find values
+//! that were not (or wrongly) migrated.
+//!
+//! The check runs in whatever externalities it is called in. To verify a migration against the
+//! state of a live chain, load a snapshot with `remote-externalities`:
+//!
+//! ```ignore
+//! let mut ext = remote_externalities::Builder::<Block>::new()
+//! 	.mode(Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new("chain.snap") }))
+//! 	.build()
+//! 	.await
+//! 	.unwrap();
+//!
+//! ext.execute_with(|| {
+//! 	let report = MigrationCheck::new(v0::storage_metadata(), Pallet::<Runtime>::storage_metadata())
+//! 		.expect("Members", Expectation::CountUnchanged)
+//! 		.run::<v1::MigrateToV1<Runtime>>();
+//! 	assert!(report.is_ok(), "{report}");
+//! });
+//! ```
+//!
+//! The metadata of the old storage layout can be built from `storage_alias` types with
+//! [`StorageEntryMetadataBuilder`](crate::storage::types::StorageEntryMetadataBuilder).
+
+use crate::traits::{OnRuntimeUpgrade, STORAGE_VERSION_STORAGE_KEY_POSTFIX};
+use codec::{Compact, Decode};
+use scale_info::{
+	form::PortableForm, IntoPortable, PortableRegistry, Registry, TypeDef, TypeDefPrimitive,
+};
+use sp_io::hashing::twox_128;
+use sp_metadata_ir::{PalletStorageMetadataIR, StorageEntryTypeIR, StorageHasherIR};
+use sp_std::collections::btree_map::BTreeMap;
+
+/// What is expected to happen to the keys of a storage entry during a migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expectation {
+	/// The number of keys stays the same.
+	CountUnchanged,
+	/// The entry holds exactly this many keys after the migration.
+	Count(u32),
+	/// No key is added, removed or changed.
+	Untouched,
+	/// All keys are removed.
+	Removed,
+}
+
+impl Expectation {
+	fn holds(&self, diff: &EntryDiff) -> bool {
+		match self {
+			Self::CountUnchanged => diff.before == diff.after,
+			Self::Count(count) => diff.after == *count,
+			Self::Untouched => diff.added == 0 && diff.removed == 0 && diff.changed == 0,
+			Self::Removed => diff.after == 0,
+		}
+	}
+}
+
+/// How the keys of a storage entry changed during a migration.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EntryDiff {
+	/// The name of the storage entry.
+	pub name: String,
+	/// The number of keys before the migration.
+	pub before: u32,
+	/// The number of keys after the migration.
+	pub after: u32,
+	/// The number of keys that only exist after the migration.
+	pub added: u32,
+	/// The number of keys that only existed before the migration.
+	pub removed: u32,
+	/// The number of keys whose value changed.
+	pub changed: u32,
+}
+
+/// A storage key whose key or value could not be decoded with the storage metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndecodableKey {
+	/// The name of the storage entry, if the key belongs to any.
+	pub entry: Option<String>,
+	/// The full storage key.
+	pub key: Vec<u8>,
+	/// Why decoding failed.
+	pub error: String,
+}
+
+/// An [`Expectation`] that did not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+	/// The expectation.
+	pub expectation: Expectation,
+	/// What actually happened to the storage entry.
+	pub diff: EntryDiff,
+}
+
+/// The result of [`MigrationCheck::run`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MigrationReport {
+	/// How the keys of every storage entry of the old and the new metadata changed.
+	pub entries: Vec<EntryDiff>,
+	/// Keys that could not be decoded with the old metadata before the migration.
+	pub undecodable_before: Vec<UndecodableKey>,
+	/// Keys that could not be decoded with the new metadata after the migration.
+	pub undecodable_after: Vec<UndecodableKey>,
+	/// Keys that are left under the pallet prefix after the migration, but do not belong to any
+	/// storage entry of the new metadata.
+	pub orphaned: Vec<Vec<u8>>,
+	/// Expectations that did not hold.
+	pub violations: Vec<Violation>,
+}
+
+impl MigrationReport {
+	/// Whether no problem was found.
+	pub fn is_ok(&self) -> bool {
+		self.undecodable_before.is_empty() &&
+			self.undecodable_after.is_empty() &&
+			self.orphaned.is_empty() &&
+			self.violations.is_empty()
+	}
+
+	/// The diff of the storage entry with the given `name`.
+	pub fn entry(&self, name: &str) -> Option<&EntryDiff> {
+		self.entries.iter().find(|diff| diff.name == name)
+	}
+}
+
+impl core::fmt::Display for MigrationReport {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		for (when, undecodable) in
+			[("before", &self.undecodable_before), ("after", &self.undecodable_after)]
+		{
+			for key in undecodable {
+				writeln!(
+					f,
+					"undecodable {} the migration: {} in {}: {}",
+					when,
+					array_bytes::bytes2hex("0x", &key.key),
+					key.entry.as_deref().unwrap_or("<unknown entry>"),
+					key.error,
+				)?;
+			}
+		}
+		for key in &self.orphaned {
+			writeln!(f, "orphaned: {}", array_bytes::bytes2hex("0x", key))?;
+		}
+		for violation in &self.violations {
+			writeln!(
+				f,
+				"expectation {:?} does not hold: {:?}",
+				violation.expectation, violation.diff
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Runs a migration and verifies the storage of a pallet with its old and new metadata.
+///
+/// See the [module documentation](self) for an example.
+pub struct MigrationCheck {
+	old: PalletLayout,
+	new: PalletLayout,
+	expectations: Vec<(String, Expectation)>,
+}
+
+impl MigrationCheck {
+	/// Create a new check with the storage metadata of the pallet before and after the migration.
+	pub fn new(old: PalletStorageMetadataIR, new: PalletStorageMetadataIR) -> Self {
+		Self { old: PalletLayout::new(old), new: PalletLayout::new(new), expectations: Vec::new() }
+	}
+
+	/// Expect `expectation` to hold for the storage entry `entry` after the migration.
+	pub fn expect(mut self, entry: &str, expectation: Expectation) -> Self {
+		self.expectations.push((entry.into(), expectation));
+		self
+	}
+
+	/// Run the migration `M` and report the problems found in the storage of the pallet.
+	///
+	/// Must be called within externalities. The changes of the migration are kept.
+	pub fn run<M: OnRuntimeUpgrade>(&self) -> MigrationReport {
+		let before = storage_under(&self.old.prefix);
+		M::on_runtime_upgrade();
+		let after = storage_under(&self.new.prefix);
+
+		let mut report = MigrationReport::default();
+		if self.old.prefix != self.new.prefix {
+			report.orphaned.extend(
+				storage_under(&self.old.prefix)
+					.into_keys()
+					.map(|suffix| [&self.old.prefix[..], &suffix].concat()),
+			);
+		}
+
+		let mut diffs = BTreeMap::<String, EntryDiff>::new();
+		for name in self.old.entries.values().chain(self.new.entries.values()).map(|e| &e.name) {
+			diffs.insert(name.clone(), EntryDiff { name: name.clone(), ..Default::default() });
+		}
+
+		for (suffix, value) in &before {
+			let key = [&self.old.prefix[..], suffix].concat();
+			let Some(name) = self.entry_name(suffix) else {
+				report.undecodable_before.push(UndecodableKey {
+					entry: None,
+					key,
+					error: "does not belong to any storage entry".into(),
+				});
+				continue
+			};
+
+			let diff = diffs.get_mut(name).expect("all entry names are in `diffs`; qed");
+			diff.before += 1;
+			match after.get(suffix) {
+				None => diff.removed += 1,
+				Some(new_value) if new_value != value => diff.changed += 1,
+				Some(_) => {},
+			}
+
+			if let Err(error) = self.old.decode(suffix, value) {
+				report.undecodable_before.push(UndecodableKey {
+					entry: Some(name.into()),
+					key,
+					error,
+				});
+			}
+		}
+
+		for (suffix, value) in &after {
+			let key = [&self.new.prefix[..], suffix].concat();
+			if let Some(name) = self.entry_name(suffix) {
+				let diff = diffs.get_mut(name).expect("all entry names are in `diffs`; qed");
+				diff.after += 1;
+				if !before.contains_key(suffix) {
+					diff.added += 1;
+				}
+			}
+
+			match self.new.decode(suffix, value) {
+				Ok(()) => {},
+				Err(_) if self.new.entry(suffix).is_none() => report.orphaned.push(key),
+				Err(error) => report.undecodable_after.push(UndecodableKey {
+					entry: self.entry_name(suffix).map(Into::into),
+					key,
+					error,
+				}),
+			}
+		}
+
+		for (name, expectation) in &self.expectations {
+			let diff = diffs
+				.get(name)
+				.cloned()
+				.unwrap_or_else(|| EntryDiff { name: name.clone(), ..Default::default() });
+			if !expectation.holds(&diff) {
+				report.violations.push(Violation { expectation: *expectation, diff });
+			}
+		}
+
+		report.entries = diffs.into_values().collect();
+		report
+	}
+
+	/// The name of the storage entry that `suffix` belongs to in either metadata.
+	fn entry_name(&self, suffix: &[u8]) -> Option<&str> {
+		self.old
+			.entry(suffix)
+			.or_else(|| self.new.entry(suffix))
+			.map(|entry| entry.name.as_str())
+	}
+}
+
+/// All keys and values in storage under `prefix`, with the prefix stripped from the keys.
+fn storage_under(prefix: &[u8]) -> BTreeMap<Vec<u8>, Vec<u8>> {
+	let mut storage = BTreeMap::new();
+	let mut previous_key = prefix.to_vec();
+	while let Some(key) = sp_io::storage::next_key(&previous_key).filter(|k| k.starts_with(prefix))
+	{
+		if let Some(value) = sp_io::storage::get(&key) {
+			storage.insert(key[prefix.len()..].to_vec(), value.to_vec());
+		}
+		previous_key = key;
+	}
+	storage
+}
+
+/// The storage entries of a pallet with their types resolved in a registry.
+struct PalletLayout {
+	/// The hashed pallet prefix.
+	prefix: [u8; 16],
+	/// The storage entries by their hashed name.
+	entries: BTreeMap<[u8; 16], EntryLayout>,
+	registry: PortableRegistry,
+}
+
+struct EntryLayout {
+	name: String,
+	ty: StorageEntryTypeIR<PortableForm>,
+}
+
+impl PalletLayout {
+	fn new(metadata: PalletStorageMetadataIR) -> Self {
+		let mut registry = Registry::new();
+		let metadata = metadata.into_portable(&mut registry);
+
+		let mut entries: BTreeMap<_, _> = metadata
+			.entries
+			.into_iter()
+			.map(|entry| {
+				(twox_128(entry.name.as_bytes()), EntryLayout { name: entry.name, ty: entry.ty })
+			})
+			.collect();
+		// The storage version is not part of the metadata, but lives under the pallet prefix.
+		entries.insert(
+			twox_128(STORAGE_VERSION_STORAGE_KEY_POSTFIX),
+			EntryLayout {
+				name: String::from_utf8_lossy(STORAGE_VERSION_STORAGE_KEY_POSTFIX).into(),
+				ty: StorageEntryTypeIR::Plain(
+					registry.register_type(&scale_info::meta_type::<u16>()),
+				),
+			},
+		);
+
+		Self { prefix: twox_128(metadata.prefix.as_bytes()), entries, registry: registry.into() }
+	}
+
+	/// The storage entry that `suffix`, a key without the pallet prefix, belongs to.
+	fn entry(&self, suffix: &[u8]) -> Option<&EntryLayout> {
+		let hashed_name: [u8; 16] = suffix.get(..16)?.try_into().ok()?;
+		self.entries.get(&hashed_name)
+	}
+
+	/// Decode the key `suffix`, without the pallet prefix, and its `value`.
+	fn decode(&self, suffix: &[u8], value: &[u8]) -> Result<(), String> {
+		let entry = self.entry(suffix).ok_or("does not belong to any storage entry")?;
+		let key = &suffix[16..];
+		let value_ty = match &entry.ty {
+			StorageEntryTypeIR::Plain(value) => {
+				if !key.is_empty() {
+					return Err("plain storage value with a key suffix".into())
+				}
+				value.id
+			},
+			StorageEntryTypeIR::Map { hashers, key: key_ty, value } => {
+				self.decode_key(hashers, key_ty.id, key).map_err(|e| format!("key: {e}"))?;
+				value.id
+			},
+		};
+		self.decode_all(value_ty, value).map_err(|e| format!("value: {e}"))
+	}
+
+	/// Decode the hashed `key` of a map with the given `hashers` and type `key_ty`.
+	fn decode_key(
+		&self,
+		hashers: &[StorageHasherIR],
+		key_ty: u32,
+		mut key: &[u8],
+	) -> Result<(), codec::Error> {
+		let key_types = if hashers.len() == 1 {
+			vec![key_ty]
+		} else {
+			match &self.resolve(key_ty)?.type_def {
+				TypeDef::Tuple(tuple) if tuple.fields.len() == hashers.len() =>
+					tuple.fields.iter().map(|ty| ty.id).collect(),
+				_ => return Err("key type does not match the hashers".into()),
+			}
+		};
+
+		for (hasher, ty) in hashers.iter().zip(key_types) {
+			let (hash_len, concat) = match hasher {
+				StorageHasherIR::Blake2_128 | StorageHasherIR::Twox128 => (16, false),
+				StorageHasherIR::Blake2_256 | StorageHasherIR::Twox256 => (32, false),
+				StorageHasherIR::Blake2_128Concat => (16, true),
+				StorageHasherIR::Twox64Concat => (8, true),
+				StorageHasherIR::Identity => (0, true),
+			};
+			skip_bytes(&mut key, hash_len)?;
+			if concat {
+				self.skip(ty, &mut key)?;
+			}
+		}
+
+		if !key.is_empty() {
+			return Err("input too large".into())
+		}
+		Ok(())
+	}
+
+	/// Decode `input` as a value of type `ty`, failing if not all of it is consumed.
+	fn decode_all(&self, ty: u32, mut input: &[u8]) -> Result<(), codec::Error> {
+		self.skip(ty, &mut input)?;
+		if !input.is_empty() {
+			return Err("input too large".into())
+		}
+		Ok(())
+	}
+
+	/// Skip over a value of type `ty` in `input`, failing if it is not a valid encoding.
+	fn skip(&self, ty: u32, input: &mut &[u8]) -> Result<(), codec::Error> {
+		match &self.resolve(ty)?.type_def {
+			TypeDef::Composite(composite) =>
+				composite.fields.iter().try_for_each(|field| self.skip(field.ty.id, input)),
+			TypeDef::Variant(variant) => {
+				let index = u8::decode(input)?;
+				let variant = variant
+					.variants
+					.iter()
+					.find(|variant| variant.index == index)
+					.ok_or("invalid variant index")?;
+				variant.fields.iter().try_for_each(|field| self.skip(field.ty.id, input))
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input)?.0;
+				if let TypeDef::Primitive(TypeDefPrimitive::U8) =
+					self.resolve(sequence.type_param.id)?.type_def
+				{
+					return skip_bytes(input, len as usize)
+				}
+				(0..len).try_for_each(|_| self.skip(sequence.type_param.id, input))
+			},
+			TypeDef::Array(array) =>
+				(0..array.len).try_for_each(|_| self.skip(array.type_param.id, input)),
+			TypeDef::Tuple(tuple) => tuple.fields.iter().try_for_each(|ty| self.skip(ty.id, input)),
+			TypeDef::Primitive(primitive) => skip_primitive(primitive, input),
+			TypeDef::Compact(_) => Compact::<u128>::decode(input).map(|_| ()),
+			TypeDef::BitSequence(bits) => {
+				let store_len = match self.resolve(bits.bit_store_type.id)?.type_def {
+					TypeDef::Primitive(TypeDefPrimitive::U8) => 1,
+					TypeDef::Primitive(TypeDefPrimitive::U16) => 2,
+					TypeDef::Primitive(TypeDefPrimitive::U32) => 4,
+					TypeDef::Primitive(TypeDefPrimitive::U64) => 8,
+					_ => return Err("unsupported bit store type".into()),
+				};
+				let bits = Compact::<u32>::decode(input)?.0 as usize;
+				let store_bits = store_len * 8;
+				skip_bytes(input, bits.div_ceil(store_bits) * store_len)
+			},
+		}
+	}
+
+	fn resolve(&self, ty: u32) -> Result<&scale_info::Type<PortableForm>, codec::Error> {
+		self.registry
+			.resolve(ty)
+			.ok_or_else(|| "type is missing from the registry".into())
+	}
+}
+
+fn skip_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<(), codec::Error> {
+	let len = match primitive {
+		TypeDefPrimitive::Bool => return bool::decode(input).map(|_| ()),
+		TypeDefPrimitive::Char => {
+			let c = u32::decode(input)?;
+			return char::from_u32(c).map(|_| ()).ok_or_else(|| "invalid char".into())
+		},
+		TypeDefPrimitive::Str => return String::decode(input).map(|_| ()),
+		TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+		TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+		TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+		TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+		TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+	};
+	skip_bytes(input, len)
+}
+
+fn skip_bytes(input: &mut &[u8], len: usize) -> Result<(), codec::Error> {
+	if input.len() < len {
+		return Err("not enough data to fill buffer".into())
+	}
+	*input = &input[len..];
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		storage::{
+			storage_prefix,
+			types::{Key, StorageEntryMetadataBuilder},
+			unhashed,
+		},
+		weights::Weight,
+		Blake2_128Concat, Twox64Concat,
+	};
+	use sp_io::TestExternalities;
+	use sp_metadata_ir::StorageEntryMetadataIR;
+
+	mod v0 {
+		use super::*;
+
+		#[crate::storage_alias]
+		pub type Value = StorageValue<Example, u32>;
+		#[crate::storage_alias]
+		pub type Map = StorageMap<Example, Twox64Concat, u32, u32>;
+	}
+
+	mod v1 {
+		use super::*;
+
+		#[crate::storage_alias]
+		pub type Value = StorageValue<Example, u32>;
+		#[crate::storage_alias]
+		pub type Map = StorageMap<Example, Twox64Concat, u32, u64>;
+		#[crate::storage_alias]
+		pub type Nested = StorageNMap<
+			Example,
+			(Key<Blake2_128Concat, u32>, Key<Twox64Concat, (bool, String)>),
+			Vec<Option<(BTreeMap<u8, u128>, codec::Compact<u64>)>>,
+		>;
+	}
+
+	fn metadata(build: impl FnOnce(&mut Vec<StorageEntryMetadataIR>)) -> PalletStorageMetadataIR {
+		let mut entries = vec![];
+		build(&mut entries);
+		PalletStorageMetadataIR { prefix: "Example", entries }
+	}
+
+	fn old_metadata() -> PalletStorageMetadataIR {
+		metadata(|entries| {
+			v0::Value::build_metadata(vec![], entries);
+			v0::Map::build_metadata(vec![], entries);
+		})
+	}
+
+	fn new_metadata() -> PalletStorageMetadataIR {
+		metadata(|entries| {
+			v1::Value::build_metadata(vec![], entries);
+			v1::Map::build_metadata(vec![], entries);
+			v1::Nested::build_metadata(vec![], entries);
+		})
+	}
+
+	fn new_test_ext() -> TestExternalities {
+		let mut ext = TestExternalities::default();
+		ext.execute_with(|| {
+			v0::Value::put(7);
+			(0..3).for_each(|i| v0::Map::insert(i, i * 10));
+		});
+		ext
+	}
+
+	struct MigrateToV1;
+	impl OnRuntimeUpgrade for MigrateToV1 {
+		fn on_runtime_upgrade() -> Weight {
+			v1::Map::translate::<u32, _>(|_, value| Some(value.into()));
+			v1::Nested::insert(
+				(1, (true, String::from("one"))),
+				vec![None, Some((BTreeMap::from([(1, 2)]), 3.into()))],
+			);
+			Weight::zero()
+		}
+	}
+
+	/// Drops one value, leaves the others unmigrated and writes a key no entry knows about.
+	struct BrokenMigration;
+	impl OnRuntimeUpgrade for BrokenMigration {
+		fn on_runtime_upgrade() -> Weight {
+			v1::Map::remove(0);
+			unhashed::put(&storage_prefix(b"Example", b"Stray"), &1u32);
+			Weight::zero()
+		}
+	}
+
+	#[test]
+	fn correct_migration_passes() {
+		new_test_ext().execute_with(|| {
+			let report = MigrationCheck::new(old_metadata(), new_metadata())
+				.expect("Value", Expectation::Untouched)
+				.expect("Map", Expectation::CountUnchanged)
+				.expect("Nested", Expectation::Count(1))
+				.run::<MigrateToV1>();
+
+			assert!(report.is_ok(), "{report}");
+			assert_eq!(
+				report.entry("Map"),
+				Some(&EntryDiff {
+					name: "Map".into(),
+					before: 3,
+					after: 3,
+					changed: 3,
+					..Default::default()
+				})
+			);
+			assert_eq!(
+				report.entry("Nested"),
+				Some(&EntryDiff {
+					name: "Nested".into(),
+					after: 1,
+					added: 1,
+					..Default::default()
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn broken_migration_is_reported() {
+		new_test_ext().execute_with(|| {
+			let report = MigrationCheck::new(old_metadata(), new_metadata())
+				.expect("Map", Expectation::CountUnchanged)
+				.run::<BrokenMigration>();
+
+			assert!(report.undecodable_before.is_empty());
+			assert_eq!(report.undecodable_after.len(), 2);
+			assert!(report
+				.undecodable_after
+				.iter()
+				.all(|key| key.entry.as_deref() == Some("Map") && key.error.starts_with("value:")));
+			assert_eq!(report.orphaned, vec![storage_prefix(b"Example", b"Stray").to_vec()]);
+			assert_eq!(
+				report.violations,
+				vec![Violation {
+					expectation: Expectation::CountUnchanged,
+					diff: EntryDiff {
+						name: "Map".into(),
+						before: 3,
+						after: 2,
+						removed: 1,
+						..Default::default()
+					},
+				}]
+			);
+		});
+	}
+
+	#[test]
+	fn undecodable_state_before_the_migration_is_reported() {
+		new_test_ext().execute_with(|| {
+			unhashed::put_raw(&v0::Map::hashed_key_for(3), &[1, 2]);
+			unhashed::put(&storage_prefix(b"Example", b"Stray"), &1u32);
+			unhashed::put(
+				&storage_prefix(b"Example", STORAGE_VERSION_STORAGE_KEY_POSTFIX),
+				&crate::traits::StorageVersion::new(1),
+			);
+
+			let report = MigrationCheck::new(old_metadata(), old_metadata()).run::<()>();
+
+			assert_eq!(report.undecodable_before.len(), 2);
+			assert!(report.undecodable_before.contains(&UndecodableKey {
+				entry: None,
+				key: storage_prefix(b"Example", b"Stray").to_vec(),
+				error: "does not belong to any storage entry".into(),
+			}));
+			assert!(report
+				.undecodable_before
+				.iter()
+				.any(|key| key.entry.as_deref() == Some("Map") &&
+					key.key == v0::Map::hashed_key_for(3) &&
+					key.error.starts_with("value:")));
+			// The storage version is known, although it is not part of the metadata.
+			assert_eq!(report.entry(":__STORAGE_VERSION__:").map(|diff| diff.before), Some(1));
+		});
+	}
+}
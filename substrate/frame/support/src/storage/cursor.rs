@@ -0,0 +1,424 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed cursors to iterate storage maps in pages and resume the iteration later.
+//!
+//! All map types ([`StorageMap`](super::types::StorageMap),
+//! [`StorageDoubleMap`](super::types::StorageDoubleMap), [`StorageNMap`](super::types::StorageNMap)
+//! and their counted variants) provide `*_paged` functions next to their `iter*` functions. Each
+//! call returns a [`Page`] of at most as many items as allowed by the given [`IterationLimit`],
+//! together with a [`StorageCursor`] to continue the iteration after the last item of the page.
+//!
+//! A cursor is typed by the map it was created for and can be stored, e.g. to continue the
+//! iteration in the next `on_idle` hook or the next step of a multi-block migration, or be handed
+//! to an off-chain client paginating over a runtime API. Cursors are checked against the iterated
+//! prefix before they are used, since they may come from an untrusted source.
+//!
+//! Any iterator implementing [`ResumableIterator`], including the
+//! [`ChildTriePrefixIterator`](super::ChildTriePrefixIterator) for maps stored in a child trie,
+//! can be paged with [`page`].
+
+use crate::{
+	storage::KeyLenOf, traits::Get, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use codec::{Compact, Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The position after which the iteration of the storage map `Map` is resumed.
+///
+/// Encodes as the raw storage key of the last item returned.
+#[derive(
+	CloneNoBound, EqNoBound, PartialEqNoBound, Encode, Decode, TypeInfo, RuntimeDebugNoBound,
+)]
+#[scale_info(skip_type_params(Map))]
+pub struct StorageCursor<Map> {
+	last_raw_key: Vec<u8>,
+	_phantom: PhantomData<fn() -> Map>,
+}
+
+impl<Map> StorageCursor<Map> {
+	/// Create a cursor resuming the iteration after `last_raw_key`.
+	///
+	/// The key is checked against the iterated prefix when the cursor is used.
+	pub fn from_raw_key(last_raw_key: Vec<u8>) -> Self {
+		Self { last_raw_key, _phantom: PhantomData }
+	}
+
+	/// The raw storage key after which the iteration is resumed.
+	pub fn last_raw_key(&self) -> &[u8] {
+		&self.last_raw_key
+	}
+
+	/// Consume the cursor, returning the raw storage key after which the iteration is resumed.
+	pub fn into_raw_key(self) -> Vec<u8> {
+		self.last_raw_key
+	}
+}
+
+impl<Map> MaxEncodedLen for StorageCursor<Map>
+where
+	KeyLenOf<Map>: Get<u32>,
+{
+	fn max_encoded_len() -> usize {
+		let max_key_len = KeyLenOf::<Map>::get();
+		Compact(max_key_len).encoded_size().saturating_add(max_key_len as usize)
+	}
+}
+
+/// The cursor did not point into the iterated prefix.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct InvalidCursor;
+
+/// The limits of a single page of iteration.
+///
+/// The proof size is estimated as the number of bytes of the keys and values read from storage,
+/// excluding the trie nodes proving them. Items are read until a limit is reached, so a page may
+/// exceed the proof size limit by the size of its last item. A page always contains at least one
+/// item if any is left, so that the iteration makes progress.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct IterationLimit {
+	/// The maximum number of items in the page.
+	pub items: Option<u32>,
+	/// The maximum number of bytes to read from storage.
+	pub proof_size: Option<u64>,
+}
+
+impl IterationLimit {
+	/// No limit, iterate until the end.
+	pub fn unlimited() -> Self {
+		Self::default()
+	}
+
+	/// Limit the page to `items` items.
+	pub fn items(items: u32) -> Self {
+		Self { items: Some(items), proof_size: None }
+	}
+
+	/// Limit the page to reading `proof_size` bytes from storage.
+	pub fn proof_size(proof_size: u64) -> Self {
+		Self { items: None, proof_size: Some(proof_size) }
+	}
+
+	/// Whether a page of `items` items reading `proof_size` bytes reached the limit.
+	fn is_reached(&self, items: u32, proof_size: u64) -> bool {
+		self.items.map_or(false, |limit| items >= limit) ||
+			self.proof_size.map_or(false, |limit| proof_size >= limit)
+	}
+}
+
+/// A page of items of the storage map `Map`.
+pub struct Page<T, Map> {
+	/// The items of the page, in iteration order.
+	pub items: Vec<T>,
+	/// The cursor to continue the iteration after the last item, or `None` if all items were
+	/// returned.
+	///
+	/// A cursor may be returned when the last item of the page is the last item of the map, in
+	/// which case the next page is empty.
+	pub cursor: Option<StorageCursor<Map>>,
+	/// The estimated proof size of reading the page, see [`IterationLimit`].
+	pub proof_size: u64,
+}
+
+impl<T: Clone, Map> Clone for Page<T, Map> {
+	fn clone(&self) -> Self {
+		Self { items: self.items.clone(), cursor: self.cursor.clone(), proof_size: self.proof_size }
+	}
+}
+
+impl<T: PartialEq, Map> PartialEq for Page<T, Map> {
+	fn eq(&self, other: &Self) -> bool {
+		self.items == other.items &&
+			self.cursor == other.cursor &&
+			self.proof_size == other.proof_size
+	}
+}
+
+impl<T: Eq, Map> Eq for Page<T, Map> {}
+
+impl<T: sp_std::fmt::Debug, Map> sp_std::fmt::Debug for Page<T, Map> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		f.debug_struct("Page")
+			.field("items", &self.items)
+			.field("cursor", &self.cursor)
+			.field("proof_size", &self.proof_size)
+			.finish()
+	}
+}
+
+/// An iterator over storage items under a prefix, which can be resumed after any key.
+///
+/// Implemented by [`PrefixIterator`](super::PrefixIterator),
+/// [`KeyPrefixIterator`](super::KeyPrefixIterator) and
+/// [`ChildTriePrefixIterator`](super::ChildTriePrefixIterator).
+pub trait ResumableIterator: Iterator {
+	/// The prefix of all keys iterated on.
+	fn prefix(&self) -> &[u8];
+
+	/// The last key iterated on.
+	fn last_raw_key(&self) -> &[u8];
+
+	/// Set the key that the iterator should continue iterating after.
+	fn set_last_raw_key(&mut self, previous_key: Vec<u8>);
+
+	/// Advance the iterator, adding the number of bytes read from storage to `proof_size`.
+	///
+	/// Bytes of items that fail to decode are accounted as well.
+	fn next_metered(&mut self, proof_size: &mut u64) -> Option<Self::Item>;
+}
+
+/// Read a page of at most `limit` items from `iter`, resuming after `cursor` if given.
+///
+/// Returns [`InvalidCursor`] if the cursor does not point into the prefix of `iter`.
+pub fn page<Map, I>(
+	mut iter: I,
+	cursor: Option<StorageCursor<Map>>,
+	limit: IterationLimit,
+) -> Result<Page<I::Item, Map>, InvalidCursor>
+where
+	I: ResumableIterator,
+{
+	if let Some(cursor) = cursor {
+		if !cursor.last_raw_key.starts_with(iter.prefix()) {
+			return Err(InvalidCursor)
+		}
+		iter.set_last_raw_key(cursor.last_raw_key);
+	}
+
+	let mut items = Vec::new();
+	let mut proof_size = 0u64;
+	loop {
+		if !items.is_empty() && limit.is_reached(items.len() as u32, proof_size) {
+			let cursor = StorageCursor::from_raw_key(iter.last_raw_key().to_vec());
+			return Ok(Page { items, cursor: Some(cursor), proof_size })
+		}
+
+		match iter.next_metered(&mut proof_size) {
+			Some(item) => items.push(item),
+			None => return Ok(Page { items, cursor: None, proof_size }),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		hash::*,
+		storage::{
+			child,
+			types::{
+				CountedStorageNMap, CountedStorageNMapInstance, Key as NMapKey, StorageDoubleMap,
+				StorageMap, StorageNMap,
+			},
+			ChildTriePrefixIterator,
+		},
+		traits::StorageInstance,
+	};
+	use sp_core::storage::ChildInfo;
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct CounterPrefix;
+	impl StorageInstance for CounterPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "counter_for_foo";
+	}
+	impl CountedStorageNMapInstance for Prefix {
+		type CounterPrefix = CounterPrefix;
+	}
+
+	type Map = StorageMap<Prefix, Twox64Concat, u16, u32>;
+
+	/// Collect all items of `Map` a page of `limit` at a time, resuming from an encoded cursor.
+	fn collect_in_pages(limit: IterationLimit) -> (Vec<(u16, u32)>, usize) {
+		let mut items = Vec::new();
+		let mut pages = 0;
+		let mut stored: Option<Vec<u8>> = None;
+		loop {
+			let cursor = stored.map(|encoded| {
+				StorageCursor::<Map>::decode(&mut &encoded[..]).expect("cursor decodes; qed")
+			});
+			let page = Map::iter_paged(cursor, limit).unwrap();
+			pages += 1;
+			items.extend(page.items);
+			match page.cursor {
+				Some(cursor) => stored = Some(cursor.encode()),
+				None => return (items, pages),
+			}
+		}
+	}
+
+	#[test]
+	fn map_pages_cover_all_items() {
+		TestExternalities::default().execute_with(|| {
+			for i in 0..10u16 {
+				Map::insert(i, i as u32 * 10);
+			}
+			let all = Map::iter().collect::<Vec<_>>();
+
+			let (items, pages) = collect_in_pages(IterationLimit::items(3));
+			assert_eq!(items, all);
+			// 3 + 3 + 3 + 1.
+			assert_eq!(pages, 4);
+
+			let (items, pages) = collect_in_pages(IterationLimit::items(5));
+			assert_eq!(items, all);
+			// The second page ends with the last item, the third one is empty.
+			assert_eq!(pages, 3);
+
+			let (items, pages) = collect_in_pages(IterationLimit::unlimited());
+			assert_eq!(items, all);
+			assert_eq!(pages, 1);
+		});
+	}
+
+	#[test]
+	fn proof_size_limit_works() {
+		TestExternalities::default().execute_with(|| {
+			for i in 0..10u16 {
+				Map::insert(i, i as u32);
+			}
+			// Two Twox128, Twox64Concat of a u16 and a u32 value.
+			let item_size = 16 * 2 + 8 + 2 + 4;
+
+			let page = Map::iter_paged(None, IterationLimit::proof_size(item_size * 2)).unwrap();
+			assert_eq!(page.items.len(), 2);
+			assert_eq!(page.proof_size, item_size * 2);
+
+			// Exceeded by the last item.
+			let page = Map::iter_paged(None, IterationLimit::proof_size(item_size + 1)).unwrap();
+			assert_eq!(page.items.len(), 2);
+
+			// At least one item is returned.
+			let page = Map::iter_paged(None, IterationLimit::proof_size(1)).unwrap();
+			assert_eq!(page.items.len(), 1);
+
+			// Keys only.
+			let limit = IterationLimit { items: Some(2), proof_size: Some(item_size * 10) };
+			let page = Map::iter_keys_paged(None, limit).unwrap();
+			assert_eq!(page.items.len(), 2);
+			assert_eq!(page.proof_size, (item_size - 4) * 2);
+		});
+	}
+
+	#[test]
+	fn cursor_outside_of_prefix_is_rejected() {
+		type DoubleMap = StorageDoubleMap<Prefix, Identity, u16, Identity, u16, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..4u16 {
+				DoubleMap::insert(1, i, i as u32);
+				DoubleMap::insert(2, i, i as u32);
+			}
+
+			let page = DoubleMap::iter_prefix_paged(1, None, IterationLimit::items(2)).unwrap();
+			assert_eq!(page.items, vec![(0, 0), (1, 1)]);
+			let cursor = page.cursor.unwrap();
+
+			// The cursor of the first key can't be used to iterate another first key.
+			assert_eq!(
+				DoubleMap::iter_prefix_paged(2, Some(cursor.clone()), IterationLimit::unlimited()),
+				Err(InvalidCursor),
+			);
+			// But can be used to iterate the whole map.
+			let page =
+				DoubleMap::iter_keys_paged(Some(cursor.clone()), IterationLimit::items(3)).unwrap();
+			assert_eq!(page.items, vec![(1, 2), (1, 3), (2, 0)]);
+
+			let page =
+				DoubleMap::iter_key_prefix_paged(1, Some(cursor), IterationLimit::unlimited())
+					.unwrap();
+			assert_eq!(page.items, vec![2, 3]);
+			assert_eq!(page.cursor, None);
+
+			// Cursors from an untrusted source are checked.
+			let cursor = StorageCursor::from_raw_key(b"unknown".to_vec());
+			assert_eq!(
+				DoubleMap::iter_paged(Some(cursor), IterationLimit::unlimited()),
+				Err(InvalidCursor)
+			);
+		});
+	}
+
+	#[test]
+	fn nmap_pages_work() {
+		type NMap = StorageNMap<Prefix, (NMapKey<Identity, u16>, NMapKey<Identity, u16>), u32>;
+		type CountedNMap =
+			CountedStorageNMap<Prefix, (NMapKey<Identity, u16>, NMapKey<Identity, u16>), u32>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..4u16 {
+				NMap::insert((1, i), i as u32);
+			}
+			let page = NMap::iter_prefix_paged((1,), None, IterationLimit::items(3)).unwrap();
+			assert_eq!(page.items, vec![(0, 0), (1, 1), (2, 2)]);
+			let page =
+				NMap::iter_key_prefix_paged((1,), page.cursor, IterationLimit::items(3)).unwrap();
+			assert_eq!(page.items, vec![3]);
+			assert_eq!(page.cursor, None);
+		});
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..4u16 {
+				CountedNMap::insert((1, i), i as u32);
+			}
+			let page = CountedNMap::iter_paged(None, IterationLimit::items(3)).unwrap();
+			assert_eq!(page.items.len(), 3);
+			let page = CountedNMap::iter_keys_paged(page.cursor, IterationLimit::items(3)).unwrap();
+			assert_eq!(page.items, vec![(1, 3)]);
+		});
+	}
+
+	#[test]
+	fn child_trie_pages_work() {
+		struct ChildMap;
+
+		TestExternalities::default().execute_with(|| {
+			let child_info = ChildInfo::new_default(b"child");
+			for i in 0..4u8 {
+				child::put(&child_info, &[b'p', i], &(i as u32));
+			}
+
+			let iter = ChildTriePrefixIterator::<(Vec<u8>, u32)>::with_prefix(&child_info, b"p");
+			let first = page::<ChildMap, _>(iter, None, IterationLimit::items(3)).unwrap();
+			assert_eq!(first.items, vec![(vec![0], 0), (vec![1], 1), (vec![2], 2)]);
+
+			let iter = ChildTriePrefixIterator::<(Vec<u8>, u32)>::with_prefix(&child_info, b"p");
+			let second = page::<ChildMap, _>(iter, first.cursor, IterationLimit::items(3)).unwrap();
+			assert_eq!(second.items, vec![(vec![3], 3)]);
+			assert_eq!(second.cursor, None);
+		});
+	}
+
+	#[test]
+	fn max_encoded_len_works() {
+		// Compact length and two Twox128, Twox64Concat of a u16.
+		assert_eq!(StorageCursor::<Map>::max_encoded_len(), 1 + 16 * 2 + 8 + 2);
+	}
+}
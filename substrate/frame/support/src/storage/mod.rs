@@ -43,6 +43,7 @@ pub mod bounded_btree_map;
 pub mod bounded_btree_set;
 pub mod bounded_vec;
 pub mod child;
+pub mod cursor;
 #[doc(hidden)]
 pub mod generator;
 pub mod hashed;
@@ -52,6 +53,7 @@ mod stream_iter;
 pub mod transactional;
 pub mod types;
 pub mod unhashed;
+pub mod usage;
 pub mod weak_bounded_vec;
 
 /// Utility type for converting a storage map into a `Get<u32>` impl which returns the maximum
@@ -1086,12 +1088,33 @@ impl<T, OnRemoval: PrefixIteratorOnRemoval> Iterator for PrefixIterator<T, OnRem
 	type Item = T;
 
 	fn next(&mut self) -> Option<Self::Item> {
+		cursor::ResumableIterator::next_metered(self, &mut 0)
+	}
+}
+
+impl<T, OnRemoval: PrefixIteratorOnRemoval> cursor::ResumableIterator
+	for PrefixIterator<T, OnRemoval>
+{
+	fn prefix(&self) -> &[u8] {
+		&self.prefix
+	}
+
+	fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+
+	fn set_last_raw_key(&mut self, previous_key: Vec<u8>) {
+		self.previous_key = previous_key;
+	}
+
+	fn next_metered(&mut self, proof_size: &mut u64) -> Option<T> {
 		loop {
 			let maybe_next = sp_io::storage::next_key(&self.previous_key)
 				.filter(|n| n.starts_with(&self.prefix));
 			break match maybe_next {
 				Some(next) => {
 					self.previous_key = next;
+					*proof_size = proof_size.saturating_add(self.previous_key.len() as u64);
 					let raw_value = match unhashed::get_raw(&self.previous_key) {
 						Some(raw_value) => raw_value,
 						None => {
@@ -1102,6 +1125,7 @@ impl<T, OnRemoval: PrefixIteratorOnRemoval> Iterator for PrefixIterator<T, OnRem
 							continue
 						},
 					};
+					*proof_size = proof_size.saturating_add(raw_value.len() as u64);
 					if self.drain {
 						unhashed::kill(&self.previous_key);
 						OnRemoval::on_removal(&self.previous_key, &raw_value);
@@ -1182,12 +1206,31 @@ impl<T> Iterator for KeyPrefixIterator<T> {
 	type Item = T;
 
 	fn next(&mut self) -> Option<Self::Item> {
+		cursor::ResumableIterator::next_metered(self, &mut 0)
+	}
+}
+
+impl<T> cursor::ResumableIterator for KeyPrefixIterator<T> {
+	fn prefix(&self) -> &[u8] {
+		&self.prefix
+	}
+
+	fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+
+	fn set_last_raw_key(&mut self, previous_key: Vec<u8>) {
+		self.previous_key = previous_key;
+	}
+
+	fn next_metered(&mut self, proof_size: &mut u64) -> Option<T> {
 		loop {
 			let maybe_next = sp_io::storage::next_key(&self.previous_key)
 				.filter(|n| n.starts_with(&self.prefix));
 
 			if let Some(next) = maybe_next {
 				self.previous_key = next;
+				*proof_size = proof_size.saturating_add(self.previous_key.len() as u64);
 				if self.drain {
 					unhashed::kill(&self.previous_key);
 				}
@@ -1291,6 +1334,25 @@ impl<T> Iterator for ChildTriePrefixIterator<T> {
 	type Item = T;
 
 	fn next(&mut self) -> Option<Self::Item> {
+		cursor::ResumableIterator::next_metered(self, &mut 0)
+	}
+}
+
+impl<T> cursor::ResumableIterator for ChildTriePrefixIterator<T> {
+	fn prefix(&self) -> &[u8] {
+		&self.prefix
+	}
+
+	fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+
+	fn set_last_raw_key(&mut self, previous_key: Vec<u8>) {
+		self.previous_key = previous_key;
+		self.fetch_previous_key = false;
+	}
+
+	fn next_metered(&mut self, proof_size: &mut u64) -> Option<T> {
 		loop {
 			let maybe_next = if self.fetch_previous_key {
 				self.fetch_previous_key = false;
@@ -1305,6 +1367,7 @@ impl<T> Iterator for ChildTriePrefixIterator<T> {
 			break match maybe_next {
 				Some(next) => {
 					self.previous_key = next;
+					*proof_size = proof_size.saturating_add(self.previous_key.len() as u64);
 					let raw_value = match child::get_raw(&self.child_info, &self.previous_key) {
 						Some(raw_value) => raw_value,
 						None => {
@@ -1315,6 +1378,7 @@ impl<T> Iterator for ChildTriePrefixIterator<T> {
 							continue
 						},
 					};
+					*proof_size = proof_size.saturating_add(raw_value.len() as u64);
 					if self.drain {
 						child::kill(&self.child_info, &self.previous_key)
 					}
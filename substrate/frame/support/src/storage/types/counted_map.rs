@@ -19,12 +19,14 @@
 
 use crate::{
 	storage::{
+		cursor::{self, InvalidCursor, IterationLimit, Page, StorageCursor},
 		generator::StorageMap as _,
 		types::{
 			OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder, StorageMap, StorageValue,
 			ValueQuery,
 		},
-		StorageAppend, StorageDecodeLength, StorageTryAppend,
+		usage::OnRemovalUsageUpdate,
+		KeyLenOf, PrefixIteratorOnRemoval, StorageAppend, StorageDecodeLength, StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageInstance},
 	Never,
@@ -119,6 +121,17 @@ impl<P: CountedStorageMapInstance, H, K, V, Q, O, M> MapWrapper
 	type Map = StorageMap<P, H, K, V, Q, O, M>;
 }
 
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> Get<u32>
+	for KeyLenOf<CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>>
+where
+	Prefix: CountedStorageMapInstance,
+	KeyLenOf<StorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>>: Get<u32>,
+{
+	fn get() -> u32 {
+		KeyLenOf::<StorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>>::get()
+	}
+}
+
 /// The numeric counter type.
 pub type Counter = u32;
 
@@ -129,11 +142,10 @@ type CounterFor<P> =
 /// [`crate::storage::PrefixIterator`].
 pub struct OnRemovalCounterUpdate<Prefix>(core::marker::PhantomData<Prefix>);
 
-impl<Prefix: CountedStorageMapInstance> crate::storage::PrefixIteratorOnRemoval
-	for OnRemovalCounterUpdate<Prefix>
-{
-	fn on_removal(_key: &[u8], _value: &[u8]) {
+impl<Prefix: CountedStorageMapInstance> PrefixIteratorOnRemoval for OnRemovalCounterUpdate<Prefix> {
+	fn on_removal(key: &[u8], value: &[u8]) {
 		CounterFor::<Prefix>::mutate(|value| value.saturating_dec());
+		OnRemovalUsageUpdate::<Prefix>::on_removal(key, value);
 	}
 }
 
@@ -495,6 +507,28 @@ where
 	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key> {
 		<Self as MapWrapper>::Map::iter_keys()
 	}
+
+	/// Enumerate a page of elements in the counted map in no particular order, resuming after
+	/// `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter(), cursor, limit)
+	}
+
+	/// Enumerate a page of keys in the counted map in no particular order, resuming after
+	/// `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_keys_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<Key, Self>, InvalidCursor> {
+		cursor::page(Self::iter_keys(), cursor, limit)
+	}
 }
 
 impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> StorageEntryMetadataBuilder
@@ -19,11 +19,14 @@
 
 use crate::{
 	storage::{
+		cursor::{self, InvalidCursor, IterationLimit, Page, StorageCursor},
 		types::{
 			EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, OptionQuery, QueryKindTrait,
 			StorageEntryMetadataBuilder, StorageNMap, StorageValue, TupleToEncodedIter, ValueQuery,
 		},
-		KeyGenerator, PrefixIterator, StorageAppend, StorageDecodeLength,
+		usage::OnRemovalUsageUpdate,
+		KeyGenerator, KeyLenOf, PrefixIterator, PrefixIteratorOnRemoval, StorageAppend,
+		StorageDecodeLength,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
 	Never,
@@ -114,6 +117,17 @@ impl<P: CountedStorageNMapInstance, K, V, Q, O, M> MapWrapper
 	type Map = StorageNMap<P, K, V, Q, O, M>;
 }
 
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues> Get<u32>
+	for KeyLenOf<CountedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>>
+where
+	Prefix: CountedStorageNMapInstance,
+	KeyLenOf<StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>>: Get<u32>,
+{
+	fn get() -> u32 {
+		KeyLenOf::<StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>>::get()
+	}
+}
+
 type Counter = super::counted_map::Counter;
 
 type CounterFor<P> =
@@ -123,11 +137,12 @@ type CounterFor<P> =
 /// [`crate::storage::PrefixIterator`].
 pub struct OnRemovalCounterUpdate<Prefix>(core::marker::PhantomData<Prefix>);
 
-impl<Prefix: CountedStorageNMapInstance> crate::storage::PrefixIteratorOnRemoval
+impl<Prefix: CountedStorageNMapInstance> PrefixIteratorOnRemoval
 	for OnRemovalCounterUpdate<Prefix>
 {
-	fn on_removal(_key: &[u8], _value: &[u8]) {
+	fn on_removal(key: &[u8], value: &[u8]) {
 		CounterFor::<Prefix>::mutate(|value| value.saturating_dec());
+		OnRemovalUsageUpdate::<Prefix>::on_removal(key, value);
 	}
 }
 
@@ -548,6 +563,36 @@ where
 		<Self as MapWrapper>::Map::iter_key_prefix_from(kp, starting_raw_key)
 	}
 
+	/// Enumerate a page of elements in the map with prefix key `kp` in no particular order,
+	/// resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_prefix_paged<KP>(
+		kp: KP,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(<Key as HasKeyPrefix<KP>>::Suffix, Value), Self>, InvalidCursor>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		cursor::page(Self::iter_prefix(kp), cursor, limit)
+	}
+
+	/// Enumerate a page of suffix keys in the map with prefix key `kp` in no particular order,
+	/// resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_key_prefix_paged<KP>(
+		kp: KP,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<<Key as HasKeyPrefix<KP>>::Suffix, Self>, InvalidCursor>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		cursor::page(Self::iter_key_prefix(kp), cursor, limit)
+	}
+
 	/// Remove all elements from the map with prefix key `kp` and iterate through them in no
 	/// particular order.
 	///
@@ -598,6 +643,26 @@ where
 		<Self as MapWrapper>::Map::iter_keys_from(starting_raw_key)
 	}
 
+	/// Enumerate a page of elements in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key::Key, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter(), cursor, limit)
+	}
+
+	/// Enumerate a page of keys in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_keys_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<Key::Key, Self>, InvalidCursor> {
+		cursor::page(Self::iter_keys(), cursor, limit)
+	}
+
 	/// Remove all elements from the map and iterate through them in no particular order.
 	///
 	/// If you add elements to the map while doing this, you'll get undefined results.
@@ -20,7 +20,9 @@
 
 use crate::{
 	storage::{
+		cursor::{self, InvalidCursor, IterationLimit, Page, StorageCursor},
 		types::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder},
+		usage::{self, OnRemovalUsageUpdate},
 		KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap, StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
@@ -260,7 +262,13 @@ where
 		k2: KArg2,
 		q: QueryKind::Query,
 	) {
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::set(k1, k2, q)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::set(k1, k2, q)
+			},
+		)
 	}
 
 	/// Take a value from storage, removing it afterwards.
@@ -269,7 +277,11 @@ where
 		KArg1: EncodeLike<Key1>,
 		KArg2: EncodeLike<Key2>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::take(k1, k2)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| <Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::take(k1, k2),
+		)
 	}
 
 	/// Swap the values of two key-pairs.
@@ -284,7 +296,21 @@ where
 		YKArg1: EncodeLike<Key1>,
 		YKArg2: EncodeLike<Key2>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::swap(x_k1, x_k2, y_k1, y_k2)
+		usage::track::<Prefix, _, _, _>(
+			(x_k1, x_k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(x_k1, x_k2)| {
+				usage::track::<Prefix, _, _, _>(
+					(y_k1, y_k2),
+					|(k1, k2)| Self::hashed_key_for(k1, k2),
+					|(y_k1, y_k2)| {
+						<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::swap(
+							x_k1, x_k2, y_k1, y_k2,
+						)
+					},
+				)
+			},
+		)
 	}
 
 	/// Store a value to be associated with the given keys from the double map.
@@ -294,7 +320,13 @@ where
 		KArg2: EncodeLike<Key2>,
 		VArg: EncodeLike<Value>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::insert(k1, k2, val)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::insert(k1, k2, val)
+			},
+		)
 	}
 
 	/// Remove the value under the given keys.
@@ -303,7 +335,13 @@ where
 		KArg1: EncodeLike<Key1>,
 		KArg2: EncodeLike<Key2>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::remove(k1, k2)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::remove(k1, k2)
+			},
+		)
 	}
 
 	/// Remove all values under `k1` in the overlay and up to `limit` in the
@@ -323,8 +361,17 @@ where
 	where
 		KArg1: ?Sized + EncodeLike<Key1>,
 	{
-		#[allow(deprecated)]
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::remove_prefix(k1, limit)
+		usage::clear_prefix::<Prefix, _, _>(
+			|| [&Prefix::prefix_hash()[..], k1.using_encoded(Hasher1::hash).as_ref()].concat(),
+			limit,
+			None,
+			|| {
+				#[allow(deprecated)]
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::remove_prefix(
+					&k1, limit,
+				)
+			},
+		)
 	}
 
 	/// Attempt to remove items from the map matching a `first_key` prefix.
@@ -358,10 +405,20 @@ where
 	where
 		KArg1: ?Sized + EncodeLike<Key1>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::clear_prefix(
-			first_key,
-			limit,
+		usage::clear_prefix::<Prefix, _, _>(
+			|| {
+				[&Prefix::prefix_hash()[..], first_key.using_encoded(Hasher1::hash).as_ref()]
+					.concat()
+			},
+			Some(limit),
 			maybe_cursor,
+			|| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::clear_prefix(
+					&first_key,
+					limit,
+					maybe_cursor,
+				)
+			},
 		)
 	}
 
@@ -380,7 +437,13 @@ where
 		KArg2: EncodeLike<Key2>,
 		F: FnOnce(&mut QueryKind::Query) -> R,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::mutate(k1, k2, f)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::mutate(k1, k2, f)
+			},
+		)
 	}
 
 	/// Mutate the value under the given keys when the closure returns `Ok`.
@@ -390,7 +453,13 @@ where
 		KArg2: EncodeLike<Key2>,
 		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::try_mutate(k1, k2, f)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::try_mutate(k1, k2, f)
+			},
+		)
 	}
 
 	/// Mutate the value under the given keys. Deletes the item if mutated to a `None`.
@@ -400,7 +469,15 @@ where
 		KArg2: EncodeLike<Key2>,
 		F: FnOnce(&mut Option<Value>) -> R,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::mutate_exists(k1, k2, f)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::mutate_exists(
+					k1, k2, f,
+				)
+			},
+		)
 	}
 
 	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
@@ -412,7 +489,15 @@ where
 		KArg2: EncodeLike<Key2>,
 		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::try_mutate_exists(k1, k2, f)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::try_mutate_exists(
+					k1, k2, f,
+				)
+			},
+		)
 	}
 
 	/// Append the given item to the value in the storage.
@@ -432,7 +517,13 @@ where
 		EncodeLikeItem: EncodeLike<Item>,
 		Value: StorageAppend<Item>,
 	{
-		<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::append(k1, k2, item)
+		usage::track::<Prefix, _, _, _>(
+			(k1, k2),
+			|(k1, k2)| Self::hashed_key_for(k1, k2),
+			|(k1, k2)| {
+				<Self as crate::storage::StorageDoubleMap<Key1, Key2, Value>>::append(k1, k2, item)
+			},
+		)
 	}
 
 	/// Read the length of the storage value without decoding the entire value under the
@@ -515,8 +606,10 @@ where
 	/// overlay are not taken into account when deleting keys in the backend.
 	#[deprecated = "Use `clear` instead"]
 	pub fn remove_all(limit: Option<u32>) -> sp_io::KillStorageResult {
-		#[allow(deprecated)]
-		<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit)
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, limit, None, || {
+			#[allow(deprecated)]
+			<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit)
+		})
 	}
 
 	/// Attempt to remove all items from the map.
@@ -543,7 +636,9 @@ where
 	/// operating on the same map should always pass `Some`, and this should be equal to the
 	/// previous call result's `maybe_cursor` field.
 	pub fn clear(limit: u32, maybe_cursor: Option<&[u8]>) -> sp_io::MultiRemovalResults {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, Some(limit), maybe_cursor, || {
+			<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		})
 	}
 
 	/// Iter over all value of the storage.
@@ -566,8 +661,15 @@ where
 	/// # Usage
 	///
 	/// This would typically be called inside the module implementation of on_runtime_upgrade.
-	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(f: F) {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(mut f: F) {
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|_| Ok(()),
+			|(), value| f(value),
+		)
 	}
 
 	/// Try and append the given item to the value in the storage.
@@ -585,8 +687,14 @@ where
 		EncodeLikeItem: EncodeLike<Item>,
 		Value: StorageTryAppend<Item>,
 	{
-		<Self as crate::storage::TryAppendDoubleMap<Key1, Key2, Value, Item>>::try_append(
-			key1, key2, item,
+		usage::track::<Prefix, _, _, _>(
+			(key1, key2),
+			|(key1, key2)| Self::hashed_key_for(key1, key2),
+			|(key1, key2)| {
+				<Self as crate::storage::TryAppendDoubleMap<Key1, Key2, Value, Item>>::try_append(
+					key1, key2, item,
+				)
+			},
 		)
 	}
 }
@@ -651,6 +759,30 @@ where
 		)
 	}
 
+	/// Enumerate a page of elements in the map with first key `k1` in no particular order,
+	/// resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_prefix_paged(
+		k1: impl EncodeLike<Key1>,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key2, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter_prefix(k1), cursor, limit)
+	}
+
+	/// Enumerate a page of second keys `k2` in the map with the same first key `k1` in no
+	/// particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_key_prefix_paged(
+		k1: impl EncodeLike<Key1>,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<Key2, Self>, InvalidCursor> {
+		cursor::page(Self::iter_key_prefix(k1), cursor, limit)
+	}
+
 	/// Remove all elements from the map with first key `k1` and iterate through them in no
 	/// particular order.
 	///
@@ -658,8 +790,9 @@ where
 	/// results.
 	pub fn drain_prefix(
 		k1: impl EncodeLike<Key1>,
-	) -> crate::storage::PrefixIterator<(Key2, Value)> {
+	) -> crate::storage::PrefixIterator<(Key2, Value), OnRemovalUsageUpdate<Prefix>> {
 		<Self as crate::storage::IterableStorageDoubleMap<Key1, Key2, Value>>::drain_prefix(k1)
+			.convert_on_removal()
 	}
 
 	/// Enumerate all elements in the map in no particular order.
@@ -700,11 +833,34 @@ where
 		)
 	}
 
+	/// Enumerate a page of elements in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key1, Key2, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter(), cursor, limit)
+	}
+
+	/// Enumerate a page of keys `k1` and `k2` in the map in no particular order, resuming after
+	/// `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_keys_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key1, Key2), Self>, InvalidCursor> {
+		cursor::page(Self::iter_keys(), cursor, limit)
+	}
+
 	/// Remove all elements from the map and iterate through them in no particular order.
 	///
 	/// If you add elements to the map while doing this, you'll get undefined results.
-	pub fn drain() -> crate::storage::PrefixIterator<(Key1, Key2, Value)> {
+	pub fn drain(
+	) -> crate::storage::PrefixIterator<(Key1, Key2, Value), OnRemovalUsageUpdate<Prefix>> {
 		<Self as crate::storage::IterableStorageDoubleMap<Key1, Key2, Value>>::drain()
+			.convert_on_removal()
 	}
 
 	/// Translate the values of all elements by a function `f`, in the map in no particular order.
@@ -712,8 +868,22 @@ where
 	/// By returning `None` from `f` for an element, you'll remove it from the map.
 	///
 	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
-	pub fn translate<O: Decode, F: FnMut(Key1, Key2, O) -> Option<Value>>(f: F) {
-		<Self as crate::storage::IterableStorageDoubleMap<Key1, Key2, Value>>::translate(f)
+	pub fn translate<O: Decode, F: FnMut(Key1, Key2, O) -> Option<Value>>(mut f: F) {
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::IterableStorageDoubleMap<Key1, Key2, Value>>::translate(
+				f,
+			)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|key_material| {
+				let mut key_material = Hasher1::reverse(key_material);
+				let key1 = Key1::decode(&mut key_material)?;
+				let key2 = Key2::decode(&mut Hasher2::reverse(key_material))?;
+				Ok((key1, key2))
+			},
+			|(key1, key2), value| f(key1, key2, value),
+		)
 	}
 }
 
@@ -201,6 +201,19 @@ impl<'a, T: EncodeLike<U> + EncodeLikeTuple<U>, U: Encode> EncodeLikeTuple<U>
 {
 }
 
+/// A borrowed key tuple, to compute the final key of an entry without consuming the key.
+pub(crate) struct KeyTupleRef<'a, T>(pub &'a T);
+
+impl<'a, T> crate::storage::private::Sealed for KeyTupleRef<'a, T> {}
+
+impl<'a, T: EncodeLikeTuple<U>, U> EncodeLikeTuple<U> for KeyTupleRef<'a, T> {}
+
+impl<'a, T: TupleToEncodedIter> TupleToEncodedIter for KeyTupleRef<'a, T> {
+	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>> {
+		self.0.to_encoded_iter()
+	}
+}
+
 /// Trait to indicate that a tuple can be converted into an iterator of a vector of encoded bytes.
 pub trait TupleToEncodedIter {
 	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>>;
@@ -20,7 +20,9 @@
 
 use crate::{
 	storage::{
+		cursor::{self, InvalidCursor, IterationLimit, Page, StorageCursor},
 		types::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder},
+		usage::{self, OnRemovalUsageUpdate},
 		KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap, StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
@@ -181,22 +183,44 @@ where
 
 	/// Swap the values of two keys.
 	pub fn swap<KeyArg1: EncodeLike<Key>, KeyArg2: EncodeLike<Key>>(key1: KeyArg1, key2: KeyArg2) {
-		<Self as crate::storage::StorageMap<Key, Value>>::swap(key1, key2)
+		usage::track::<Prefix, _, _, _>(
+			key1,
+			|key| Self::hashed_key_for(key),
+			|key1| {
+				usage::track::<Prefix, _, _, _>(
+					key2,
+					|key| Self::hashed_key_for(key),
+					|key2| <Self as crate::storage::StorageMap<Key, Value>>::swap(key1, key2),
+				)
+			},
+		)
 	}
 
 	/// Store or remove the value to be associated with `key` so that `get` returns the `query`.
 	pub fn set<KeyArg: EncodeLike<Key>>(key: KeyArg, q: QueryKind::Query) {
-		<Self as crate::storage::StorageMap<Key, Value>>::set(key, q)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::set(key, q),
+		)
 	}
 
 	/// Store a value to be associated with the given key from the map.
 	pub fn insert<KeyArg: EncodeLike<Key>, ValArg: EncodeLike<Value>>(key: KeyArg, val: ValArg) {
-		<Self as crate::storage::StorageMap<Key, Value>>::insert(key, val)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::insert(key, val),
+		)
 	}
 
 	/// Remove the value under a key.
 	pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
-		<Self as crate::storage::StorageMap<Key, Value>>::remove(key)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::remove(key),
+		)
 	}
 
 	/// Mutate the value under a key.
@@ -204,7 +228,11 @@ where
 		key: KeyArg,
 		f: F,
 	) -> R {
-		<Self as crate::storage::StorageMap<Key, Value>>::mutate(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::mutate(key, f),
+		)
 	}
 
 	/// Mutate the item, only if an `Ok` value is returned.
@@ -213,7 +241,11 @@ where
 		KeyArg: EncodeLike<Key>,
 		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageMap<Key, Value>>::try_mutate(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::try_mutate(key, f),
+		)
 	}
 
 	/// Mutate the value under a key iff it exists. Do nothing and return the default value if not.
@@ -221,7 +253,11 @@ where
 		key: KeyArg,
 		f: F,
 	) -> R {
-		<Self as crate::storage::StorageMap<Key, Value>>::mutate_extant(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::mutate_extant(key, f),
+		)
 	}
 
 	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
@@ -229,7 +265,11 @@ where
 		key: KeyArg,
 		f: F,
 	) -> R {
-		<Self as crate::storage::StorageMap<Key, Value>>::mutate_exists(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::mutate_exists(key, f),
+		)
 	}
 
 	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
@@ -240,12 +280,20 @@ where
 		KeyArg: EncodeLike<Key>,
 		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageMap<Key, Value>>::try_mutate_exists(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::try_mutate_exists(key, f),
+		)
 	}
 
 	/// Take the value under a key.
 	pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
-		<Self as crate::storage::StorageMap<Key, Value>>::take(key)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::take(key),
+		)
 	}
 
 	/// Append the given items to the value in the storage.
@@ -264,7 +312,11 @@ where
 		EncodeLikeItem: EncodeLike<Item>,
 		Value: StorageAppend<Item>,
 	{
-		<Self as crate::storage::StorageMap<Key, Value>>::append(key, item)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::StorageMap<Key, Value>>::append(key, item),
+		)
 	}
 
 	/// Read the length of the storage value without decoding the entire value under the
@@ -329,8 +381,10 @@ where
 	/// overlay are not taken into account when deleting keys in the backend.
 	#[deprecated = "Use `clear` instead"]
 	pub fn remove_all(limit: Option<u32>) -> sp_io::KillStorageResult {
-		#[allow(deprecated)]
-		<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit)
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, limit, None, || {
+			#[allow(deprecated)]
+			<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit)
+		})
 	}
 
 	/// Attempt to remove all items from the map.
@@ -357,7 +411,9 @@ where
 	/// operating on the same map should always pass `Some`, and this should be equal to the
 	/// previous call result's `maybe_cursor` field.
 	pub fn clear(limit: u32, maybe_cursor: Option<&[u8]>) -> sp_io::MultiRemovalResults {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, Some(limit), maybe_cursor, || {
+			<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		})
 	}
 
 	/// Iter over all value of the storage.
@@ -381,8 +437,15 @@ where
 	/// # Usage
 	///
 	/// This would typically be called inside the module implementation of on_runtime_upgrade.
-	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(f: F) {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(mut f: F) {
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|_| Ok(()),
+			|(), value| f(value),
+		)
 	}
 
 	/// Try and append the given item to the value in the storage.
@@ -395,7 +458,11 @@ where
 		EncodeLikeItem: EncodeLike<Item>,
 		Value: StorageTryAppend<Item>,
 	{
-		<Self as crate::storage::TryAppendMap<Key, Value, Item>>::try_append(key, item)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(key),
+			|key| <Self as crate::storage::TryAppendMap<Key, Value, Item>>::try_append(key, item),
+		)
 	}
 }
 
@@ -460,11 +527,31 @@ where
 		Self::iter_keys_from(Self::hashed_key_for(starting_key))
 	}
 
+	/// Enumerate a page of elements in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter(), cursor, limit)
+	}
+
+	/// Enumerate a page of keys in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_keys_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<Key, Self>, InvalidCursor> {
+		cursor::page(Self::iter_keys(), cursor, limit)
+	}
+
 	/// Remove all elements from the map and iterate through them in no particular order.
 	///
 	/// If you add elements to the map while doing this, you'll get undefined results.
-	pub fn drain() -> crate::storage::PrefixIterator<(Key, Value)> {
-		<Self as crate::storage::IterableStorageMap<Key, Value>>::drain()
+	pub fn drain() -> crate::storage::PrefixIterator<(Key, Value), OnRemovalUsageUpdate<Prefix>> {
+		<Self as crate::storage::IterableStorageMap<Key, Value>>::drain().convert_on_removal()
 	}
 
 	/// Translate the values of all elements by a function `f`, in the map in no particular order.
@@ -473,7 +560,14 @@ where
 	///
 	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
 	pub fn translate<O: Decode, F: FnMut(Key, O) -> Option<Value>>(f: F) {
-		<Self as crate::storage::IterableStorageMap<Key, Value>>::translate(f)
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::IterableStorageMap<Key, Value>>::translate(f)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|key_material| Key::decode(&mut Hasher::reverse(key_material)),
+			f,
+		)
 	}
 }
 
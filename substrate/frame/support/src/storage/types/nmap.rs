@@ -20,13 +20,19 @@
 
 use crate::{
 	storage::{
+		cursor::{self, InvalidCursor, IterationLimit, Page, StorageCursor},
 		types::{
-			EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, OptionQuery, QueryKindTrait,
-			StorageEntryMetadataBuilder, TupleToEncodedIter,
+			key::KeyTupleRef, EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix,
+			KeyGeneratorMaxEncodedLen, OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder,
+			TupleToEncodedIter,
 		},
-		KeyGenerator, PrefixIterator, StorageAppend, StorageDecodeLength, StoragePrefixedMap,
+		unhashed,
+		usage::{self, OnRemovalUsageUpdate},
+		KeyGenerator, KeyLenOf, PrefixIterator, StorageAppend, StorageDecodeLength,
+		StoragePrefixedMap,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
+	StorageHasher, Twox128,
 };
 use codec::{Decode, Encode, EncodeLike, FullCodec, MaxEncodedLen};
 use sp_metadata_ir::{StorageEntryMetadataIR, StorageEntryTypeIR};
@@ -90,6 +96,20 @@ pub struct StorageNMap<
 	MaxValues = GetDefault,
 >(core::marker::PhantomData<(Prefix, Key, Value, QueryKind, OnEmpty, MaxValues)>);
 
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues> Get<u32>
+	for KeyLenOf<StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>>
+where
+	Prefix: StorageInstance,
+	Key: KeyGeneratorMaxEncodedLen,
+{
+	fn get() -> u32 {
+		// The `max_len` of all key hashes plus the pallet prefix and storage prefix (which both
+		// are hashed with `Twox128`).
+		let z = Key::key_max_encoded_len() + Twox128::max_len::<()>() * 2;
+		z as u32
+	}
+}
+
 impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>
 	crate::storage::generator::StorageNMap<Key, Value>
 	for StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>
@@ -180,14 +200,22 @@ where
 		key: KArg,
 		query: QueryKind::Query,
 	) {
-		<Self as crate::storage::StorageNMap<Key, Value>>::set(key, query)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::set(key, query),
+		)
 	}
 
 	/// Take a value from storage, removing it afterwards.
 	pub fn take<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(
 		key: KArg,
 	) -> QueryKind::Query {
-		<Self as crate::storage::StorageNMap<Key, Value>>::take(key)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::take(key),
+		)
 	}
 
 	/// Swap the values of two key-pairs.
@@ -197,7 +225,23 @@ where
 		KArg1: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		KArg2: EncodeLikeTuple<KOther::KArg> + TupleToEncodedIter,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::swap::<KOther, _, _>(key1, key2)
+		usage::track::<Prefix, _, _, _>(
+			key1,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key1| {
+				usage::track::<Prefix, _, _, _>(
+					key2,
+					|key| {
+						[&Prefix::prefix_hash()[..], &KOther::final_key(KeyTupleRef(key))].concat()
+					},
+					|key2| {
+						<Self as crate::storage::StorageNMap<Key, Value>>::swap::<KOther, _, _>(
+							key1, key2,
+						)
+					},
+				)
+			},
+		)
 	}
 
 	/// Store a value to be associated with the given keys from the map.
@@ -206,12 +250,20 @@ where
 		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		VArg: EncodeLike<Value>,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::insert(key, val)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::insert(key, val),
+		)
 	}
 
 	/// Remove the value under the given keys.
 	pub fn remove<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(key: KArg) {
-		<Self as crate::storage::StorageNMap<Key, Value>>::remove(key)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::remove(key),
+		)
 	}
 
 	/// Remove all values starting with `partial_key` in the overlay and up to `limit` in the
@@ -231,8 +283,16 @@ where
 	where
 		Key: HasKeyPrefix<KP>,
 	{
-		#[allow(deprecated)]
-		<Self as crate::storage::StorageNMap<Key, Value>>::remove_prefix(partial_key, limit)
+		let prefix =
+			<Self as crate::storage::generator::StorageNMap<Key, Value>>::storage_n_map_partial_key(
+				partial_key,
+			);
+		usage::clear_prefix::<Prefix, _, _>(
+			|| &prefix,
+			limit,
+			None,
+			|| unhashed::clear_prefix(&prefix, limit, None).into(),
+		)
 	}
 
 	/// Attempt to remove items from the map matching a `partial_key` prefix.
@@ -266,10 +326,15 @@ where
 	where
 		Key: HasKeyPrefix<KP>,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::clear_prefix(
-			partial_key,
-			limit,
+		let prefix =
+			<Self as crate::storage::generator::StorageNMap<Key, Value>>::storage_n_map_partial_key(
+				partial_key,
+			);
+		usage::clear_prefix::<Prefix, _, _>(
+			|| &prefix,
+			Some(limit),
 			maybe_cursor,
+			|| unhashed::clear_prefix(&prefix, Some(limit), maybe_cursor),
 		)
 	}
 
@@ -287,7 +352,11 @@ where
 		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		F: FnOnce(&mut QueryKind::Query) -> R,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::mutate(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::mutate(key, f),
+		)
 	}
 
 	/// Mutate the value under the given keys when the closure returns `Ok`.
@@ -296,7 +365,11 @@ where
 		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::try_mutate(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::try_mutate(key, f),
+		)
 	}
 
 	/// Mutate the value under the given keys. Deletes the item if mutated to a `None`.
@@ -305,7 +378,11 @@ where
 		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		F: FnOnce(&mut Option<Value>) -> R,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::mutate_exists(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::mutate_exists(key, f),
+		)
 	}
 
 	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
@@ -316,7 +393,11 @@ where
 		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
 		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::try_mutate_exists(key, f)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::try_mutate_exists(key, f),
+		)
 	}
 
 	/// Append the given item to the value in the storage.
@@ -335,7 +416,11 @@ where
 		EncodeLikeItem: EncodeLike<Item>,
 		Value: StorageAppend<Item>,
 	{
-		<Self as crate::storage::StorageNMap<Key, Value>>::append(key, item)
+		usage::track::<Prefix, _, _, _>(
+			key,
+			|key| Self::hashed_key_for(KeyTupleRef(key)),
+			|key| <Self as crate::storage::StorageNMap<Key, Value>>::append(key, item),
+		)
 	}
 
 	/// Read the length of the storage value without decoding the entire value under the
@@ -382,8 +467,10 @@ where
 	/// overlay are not taken into account when deleting keys in the backend.
 	#[deprecated = "Use `clear` instead"]
 	pub fn remove_all(limit: Option<u32>) -> sp_io::KillStorageResult {
-		#[allow(deprecated)]
-		<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit).into()
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, limit, None, || {
+			#[allow(deprecated)]
+			<Self as crate::storage::StoragePrefixedMap<Value>>::remove_all(limit).into()
+		})
 	}
 
 	/// Attempt to remove all items from the map.
@@ -410,7 +497,9 @@ where
 	/// operating on the same map should always pass `Some`, and this should be equal to the
 	/// previous call result's `maybe_cursor` field.
 	pub fn clear(limit: u32, maybe_cursor: Option<&[u8]>) -> sp_io::MultiRemovalResults {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		usage::clear_prefix::<Prefix, _, _>(Prefix::prefix_hash, Some(limit), maybe_cursor, || {
+			<Self as crate::storage::StoragePrefixedMap<Value>>::clear(limit, maybe_cursor)
+		})
 	}
 
 	/// Iter over all value of the storage.
@@ -433,8 +522,15 @@ where
 	/// # Usage
 	///
 	/// This would typically be called inside the module implementation of on_runtime_upgrade.
-	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(f: F) {
-		<Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(mut f: F) {
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::StoragePrefixedMap<Value>>::translate_values(f)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|_| Ok(()),
+			|(), value| f(value),
+		)
 	}
 }
 
@@ -510,6 +606,36 @@ where
 		)
 	}
 
+	/// Enumerate a page of elements in the map with prefix key `kp` in no particular order,
+	/// resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_prefix_paged<KP>(
+		kp: KP,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(<Key as HasKeyPrefix<KP>>::Suffix, Value), Self>, InvalidCursor>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		cursor::page(Self::iter_prefix(kp), cursor, limit)
+	}
+
+	/// Enumerate a page of suffix keys in the map with prefix key `kp` in no particular order,
+	/// resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_key_prefix_paged<KP>(
+		kp: KP,
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<<Key as HasKeyPrefix<KP>>::Suffix, Self>, InvalidCursor>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		cursor::page(Self::iter_key_prefix(kp), cursor, limit)
+	}
+
 	/// Remove all elements from the map with prefix key `kp` and iterate through them in no
 	/// particular order.
 	///
@@ -517,11 +643,15 @@ where
 	/// results.
 	pub fn drain_prefix<KP>(
 		kp: KP,
-	) -> crate::storage::PrefixIterator<(<Key as HasKeyPrefix<KP>>::Suffix, Value)>
+	) -> crate::storage::PrefixIterator<
+		(<Key as HasKeyPrefix<KP>>::Suffix, Value),
+		OnRemovalUsageUpdate<Prefix>,
+	>
 	where
 		Key: HasReversibleKeyPrefix<KP>,
 	{
 		<Self as crate::storage::IterableStorageNMap<Key, Value>>::drain_prefix(kp)
+			.convert_on_removal()
 	}
 
 	/// Enumerate all elements in the map in no particular order.
@@ -556,11 +686,32 @@ where
 		<Self as crate::storage::IterableStorageNMap<Key, Value>>::iter_keys_from(starting_raw_key)
 	}
 
+	/// Enumerate a page of elements in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<(Key::Key, Value), Self>, InvalidCursor> {
+		cursor::page(Self::iter(), cursor, limit)
+	}
+
+	/// Enumerate a page of keys in the map in no particular order, resuming after `cursor`.
+	///
+	/// See [`cursor`](crate::storage::cursor) for details.
+	pub fn iter_keys_paged(
+		cursor: Option<StorageCursor<Self>>,
+		limit: IterationLimit,
+	) -> Result<Page<Key::Key, Self>, InvalidCursor> {
+		cursor::page(Self::iter_keys(), cursor, limit)
+	}
+
 	/// Remove all elements from the map and iterate through them in no particular order.
 	///
 	/// If you add elements to the map while doing this, you'll get undefined results.
-	pub fn drain() -> crate::storage::PrefixIterator<(Key::Key, Value)> {
-		<Self as crate::storage::IterableStorageNMap<Key, Value>>::drain()
+	pub fn drain() -> crate::storage::PrefixIterator<(Key::Key, Value), OnRemovalUsageUpdate<Prefix>>
+	{
+		<Self as crate::storage::IterableStorageNMap<Key, Value>>::drain().convert_on_removal()
 	}
 
 	/// Translate the values of all elements by a function `f`, in the map in no particular order.
@@ -569,7 +720,14 @@ where
 	///
 	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
 	pub fn translate<O: Decode, F: FnMut(Key::Key, O) -> Option<Value>>(f: F) {
-		<Self as crate::storage::IterableStorageNMap<Key, Value>>::translate(f)
+		if !Prefix::TRACK_STORAGE_USAGE {
+			return <Self as crate::storage::IterableStorageNMap<Key, Value>>::translate(f)
+		}
+		usage::translate::<Prefix, _, _, _>(
+			&Prefix::prefix_hash(),
+			|key_material| Key::decode_final_key(key_material).map(|(key, _)| key),
+			f,
+		)
 	}
 }
 
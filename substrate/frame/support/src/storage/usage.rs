@@ -0,0 +1,462 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic accounting of the storage used by pallets.
+//!
+//! A pallet annotated with `#[pallet::track_storage_usage]` sets
+//! [`StorageInstance::TRACK_STORAGE_USAGE`] for all of its storage items. The map storage types
+//! ([`StorageMap`](super::types::StorageMap), [`StorageDoubleMap`](super::types::StorageDoubleMap),
+//! [`StorageNMap`](super::types::StorageNMap) and their counted variants) then account for the
+//! bytes of every key and value they add to or remove from the state in a counter per pallet.
+//!
+//! The counter is stored under Twox128(pallet_prefix) ++ Twox128([`STORAGE_USAGE_KEY_POSTFIX`]).
+//! An optional quota can be stored next to it under Twox128(pallet_prefix) ++
+//! Twox128([`STORAGE_QUOTA_KEY_POSTFIX`]).
+//!
+//! The accounting is incremental: single entries are measured before and after being written, and
+//! operations on many entries, like `clear` or `translate`, account for the bytes of every entry
+//! they remove or rewrite. The state under a prefix is never scanned as a whole, except by
+//! [`initialize`] and the `try_state` hook.
+//!
+//! # Dispatchables
+//!
+//! Every dispatchable of a tracking pallet is executed with [`dispatch_within_quota`]:
+//! * A dispatchable growing the usage of a pallet beyond its quota fails with
+//!   [`DispatchError::Exhausted`] and all its storage changes are reverted. Shrinking the usage of
+//!   a pallet is always possible, even if the pallet exceeds its quota.
+//! * The weight of the dispatchable includes the overhead of accounting for
+//!   [`StorageUsageOverhead::of_changes`] entries, one by default, which is charged to the sender.
+//!   The database reads and writes done for the accounting beyond that are registered as extra
+//!   weight of the block.
+//!
+//! Code running outside of such a dispatchable, e.g. hooks or dispatchables of pallets not
+//! tracking their usage, is neither limited by the quota nor charged automatically. Such code can
+//! use [`metered`] to learn about the overhead to include in its weight, and [`exceeds_quota`] to
+//! check the quota.
+//!
+//! # Limitations
+//!
+//! Only the functions of the storage types are accounted. Removing items with
+//! [`PrefixIterator::drain`](super::PrefixIterator::drain) on an iterator returned by an `iter*`
+//! function, or writing to the state through [`unhashed`] or the generator traits, goes unnoticed.
+//! Such drift is reported by the `try_state` hook, which compares the counter with the bytes
+//! actually found in the state.
+
+use crate::{
+	dispatch::{DispatchResultWithPostInfo, PostDispatchInfo},
+	storage::{storage_prefix, unhashed, with_storage_layer, PrefixIteratorOnRemoval},
+	traits::{StorageInstance, StorageUsageTrait},
+	weights::{RuntimeDbWeight, Weight},
+};
+use codec::{Decode, Encode};
+use sp_io::MultiRemovalResults;
+use sp_runtime::{traits::Saturating, DispatchError};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The storage key postfix that is used to store the storage usage of a pallet.
+///
+/// The full storage key is built by using:
+/// Twox128(pallet_prefix) ++ Twox128([`STORAGE_USAGE_KEY_POSTFIX`])
+pub const STORAGE_USAGE_KEY_POSTFIX: &[u8] = b":__STORAGE_USAGE__:";
+
+/// The storage key postfix that is used to store the storage quota of a pallet.
+///
+/// The full storage key is built by using:
+/// Twox128(pallet_prefix) ++ Twox128([`STORAGE_QUOTA_KEY_POSTFIX`])
+pub const STORAGE_QUOTA_KEY_POSTFIX: &[u8] = b":__STORAGE_QUOTA__:";
+
+/// Returns the storage key of the storage usage of the pallet with the given prefix.
+pub fn storage_usage_key(pallet_prefix: &[u8]) -> [u8; 32] {
+	storage_prefix(pallet_prefix, STORAGE_USAGE_KEY_POSTFIX)
+}
+
+/// Returns the storage key of the storage quota of the pallet with the given prefix.
+pub fn storage_quota_key(pallet_prefix: &[u8]) -> [u8; 32] {
+	storage_prefix(pallet_prefix, STORAGE_QUOTA_KEY_POSTFIX)
+}
+
+/// The number of bytes accounted for the pallet with the given prefix.
+pub fn usage(pallet_prefix: &[u8]) -> u64 {
+	unhashed::get_or_default(&storage_usage_key(pallet_prefix))
+}
+
+/// Overwrite the number of bytes accounted for the pallet with the given prefix.
+///
+/// Only meant to initialize the counter when a pallet starts tracking its storage usage, see
+/// [`initialize`].
+pub fn set_usage(pallet_prefix: &[u8], bytes: u64) {
+	unhashed::put(&storage_usage_key(pallet_prefix), &bytes)
+}
+
+/// The maximum number of bytes the pallet with the given prefix is allowed to use, if any.
+pub fn quota(pallet_prefix: &[u8]) -> Option<u64> {
+	unhashed::get(&storage_quota_key(pallet_prefix))
+}
+
+/// Set or remove the maximum number of bytes the pallet with the given prefix is allowed to use.
+pub fn set_quota(pallet_prefix: &[u8], quota: Option<u64>) {
+	let key = storage_quota_key(pallet_prefix);
+	match quota {
+		Some(quota) => unhashed::put(&key, &quota),
+		None => unhashed::kill(&key),
+	}
+}
+
+/// Whether the pallet with the given prefix uses more bytes than its quota allows.
+pub fn exceeds_quota(pallet_prefix: &[u8]) -> bool {
+	quota(pallet_prefix).map_or(false, |quota| usage(pallet_prefix) > quota)
+}
+
+/// The database accesses done to account for the storage usage of pallets.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct StorageUsageOverhead {
+	/// The number of database reads.
+	pub reads: u64,
+	/// The number of database writes.
+	pub writes: u64,
+}
+
+impl StorageUsageOverhead {
+	/// Whether no database access was done.
+	pub fn is_zero(&self) -> bool {
+		self.reads == 0 && self.writes == 0
+	}
+
+	/// The most database accesses done to account for `changes` entries written one by one.
+	pub fn of_changes(changes: u64) -> Self {
+		Self { reads: changes.saturating_mul(4), writes: changes }
+	}
+
+	/// The database accesses done in addition to `other`.
+	pub fn saturating_sub(self, other: Self) -> Self {
+		Self {
+			reads: self.reads.saturating_sub(other.reads),
+			writes: self.writes.saturating_sub(other.writes),
+		}
+	}
+
+	/// The weight of the database accesses.
+	pub fn weight(&self, db_weight: RuntimeDbWeight) -> Weight {
+		db_weight.reads_writes(self.reads, self.writes)
+	}
+}
+
+#[derive(Default)]
+struct UsageMeter {
+	overhead: StorageUsageOverhead,
+	quota_exceeded: bool,
+}
+
+environmental::environmental!(USAGE_METER: UsageMeter);
+
+/// Call `f`, returning the database accesses done to account for the storage usage meanwhile.
+///
+/// Accesses done within nested calls to [`metered`] are only reported by the innermost call.
+pub fn metered<R>(f: impl FnOnce() -> R) -> (R, StorageUsageOverhead) {
+	let mut meter = UsageMeter::default();
+	let result = USAGE_METER::using(&mut meter, f);
+	(result, meter.overhead)
+}
+
+/// Call the dispatchable `f` in a new storage layer, failing it if it grows the storage usage of
+/// any pallet beyond its quota.
+///
+/// Returns the result of `f` together with the database accesses done to account for the storage
+/// usage, which the caller must charge.
+pub fn dispatch_within_quota<R, E: From<DispatchError>>(
+	f: impl FnOnce() -> Result<R, E>,
+) -> (Result<R, E>, StorageUsageOverhead) {
+	metered(|| {
+		with_storage_layer(|| {
+			let result = f()?;
+			if USAGE_METER::with(|meter| meter.quota_exceeded).unwrap_or(false) {
+				return Err(DispatchError::Exhausted.into())
+			}
+			Ok(result)
+		})
+	})
+}
+
+/// Add the weight of the accounting overhead declared by a dispatchable to the actual weight it
+/// reports, so that the overhead isn't refunded.
+pub fn with_declared_overhead(
+	result: DispatchResultWithPostInfo,
+	overhead: Weight,
+) -> DispatchResultWithPostInfo {
+	let add_overhead = |mut post_info: PostDispatchInfo| {
+		post_info.actual_weight = post_info.actual_weight.map(|w| w.saturating_add(overhead));
+		post_info
+	};
+	result.map(add_overhead).map_err(|mut err| {
+		err.post_info = add_overhead(err.post_info);
+		err
+	})
+}
+
+fn note_overhead(reads: u64, writes: u64) {
+	USAGE_METER::with(|meter| {
+		meter.overhead.reads.saturating_accrue(reads);
+		meter.overhead.writes.saturating_accrue(writes);
+	});
+}
+
+/// Initialize the counters of the given pallets with the bytes they currently use in the state.
+///
+/// Must be called, e.g. in a migration, when a pallet with existing storage starts tracking its
+/// storage usage.
+pub fn initialize<P: StorageUsageTrait>() {
+	for usage in P::computed_storage_usage() {
+		set_usage(&usage.pallet_name, usage.bytes);
+	}
+}
+
+/// The number of bytes the entry under `key` occupies in the state, counting both the key and
+/// the value.
+pub fn entry_size(key: &[u8]) -> u64 {
+	note_overhead(1, 0);
+	sp_io::storage::read(key, &mut [], 0)
+		.map_or(0, |len| (key.len() as u64).saturating_add(len.into()))
+}
+
+/// The number of bytes all entries under `prefix` occupy in the state, counting both keys and
+/// values.
+pub fn prefix_size(prefix: &[u8]) -> u64 {
+	let mut size = 0u64;
+	let mut previous_key = prefix.to_vec();
+	while let Some(key) =
+		sp_io::storage::next_key(&previous_key).filter(|key| key.starts_with(prefix))
+	{
+		size = size.saturating_add(entry_size(&key));
+		previous_key = key;
+	}
+	size
+}
+
+/// Account for entries of the pallet with the given prefix changing size from `before` to
+/// `after` bytes.
+///
+/// Growing the usage beyond the quota of the pallet fails the current dispatchable, see
+/// [`dispatch_within_quota`].
+pub fn note_change(pallet_prefix: &[u8], before: u64, after: u64) {
+	if before == after {
+		return
+	}
+
+	let key = storage_usage_key(pallet_prefix);
+	let usage: u64 = unhashed::get_or_default(&key);
+	let usage = if after > before {
+		let usage = usage.saturating_add(after - before);
+		if quota(pallet_prefix).map_or(false, |quota| usage > quota) {
+			log::debug!(
+				target: crate::LOG_TARGET,
+				"{}: storage usage of {} bytes exceeds the quota",
+				sp_std::str::from_utf8(pallet_prefix).unwrap_or("<invalid>"),
+				usage,
+			);
+			USAGE_METER::with(|meter| meter.quota_exceeded = true);
+		}
+		note_overhead(2, 1);
+		usage
+	} else {
+		note_overhead(1, 1);
+		usage.saturating_sub(before - after)
+	};
+	unhashed::put(&key, &usage);
+}
+
+/// Call `f` with `key`, accounting for the change in size of the entry under the final key
+/// returned by `hashed_key` if `Prefix` tracks its storage usage.
+pub(crate) fn track<Prefix, K, H, R>(
+	key: K,
+	hashed_key: impl FnOnce(&K) -> H,
+	f: impl FnOnce(K) -> R,
+) -> R
+where
+	Prefix: StorageInstance,
+	H: AsRef<[u8]>,
+{
+	if !Prefix::TRACK_STORAGE_USAGE {
+		return f(key)
+	}
+
+	let hashed_key = hashed_key(&key);
+	let before = entry_size(hashed_key.as_ref());
+	let result = f(key);
+	note_change(Prefix::pallet_prefix().as_bytes(), before, entry_size(hashed_key.as_ref()));
+	result
+}
+
+/// Remove up to `limit` entries under the prefix returned by `prefix`, accounting for the bytes of
+/// every removed entry if `Prefix` tracks its storage usage.
+///
+/// `f` does the removal if `Prefix` does not track its storage usage. Otherwise the entries are
+/// removed one by one, starting after `maybe_cursor`.
+pub(crate) fn clear_prefix<Prefix, P, R>(
+	prefix: impl FnOnce() -> P,
+	limit: Option<u32>,
+	maybe_cursor: Option<&[u8]>,
+	f: impl FnOnce() -> R,
+) -> R
+where
+	Prefix: StorageInstance,
+	P: AsRef<[u8]>,
+	R: From<MultiRemovalResults>,
+{
+	if !Prefix::TRACK_STORAGE_USAGE {
+		return f()
+	}
+
+	let prefix = prefix();
+	let prefix = prefix.as_ref();
+	let next_key = |previous_key: &[u8]| {
+		sp_io::storage::next_key(previous_key).filter(|key| key.starts_with(prefix))
+	};
+
+	let mut removed = 0u32;
+	let mut removed_bytes = 0u64;
+	let mut previous_key = maybe_cursor.unwrap_or(prefix).to_vec();
+	let mut maybe_next = next_key(&previous_key);
+	while let Some(key) = maybe_next.take() {
+		if limit.map_or(false, |limit| removed >= limit) {
+			maybe_next = Some(key);
+			break
+		}
+		removed_bytes = removed_bytes.saturating_add(entry_size(&key));
+		unhashed::kill(&key);
+		removed.saturating_inc();
+		maybe_next = next_key(&key);
+		previous_key = key;
+	}
+	note_change(Prefix::pallet_prefix().as_bytes(), removed_bytes, 0);
+
+	MultiRemovalResults {
+		maybe_cursor: maybe_next.map(|_| previous_key),
+		backend: removed,
+		unique: removed,
+		loops: removed,
+	}
+	.into()
+}
+
+/// Translate the entries under `prefix`, accounting for the bytes of every entry rewritten or
+/// removed by `f`.
+///
+/// `decode_key` decodes the key of an entry from its final key stripped of `prefix`. Entries whose
+/// key or value fail to decode are skipped.
+pub(crate) fn translate<Prefix, K, O, V>(
+	prefix: &[u8],
+	decode_key: impl Fn(&[u8]) -> Result<K, codec::Error>,
+	mut f: impl FnMut(K, O) -> Option<V>,
+) where
+	Prefix: StorageInstance,
+	O: Decode,
+	V: Encode,
+{
+	let mut before = 0u64;
+	let mut after = 0u64;
+	let mut previous_key = prefix.to_vec();
+	while let Some(key) =
+		sp_io::storage::next_key(&previous_key).filter(|key| key.starts_with(prefix))
+	{
+		previous_key = key;
+		let Some(raw_value) = unhashed::get_raw(&previous_key) else { continue };
+		let Ok(value) = O::decode(&mut &raw_value[..]) else {
+			log::error!("Invalid translate: fail to decode old value");
+			continue
+		};
+		let Ok(key) = decode_key(&previous_key[prefix.len()..]) else {
+			log::error!("Invalid translate: fail to decode key");
+			continue
+		};
+
+		let key_len = previous_key.len() as u64;
+		before = before.saturating_add(key_len.saturating_add(raw_value.len() as u64));
+		match f(key, value) {
+			Some(new) => {
+				after = after.saturating_add(key_len.saturating_add(new.encoded_size() as u64));
+				unhashed::put(&previous_key, &new)
+			},
+			None => unhashed::kill(&previous_key),
+		}
+	}
+	note_change(Prefix::pallet_prefix().as_bytes(), before, after);
+}
+
+/// On removal logic for accounting the storage usage while draining a map with
+/// [`crate::storage::PrefixIterator`].
+pub struct OnRemovalUsageUpdate<Prefix>(PhantomData<Prefix>);
+
+impl<Prefix: StorageInstance> PrefixIteratorOnRemoval for OnRemovalUsageUpdate<Prefix> {
+	fn on_removal(key: &[u8], value: &[u8]) {
+		if Prefix::TRACK_STORAGE_USAGE {
+			let size = (key.len() as u64).saturating_add(value.len() as u64);
+			note_change(Prefix::pallet_prefix().as_bytes(), size, 0);
+		}
+	}
+}
+
+/// Check that the counters of the given pallets match the bytes they use in the state and that
+/// no pallet exceeds its quota.
+#[cfg(feature = "try-runtime")]
+pub fn try_state<P: StorageUsageTrait>() -> Result<(), sp_runtime::TryRuntimeError> {
+	let accounted = P::storage_usage();
+	let computed = P::computed_storage_usage();
+
+	for (accounted, computed) in accounted.iter().zip(computed.iter()) {
+		let pallet_name = sp_std::str::from_utf8(&accounted.pallet_name).unwrap_or("<invalid>");
+
+		if accounted.bytes != computed.bytes {
+			log::error!(
+				target: crate::LOG_TARGET,
+				"{}: accounted storage usage of {} bytes does not match the {} bytes used",
+				pallet_name,
+				accounted.bytes,
+				computed.bytes,
+			);
+			return Err("Accounted storage usage does not match the storage used".into())
+		}
+
+		if let Some(quota) = accounted.quota {
+			if accounted.bytes > quota {
+				log::error!(
+					target: crate::LOG_TARGET,
+					"{}: storage usage of {} bytes exceeds the quota of {} bytes",
+					pallet_name,
+					accounted.bytes,
+					quota,
+				);
+				return Err("Storage quota exceeded".into())
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Runtime API for querying the storage usage of pallets.
+pub mod runtime_api {
+	use crate::traits::PalletStorageUsage;
+	use sp_std::vec::Vec;
+
+	sp_api::decl_runtime_apis! {
+		/// Runtime API for querying the storage usage of pallets.
+		pub trait StorageUsageApi {
+			/// The storage usage accounted by the pallets tracking it.
+			fn storage_usage() -> Vec<PalletStorageUsage>;
+		}
+	}
+}
@@ -85,14 +85,15 @@ mod hooks;
 pub use hooks::GenesisBuild;
 pub use hooks::{
 	BeforeAllRuntimeMigrations, BuildGenesisConfig, Hooks, IntegrityTest, OnFinalize, OnGenesis,
-	OnIdle, OnInitialize, OnRuntimeUpgrade, OnTimestampSet,
+	OnIdle, OnInitialize, OnPoll, OnPollPallets, OnRuntimeUpgrade, OnTimestampSet,
 };
 
 pub mod schedule;
 mod storage;
 pub use storage::{
-	Consideration, Footprint, Incrementable, Instance, LinearStoragePrice, PartialStorageInfoTrait,
-	StorageInfo, StorageInfoTrait, StorageInstance, TrackedStorageKey, WhitelistedStorageKeys,
+	Consideration, Footprint, Incrementable, Instance, LinearStoragePrice, PalletStorageUsage,
+	PartialStorageInfoTrait, StorageInfo, StorageInfoTrait, StorageInstance, StorageUsageTrait,
+	TrackedStorageKey, WhitelistedStorageKeys,
 };
 
 mod dispatch;
@@ -123,10 +124,13 @@ pub use safe_mode::{SafeMode, SafeModeError, SafeModeNotify};
 mod tx_pause;
 pub use tx_pause::{TransactionPause, TransactionPauseError};
 
+mod tasks;
+pub use tasks::Task;
+
 #[cfg(feature = "try-runtime")]
 mod try_runtime;
 #[cfg(feature = "try-runtime")]
 pub use try_runtime::{
-	Select as TryStateSelect, TryDecodeEntireStorage, TryDecodeEntireStorageError, TryState,
-	UpgradeCheckSelect,
+	BlockHook, Select as TryStateSelect, TryDecodeEntireStorage, TryDecodeEntireStorageError,
+	TryState, UpgradeCheckSelect,
 };
@@ -21,7 +21,10 @@
 
 #![deny(missing_docs)]
 
-use crate::weights::Weight;
+use crate::{
+	traits::PalletInfoAccess,
+	weights::{Weight, WeightMeter},
+};
 use impl_trait_for_tuples::impl_for_tuples;
 use sp_runtime::traits::AtLeast32BitUnsigned;
 use sp_std::prelude::*;
@@ -86,6 +89,67 @@ impl<BlockNumber: Copy + AtLeast32BitUnsigned> OnIdle<BlockNumber> for Tuple {
 	}
 }
 
+/// See [`Hooks::on_poll`].
+pub trait OnPoll<BlockNumber> {
+	/// See [`Hooks::on_poll`].
+	fn on_poll(_n: BlockNumber, _weight: &mut WeightMeter) {}
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl<BlockNumber: Clone> OnPoll<BlockNumber> for Tuple {
+	fn on_poll(n: BlockNumber, weight: &mut WeightMeter) {
+		for_tuples!( #( Tuple::on_poll(n.clone(), weight); )* );
+	}
+}
+
+/// A set of pallets whose [`OnPoll`] hooks can be called one pallet at a time.
+///
+/// This is implemented for tuples of pallets and is used by `frame-executive` to split the poll
+/// budget of a block fairly between all pallets, instead of calling them all with one shared
+/// [`WeightMeter`] like the [`OnPoll`] implementation for tuples does.
+pub trait OnPollPallets<BlockNumber> {
+	/// The number of pallets in the set.
+	fn pallet_count() -> u32;
+
+	/// The index of the pallet at `position` in the set, as given in
+	/// [`crate::construct_runtime`].
+	///
+	/// Returns `None` if `position` is out of bounds.
+	fn pallet_index(position: u32) -> Option<u32>;
+
+	/// Call [`OnPoll::on_poll`] of the pallet at `position` in the set.
+	///
+	/// Does nothing if `position` is out of bounds.
+	fn on_poll_pallet(position: u32, n: BlockNumber, weight: &mut WeightMeter);
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl<BlockNumber> OnPollPallets<BlockNumber> for Tuple {
+	for_tuples!( where #( Tuple: OnPoll<BlockNumber> + PalletInfoAccess )* );
+
+	fn pallet_count() -> u32 {
+		let index_functions: &[fn() -> usize] = &[for_tuples!( #( Tuple::index ),* )];
+		index_functions.len() as u32
+	}
+
+	fn pallet_index(position: u32) -> Option<u32> {
+		let index_functions: &[fn() -> usize] = &[for_tuples!( #( Tuple::index ),* )];
+		index_functions.get(position as usize).map(|index| index() as u32)
+	}
+
+	fn on_poll_pallet(position: u32, n: BlockNumber, weight: &mut WeightMeter) {
+		let on_poll_functions: &[fn(BlockNumber, &mut WeightMeter)] =
+			&[for_tuples!( #( Tuple::on_poll ),* )];
+		if let Some(on_poll) = on_poll_functions.get(position as usize) {
+			on_poll(n, weight);
+		}
+	}
+}
+
 /// A trait that will be called at genesis.
 ///
 /// Implementing this trait for a pallet let's you express operations that should
@@ -293,8 +357,11 @@ pub trait IntegrityTest {
 /// 	Extrinsic1
 /// 	Extrinsic2
 ///
+/// 	OnPoll
+///
 /// 	Inherent1 --> Inherent2
-/// 	Inherent2 --> Extrinsic1
+/// 	Inherent2 --> OnPoll
+/// 	OnPoll --> Extrinsic1
 /// 	Extrinsic1 --> Extrinsic2
 /// end
 ///
@@ -310,6 +377,7 @@ pub trait IntegrityTest {
 /// * `OnRuntimeUpgrade` is mandatorily at the beginning of the block body (extrinsics) being
 ///   processed. change is detected.
 /// * Extrinsics start with inherents, and continue with other signed or unsigned extrinsics.
+/// * `OnPoll` comes after the inherents and before any other extrinsic.
 /// * `OnIdle` optionally comes after extrinsics.
 /// `OnFinalize` mandatorily comes after `OnIdle`.
 ///
@@ -370,6 +438,25 @@ pub trait Hooks<BlockNumber> {
 		Weight::zero()
 	}
 
+	/// Hook to perform deferrable but regular work. This is called after all inherents of a block
+	/// are applied and before the first transaction.
+	///
+	/// Unlike [`Hooks::on_initialize`], nothing needs to be reserved upfront: the work is bounded
+	/// by `weight`, which holds this pallet's share of the weight that is left in the block.
+	/// Unlike [`Hooks::on_idle`], it runs before transactions and can therefore not be starved by
+	/// them.
+	///
+	/// `frame-executive` splits the budget between all pallets and rotates the pallet that is
+	/// polled first with every block. Weight that a pallet leaves unused is passed on to the
+	/// pallets polled after it.
+	///
+	/// Any implementation must consume all the weight it uses from `weight` and must not do any
+	/// work that does not fit into it. The consumed weight is accounted as
+	/// `DispatchClass::Mandatory`.
+	///
+	/// NOTE: This hook is not called while multi-block migrations are ongoing.
+	fn on_poll(_n: BlockNumber, _weight: &mut WeightMeter) {}
+
 	/// Hook executed when a code change (aka. a "runtime upgrade") is detected by FRAME.
 	///
 	/// Be aware that this is called before [`Hooks::on_initialize`] of any pallet; therefore, a lot
@@ -651,4 +738,65 @@ mod tests {
 			ON_IDLE_INVOCATION_ORDER.clear();
 		}
 	}
+
+	#[test]
+	fn on_poll_pallets_works() {
+		crate::parameter_types! {
+			pub static Polled: Vec<(u32, Weight)> = Default::default();
+		}
+
+		macro_rules! impl_test_type {
+			($name:ident, $index:literal) => {
+				struct $name;
+				impl OnPoll<u32> for $name {
+					fn on_poll(_n: u32, weight: &mut WeightMeter) {
+						Polled::mutate(|p| p.push(($index, weight.limit())));
+						let _ = weight.try_consume(Weight::from_parts($index, 0));
+					}
+				}
+				impl PalletInfoAccess for $name {
+					fn index() -> usize {
+						$index
+					}
+					fn name() -> &'static str {
+						stringify!($name)
+					}
+					fn name_hash() -> [u8; 16] {
+						Default::default()
+					}
+					fn module_name() -> &'static str {
+						stringify!($name)
+					}
+					fn crate_version() -> crate::traits::CrateVersion {
+						Default::default()
+					}
+				}
+			};
+		}
+
+		impl_test_type!(Foo, 7);
+		impl_test_type!(Bar, 3);
+		type TestTuple = (Foo, Bar);
+
+		assert_eq!(<TestTuple as OnPollPallets<u32>>::pallet_count(), 2);
+		assert_eq!(<TestTuple as OnPollPallets<u32>>::pallet_index(0), Some(7));
+		assert_eq!(<TestTuple as OnPollPallets<u32>>::pallet_index(1), Some(3));
+		assert_eq!(<TestTuple as OnPollPallets<u32>>::pallet_index(2), None);
+		assert_eq!(<() as OnPollPallets<u32>>::pallet_count(), 0);
+
+		let mut meter = WeightMeter::with_limit(Weight::from_parts(5, 0));
+		<TestTuple as OnPollPallets<u32>>::on_poll_pallet(1, 0, &mut meter);
+		<TestTuple as OnPollPallets<u32>>::on_poll_pallet(2, 0, &mut meter);
+		assert_eq!(Polled::take(), vec![(3, Weight::from_parts(5, 0))]);
+		assert_eq!(meter.consumed(), Weight::from_parts(3, 0));
+
+		// The tuple implementation of `OnPoll` polls all pallets with the same meter.
+		let mut meter = WeightMeter::with_limit(Weight::from_parts(12, 0));
+		<TestTuple as OnPoll<u32>>::on_poll(0, &mut meter);
+		assert_eq!(
+			Polled::take(),
+			vec![(7, Weight::from_parts(12, 0)), (3, Weight::from_parts(12, 0))]
+		);
+		assert_eq!(meter.consumed(), Weight::from_parts(10, 0));
+	}
 }
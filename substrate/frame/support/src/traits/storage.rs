@@ -72,6 +72,11 @@ pub trait StorageInstance {
 	/// Prefix given to a storage to isolate from other storages in the pallet.
 	const STORAGE_PREFIX: &'static str;
 
+	/// Whether the storage accounts for the bytes it uses in the storage usage of the pallet.
+	///
+	/// Only honoured by the map storage types. See [`crate::storage::usage`] for details.
+	const TRACK_STORAGE_USAGE: bool = false;
+
 	/// Return the prefix hash of storage instance.
 	///
 	/// NOTE: This hash must be `twox_128(STORAGE_PREFIX)`.
@@ -125,6 +130,48 @@ impl StorageInfoTrait for Tuple {
 	}
 }
 
+/// The storage usage of a pallet, as accounted by [`crate::storage::usage`].
+#[derive(Debug, codec::Encode, codec::Decode, Eq, PartialEq, Clone, scale_info::TypeInfo)]
+pub struct PalletStorageUsage {
+	/// Encoded string of pallet name.
+	pub pallet_name: Vec<u8>,
+	/// The number of bytes, counting both keys and values, used by the storage of the pallet.
+	pub bytes: u64,
+	/// The maximum number of bytes the pallet is allowed to use, or none if no quota is set.
+	pub quota: Option<u64>,
+}
+
+/// A trait to give the storage usage of pallets.
+///
+/// Only pallets annotated with `#[pallet::track_storage_usage]` report their usage.
+pub trait StorageUsageTrait {
+	/// The storage usage accounted by the pallets.
+	fn storage_usage() -> Vec<PalletStorageUsage>;
+
+	/// The storage usage of the pallets computed by iterating their tracked storage items in the
+	/// state.
+	///
+	/// This is expensive and only meant for checks and migrations.
+	fn computed_storage_usage() -> Vec<PalletStorageUsage>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl StorageUsageTrait for Tuple {
+	fn storage_usage() -> Vec<PalletStorageUsage> {
+		let mut res = vec![];
+		for_tuples!( #( res.extend(Tuple::storage_usage()); )* );
+		res
+	}
+
+	fn computed_storage_usage() -> Vec<PalletStorageUsage> {
+		let mut res = vec![];
+		for_tuples!( #( res.extend(Tuple::computed_storage_usage()); )* );
+		res
+	}
+}
+
 /// Similar to [`StorageInfoTrait`], a trait to give partial information about storage.
 ///
 /// This is useful when a type can give some partial information with its generic parameter doesn't
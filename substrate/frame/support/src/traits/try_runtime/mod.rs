@@ -139,6 +139,28 @@ impl core::str::FromStr for UpgradeCheckSelect {
 	}
 }
 
+/// A part of the execution of a block that runs the hooks of the pallets.
+///
+/// Executing all of them in order, with the extrinsics of the block applied between
+/// [`BlockHook::OnInitialize`] and [`BlockHook::OnPoll`], executes the block.
+#[derive(codec::Encode, codec::Decode, Clone, Debug, Copy, PartialEq, Eq, scale_info::TypeInfo)]
+pub enum BlockHook {
+	/// Run `on_runtime_upgrade` if the runtime was upgraded and initialize `frame_system` for the
+	/// block.
+	OnRuntimeUpgrade,
+	/// Run `on_initialize`.
+	OnInitialize,
+	/// Note that the inherents were applied and step the multi-block migrations if they are
+	/// ongoing, or run `on_poll` otherwise.
+	///
+	/// Does nothing if an extrinsic that is not an inherent was applied before.
+	OnPoll,
+	/// Run `on_idle` with the remaining weight of the block.
+	OnIdle,
+	/// Run `on_finalize`.
+	OnFinalize,
+}
+
 /// Execute some checks to ensure the internal state of a pallet is consistent.
 ///
 /// Usually, these checks should check all of the invariants that are expected to be held on all of
@@ -0,0 +1,143 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits and types for runtime view functions.
+//!
+//! View functions are read-only queries declared by pallets inside a
+//! `#[pallet::view_functions]` impl block. The pallet macro generates one type per function
+//! implementing [`ViewFunction`] and an implementation of [`DispatchViewFunction`] for the pallet.
+//! `construct_runtime!` aggregates the latter into `RuntimeViewFunction`, which can be exposed
+//! to clients through the [`runtime_api::RuntimeViewFunction`] runtime API.
+
+use codec::{Decode, DecodeAll, Encode, MaxEncodedLen, Output};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// The unique identifier of a view function.
+///
+/// The `prefix` identifies the pallet and is the `twox_128` hash of the pallet name in the
+/// runtime. The `suffix` identifies the function within the pallet and is the `twox_128` hash
+/// of the function signature.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct ViewFunctionId {
+	/// The part of the id identifying the pallet.
+	pub prefix: [u8; 16],
+	/// The part of the id identifying the function within the pallet.
+	pub suffix: [u8; 16],
+}
+
+impl From<ViewFunctionId> for [u8; 32] {
+	fn from(value: ViewFunctionId) -> Self {
+		let mut output = [0u8; 32];
+		output[..16].copy_from_slice(&value.prefix);
+		output[16..].copy_from_slice(&value.suffix);
+		output
+	}
+}
+
+/// Error returned when dispatching a view function fails.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub enum ViewFunctionDispatchError {
+	/// No view function with the given id is known.
+	NotFound(ViewFunctionId),
+	/// The input could not be decoded into the arguments of the view function.
+	Codec,
+}
+
+impl From<codec::Error> for ViewFunctionDispatchError {
+	fn from(_: codec::Error) -> Self {
+		ViewFunctionDispatchError::Codec
+	}
+}
+
+/// Provides the prefix of the [`ViewFunctionId`] of all view functions of a pallet.
+pub trait ViewFunctionIdPrefix {
+	/// The prefix shared by all view functions of this pallet.
+	fn prefix() -> [u8; 16];
+}
+
+/// Provides the suffix of the [`ViewFunctionId`] of a single view function.
+pub trait ViewFunctionIdSuffix {
+	/// The suffix identifying this view function within its pallet.
+	const SUFFIX: [u8; 16];
+}
+
+/// A single view function, holding its arguments.
+pub trait ViewFunction: DecodeAll {
+	/// The type returned by the view function.
+	type ReturnType: Encode;
+
+	/// The unique identifier of this view function.
+	fn id() -> ViewFunctionId;
+
+	/// Call the view function with the arguments held by `self`.
+	fn invoke(self) -> Self::ReturnType;
+
+	/// Decode the arguments from `input`, call the view function and encode its result into
+	/// `output`.
+	fn execute<O: Output>(
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		let view_function = Self::decode_all(input)?;
+		let result = view_function.invoke();
+		Encode::encode_to(&result, output);
+		Ok(())
+	}
+}
+
+/// Dispatch a view function by its [`ViewFunctionId`].
+///
+/// Implemented by the pallet macro for every pallet and by `construct_runtime!` for
+/// `RuntimeViewFunction`.
+pub trait DispatchViewFunction {
+	/// Dispatch the view function identified by `id`, decoding its arguments from `input` and
+	/// encoding its result into `output`.
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError>;
+}
+
+impl DispatchViewFunction for () {
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		_input: &mut &[u8],
+		_output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		Err(ViewFunctionDispatchError::NotFound(*id))
+	}
+}
+
+/// Runtime API for executing view functions.
+pub mod runtime_api {
+	use super::*;
+
+	sp_api::decl_runtime_apis! {
+		/// Runtime API for executing view functions.
+		pub trait RuntimeViewFunction {
+			/// Execute the view function identified by `id` with the SCALE encoded arguments
+			/// `input`, returning the SCALE encoded result.
+			fn execute_view_function(
+				id: ViewFunctionId,
+				input: Vec<u8>,
+			) -> Result<Vec<u8>, ViewFunctionDispatchError>;
+		}
+	}
+}
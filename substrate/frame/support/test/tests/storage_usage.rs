@@ -0,0 +1,425 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	assert_err, assert_ok,
+	dispatch::{DispatchClass, GetDispatchInfo},
+	pallet_prelude::{ConstU32, Weight},
+	parameter_types,
+	storage::usage::{self, StorageUsageOverhead},
+	traits::{PalletStorageUsage, StorageUsageTrait, UnfilteredDispatchable},
+	weights::RuntimeDbWeight,
+};
+use sp_io::TestExternalities;
+use sp_runtime::DispatchError;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::track_storage_usage]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::storage]
+	pub type Value<T> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type Map<T> = StorageMap<_, Twox64Concat, u32, Vec<u8>>;
+
+	#[pallet::storage]
+	pub type DoubleMap<T> = StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, u32, u32>;
+
+	#[pallet::storage]
+	pub type NMap<T> = StorageNMap<
+		_,
+		(NMapKey<Twox64Concat, u32>, NMapKey<Twox64Concat, u32>, NMapKey<Twox64Concat, u32>),
+		u32,
+	>;
+
+	#[pallet::storage]
+	pub type CountedMap<T> = CountedStorageMap<_, Twox64Concat, u32, u32>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(Weight::zero())]
+		pub fn insert(_origin: OriginFor<T>, key: u32, value: Vec<u8>) -> DispatchResult {
+			Map::<T>::insert(key, value);
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(Weight::zero())]
+		pub fn remove(_origin: OriginFor<T>, key: u32) -> DispatchResultWithPostInfo {
+			Map::<T>::remove(key);
+			Ok(Some(Weight::zero()).into())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(Weight::zero())]
+		pub fn insert_many(_origin: OriginFor<T>, keys: Vec<u32>) -> DispatchResult {
+			for key in keys {
+				Map::<T>::insert(key, Vec::<u8>::new());
+			}
+			Ok(())
+		}
+	}
+}
+
+#[frame_support::pallet]
+pub mod untracked {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::storage]
+	pub type Map<T> = StorageMap<_, Twox64Concat, u32, u32>;
+}
+
+pub type BlockNumber = u32;
+pub type Nonce = u64;
+pub type AccountId = u64;
+pub type Header = sp_runtime::generic::Header<BlockNumber, sp_runtime::traits::BlakeTwo256>;
+pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, RuntimeCall, (), ()>;
+pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+
+parameter_types! {
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
+	type Nonce = Nonce;
+	type Hash = sp_runtime::testing::H256;
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU32<250>;
+	type DbWeight = DbWeight;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet::Config for Runtime {}
+impl untracked::Config for Runtime {}
+
+frame_support::construct_runtime!(
+	pub struct Runtime {
+		System: frame_system,
+		Tracked: pallet,
+		Untracked: untracked,
+	}
+);
+
+type Tracked = pallet::Pallet<Runtime>;
+
+/// The size of a key of a map with a single `Twox64Concat` hashed `u32` key.
+const MAP_KEY_LEN: u64 = 32 + 8 + 4;
+
+fn accounted() -> u64 {
+	usage::usage(b"Tracked")
+}
+
+fn assert_accounted_matches_computed() {
+	let accounted = <Tracked as StorageUsageTrait>::storage_usage();
+	let computed = <Tracked as StorageUsageTrait>::computed_storage_usage();
+	assert_eq!(accounted[0].bytes, computed[0].bytes);
+}
+
+#[test]
+fn map_operations_are_accounted() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(accounted(), 0);
+
+		pallet::Map::<Runtime>::insert(1, vec![1u8, 2, 3]);
+		// The value is encoded with a one byte length prefix.
+		assert_eq!(accounted(), MAP_KEY_LEN + 4);
+
+		pallet::Map::<Runtime>::append(1, 4u8);
+		assert_eq!(accounted(), MAP_KEY_LEN + 5);
+
+		pallet::Map::<Runtime>::mutate(2, |value| *value = Some(vec![]));
+		assert_eq!(accounted(), 2 * MAP_KEY_LEN + 6);
+
+		pallet::Map::<Runtime>::swap(1, 3);
+		assert_eq!(accounted(), 2 * MAP_KEY_LEN + 6);
+		assert_accounted_matches_computed();
+
+		assert_eq!(pallet::Map::<Runtime>::take(3), Some(vec![1, 2, 3, 4]));
+		assert_eq!(accounted(), MAP_KEY_LEN + 1);
+
+		pallet::Map::<Runtime>::remove(2);
+		assert_eq!(accounted(), 0);
+
+		// Removing a non existing item is free.
+		pallet::Map::<Runtime>::remove(2);
+		assert_eq!(accounted(), 0);
+	});
+}
+
+#[test]
+fn prefix_operations_are_accounted() {
+	TestExternalities::default().execute_with(|| {
+		for i in 0..3 {
+			pallet::DoubleMap::<Runtime>::insert(i, i, i);
+			pallet::DoubleMap::<Runtime>::insert(i, i + 1, i);
+			pallet::NMap::<Runtime>::insert((i, i, i), i);
+			pallet::NMap::<Runtime>::insert((i, i + 1, i), i);
+		}
+		assert_accounted_matches_computed();
+
+		let _ = pallet::DoubleMap::<Runtime>::clear_prefix(0, u32::max_value(), None);
+		assert_accounted_matches_computed();
+
+		let _ = pallet::NMap::<Runtime>::clear_prefix((1u32,), u32::max_value(), None);
+		assert_accounted_matches_computed();
+
+		assert_eq!(pallet::DoubleMap::<Runtime>::drain_prefix(1).count(), 2);
+		assert_accounted_matches_computed();
+
+		pallet::NMap::<Runtime>::translate_values::<u32, _>(|_| None);
+		assert_accounted_matches_computed();
+
+		let _ = pallet::DoubleMap::<Runtime>::clear(u32::max_value(), None);
+		assert_eq!(accounted(), 0);
+	});
+}
+
+#[test]
+fn clear_accounts_removed_entries_and_resumes_from_cursor() {
+	TestExternalities::default().execute_with(|| {
+		for i in 0..5 {
+			pallet::Map::<Runtime>::insert(i, vec![1u8]);
+		}
+		untracked::Map::<Runtime>::insert(0, 0);
+		assert_eq!(accounted(), 5 * (MAP_KEY_LEN + 2));
+
+		let result = pallet::Map::<Runtime>::clear(2, None);
+		assert_eq!(result.backend, 2);
+		assert_eq!(accounted(), 3 * (MAP_KEY_LEN + 2));
+		assert_accounted_matches_computed();
+
+		let result = pallet::Map::<Runtime>::clear(2, result.maybe_cursor.as_deref());
+		assert_eq!(result.backend, 2);
+		assert!(result.maybe_cursor.is_some());
+		assert_eq!(accounted(), MAP_KEY_LEN + 2);
+
+		let result = pallet::Map::<Runtime>::clear(2, result.maybe_cursor.as_deref());
+		assert_eq!(result.backend, 1);
+		assert_eq!(result.maybe_cursor, None);
+		assert_eq!(accounted(), 0);
+		assert_eq!(pallet::Map::<Runtime>::iter().count(), 0);
+		assert_eq!(untracked::Map::<Runtime>::get(0), Some(0));
+	});
+}
+
+#[test]
+fn translate_accounts_rewritten_entries() {
+	TestExternalities::default().execute_with(|| {
+		pallet::Map::<Runtime>::insert(1, vec![1u8]);
+		pallet::Map::<Runtime>::insert(2, vec![2u8]);
+		pallet::DoubleMap::<Runtime>::insert(1, 2, 3);
+
+		pallet::Map::<Runtime>::translate::<Vec<u8>, _>(|key, value| {
+			(key == 1).then(|| [value, vec![0; 10]].concat())
+		});
+		assert_eq!(pallet::Map::<Runtime>::get(1), Some(vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+		assert_eq!(pallet::Map::<Runtime>::get(2), None);
+		assert_accounted_matches_computed();
+
+		pallet::DoubleMap::<Runtime>::translate::<u32, _>(|key1, key2, value| {
+			Some(key1 + key2 + value)
+		});
+		assert_eq!(pallet::DoubleMap::<Runtime>::get(1, 2), Some(6));
+		assert_accounted_matches_computed();
+
+		pallet::Map::<Runtime>::translate_values::<Vec<u8>, _>(|_| Some(vec![]));
+		assert_eq!(accounted(), MAP_KEY_LEN + 1 + (MAP_KEY_LEN + 8 + 4) + 4);
+		assert_accounted_matches_computed();
+	});
+}
+
+#[test]
+fn accounting_overhead_is_metered() {
+	TestExternalities::default().execute_with(|| {
+		// The entry is measured before and after the write, the counter and the quota are read and
+		// the counter is written.
+		let ((), overhead) = usage::metered(|| pallet::Map::<Runtime>::insert(1, vec![1u8]));
+		assert_eq!(overhead, StorageUsageOverhead { reads: 4, writes: 1 });
+
+		// The quota is not read when shrinking.
+		let ((), overhead) = usage::metered(|| pallet::Map::<Runtime>::remove(1));
+		assert_eq!(overhead, StorageUsageOverhead { reads: 3, writes: 1 });
+
+		let ((), overhead) = usage::metered(|| untracked::Map::<Runtime>::insert(1, 1));
+		assert!(overhead.is_zero());
+	});
+}
+
+#[test]
+fn dispatchables_are_charged_for_the_accounting_overhead() {
+	TestExternalities::default().execute_with(|| {
+		// The overhead of accounting for one change is part of the weight of every dispatchable.
+		let declared = Weight::from_parts(4 * 10 + 100, 0);
+		let call = RuntimeCall::Tracked(pallet::Call::remove { key: 1 });
+		assert_eq!(call.get_dispatch_info().weight, declared);
+
+		// And it is not refunded.
+		let post_info = call.dispatch_bypass_filter(RuntimeOrigin::signed(1)).unwrap();
+		assert_eq!(post_info.actual_weight, Some(declared));
+
+		// Only the overhead beyond the declared one is charged to the block.
+		assert_ok!(Tracked::insert(RuntimeOrigin::signed(1), 1, vec![1u8]));
+		assert_eq!(*System::block_weight().get(DispatchClass::Normal), Weight::zero());
+
+		assert_ok!(Tracked::insert_many(RuntimeOrigin::signed(1), vec![2, 3, 4]));
+		assert_eq!(
+			*System::block_weight().get(DispatchClass::Normal),
+			Weight::from_parts(2 * (4 * 10 + 100), 0),
+		);
+	});
+}
+
+#[test]
+fn quota_is_enforced_on_dispatch() {
+	TestExternalities::default().execute_with(|| {
+		usage::set_quota(b"Tracked", Some(MAP_KEY_LEN + 4));
+
+		// Reaching the quota is fine.
+		assert_ok!(Tracked::insert(RuntimeOrigin::signed(1), 1, vec![1u8, 2, 3]));
+		assert_eq!(accounted(), MAP_KEY_LEN + 4);
+
+		// Exceeding it fails and reverts the changes.
+		assert_err!(Tracked::insert(RuntimeOrigin::signed(1), 2, vec![]), DispatchError::Exhausted);
+		assert_eq!(pallet::Map::<Runtime>::get(2), None);
+		assert_eq!(accounted(), MAP_KEY_LEN + 4);
+
+		// Shrinking is possible even if the quota is exceeded.
+		usage::set_quota(b"Tracked", Some(1));
+		assert_ok!(Tracked::remove(RuntimeOrigin::signed(1), 1));
+		assert_eq!(accounted(), 0);
+
+		// The quota is not enforced outside of dispatchables.
+		pallet::Map::<Runtime>::insert(2, vec![]);
+		assert!(usage::exceeds_quota(b"Tracked"));
+	});
+}
+
+#[test]
+fn counted_map_operations_are_accounted() {
+	TestExternalities::default().execute_with(|| {
+		pallet::CountedMap::<Runtime>::insert(1, 1);
+		pallet::CountedMap::<Runtime>::insert(2, 2);
+		assert_eq!(accounted(), 2 * (MAP_KEY_LEN + 4));
+
+		assert_eq!(pallet::CountedMap::<Runtime>::drain().count(), 2);
+		assert_eq!(pallet::CountedMap::<Runtime>::count(), 0);
+		assert_eq!(accounted(), 0);
+	});
+}
+
+#[test]
+fn values_and_untracked_pallets_are_not_accounted() {
+	TestExternalities::default().execute_with(|| {
+		pallet::Value::<Runtime>::put(1);
+		untracked::Map::<Runtime>::insert(1, 1);
+
+		assert_eq!(accounted(), 0);
+		assert_eq!(usage::usage(b"Untracked"), 0);
+		assert!(<untracked::Pallet<Runtime> as StorageUsageTrait>::storage_usage().is_empty());
+	});
+}
+
+#[test]
+fn storage_usage_is_reported_with_quota() {
+	TestExternalities::default().execute_with(|| {
+		pallet::CountedMap::<Runtime>::insert(1, 1);
+		usage::set_quota(b"Tracked", Some(MAP_KEY_LEN));
+
+		assert_eq!(
+			<AllPalletsWithSystem as StorageUsageTrait>::storage_usage(),
+			vec![PalletStorageUsage {
+				pallet_name: b"Tracked".to_vec(),
+				bytes: MAP_KEY_LEN + 4,
+				quota: Some(MAP_KEY_LEN),
+			}],
+		);
+		assert!(usage::exceeds_quota(b"Tracked"));
+	});
+}
+
+#[test]
+fn initialize_sets_usage_from_state() {
+	TestExternalities::default().execute_with(|| {
+		pallet::CountedMap::<Runtime>::insert(1, 1);
+		frame_support::storage::unhashed::kill(&usage::storage_usage_key(b"Tracked"));
+		assert_eq!(accounted(), 0);
+
+		usage::initialize::<AllPalletsWithSystem>();
+		assert_eq!(accounted(), MAP_KEY_LEN + 4);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_detects_drift_and_exceeded_quota() {
+	use frame_support::traits::{TryState, TryStateSelect};
+
+	TestExternalities::default().execute_with(|| {
+		pallet::Map::<Runtime>::insert(1, vec![1]);
+		assert!(Tracked::try_state(0, TryStateSelect::All).is_ok());
+
+		// Writes bypassing the storage types are not accounted.
+		frame_support::storage::unhashed::put(&pallet::Map::<Runtime>::hashed_key_for(2), &[0u8]);
+		assert_eq!(
+			Tracked::try_state(0, TryStateSelect::All),
+			Err("Accounted storage usage does not match the storage used".into()),
+		);
+
+		usage::initialize::<Tracked>();
+		assert!(Tracked::try_state(0, TryStateSelect::All).is_ok());
+
+		usage::set_quota(b"Tracked", Some(1));
+		assert_eq!(
+			Tracked::try_state(0, TryStateSelect::All),
+			Err("Storage quota exceeded".into()),
+		);
+	});
+}
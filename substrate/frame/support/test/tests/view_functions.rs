@@ -0,0 +1,308 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	pallet_prelude::ConstU32,
+	view_functions::{
+		DispatchViewFunction, ViewFunction, ViewFunctionDispatchError, ViewFunctionId,
+		ViewFunctionIdPrefix,
+	},
+};
+use sp_io::{hashing::twox_128, TestExternalities};
+use sp_metadata_ir::{PalletViewFunctionMetadataIR, PalletViewFunctionParamMetadataIR};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::storage]
+	pub type SomeValue<T> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type SomeMap<T> = StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// Query the value of `SomeValue`.
+		pub fn get_value() -> Option<u32> {
+			SomeValue::<T>::get()
+		}
+
+		/// Query the value of `SomeMap` under `key`.
+		pub fn get_value_with_arg(key: u32) -> Option<u32> {
+			SomeMap::<T>::get(key)
+		}
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet2 {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {}
+
+	#[pallet::storage]
+	pub type SomeValue<T: Config<I>, I: 'static = ()> = StorageValue<_, u32>;
+
+	#[pallet::view_functions]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Query the value of `SomeValue` plus `add`.
+		pub fn get_value_plus(add: u32) -> Option<u32> {
+			SomeValue::<T, I>::get().map(|value| value.saturating_add(add))
+		}
+	}
+}
+
+pub type BlockNumber = u32;
+pub type Nonce = u64;
+pub type AccountId = u64;
+pub type Header = sp_runtime::generic::Header<BlockNumber, sp_runtime::traits::BlakeTwo256>;
+pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, RuntimeCall, (), ()>;
+pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
+	type Nonce = Nonce;
+	type Hash = sp_runtime::testing::H256;
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU32<250>;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet::Config for Runtime {}
+impl pallet2::Config for Runtime {}
+impl pallet2::Config<pallet2::Instance1> for Runtime {}
+
+frame_support::construct_runtime!(
+	pub struct Runtime {
+		System: frame_system,
+		ViewFunctionsExample: pallet,
+		ViewFunctionsInstance: pallet2,
+		ViewFunctionsInstance1: pallet2<Instance1>,
+	}
+);
+
+fn execute<V: ViewFunction>(view_function: V) -> Result<V::ReturnType, ViewFunctionDispatchError>
+where
+	V::ReturnType: Decode,
+{
+	let mut output = Vec::new();
+	RuntimeViewFunction::dispatch_view_function(
+		&V::id(),
+		&mut &view_function.encode()[..],
+		&mut output,
+	)?;
+	Ok(V::ReturnType::decode(&mut &output[..]).expect("output is a valid return type; qed"))
+}
+
+#[test]
+fn view_function_id_is_pallet_name_and_signature() {
+	assert_eq!(
+		pallet::GetValue::<Runtime>::id(),
+		ViewFunctionId {
+			prefix: twox_128(b"ViewFunctionsExample"),
+			suffix: twox_128(b"get_value()->Option<u32>"),
+		},
+	);
+	assert_eq!(
+		pallet::GetValueWithArg::<Runtime>::id().suffix,
+		twox_128(b"get_value_with_arg(u32)->Option<u32>"),
+	);
+	// Instances share the suffix but not the prefix.
+	assert_eq!(pallet2::GetValuePlus::<Runtime>::id().prefix, twox_128(b"ViewFunctionsInstance"),);
+	assert_eq!(
+		pallet2::GetValuePlus::<Runtime, pallet2::Instance1>::id().prefix,
+		twox_128(b"ViewFunctionsInstance1"),
+	);
+	assert_eq!(
+		pallet2::GetValuePlus::<Runtime>::id().suffix,
+		pallet2::GetValuePlus::<Runtime, pallet2::Instance1>::id().suffix,
+	);
+}
+
+#[test]
+fn view_functions_are_dispatched() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(execute(pallet::GetValue::<Runtime>::new()), Ok(None));
+		assert_eq!(execute(pallet::GetValueWithArg::<Runtime>::new(1)), Ok(None));
+
+		pallet::SomeValue::<Runtime>::put(42);
+		pallet::SomeMap::<Runtime>::insert(1, 7);
+		pallet2::SomeValue::<Runtime>::put(10);
+		pallet2::SomeValue::<Runtime, pallet2::Instance1>::put(20);
+
+		assert_eq!(execute(pallet::GetValue::<Runtime>::new()), Ok(Some(42)));
+		assert_eq!(execute(pallet::GetValueWithArg::<Runtime>::new(1)), Ok(Some(7)));
+		assert_eq!(execute(pallet::GetValueWithArg::<Runtime>::new(2)), Ok(None));
+		assert_eq!(execute(pallet2::GetValuePlus::<Runtime>::new(1)), Ok(Some(11)));
+		assert_eq!(
+			execute(pallet2::GetValuePlus::<Runtime, pallet2::Instance1>::new(1)),
+			Ok(Some(21)),
+		);
+	});
+}
+
+#[test]
+fn runtime_execute_view_function_works() {
+	TestExternalities::default().execute_with(|| {
+		pallet::SomeMap::<Runtime>::insert(3, 9);
+
+		let id = pallet::GetValueWithArg::<Runtime>::id();
+		let output = Runtime::execute_view_function(id, 3u32.encode()).unwrap();
+		assert_eq!(Option::<u32>::decode(&mut &output[..]).unwrap(), Some(9));
+
+		// Trailing bytes are rejected.
+		assert_eq!(
+			Runtime::execute_view_function(id, (3u32, 0u8).encode()),
+			Err(ViewFunctionDispatchError::Codec),
+		);
+		// Missing arguments are rejected.
+		assert_eq!(
+			Runtime::execute_view_function(id, vec![]),
+			Err(ViewFunctionDispatchError::Codec),
+		);
+	});
+}
+
+#[test]
+fn unknown_view_functions_are_not_found() {
+	TestExternalities::default().execute_with(|| {
+		let unknown_pallet = ViewFunctionId { prefix: [0; 16], suffix: [0; 16] };
+		assert_eq!(
+			Runtime::execute_view_function(unknown_pallet, vec![]),
+			Err(ViewFunctionDispatchError::NotFound(unknown_pallet)),
+		);
+
+		// `System` declares no view functions.
+		let unknown_function = ViewFunctionId {
+			prefix: <frame_system::Pallet<Runtime> as ViewFunctionIdPrefix>::prefix(),
+			suffix: pallet::GetValue::<Runtime>::id().suffix,
+		};
+		assert_eq!(
+			Runtime::execute_view_function(unknown_function, vec![]),
+			Err(ViewFunctionDispatchError::NotFound(unknown_function)),
+		);
+	});
+}
+
+#[test]
+fn metadata_ir_includes_view_functions() {
+	let metadata = Runtime::metadata_ir();
+
+	let system = metadata.pallets.iter().find(|p| p.name == "System").unwrap();
+	assert!(system.view_functions.is_empty());
+
+	let pallet = metadata.pallets.iter().find(|p| p.name == "ViewFunctionsExample").unwrap();
+	assert_eq!(
+		pallet.view_functions,
+		vec![
+			PalletViewFunctionMetadataIR {
+				name: "get_value",
+				id: pallet::GetValue::<Runtime>::id().into(),
+				inputs: vec![],
+				output: scale_info::meta_type::<Option<u32>>(),
+				docs: maybe_docs(vec![" Query the value of `SomeValue`."]),
+			},
+			PalletViewFunctionMetadataIR {
+				name: "get_value_with_arg",
+				id: pallet::GetValueWithArg::<Runtime>::id().into(),
+				inputs: vec![PalletViewFunctionParamMetadataIR {
+					name: "key",
+					ty: scale_info::meta_type::<u32>(),
+				}],
+				output: scale_info::meta_type::<Option<u32>>(),
+				docs: maybe_docs(vec![" Query the value of `SomeMap` under `key`."]),
+			},
+		],
+	);
+
+	let instance1 = metadata.pallets.iter().find(|p| p.name == "ViewFunctionsInstance1").unwrap();
+	assert_eq!(instance1.view_functions.len(), 1);
+	assert_eq!(
+		instance1.view_functions[0].id,
+		<[u8; 32]>::from(pallet2::GetValuePlus::<Runtime, pallet2::Instance1>::id()),
+	);
+}
+
+#[test]
+fn metadata_v15_includes_view_functions() {
+	use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+	use sp_metadata_ir::{ViewFunctionsCustomMetadata, VIEW_FUNCTIONS_CUSTOM_KEY};
+
+	let bytes = Runtime::metadata_at_version(15).expect("V15 is supported; qed");
+	let metadata = match RuntimeMetadataPrefixed::decode(&mut &bytes[..]).unwrap().1 {
+		RuntimeMetadata::V15(metadata) => metadata,
+		_ => panic!("metadata has been bumped, test needs to be updated"),
+	};
+
+	let custom = metadata.custom.map.get(VIEW_FUNCTIONS_CUSTOM_KEY).unwrap();
+	let view_functions = ViewFunctionsCustomMetadata::decode(&mut &custom.value[..]).unwrap();
+	let pallet_names = view_functions.pallets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		pallet_names,
+		vec!["ViewFunctionsExample", "ViewFunctionsInstance", "ViewFunctionsInstance1"]
+	);
+
+	let pallet = &view_functions.pallets[0];
+	assert_eq!(pallet.view_functions.len(), 2);
+	let get_value_with_arg = &pallet.view_functions[1];
+	assert_eq!(get_value_with_arg.name, "get_value_with_arg");
+	assert_eq!(get_value_with_arg.id, <[u8; 32]>::from(pallet::GetValueWithArg::<Runtime>::id()),);
+	assert_eq!(get_value_with_arg.inputs.len(), 1);
+	assert_eq!(get_value_with_arg.inputs[0].0, "key");
+	assert_eq!(
+		metadata.types.resolve(get_value_with_arg.output.0).unwrap().path.ident(),
+		Some("Option".into()),
+	);
+}
+
+fn maybe_docs(doc: Vec<&'static str>) -> Vec<&'static str> {
+	if cfg!(feature = "no-metadata-docs") {
+		vec![]
+	} else {
+		doc
+	}
+}
@@ -90,6 +90,27 @@ benchmarks! {
 		assert_eq!(current_code.len(), 4_000_000 as usize);
 	}
 
+	authorize_upgrade {
+		let runtime_blob = T::prepare_set_code_data();
+		T::setup_set_code_requirements(&runtime_blob)?;
+		let hash = T::Hashing::hash(&runtime_blob);
+	}: _(RawOrigin::Root, hash)
+	verify {
+		assert!(System::<T>::authorized_upgrade().is_some());
+	}
+
+	apply_authorized_upgrade {
+		let runtime_blob = T::prepare_set_code_data();
+		T::setup_set_code_requirements(&runtime_blob)?;
+		let hash = T::Hashing::hash(&runtime_blob);
+		// Will be heavier when it needs to do verification (i.e. don't use `...without_checks`).
+		System::<T>::do_authorize_upgrade(hash, true);
+	}: _(RawOrigin::None, runtime_blob)
+	verify {
+		assert!(System::<T>::authorized_upgrade().is_none());
+		T::verify_set_code()
+	}
+
 	#[skip_meta]
 	set_storage {
 		let i in 0 .. 1000;
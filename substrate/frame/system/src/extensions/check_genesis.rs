@@ -19,8 +19,8 @@ use crate::{pallet_prelude::BlockNumberFor, Config, Pallet};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{DispatchInfoOf, SignedExtension, Zero},
-	transaction_validity::TransactionValidityError,
+	traits::{DispatchInfoOf, DispatchOriginOf, SignedExtension, ValidateResult, Zero},
+	transaction_validity::{TransactionValidityError, ValidTransaction},
 };
 
 /// Genesis hash check to provide replay protection between different networks.
@@ -73,3 +73,38 @@ impl<T: Config + Send + Sync> SignedExtension for CheckGenesis<T> {
 		self.validate(who, call, info, len).map(|_| ())
 	}
 }
+
+impl<T: Config + Send + Sync> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for CheckGenesis<T>
+{
+	const IDENTIFIER: &'static str = "CheckGenesis";
+	type Implicit = T::Hash;
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		SignedExtension::additional_signed(self)
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(())
+	}
+}
@@ -20,7 +20,9 @@ use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	generic::Era,
-	traits::{DispatchInfoOf, SaturatedConversion, SignedExtension},
+	traits::{
+		DispatchInfoOf, DispatchOriginOf, SaturatedConversion, SignedExtension, ValidateResult,
+	},
 	transaction_validity::{
 		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
 	},
@@ -40,6 +42,13 @@ impl<T: Config + Send + Sync> CheckMortality<T> {
 	pub fn from(era: Era) -> Self {
 		Self(era, sp_std::marker::PhantomData)
 	}
+
+	/// The validity of a transaction of this era, whose longevity ends with the era.
+	fn validity(&self) -> ValidTransaction {
+		let current_u64 = <Pallet<T>>::block_number().saturated_into::<u64>();
+		let valid_till = self.0.death(current_u64);
+		ValidTransaction { longevity: valid_till.saturating_sub(current_u64), ..Default::default() }
+	}
 }
 
 impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckMortality<T> {
@@ -68,12 +77,7 @@ impl<T: Config + Send + Sync> SignedExtension for CheckMortality<T> {
 		_info: &DispatchInfoOf<Self::Call>,
 		_len: usize,
 	) -> TransactionValidity {
-		let current_u64 = <Pallet<T>>::block_number().saturated_into::<u64>();
-		let valid_till = self.0.death(current_u64);
-		Ok(ValidTransaction {
-			longevity: valid_till.saturating_sub(current_u64),
-			..Default::default()
-		})
+		Ok(self.validity())
 	}
 
 	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
@@ -97,6 +101,41 @@ impl<T: Config + Send + Sync> SignedExtension for CheckMortality<T> {
 	}
 }
 
+impl<T: Config + Send + Sync> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for CheckMortality<T>
+{
+	const IDENTIFIER: &'static str = "CheckMortality";
+	type Implicit = T::Hash;
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		SignedExtension::additional_signed(self)
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		Ok((self.validity(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -145,4 +184,38 @@ mod tests {
 			assert_eq!(ext.validate(&1, CALL, &normal, len).unwrap().longevity, 15);
 		})
 	}
+
+	#[test]
+	fn transaction_ext_check_era_should_change_longevity() {
+		use crate::mock::{RuntimeCall, RuntimeOrigin};
+		use sp_runtime::traits::TransactionExtension;
+
+		new_test_ext().execute_with(|| {
+			let normal = DispatchInfo {
+				weight: Weight::from_parts(100, 0),
+				class: DispatchClass::Normal,
+				pays_fee: Pays::Yes,
+			};
+			let len = 0_usize;
+			let ext = (
+				crate::CheckWeight::<Test>::new(),
+				CheckMortality::<Test>::from(Era::mortal(16, 256)),
+			);
+			System::set_block_number(17);
+			<BlockHash<Test>>::insert(16, H256::repeat_byte(1));
+
+			let implicit = <_ as TransactionExtension<RuntimeCall>>::implicit(&ext).unwrap();
+			assert_eq!(implicit.1, H256::repeat_byte(1));
+			let (valid, _, _) = TransactionExtension::validate(
+				&ext,
+				RuntimeOrigin::none(),
+				CALL,
+				&normal,
+				len,
+				&(),
+			)
+			.unwrap();
+			assert_eq!(valid.longevity, 15);
+		})
+	}
 }
@@ -20,7 +20,10 @@ use codec::{Decode, Encode};
 use frame_support::dispatch::DispatchInfo;
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{DispatchInfoOf, Dispatchable, One, SignedExtension, Zero},
+	traits::{
+		AsSystemOriginSigner, DispatchInfoOf, DispatchOriginOf, Dispatchable, One, SignedExtension,
+		ValidateResult, Zero,
+	},
 	transaction_validity::{
 		InvalidTransaction, TransactionLongevity, TransactionValidity, TransactionValidityError,
 		ValidTransaction,
@@ -130,6 +133,53 @@ where
 	}
 }
 
+/// Checks and increments the nonce of signed origins, the transactions of other origins are
+/// passed on unchecked.
+impl<T: Config> sp_runtime::traits::TransactionExtension<T::RuntimeCall> for CheckNonce<T>
+where
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+	DispatchOriginOf<T::RuntimeCall>: AsSystemOriginSigner<T::AccountId>,
+{
+	const IDENTIFIER: &'static str = "CheckNonce";
+	type Implicit = ();
+	type Val = Option<T::AccountId>;
+	type Pre = ();
+
+	fn implicit(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let Some(who) = origin.as_system_origin_signer() else {
+			return Ok((ValidTransaction::default(), None, origin))
+		};
+		let valid = SignedExtension::validate(self, who, call, info, len)?;
+		let who = who.clone();
+		Ok((valid, Some(who), origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		match val {
+			Some(who) => SignedExtension::pre_dispatch(self, &who, call, info, len),
+			None => Ok(()),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -214,4 +264,60 @@ mod tests {
 			assert_ok!(CheckNonce::<Test>(1).pre_dispatch(&3, CALL, &info, len));
 		})
 	}
+
+	#[test]
+	fn transaction_ext_check_nonce_works() {
+		use crate::mock::RuntimeOrigin;
+		use sp_runtime::traits::{DispatchTransaction, TransactionExtension};
+
+		new_test_ext().execute_with(|| {
+			crate::Account::<Test>::insert(
+				1,
+				crate::AccountInfo {
+					nonce: 1,
+					consumers: 0,
+					providers: 1,
+					sufficients: 0,
+					data: 0,
+				},
+			);
+			let info = DispatchInfo::default();
+			let len = 0_usize;
+			// stale
+			assert_eq!(
+				CheckNonce::<Test>(0)
+					.validate_only(RuntimeOrigin::signed(1), CALL, &info, len)
+					.map(|(valid, _, _)| valid),
+				Err(InvalidTransaction::Stale.into()),
+			);
+			// correct
+			assert_ok!(CheckNonce::<Test>(1).validate_and_prepare(
+				RuntimeOrigin::signed(1),
+				CALL,
+				&info,
+				len
+			));
+			assert_eq!(crate::Account::<Test>::get(1).nonce, 2);
+			// the nonce of unsigned origins is not checked
+			let (_, val, _) = TransactionExtension::validate(
+				&CheckNonce::<Test>(0),
+				RuntimeOrigin::none(),
+				CALL,
+				&info,
+				len,
+				&(),
+			)
+			.unwrap();
+			assert_eq!(val, None);
+			assert_ok!(TransactionExtension::prepare(
+				CheckNonce::<Test>(0),
+				val,
+				&RuntimeOrigin::none(),
+				CALL,
+				&info,
+				len
+			));
+			assert_eq!(crate::Account::<Test>::get(1).nonce, 2);
+		})
+	}
 }
@@ -19,8 +19,8 @@ use crate::{Config, Pallet};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{DispatchInfoOf, SignedExtension},
-	transaction_validity::TransactionValidityError,
+	traits::{DispatchInfoOf, DispatchOriginOf, SignedExtension, ValidateResult},
+	transaction_validity::{TransactionValidityError, ValidTransaction},
 };
 
 /// Ensure the runtime version registered in the transaction is the same as at present.
@@ -73,3 +73,38 @@ impl<T: Config + Send + Sync> SignedExtension for CheckSpecVersion<T> {
 		self.validate(who, call, info, len).map(|_| ())
 	}
 }
+
+impl<T: Config + Send + Sync> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for CheckSpecVersion<T>
+{
+	const IDENTIFIER: &'static str = "CheckSpecVersion";
+	type Implicit = u32;
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		SignedExtension::additional_signed(self)
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(())
+	}
+}
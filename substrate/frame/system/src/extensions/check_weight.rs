@@ -23,7 +23,10 @@ use frame_support::{
 };
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension},
+	traits::{
+		DispatchInfoOf, DispatchOriginOf, Dispatchable, PostDispatchInfoOf, SignedExtension,
+		ValidateResult,
+	},
 	transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError},
 	DispatchResult,
 };
@@ -281,6 +284,53 @@ where
 	}
 }
 
+impl<T: Config + Send + Sync> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for CheckWeight<T>
+where
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "CheckWeight";
+	type Implicit = ();
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		Ok((Self::do_validate(info, len)?, (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Self::do_pre_dispatch(info, len)
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		<Self as SignedExtension>::post_dispatch(Some(pre), info, post_info, len, result)
+	}
+}
+
 impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckWeight<T> {
 	#[cfg(feature = "std")]
 	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
@@ -663,6 +713,60 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn transaction_ext_check_weight_refund_works() {
+		use crate::mock::{RuntimeCall, RuntimeOrigin};
+		use sp_runtime::traits::TransactionExtension;
+
+		new_test_ext().execute_with(|| {
+			let info = DispatchInfo { weight: Weight::from_parts(512, 0), ..Default::default() };
+			let post_info = PostDispatchInfo {
+				actual_weight: Some(Weight::from_parts(128, 0)),
+				pays_fee: Default::default(),
+			};
+			let len = 0_usize;
+			let base_extrinsic = block_weights().get(DispatchClass::Normal).base_extrinsic;
+			let base_block = block_weights().base_block;
+
+			// The weight is checked regardless of the origin.
+			let origin = RuntimeOrigin::none();
+			let (_, val, origin) = TransactionExtension::validate(
+				&CheckWeight::<Test>(PhantomData),
+				origin,
+				CALL,
+				&info,
+				len,
+				&(),
+			)
+			.unwrap();
+			let pre = TransactionExtension::prepare(
+				CheckWeight::<Test>(PhantomData),
+				val,
+				&origin,
+				CALL,
+				&info,
+				len,
+			)
+			.unwrap();
+			assert_eq!(
+				BlockWeight::<Test>::get().total(),
+				info.weight + base_extrinsic + base_block
+			);
+
+			assert_ok!(<CheckWeight<Test> as TransactionExtension<RuntimeCall>>::post_dispatch(
+				pre,
+				&info,
+				&post_info,
+				len,
+				&Ok(())
+			));
+			assert_eq!(
+				BlockWeight::<Test>::get().total(),
+				post_info.actual_weight.unwrap() + base_extrinsic + base_block
+			);
+		})
+	}
+
 	#[test]
 	fn zero_weight_extrinsic_still_has_base_weight() {
 		new_test_ext().execute_with(|| {
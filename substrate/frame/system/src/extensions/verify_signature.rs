@@ -0,0 +1,190 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Config, RawOrigin};
+use codec::{Decode, Encode};
+use scale_info::{StaticTypeInfo, TypeInfo};
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, DispatchOriginOf, IdentifyAccount, TransactionExtension, ValidateResult,
+		Verify,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+};
+
+/// Authorizes a general transaction by a signature of its implication.
+///
+/// The signature covers the blake2-256 hash of everything the transaction implies from the
+/// point of view of this extension, i.e. the explicit and implicit data of the following
+/// extensions and the call. Put it first in a pipeline of transaction extensions to have the
+/// whole transaction signed.
+///
+/// # Transaction Validity
+///
+/// A transaction with a valid signature is dispatched with the signed origin of `account`. A
+/// disabled extension passes the transaction on unchanged. The validity is not affected in any
+/// other way.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub enum VerifySignature<T: Config, V> {
+	/// The transaction is signed by `account` with `signature`.
+	Signed {
+		/// The signature of the implication of the transaction.
+		signature: V,
+		/// The account signing the transaction.
+		account: T::AccountId,
+	},
+	/// The transaction is not authorized by this extension.
+	Disabled,
+}
+
+impl<T: Config, V> VerifySignature<T, V> {
+	/// Create new `TransactionExtension` authorizing a transaction signed by `account`.
+	pub fn new_with_signature(signature: V, account: T::AccountId) -> Self {
+		Self::Signed { signature, account }
+	}
+
+	/// Create new `TransactionExtension` not authorizing the transaction.
+	pub fn new_disabled() -> Self {
+		Self::Disabled
+	}
+}
+
+impl<T: Config, V> sp_std::fmt::Debug for VerifySignature<T, V> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		match self {
+			Self::Signed { account, .. } => write!(f, "VerifySignature({:?})", account),
+			Self::Disabled => write!(f, "VerifySignature(Disabled)"),
+		}
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T, V> TransactionExtension<T::RuntimeCall> for VerifySignature<T, V>
+where
+	T: Config + Send + Sync,
+	V: Verify + Encode + Decode + Clone + Eq + StaticTypeInfo + Send + Sync,
+	V::Signer: IdentifyAccount<AccountId = T::AccountId>,
+{
+	const IDENTIFIER: &'static str = "VerifySignature";
+	type Implicit = ();
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let Self::Signed { signature, account } = self else {
+			return Ok((ValidTransaction::default(), (), origin))
+		};
+		let msg = inherited_implication.using_encoded(blake2_256);
+		if !signature.verify(&msg[..], account) {
+			return Err(InvalidTransaction::BadProof.into())
+		}
+		Ok((ValidTransaction::default(), (), RawOrigin::Signed(account.clone()).into()))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, RuntimeOrigin, Test, CALL};
+	use frame_support::dispatch::DispatchInfo;
+	use sp_runtime::{
+		testing::TestSignature,
+		traits::{AsSystemOriginSigner, DispatchTransaction},
+	};
+
+	#[test]
+	fn valid_signature_authorizes_signed_origin() {
+		new_test_ext().execute_with(|| {
+			let info = DispatchInfo::default();
+			let msg = blake2_256(&CALL.encode()).to_vec();
+			let ext = VerifySignature::<Test, _>::new_with_signature(TestSignature(1, msg), 1);
+
+			let (_, _, origin) = ext.validate_only(RuntimeOrigin::none(), CALL, &info, 0).unwrap();
+			assert_eq!(origin.as_system_origin_signer(), Some(&1));
+		})
+	}
+
+	#[test]
+	fn invalid_signature_is_rejected() {
+		new_test_ext().execute_with(|| {
+			let info = DispatchInfo::default();
+			let msg = blake2_256(&CALL.encode()).to_vec();
+
+			// signed by another account
+			let ext = VerifySignature::<Test, _>::new_with_signature(TestSignature(2, msg), 1);
+			assert_eq!(
+				ext.validate_only(RuntimeOrigin::none(), CALL, &info, 0)
+					.map(|(valid, _, _)| valid),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+			// signing another message
+			let ext =
+				VerifySignature::<Test, _>::new_with_signature(TestSignature(1, vec![1, 2, 3]), 1);
+			assert_eq!(
+				ext.validate_only(RuntimeOrigin::none(), CALL, &info, 0)
+					.map(|(valid, _, _)| valid),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+		})
+	}
+
+	#[test]
+	fn disabled_does_not_authorize() {
+		new_test_ext().execute_with(|| {
+			let info = DispatchInfo::default();
+			let ext = VerifySignature::<Test, TestSignature>::new_disabled();
+
+			assert_eq!(
+				ext.validate_only(RuntimeOrigin::none(), CALL, &info, 0)
+					.map(|(valid, _, _)| valid),
+				Err(InvalidTransaction::UnknownOrigin.into()),
+			);
+			let (_, _, origin) =
+				ext.validate_only(RuntimeOrigin::signed(1), CALL, &info, 0).unwrap();
+			assert_eq!(origin.as_system_origin_signer(), Some(&1));
+		})
+	}
+}
@@ -90,15 +90,16 @@ use frame_support::traits::BuildGenesisConfig;
 use frame_support::{
 	dispatch::{
 		extract_actual_pays_fee, extract_actual_weight, DispatchClass, DispatchInfo,
-		DispatchResult, DispatchResultWithPostInfo, PerDispatchClass,
+		DispatchResult, DispatchResultWithPostInfo, Pays, PerDispatchClass, PostDispatchInfo,
 	},
 	impl_ensure_origin_with_arg_ignoring_arg,
 	storage::{self, StorageStreamIter},
 	traits::{
 		ConstU32, Contains, EnsureOrigin, EnsureOriginWithArg, Get, HandleLifetime,
-		OnKilledAccount, OnNewAccount, OriginTrait, PalletInfo, SortedMembers, StoredMap, TypedGet,
+		OnKilledAccount, OnNewAccount, OriginTrait, PalletInfo, SortedMembers, StoredMap, Task,
+		TypedGet,
 	},
-	Parameter,
+	CloneNoBound, EqNoBound, Parameter, PartialEqNoBound, RuntimeDebugNoBound,
 };
 use scale_info::TypeInfo;
 use sp_core::storage::well_known_keys;
@@ -125,7 +126,7 @@ pub use extensions::{
 	check_genesis::CheckGenesis, check_mortality::CheckMortality,
 	check_non_zero_sender::CheckNonZeroSender, check_nonce::CheckNonce,
 	check_spec_version::CheckSpecVersion, check_tx_version::CheckTxVersion,
-	check_weight::CheckWeight,
+	check_weight::CheckWeight, verify_signature::VerifySignature,
 };
 // Backward compatible re-export.
 pub use extensions::check_mortality::CheckMortality as CheckEra;
@@ -134,6 +135,17 @@ pub use weights::WeightInfo;
 
 const LOG_TARGET: &str = "runtime::system";
 
+/// The priority of unsigned [`Call::do_task`] transactions.
+///
+/// Tasks are service work, so they should not crowd out regular transactions.
+pub const DO_TASK_PRIORITY: sp_runtime::transaction_validity::TransactionPriority =
+	sp_runtime::transaction_validity::TransactionPriority::max_value() / 2;
+
+/// The number of blocks an unsigned [`Call::do_task`] transaction stays valid in the pool.
+///
+/// Offchain workers resubmit tasks that are still valid, so stale ones do not need to linger.
+pub const DO_TASK_LONGEVITY: sp_runtime::transaction_validity::TransactionLongevity = 16;
+
 /// Compute the trie root of a list of extrinsics.
 ///
 /// The merkle proof is using the same trie as runtime state with
@@ -239,6 +251,8 @@ pub mod pallet {
 			#[inject_runtime_type]
 			type RuntimeCall = ();
 			#[inject_runtime_type]
+			type RuntimeTask = ();
+			#[inject_runtime_type]
 			type PalletInfo = ();
 			type BaseCallFilter = frame_support::traits::Everything;
 			type BlockHashCount = frame_support::traits::ConstU64<10>;
@@ -286,6 +300,8 @@ pub mod pallet {
 			#[inject_runtime_type]
 			type RuntimeCall = ();
 			#[inject_runtime_type]
+			type RuntimeTask = ();
+			#[inject_runtime_type]
 			type PalletInfo = ();
 			type BaseCallFilter = frame_support::traits::Everything;
 			type BlockHashCount = frame_support::traits::ConstU32<256>;
@@ -340,6 +356,13 @@ pub mod pallet {
 			+ Debug
 			+ From<Call<Self>>;
 
+		/// The aggregated `RuntimeTask` type.
+		///
+		/// Generated by `construct_runtime!` from the tasks declared by the runtime's pallets,
+		/// executed through [`Call::do_task`].
+		#[pallet::no_default_bounds]
+		type RuntimeTask: Task;
+
 		/// This stores the number of previous transactions associated with a sender account.
 		type Nonce: Parameter
 			+ Member
@@ -568,6 +591,121 @@ pub mod pallet {
 			Self::deposit_event(Event::Remarked { sender: who, hash });
 			Ok(().into())
 		}
+
+		/// Execute a task declared by one of the runtime's pallets.
+		///
+		/// Can be executed by every `origin`, including none for unsigned transactions submitted
+		/// by an offchain worker. Fails with [`Error::InvalidTask`] if the task is not valid
+		/// (anymore) at the time of execution. The weight of the task covers this validity check.
+		#[pallet::call_index(8)]
+		#[pallet::weight(task.weight())]
+		pub fn do_task(_origin: OriginFor<T>, task: T::RuntimeTask) -> DispatchResultWithPostInfo {
+			ensure!(task.is_valid(), Error::<T>::InvalidTask);
+			task.run()?;
+			Self::deposit_event(Event::TaskCompleted { task });
+			Ok(().into())
+		}
+
+		/// Authorize an upgrade to a given `code_hash` for the runtime. The runtime can be supplied
+		/// later through [`Call::apply_authorized_upgrade`].
+		///
+		/// This call requires Root origin.
+		#[pallet::call_index(9)]
+		#[pallet::weight((T::SystemWeightInfo::authorize_upgrade(), DispatchClass::Operational))]
+		pub fn authorize_upgrade(origin: OriginFor<T>, code_hash: T::Hash) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_authorize_upgrade(code_hash, true);
+			Ok(())
+		}
+
+		/// Authorize an upgrade to a given `code_hash` for the runtime. The runtime can be supplied
+		/// later through [`Call::apply_authorized_upgrade`].
+		///
+		/// WARNING: This authorizes an upgrade that will take place without any safety checks, for
+		/// example that the spec name remains the same and that the version number increases. Not
+		/// recommended for normal use. Use [`Call::authorize_upgrade`] instead.
+		///
+		/// This call requires Root origin.
+		#[pallet::call_index(10)]
+		#[pallet::weight((T::SystemWeightInfo::authorize_upgrade(), DispatchClass::Operational))]
+		pub fn authorize_upgrade_without_checks(
+			origin: OriginFor<T>,
+			code_hash: T::Hash,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_authorize_upgrade(code_hash, false);
+			Ok(())
+		}
+
+		/// Provide the preimage (runtime binary) `code` for an upgrade that has been authorized.
+		///
+		/// If the authorization required a version check, this call will ensure the spec name
+		/// remains unchanged and that the spec version has increased.
+		///
+		/// Depending on the runtime's `OnSetCode` configuration, this function may directly apply
+		/// the new `code` in the same block or attempt to schedule the upgrade.
+		///
+		/// All origins are allowed, including none for unsigned transactions. The call is free if
+		/// it succeeds.
+		#[pallet::call_index(11)]
+		#[pallet::weight((
+			T::SystemWeightInfo::apply_authorized_upgrade(),
+			DispatchClass::Operational,
+		))]
+		pub fn apply_authorized_upgrade(
+			_origin: OriginFor<T>,
+			code: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			Self::do_apply_authorized_upgrade(code)
+		}
+
+		/// Set or remove the maximum number of bytes the pallet named `pallet_name` is allowed to
+		/// use in storage.
+		///
+		/// The quota is only meaningful for pallets tracking their storage usage, see
+		/// [`frame_support::storage::usage`].
+		///
+		/// This call requires Root origin.
+		#[pallet::call_index(12)]
+		#[pallet::weight((T::SystemWeightInfo::set_storage(1), DispatchClass::Operational))]
+		pub fn set_storage_quota(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			quota: Option<u64>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			storage::usage::set_quota(&pallet_name, quota);
+			Self::deposit_event(Event::StorageQuotaSet { pallet_name, quota });
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> sp_runtime::traits::ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::do_task { task } if task.is_valid() =>
+					ValidTransaction::with_tag_prefix("SystemDoTask")
+						.priority(DO_TASK_PRIORITY)
+						.and_provides((task.task_index(), task.encode()))
+						.longevity(DO_TASK_LONGEVITY)
+						.propagate(true)
+						.build(),
+				Call::apply_authorized_upgrade { code } => {
+					let code_hash = Self::validate_authorized_upgrade(&code[..])
+						.map_err(|_| InvalidTransaction::Call)?;
+					ValidTransaction::with_tag_prefix("SystemApplyAuthorizedUpgrade")
+						.priority(100)
+						.and_provides(code_hash)
+						.longevity(TransactionLongevity::max_value())
+						.propagate(true)
+						.build()
+				},
+				_ => Err(InvalidTransaction::Call.into()),
+			}
+		}
 	}
 
 	/// Event for the System pallet.
@@ -585,6 +723,14 @@ pub mod pallet {
 		KilledAccount { account: T::AccountId },
 		/// On on-chain remark happened.
 		Remarked { sender: T::AccountId, hash: T::Hash },
+		/// A task was executed through [`Call::do_task`].
+		TaskCompleted { task: T::RuntimeTask },
+		/// An upgrade was authorized.
+		UpgradeAuthorized { code_hash: T::Hash, check_version: bool },
+		/// The storage quota of a pallet was set or removed.
+		StorageQuotaSet { pallet_name: Vec<u8>, quota: Option<u64> },
+		/// The `on_poll` hook of a pallet consumed some weight.
+		PalletPolled { pallet_index: u32, consumed: Weight },
 	}
 
 	/// Error for the System pallet
@@ -606,6 +752,12 @@ pub mod pallet {
 		NonZeroRefCount,
 		/// The origin filter prevent the call to be dispatched.
 		CallFiltered,
+		/// The task is not valid and can therefore not be executed.
+		InvalidTask,
+		/// No upgrade authorized.
+		NothingAuthorized,
+		/// The submitted code is not authorized.
+		Unauthorized,
 	}
 
 	/// Exposed trait-generic origin type.
@@ -721,6 +873,17 @@ pub mod pallet {
 	#[pallet::whitelist_storage]
 	pub(super) type ExecutionPhase<T: Config> = StorageValue<_, Phase>;
 
+	/// Whether all inherents of the current block have been applied.
+	#[pallet::storage]
+	#[pallet::whitelist_storage]
+	pub(super) type InherentsApplied<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// `Some` if a code upgrade has been authorized.
+	#[pallet::storage]
+	#[pallet::getter(fn authorized_upgrade)]
+	pub(super) type AuthorizedUpgrade<T: Config> =
+		StorageValue<_, CodeUpgradeAuthorization<T>, OptionQuery>;
+
 	#[derive(frame_support::DefaultNoBound)]
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -745,6 +908,26 @@ pub mod pallet {
 pub type Key = Vec<u8>;
 pub type KeyValue = (Vec<u8>, Vec<u8>);
 
+/// A code upgrade authorized through [`Call::authorize_upgrade`], waiting for the runtime binary
+/// to be supplied through [`Call::apply_authorized_upgrade`].
+#[derive(
+	CloneNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct CodeUpgradeAuthorization<T: Config> {
+	/// Hash of the new runtime binary.
+	pub code_hash: T::Hash,
+	/// Whether or not to carry out version checks.
+	pub check_version: bool,
+}
+
 /// A phase of a block's execution.
 #[derive(Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone))]
@@ -1435,6 +1618,24 @@ impl<T: Config> Pallet<T> {
 		});
 	}
 
+	/// Charge the database accesses done by a dispatchable to account for the storage usage of
+	/// pallets beyond the `declared` ones, included in its weight, to the block, see
+	/// [`frame_support::storage::usage`].
+	///
+	/// Called by every dispatchable of a pallet declared with `#[pallet::track_storage_usage]`.
+	pub fn register_storage_usage_overhead(
+		overhead: storage::usage::StorageUsageOverhead,
+		declared: storage::usage::StorageUsageOverhead,
+	) {
+		let excess = overhead.saturating_sub(declared);
+		if !excess.is_zero() {
+			Self::register_extra_weight_unchecked(
+				excess.weight(T::DbWeight::get()),
+				DispatchClass::Normal,
+			);
+		}
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize(number: &BlockNumberFor<T>, parent_hash: &T::Hash, digest: &generic::Digest) {
 		// populate environment
@@ -1490,6 +1691,7 @@ impl<T: Config> Pallet<T> {
 			).deconstruct(),
 		);
 		ExecutionPhase::<T>::kill();
+		InherentsApplied::<T>::kill();
 		AllExtrinsicsLen::<T>::kill();
 		storage::unhashed::kill(well_known_keys::INTRABLOCK_ENTROPY);
 
@@ -1711,6 +1913,28 @@ impl<T: Config> Pallet<T> {
 		ExecutionPhase::<T>::put(Phase::ApplyExtrinsic(0))
 	}
 
+	/// To be called immediately after the last inherent of the block has been applied.
+	pub fn note_inherents_applied() {
+		InherentsApplied::<T>::put(true);
+	}
+
+	/// Whether all inherents of the current block have been applied.
+	pub fn inherents_applied() -> bool {
+		InherentsApplied::<T>::get()
+	}
+
+	/// Record that the `on_poll` hook of the pallet with `pallet_index` consumed `consumed` weight.
+	///
+	/// The weight is registered as [`DispatchClass::Mandatory`] and a
+	/// [`Event::PalletPolled`] is deposited if it is not zero.
+	pub fn note_pallet_polled(pallet_index: u32, consumed: Weight) {
+		if consumed.is_zero() {
+			return
+		}
+		Self::register_extra_weight_unchecked(consumed, DispatchClass::Mandatory);
+		Self::deposit_event(Event::PalletPolled { pallet_index, consumed });
+	}
+
 	/// An account is being created.
 	pub fn on_created_account(who: T::AccountId, _a: &mut AccountInfo<T::Nonce, T::AccountData>) {
 		T::OnNewAccount::on_new_account(&who);
@@ -1752,6 +1976,42 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 	}
+
+	/// Authorize the given `code_hash` as upgrade.
+	pub fn do_authorize_upgrade(code_hash: T::Hash, check_version: bool) {
+		AuthorizedUpgrade::<T>::put(CodeUpgradeAuthorization { code_hash, check_version });
+		Self::deposit_event(Event::UpgradeAuthorized { code_hash, check_version });
+	}
+
+	/// Apply an authorized upgrade, performing any validation checks, and remove the
+	/// authorization.
+	pub fn do_apply_authorized_upgrade(code: Vec<u8>) -> DispatchResultWithPostInfo {
+		Self::validate_authorized_upgrade(&code[..])?;
+		T::OnSetCode::set_code(code)?;
+		AuthorizedUpgrade::<T>::kill();
+		Ok(PostDispatchInfo {
+			// consume the rest of the block to prevent further transactions
+			actual_weight: Some(T::BlockWeights::get().max_block),
+			// no fee for a valid upgrade
+			pays_fee: Pays::No,
+		})
+	}
+
+	/// Check that the `code` matches the authorized upgrade and, if required by the
+	/// authorization, passes the version checks of [`Self::can_set_code`].
+	///
+	/// Returns the hash of `code`.
+	pub fn validate_authorized_upgrade(code: &[u8]) -> Result<T::Hash, DispatchError> {
+		let authorization = AuthorizedUpgrade::<T>::get().ok_or(Error::<T>::NothingAuthorized)?;
+		let actual_hash = T::Hashing::hash(code);
+		if actual_hash != authorization.code_hash {
+			return Err(Error::<T>::Unauthorized.into())
+		}
+		if authorization.check_version {
+			Self::can_set_code(code)?
+		}
+		Ok(actual_hash)
+	}
 }
 
 /// Returns a 32 byte datum which is guaranteed to be universally unique. `entropy` is provided
@@ -57,6 +57,7 @@
 #![warn(missing_docs)]
 
 use codec::Encode;
+use frame_support::traits::Task;
 use sp_runtime::{
 	app_crypto::RuntimeAppPublic,
 	traits::{Extrinsic as ExtrinsicT, IdentifyAccount, One},
@@ -100,6 +101,40 @@ where
 	}
 }
 
+/// Submit the valid ones among `tasks` as unsigned [`Call::do_task`] transactions.
+///
+/// Meant to be called from an `offchain_worker` hook, so that work discovered by pallets
+/// declaring `#[pallet::tasks]` gets executed without anyone having to submit it manually. Pass
+/// `<T as Config>::RuntimeTask::iter()` to submit the tasks of all pallets, or the converted
+/// tasks of a single pallet. At most `limit` tasks are submitted, `tasks` is not advanced any
+/// further once the limit is reached.
+///
+/// Returns the number of tasks that were submitted successfully.
+///
+/// [`Call::do_task`]: crate::Call::do_task
+pub fn submit_valid_tasks<T>(
+	tasks: impl Iterator<Item = <T as crate::Config>::RuntimeTask>,
+	limit: usize,
+) -> usize
+where
+	T: crate::Config + SendTransactionTypes<crate::Call<T>>,
+{
+	tasks
+		.filter(|task| task.is_valid())
+		.filter(|task| {
+			let call = crate::Call::<T>::do_task { task: task.clone() };
+			let submitted =
+				SubmitTransaction::<T, crate::Call<T>>::submit_unsigned_transaction(call.into())
+					.is_ok();
+			if !submitted {
+				log::error!(target: crate::LOG_TARGET, "Failed to submit task: {:?}", task);
+			}
+			submitted
+		})
+		.take(limit)
+		.count()
+}
+
 /// Provides an implementation for signing transaction payloads.
 ///
 /// Keys used for signing are defined when instantiating the signer object.
@@ -591,20 +591,31 @@ fn prunes_block_hash_mappings() {
 	})
 }
 
-#[test]
-fn set_code_checks_works() {
-	struct ReadRuntimeVersion(Vec<u8>);
-
-	impl sp_core::traits::ReadRuntimeVersion for ReadRuntimeVersion {
-		fn read_runtime_version(
-			&self,
-			_wasm_code: &[u8],
-			_ext: &mut dyn sp_externalities::Externalities,
-		) -> Result<Vec<u8>, String> {
-			Ok(self.0.clone())
-		}
+struct ReadRuntimeVersion(Vec<u8>);
+
+impl sp_core::traits::ReadRuntimeVersion for ReadRuntimeVersion {
+	fn read_runtime_version(
+		&self,
+		_wasm_code: &[u8],
+		_ext: &mut dyn sp_externalities::Externalities,
+	) -> Result<Vec<u8>, String> {
+		Ok(self.0.clone())
 	}
+}
 
+/// Test externalities in which any code is read as a runtime of the given spec name and version.
+fn new_test_ext_with_code_version(spec_name: &'static str, spec_version: u32) -> TestExternalities {
+	let version =
+		RuntimeVersion { spec_name: spec_name.into(), spec_version, ..Default::default() };
+	let mut ext = new_test_ext();
+	ext.register_extension(sp_core::traits::ReadRuntimeVersionExt::new(ReadRuntimeVersion(
+		version.encode(),
+	)));
+	ext
+}
+
+#[test]
+fn set_code_checks_works() {
 	let test_data = vec![
 		("test", 1, 2, Err(Error::<Test>::SpecVersionNeedsToIncrease)),
 		("test", 1, 1, Err(Error::<Test>::SpecVersionNeedsToIncrease)),
@@ -675,6 +686,152 @@ fn set_code_with_real_wasm_blob() {
 	});
 }
 
+#[test]
+fn authorize_upgrade_requires_root() {
+	new_test_ext().execute_with(|| {
+		let hash = <Test as Config>::Hashing::hash(&[1, 2, 3, 4]);
+		assert_noop!(
+			System::authorize_upgrade(RuntimeOrigin::signed(1), hash),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			System::authorize_upgrade_without_checks(RuntimeOrigin::signed(1), hash),
+			DispatchError::BadOrigin,
+		);
+		assert_eq!(System::authorized_upgrade(), None);
+	});
+}
+
+#[test]
+fn authorized_upgrade_is_applied() {
+	new_test_ext_with_code_version("test", 2).execute_with(|| {
+		System::set_block_number(1);
+		let code = vec![1, 2, 3, 4];
+		let hash = <Test as Config>::Hashing::hash(&code);
+
+		assert_noop!(
+			System::apply_authorized_upgrade(RawOrigin::None.into(), code.clone()),
+			Error::<Test>::NothingAuthorized,
+		);
+
+		assert_ok!(System::authorize_upgrade(RawOrigin::Root.into(), hash));
+		System::assert_has_event(
+			SysEvent::UpgradeAuthorized { code_hash: hash, check_version: true }.into(),
+		);
+		assert_eq!(
+			System::authorized_upgrade(),
+			Some(CodeUpgradeAuthorization { code_hash: hash, check_version: true }),
+		);
+
+		assert_noop!(
+			System::apply_authorized_upgrade(RawOrigin::None.into(), vec![1, 2, 3]),
+			Error::<Test>::Unauthorized,
+		);
+
+		// Anyone can apply the authorized code, for free.
+		assert_eq!(
+			System::apply_authorized_upgrade(RuntimeOrigin::signed(1), code.clone()),
+			Ok(PostDispatchInfo {
+				actual_weight: Some(<Test as Config>::BlockWeights::get().max_block),
+				pays_fee: Pays::No,
+			}),
+		);
+		System::assert_last_event(SysEvent::CodeUpdated.into());
+		assert_eq!(storage::unhashed::get_raw(well_known_keys::CODE), Some(code.clone()));
+		assert_runtime_updated_digest(1);
+
+		// The authorization is consumed.
+		assert_eq!(System::authorized_upgrade(), None);
+		assert_noop!(
+			System::apply_authorized_upgrade(RawOrigin::None.into(), code),
+			Error::<Test>::NothingAuthorized,
+		);
+	});
+}
+
+#[test]
+fn set_storage_quota_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			System::set_storage_quota(RuntimeOrigin::signed(1), b"Example".to_vec(), Some(10)),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(System::set_storage_quota(
+			RawOrigin::Root.into(),
+			b"Example".to_vec(),
+			Some(10)
+		));
+		System::assert_last_event(
+			SysEvent::StorageQuotaSet { pallet_name: b"Example".to_vec(), quota: Some(10) }.into(),
+		);
+		assert_eq!(storage::usage::quota(b"Example"), Some(10));
+		assert!(!storage::usage::exceeds_quota(b"Example"));
+
+		storage::usage::set_usage(b"Example", 11);
+		assert!(storage::usage::exceeds_quota(b"Example"));
+
+		assert_ok!(System::set_storage_quota(RawOrigin::Root.into(), b"Example".to_vec(), None));
+		assert_eq!(storage::usage::quota(b"Example"), None);
+		assert!(!storage::usage::exceeds_quota(b"Example"));
+	});
+}
+
+#[test]
+fn authorized_upgrade_checks_version_unless_told_otherwise() {
+	new_test_ext_with_code_version("test", 1).execute_with(|| {
+		let code = vec![1, 2, 3, 4];
+		let hash = <Test as Config>::Hashing::hash(&code);
+
+		assert_ok!(System::authorize_upgrade(RawOrigin::Root.into(), hash));
+		assert_noop!(
+			System::apply_authorized_upgrade(RawOrigin::None.into(), code.clone()),
+			Error::<Test>::SpecVersionNeedsToIncrease,
+		);
+
+		// A new authorization replaces the previous one.
+		assert_ok!(System::authorize_upgrade_without_checks(RawOrigin::Root.into(), hash));
+		assert_eq!(
+			System::authorized_upgrade(),
+			Some(CodeUpgradeAuthorization { code_hash: hash, check_version: false }),
+		);
+		assert_ok!(System::apply_authorized_upgrade(RawOrigin::None.into(), code.clone()));
+		assert_eq!(storage::unhashed::get_raw(well_known_keys::CODE), Some(code));
+		assert_eq!(System::authorized_upgrade(), None);
+	});
+}
+
+#[test]
+fn apply_authorized_upgrade_validate_unsigned_works() {
+	use sp_runtime::{
+		traits::ValidateUnsigned,
+		transaction_validity::{InvalidTransaction, TransactionSource},
+	};
+
+	new_test_ext_with_code_version("test", 2).execute_with(|| {
+		let code = vec![1, 2, 3, 4];
+		let hash = <Test as Config>::Hashing::hash(&code);
+		let call = Call::<Test>::apply_authorized_upgrade { code: code.clone() };
+
+		assert_eq!(
+			System::validate_unsigned(TransactionSource::External, &call),
+			Err(InvalidTransaction::Call.into()),
+		);
+
+		assert_ok!(System::authorize_upgrade(RawOrigin::Root.into(), hash));
+		let valid = System::validate_unsigned(TransactionSource::External, &call).unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.provides, vec![("SystemApplyAuthorizedUpgrade", hash).encode()]);
+
+		let wrong_call = Call::<Test>::apply_authorized_upgrade { code: vec![1, 2, 3] };
+		assert_eq!(
+			System::validate_unsigned(TransactionSource::External, &wrong_call),
+			Err(InvalidTransaction::Call.into()),
+		);
+	});
+}
+
 #[test]
 fn runtime_upgraded_with_set_storage() {
 	let executor = substrate_test_runtime_client::new_native_or_wasm_executor();
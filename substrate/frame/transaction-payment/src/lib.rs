@@ -61,8 +61,8 @@ pub use pallet::*;
 pub use payment::*;
 use sp_runtime::{
 	traits::{
-		Convert, DispatchInfoOf, Dispatchable, One, PostDispatchInfoOf, SaturatedConversion,
-		Saturating, SignedExtension, Zero,
+		AsSystemOriginSigner, Convert, DispatchInfoOf, DispatchOriginOf, Dispatchable, One,
+		PostDispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, ValidateResult, Zero,
 	},
 	transaction_validity::{
 		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
@@ -882,6 +882,63 @@ where
 	}
 }
 
+/// Charges the fee of transactions of signed origins to the signer, the transactions of other
+/// origins are passed on without paying any fee.
+impl<T: Config> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for ChargeTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync + From<u64>,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	DispatchOriginOf<T::RuntimeCall>: AsSystemOriginSigner<T::AccountId>,
+{
+	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
+	type Implicit = ();
+	type Val = Option<T::AccountId>;
+	type Pre = Option<<Self as SignedExtension>::Pre>;
+
+	fn implicit(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let Some(who) = origin.as_system_origin_signer() else {
+			return Ok((ValidTransaction::default(), None, origin))
+		};
+		let valid = SignedExtension::validate(self, who, call, info, len)?;
+		let who = who.clone();
+		Ok((valid, Some(who), origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		_origin: &DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		val.map(|who| SignedExtension::pre_dispatch(self, &who, call, info, len))
+			.transpose()
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		<Self as SignedExtension>::post_dispatch(pre, info, post_info, len, result)
+	}
+}
+
 impl<T: Config, AnyCall: GetDispatchInfo + Encode> EstimateCallFee<AnyCall, BalanceOf<T>>
 	for Pallet<T>
 where
@@ -172,6 +172,59 @@ fn signed_extension_transaction_payment_work() {
 		});
 }
 
+#[test]
+fn transaction_extension_transaction_payment_work() {
+	use sp_runtime::traits::{DispatchTransaction, TransactionExtension};
+
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(Weight::from_parts(5, 0))
+		.build()
+		.execute_with(|| {
+			let len = 10;
+			let info = info_from_weight(Weight::from_parts(100, 0));
+			let (pre, _) = ChargeTransactionPayment::<Runtime>::from(5 /* tipped */)
+				.validate_and_prepare(RuntimeOrigin::signed(2), CALL, &info, len)
+				.unwrap();
+			assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 100 - 5);
+
+			assert_ok!(<ChargeTransactionPayment<Runtime> as TransactionExtension<
+				RuntimeCall,
+			>>::post_dispatch(
+				pre,
+				&info,
+				&post_info_from_weight(Weight::from_parts(50, 0)),
+				len,
+				&Ok(())
+			));
+			assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 50 - 5);
+			assert_eq!(FeeUnbalancedAmount::get(), 5 + 10 + 50);
+			assert_eq!(TipUnbalancedAmount::get(), 5);
+
+			// Transactions of unsigned origins do not pay any fee.
+			let (_, val, origin) = TransactionExtension::validate(
+				&ChargeTransactionPayment::<Runtime>::from(5),
+				RuntimeOrigin::none(),
+				CALL,
+				&info,
+				len,
+				&(),
+			)
+			.unwrap();
+			let pre = TransactionExtension::prepare(
+				ChargeTransactionPayment::<Runtime>::from(5),
+				val,
+				&origin,
+				CALL,
+				&info,
+				len,
+			)
+			.unwrap();
+			assert!(pre.is_none());
+			assert_eq!(FeeUnbalancedAmount::get(), 5 + 10 + 50);
+		});
+}
+
 #[test]
 fn signed_extension_transaction_payment_multiplied_refund_works() {
 	ExtBuilder::default()
@@ -20,8 +20,9 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg(feature = "try-runtime")]
 
-pub use frame_support::traits::{TryStateSelect, UpgradeCheckSelect};
+pub use frame_support::traits::{BlockHook, TryStateSelect, UpgradeCheckSelect};
 use frame_support::weights::Weight;
+use sp_runtime::traits::Block as BlockT;
 
 sp_api::decl_runtime_apis! {
 	/// Runtime api for testing the execution of a runtime upgrade.
@@ -49,5 +50,12 @@ sp_api::decl_runtime_apis! {
 			signature_check: bool,
 			try_state: TryStateSelect,
 		) -> Weight;
+
+		/// Execute the given hook of the block with the given header on top of the current state,
+		/// returning the weight declared by the hook.
+		///
+		/// Used to profile the hooks of a block one by one, see [`BlockHook`].
+		#[api_version(2)]
+		fn execute_block_hook(header: <Block as BlockT>::Header, hook: BlockHook) -> Weight;
 	}
 }
@@ -30,6 +30,11 @@ pub use types::*;
 
 mod v14;
 mod v15;
+pub use v15::{
+	ExtrinsicCustomMetadata, PalletViewFunctionsCustomMetadata, TransactionExtensionCustomMetadata,
+	ViewFunctionCustomMetadata, ViewFunctionsCustomMetadata, EXTRINSIC_CUSTOM_KEY,
+	VIEW_FUNCTIONS_CUSTOM_KEY,
+};
 
 /// Metadata V14.
 const V14: u32 = 14;
@@ -80,11 +85,13 @@ mod test {
 			extrinsic: ExtrinsicMetadataIR {
 				ty: meta_type::<()>(),
 				version: 0,
+				versions: vec![0],
 				address_ty: meta_type::<()>(),
 				call_ty: meta_type::<()>(),
 				signature_ty: meta_type::<()>(),
 				extra_ty: meta_type::<()>(),
 				signed_extensions: vec![],
+				transaction_extensions: vec![],
 			},
 			ty: meta_type::<()>(),
 			apis: vec![],
@@ -115,4 +122,106 @@ mod test {
 
 		assert!(matches!(metadata.1, RuntimeMetadata::V15(_)));
 	}
+
+	#[test]
+	fn into_version_15_includes_view_functions() {
+		use codec::Decode;
+
+		let mut ir = ir_metadata();
+		ir.pallets.push(PalletMetadataIR {
+			name: "Example",
+			storage: None,
+			calls: None,
+			event: None,
+			constants: vec![],
+			error: None,
+			view_functions: vec![PalletViewFunctionMetadataIR {
+				name: "get_value",
+				id: [1; 32],
+				inputs: vec![PalletViewFunctionParamMetadataIR {
+					name: "key",
+					ty: meta_type::<u32>(),
+				}],
+				output: meta_type::<Option<u64>>(),
+				docs: vec![" Some docs."],
+			}],
+			index: 3,
+			docs: vec![],
+		});
+
+		let metadata = match into_version(ir, V15).expect("Should return prefixed metadata").1 {
+			RuntimeMetadata::V15(metadata) => metadata,
+			_ => panic!("Should return V15 metadata"),
+		};
+
+		let custom = metadata.custom.map.get(VIEW_FUNCTIONS_CUSTOM_KEY).unwrap();
+		assert_eq!(
+			metadata.types.resolve(custom.ty.id).unwrap().path.ident(),
+			Some("ViewFunctionsCustomMetadata".into()),
+		);
+		let view_functions = ViewFunctionsCustomMetadata::decode(&mut &custom.value[..]).unwrap();
+		assert_eq!(view_functions.pallets.len(), 1);
+		assert_eq!(view_functions.pallets[0].name, "Example");
+		assert_eq!(view_functions.pallets[0].index, 3);
+
+		let view_function = &view_functions.pallets[0].view_functions[0];
+		assert_eq!(view_function.name, "get_value");
+		assert_eq!(view_function.id, [1; 32]);
+		assert_eq!(view_function.docs, vec![" Some docs.".to_string()]);
+		let (input_name, input_ty) = &view_function.inputs[0];
+		assert_eq!(input_name, "key");
+		assert_eq!(
+			metadata.types.resolve(input_ty.0).unwrap().type_def,
+			scale_info::TypeDef::Primitive(scale_info::TypeDefPrimitive::U32),
+		);
+		assert_eq!(
+			metadata.types.resolve(view_function.output.0).unwrap().path.ident(),
+			Some("Option".into()),
+		);
+	}
+
+	#[test]
+	fn into_version_15_omits_empty_view_functions() {
+		let metadata = match into_version(ir_metadata(), V15).unwrap().1 {
+			RuntimeMetadata::V15(metadata) => metadata,
+			_ => panic!("Should return V15 metadata"),
+		};
+
+		assert!(metadata.custom.map.is_empty());
+	}
+
+	#[test]
+	fn into_version_15_includes_transaction_extensions() {
+		use codec::Decode;
+
+		let mut ir = ir_metadata();
+		ir.extrinsic.version = 4;
+		ir.extrinsic.versions = vec![4, 5];
+		ir.extrinsic.transaction_extensions = vec![TransactionExtensionMetadataIR {
+			identifier: "CheckGenesis",
+			ty: meta_type::<()>(),
+			implicit: meta_type::<[u8; 32]>(),
+		}];
+
+		let metadata = match into_version(ir, V15).expect("Should return prefixed metadata").1 {
+			RuntimeMetadata::V15(metadata) => metadata,
+			_ => panic!("Should return V15 metadata"),
+		};
+
+		assert_eq!(metadata.extrinsic.version, 4);
+		let custom = metadata.custom.map.get(EXTRINSIC_CUSTOM_KEY).unwrap();
+		assert_eq!(
+			metadata.types.resolve(custom.ty.id).unwrap().path.ident(),
+			Some("ExtrinsicCustomMetadata".into()),
+		);
+		let extrinsic = ExtrinsicCustomMetadata::decode(&mut &custom.value[..]).unwrap();
+		assert_eq!(extrinsic.versions, vec![4, 5]);
+		assert_eq!(extrinsic.transaction_extensions.len(), 1);
+		let extension = &extrinsic.transaction_extensions[0];
+		assert_eq!(extension.identifier, "CheckGenesis");
+		assert!(matches!(
+			metadata.types.resolve(extension.implicit.0).unwrap().type_def,
+			scale_info::TypeDef::Array(_),
+		));
+	}
 }
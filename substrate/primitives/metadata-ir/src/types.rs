@@ -127,6 +127,8 @@ pub struct PalletMetadataIR<T: Form = MetaForm> {
 	pub constants: Vec<PalletConstantMetadataIR<T>>,
 	/// Pallet error metadata.
 	pub error: Option<PalletErrorMetadataIR<T>>,
+	/// Pallet view functions metadata.
+	pub view_functions: Vec<PalletViewFunctionMetadataIR<T>>,
 	/// Define the index of the pallet, this index will be used for the encoding of pallet event,
 	/// call and origin variants.
 	pub index: u8,
@@ -145,6 +147,7 @@ impl IntoPortable for PalletMetadataIR {
 			event: self.event.map(|event| event.into_portable(registry)),
 			constants: registry.map_into_portable(self.constants),
 			error: self.error.map(|error| error.into_portable(registry)),
+			view_functions: registry.map_into_portable(self.view_functions),
 			index: self.index,
 			docs: registry.map_into_portable(self.docs),
 		}
@@ -160,6 +163,10 @@ pub struct ExtrinsicMetadataIR<T: Form = MetaForm> {
 	pub ty: T::Type,
 	/// Extrinsic version.
 	pub version: u8,
+	/// All the extrinsic versions the runtime can decode, including [`Self::version`].
+	///
+	/// Note: Field not exposed by metadata V14 and exposed in the custom metadata of V15.
+	pub versions: Vec<u8>,
 	/// The type of the address that signes the extrinsic
 	pub address_ty: T::Type,
 	/// The type of the outermost Call enum.
@@ -170,6 +177,11 @@ pub struct ExtrinsicMetadataIR<T: Form = MetaForm> {
 	pub extra_ty: T::Type,
 	/// The signed extensions in the order they appear in the extrinsic.
 	pub signed_extensions: Vec<SignedExtensionMetadataIR<T>>,
+	/// The transaction extensions of general extrinsics in the order they appear in the
+	/// extrinsic.
+	///
+	/// Note: Field not exposed by metadata V14 and exposed in the custom metadata of V15.
+	pub transaction_extensions: Vec<TransactionExtensionMetadataIR<T>>,
 }
 
 impl IntoPortable for ExtrinsicMetadataIR {
@@ -179,11 +191,13 @@ impl IntoPortable for ExtrinsicMetadataIR {
 		ExtrinsicMetadataIR {
 			ty: registry.register_type(&self.ty),
 			version: self.version,
+			versions: self.versions,
 			address_ty: registry.register_type(&self.address_ty),
 			call_ty: registry.register_type(&self.call_ty),
 			signature_ty: registry.register_type(&self.signature_ty),
 			extra_ty: registry.register_type(&self.extra_ty),
 			signed_extensions: registry.map_into_portable(self.signed_extensions),
+			transaction_extensions: registry.map_into_portable(self.transaction_extensions),
 		}
 	}
 }
@@ -211,6 +225,29 @@ impl IntoPortable for SignedExtensionMetadataIR {
 	}
 }
 
+/// Metadata of an extrinsic's transaction extension.
+#[derive(Clone, PartialEq, Eq, Encode, Debug)]
+pub struct TransactionExtensionMetadataIR<T: Form = MetaForm> {
+	/// The unique transaction extension identifier, which may be different from the type name.
+	pub identifier: T::String,
+	/// The type of the transaction extension, with the data to be included in the extrinsic.
+	pub ty: T::Type,
+	/// The type of the implicit data, with the data implied by the extrinsic.
+	pub implicit: T::Type,
+}
+
+impl IntoPortable for TransactionExtensionMetadataIR {
+	type Output = TransactionExtensionMetadataIR<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		TransactionExtensionMetadataIR {
+			identifier: self.identifier.into_portable(registry),
+			ty: registry.register_type(&self.ty),
+			implicit: registry.register_type(&self.implicit),
+		}
+	}
+}
+
 /// All metadata of the pallet's storage.
 #[derive(Clone, PartialEq, Eq, Encode, Debug)]
 /// The common prefix used by all storage entries.
@@ -394,6 +431,56 @@ impl IntoPortable for PalletConstantMetadataIR {
 	}
 }
 
+/// Metadata about a pallet view function.
+#[derive(Clone, PartialEq, Eq, Encode, Debug)]
+pub struct PalletViewFunctionMetadataIR<T: Form = MetaForm> {
+	/// Name of the view function.
+	pub name: T::String,
+	/// The unique identifier of the view function, the pallet prefix followed by the function
+	/// suffix.
+	pub id: [u8; 32],
+	/// Parameters of the view function.
+	pub inputs: Vec<PalletViewFunctionParamMetadataIR<T>>,
+	/// Type returned by the view function.
+	pub output: T::Type,
+	/// Documentation of the view function.
+	pub docs: Vec<T::String>,
+}
+
+impl IntoPortable for PalletViewFunctionMetadataIR {
+	type Output = PalletViewFunctionMetadataIR<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		PalletViewFunctionMetadataIR {
+			name: self.name.into_portable(registry),
+			id: self.id,
+			inputs: registry.map_into_portable(self.inputs),
+			output: registry.register_type(&self.output),
+			docs: registry.map_into_portable(self.docs),
+		}
+	}
+}
+
+/// Metadata of a pallet view function parameter.
+#[derive(Clone, PartialEq, Eq, Encode, Debug)]
+pub struct PalletViewFunctionParamMetadataIR<T: Form = MetaForm> {
+	/// Parameter name.
+	pub name: T::String,
+	/// Parameter type.
+	pub ty: T::Type,
+}
+
+impl IntoPortable for PalletViewFunctionParamMetadataIR {
+	type Output = PalletViewFunctionParamMetadataIR<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		PalletViewFunctionParamMetadataIR {
+			name: self.name.into_portable(registry),
+			ty: registry.register_type(&self.ty),
+		}
+	}
+}
+
 /// Metadata about a pallet error.
 #[derive(Clone, PartialEq, Eq, Encode, Debug)]
 pub struct PalletErrorMetadataIR<T: Form = MetaForm> {
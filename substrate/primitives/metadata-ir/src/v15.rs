@@ -20,28 +20,167 @@
 use crate::OuterEnumsIR;
 
 use super::types::{
-	ExtrinsicMetadataIR, MetadataIR, PalletMetadataIR, RuntimeApiMetadataIR,
-	RuntimeApiMethodMetadataIR, RuntimeApiMethodParamMetadataIR, SignedExtensionMetadataIR,
+	ExtrinsicMetadataIR, MetadataIR, PalletMetadataIR, PalletViewFunctionMetadataIR,
+	RuntimeApiMetadataIR, RuntimeApiMethodMetadataIR, RuntimeApiMethodParamMetadataIR,
+	SignedExtensionMetadataIR, TransactionExtensionMetadataIR,
 };
 
+use codec::{Compact, Decode, Encode};
 use frame_metadata::v15::{
-	CustomMetadata, ExtrinsicMetadata, OuterEnums, PalletMetadata, RuntimeApiMetadata,
-	RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata, RuntimeMetadataV15,
-	SignedExtensionMetadata,
+	CustomMetadata, CustomValueMetadata, ExtrinsicMetadata, OuterEnums, PalletMetadata,
+	RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata,
+	RuntimeMetadataV15, SignedExtensionMetadata,
 };
+use scale_info::{
+	form::PortableForm, meta_type, prelude::string::String, IntoPortable, Registry, TypeInfo,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// The key of the view functions in the custom metadata of V15.
+///
+/// The value is a [`ViewFunctionsCustomMetadata`] referencing types of the metadata registry.
+pub const VIEW_FUNCTIONS_CUSTOM_KEY: &str = "view_functions";
+
+/// The view functions of all pallets, as exposed in the custom metadata of V15.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct ViewFunctionsCustomMetadata {
+	/// The pallets declaring view functions, pallets without view functions are omitted.
+	pub pallets: Vec<PalletViewFunctionsCustomMetadata>,
+}
+
+/// The view functions of a pallet, as exposed in the custom metadata of V15.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct PalletViewFunctionsCustomMetadata {
+	/// Name of the pallet.
+	pub name: String,
+	/// Index of the pallet.
+	pub index: u8,
+	/// The view functions of the pallet.
+	pub view_functions: Vec<ViewFunctionCustomMetadata>,
+}
+
+/// A view function, as exposed in the custom metadata of V15.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct ViewFunctionCustomMetadata {
+	/// Name of the view function.
+	pub name: String,
+	/// The unique identifier of the view function.
+	pub id: [u8; 32],
+	/// Parameters of the view function, as pairs of name and type id.
+	pub inputs: Vec<(String, Compact<u32>)>,
+	/// Id of the type returned by the view function.
+	pub output: Compact<u32>,
+	/// Documentation of the view function.
+	pub docs: Vec<String>,
+}
+
+/// The key of the extrinsic versions and transaction extensions in the custom metadata of V15.
+///
+/// The value is an [`ExtrinsicCustomMetadata`] referencing types of the metadata registry.
+pub const EXTRINSIC_CUSTOM_KEY: &str = "extrinsic";
+
+/// The parts of the extrinsic metadata not covered by V15, as exposed in its custom metadata.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct ExtrinsicCustomMetadata {
+	/// All the extrinsic versions the runtime can decode.
+	pub versions: Vec<u8>,
+	/// The transaction extensions of general extrinsics in the order they appear in the
+	/// extrinsic.
+	pub transaction_extensions: Vec<TransactionExtensionCustomMetadata>,
+}
+
+/// A transaction extension, as exposed in the custom metadata of V15.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct TransactionExtensionCustomMetadata {
+	/// The unique transaction extension identifier.
+	pub identifier: String,
+	/// Id of the type of the transaction extension.
+	pub ty: Compact<u32>,
+	/// Id of the type of the implicit data of the transaction extension.
+	pub implicit: Compact<u32>,
+}
+
+impl From<TransactionExtensionMetadataIR<PortableForm>> for TransactionExtensionCustomMetadata {
+	fn from(ir: TransactionExtensionMetadataIR<PortableForm>) -> Self {
+		TransactionExtensionCustomMetadata {
+			identifier: ir.identifier,
+			ty: Compact(ir.ty.id),
+			implicit: Compact(ir.implicit.id),
+		}
+	}
+}
+
+impl From<PalletViewFunctionMetadataIR<PortableForm>> for ViewFunctionCustomMetadata {
+	fn from(ir: PalletViewFunctionMetadataIR<PortableForm>) -> Self {
+		ViewFunctionCustomMetadata {
+			name: ir.name,
+			id: ir.id,
+			inputs: ir.inputs.into_iter().map(|input| (input.name, Compact(input.ty.id))).collect(),
+			output: Compact(ir.output.id),
+			docs: ir.docs,
+		}
+	}
+}
 
 impl From<MetadataIR> for RuntimeMetadataV15 {
 	fn from(ir: MetadataIR) -> Self {
-		RuntimeMetadataV15::new(
-			ir.pallets.into_iter().map(Into::into).collect(),
-			ir.extrinsic.into(),
-			ir.ty,
-			ir.apis.into_iter().map(Into::into).collect(),
-			ir.outer_enums.into(),
-			// Substrate does not collect yet the custom metadata fields.
-			// This allows us to extend the V15 easily.
-			CustomMetadata { map: Default::default() },
-		)
+		let view_functions: Vec<_> = ir
+			.pallets
+			.iter()
+			.filter(|pallet| !pallet.view_functions.is_empty())
+			.map(|pallet| (pallet.name, pallet.index, pallet.view_functions.clone()))
+			.collect();
+		let versions = ir.extrinsic.versions.clone();
+		let transaction_extensions = ir.extrinsic.transaction_extensions.clone();
+
+		// Mirrors `RuntimeMetadataV15::new`, keeping the registry around to register the types
+		// of the view functions and transaction extensions, which are not part of V15 itself,
+		// last.
+		let mut registry = Registry::new();
+		let pallets =
+			registry.map_into_portable(ir.pallets.into_iter().map(Into::<PalletMetadata>::into));
+		let extrinsic = ExtrinsicMetadata::from(ir.extrinsic).into_portable(&mut registry);
+		let ty = registry.register_type(&ir.ty);
+		let apis =
+			registry.map_into_portable(ir.apis.into_iter().map(Into::<RuntimeApiMetadata>::into));
+		let outer_enums = OuterEnums::from(ir.outer_enums).into_portable(&mut registry);
+
+		let mut custom = BTreeMap::new();
+		if !view_functions.is_empty() {
+			let pallets = view_functions
+				.into_iter()
+				.map(|(name, index, view_functions)| PalletViewFunctionsCustomMetadata {
+					name: name.into(),
+					index,
+					view_functions: view_functions
+						.into_iter()
+						.map(|view_function| view_function.into_portable(&mut registry).into())
+						.collect(),
+				})
+				.collect();
+			let value = ViewFunctionsCustomMetadata { pallets }.encode();
+			let ty = registry.register_type(&meta_type::<ViewFunctionsCustomMetadata>());
+			custom.insert(VIEW_FUNCTIONS_CUSTOM_KEY.into(), CustomValueMetadata { ty, value });
+		}
+		if !transaction_extensions.is_empty() || versions.iter().any(|v| *v != extrinsic.version) {
+			let transaction_extensions = transaction_extensions
+				.into_iter()
+				.map(|extension| extension.into_portable(&mut registry).into())
+				.collect();
+			let value = ExtrinsicCustomMetadata { versions, transaction_extensions }.encode();
+			let ty = registry.register_type(&meta_type::<ExtrinsicCustomMetadata>());
+			custom.insert(EXTRINSIC_CUSTOM_KEY.into(), CustomValueMetadata { ty, value });
+		}
+
+		RuntimeMetadataV15 {
+			types: registry.into(),
+			pallets,
+			extrinsic,
+			ty,
+			apis,
+			outer_enums,
+			custom: CustomMetadata { map: custom },
+		}
 	}
 }
 
@@ -83,6 +222,7 @@ impl From<PalletMetadataIR> for PalletMetadata {
 			error: ir.error.map(Into::into),
 			index: ir.index,
 			docs: ir.docs,
+			// Note: ir.view_functions is exposed in the custom metadata.
 		}
 	}
 }
@@ -106,6 +246,7 @@ impl From<ExtrinsicMetadataIR> for ExtrinsicMetadata {
 			signature_ty: ir.signature_ty,
 			extra_ty: ir.extra_ty,
 			signed_extensions: ir.signed_extensions.into_iter().map(Into::into).collect(),
+			// Note: ir.versions and ir.transaction_extensions are exposed in the custom metadata.
 		}
 	}
 }
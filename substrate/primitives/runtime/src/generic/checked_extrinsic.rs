@@ -20,32 +20,48 @@
 
 use crate::{
 	traits::{
-		self, DispatchInfoOf, Dispatchable, MaybeDisplay, Member, PostDispatchInfoOf,
-		SignedExtension, ValidateUnsigned,
+		self, AsTransactionAuthorizedOrigin, DispatchInfoOf, DispatchTransaction, Dispatchable,
+		MaybeDisplay, Member, PostDispatchInfoOf, SignedExtension, TransactionExtension,
+		ValidateUnsigned,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 };
+use codec::Encode;
+
+/// The kind of extrinsic this is, including any fields required of that kind.
+#[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
+pub enum ExtrinsicFormat<AccountId, Extra, Extension> {
+	/// Extrinsic is bare; it must pass a [`ValidateUnsigned`] check.
+	Bare,
+	/// Extrinsic has a signer, who is the origin of the call, and signed extensions.
+	Signed(AccountId, Extra),
+	/// Extrinsic is authorized by its transaction extensions, which provide the origin of the
+	/// call.
+	General(Extension),
+}
 
 /// Definition of something that the external world might want to say; its
 /// existence implies that it has been checked and is good, particularly with
 /// regards to the signature.
 #[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
-pub struct CheckedExtrinsic<AccountId, Call, Extra> {
+pub struct CheckedExtrinsic<AccountId, Call, Extra, Extension = ()> {
 	/// Who this purports to be from and the number of extrinsics have come before
-	/// from the same signer, if anyone (note this is not a signature).
-	pub signed: Option<(AccountId, Extra)>,
+	/// from the same signer, if anyone (note this is not a signature), or the transaction
+	/// extensions authorizing a general extrinsic.
+	pub format: ExtrinsicFormat<AccountId, Extra, Extension>,
 
 	/// The function that should be called.
 	pub function: Call,
 }
 
-impl<AccountId, Call, Extra, RuntimeOrigin> traits::Applyable
-	for CheckedExtrinsic<AccountId, Call, Extra>
+impl<AccountId, Call, Extra, Extension, RuntimeOrigin> traits::Applyable
+	for CheckedExtrinsic<AccountId, Call, Extra, Extension>
 where
 	AccountId: Member + MaybeDisplay,
-	Call: Member + Dispatchable<RuntimeOrigin = RuntimeOrigin>,
+	Call: Member + Dispatchable<RuntimeOrigin = RuntimeOrigin> + Encode,
 	Extra: SignedExtension<AccountId = AccountId, Call = Call>,
-	RuntimeOrigin: From<Option<AccountId>>,
+	Extension: TransactionExtension<Call>,
+	RuntimeOrigin: From<Option<AccountId>> + AsTransactionAuthorizedOrigin,
 {
 	type Call = Call;
 
@@ -57,12 +73,17 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> TransactionValidity {
-		if let Some((ref id, ref extra)) = self.signed {
-			Extra::validate(extra, id, &self.function, info, len)
-		} else {
-			let valid = Extra::validate_unsigned(&self.function, info, len)?;
-			let unsigned_validation = U::validate_unsigned(source, &self.function)?;
-			Ok(valid.combine_with(unsigned_validation))
+		match self.format {
+			ExtrinsicFormat::Bare => {
+				let valid = Extra::validate_unsigned(&self.function, info, len)?;
+				let unsigned_validation = U::validate_unsigned(source, &self.function)?;
+				Ok(valid.combine_with(unsigned_validation))
+			},
+			ExtrinsicFormat::Signed(ref id, ref extra) =>
+				Extra::validate(extra, id, &self.function, info, len),
+			ExtrinsicFormat::General(ref extension) => extension
+				.validate_only(RuntimeOrigin::from(None), &self.function, info, len)
+				.map(|(valid, _, _)| valid),
 		}
 	}
 
@@ -71,13 +92,23 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Self::Call>> {
-		let (maybe_who, maybe_pre) = if let Some((id, extra)) = self.signed {
-			let pre = Extra::pre_dispatch(extra, &id, &self.function, info, len)?;
-			(Some(id), Some(pre))
-		} else {
-			Extra::pre_dispatch_unsigned(&self.function, info, len)?;
-			U::pre_dispatch(&self.function)?;
-			(None, None)
+		let (maybe_who, maybe_pre) = match self.format {
+			ExtrinsicFormat::Bare => {
+				Extra::pre_dispatch_unsigned(&self.function, info, len)?;
+				U::pre_dispatch(&self.function)?;
+				(None, None)
+			},
+			ExtrinsicFormat::Signed(id, extra) => {
+				let pre = Extra::pre_dispatch(extra, &id, &self.function, info, len)?;
+				(Some(id), Some(pre))
+			},
+			ExtrinsicFormat::General(extension) =>
+				return extension.dispatch_transaction(
+					RuntimeOrigin::from(None),
+					self.function,
+					info,
+					len,
+				),
 		};
 		let res = self.function.dispatch(RuntimeOrigin::from(maybe_who));
 		let post_info = match res {
@@ -365,6 +365,7 @@ where
 	Extra: SignedExtension<AccountId = u64, Call = Call>,
 {
 	type SignedExtensions = Extra;
+	type TransactionExtensions = ();
 	const VERSION: u8 = 0u8;
 }
 
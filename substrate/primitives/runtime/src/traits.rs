@@ -52,6 +52,12 @@ use std::fmt::Display;
 #[cfg(feature = "std")]
 use std::str::FromStr;
 
+mod transaction_extension;
+pub use transaction_extension::{
+	AsSystemOriginSigner, AsTransactionAuthorizedOrigin, AsTransactionExtension,
+	DispatchTransaction, TransactionExtension, ValidateResult,
+};
+
 /// A lazy value.
 pub trait Lazy<T: ?Sized> {
 	/// Get a reference to the underlying value.
@@ -1340,8 +1346,15 @@ pub trait ExtrinsicMetadata {
 	/// By format is meant the encoded representation of the `Extrinsic`.
 	const VERSION: u8;
 
+	/// All the format versions of the `Extrinsic` that can be decoded, [`Self::VERSION`] being the
+	/// version of bare and signed extrinsics.
+	const VERSIONS: &'static [u8] = &[Self::VERSION];
+
 	/// Signed extensions attached to this `Extrinsic`.
 	type SignedExtensions: SignedExtension;
+
+	/// Transaction extensions authorizing the general transactions of this `Extrinsic`.
+	type TransactionExtensions;
 }
 
 /// Extract the hashing type for a block.
@@ -1430,6 +1443,8 @@ pub trait Dispatchable {
 pub type DispatchInfoOf<T> = <T as Dispatchable>::Info;
 /// Shortcut to reference the `PostInfo` type of a `Dispatchable`.
 pub type PostDispatchInfoOf<T> = <T as Dispatchable>::PostInfo;
+/// Shortcut to reference the `RuntimeOrigin` type of a `Dispatchable`.
+pub type DispatchOriginOf<T> = <T as Dispatchable>::RuntimeOrigin;
 
 impl Dispatchable for () {
 	type RuntimeOrigin = ();
@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The [`AsTransactionExtension`] adapter struct for adapting [`SignedExtension`]s to
+//! [`TransactionExtension`]s.
+
+use crate::{
+	scale_info::{StaticTypeInfo, TypeInfo},
+	traits::{
+		AsSystemOriginSigner, DispatchInfoOf, DispatchOriginOf, PostDispatchInfoOf,
+		SignedExtension, SignedExtensionMetadata,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	DispatchResult,
+};
+use codec::{Decode, Encode};
+use sp_core::RuntimeDebug;
+use sp_std::prelude::*;
+
+use super::{TransactionExtension, ValidateResult};
+
+/// Adapter to use a [`SignedExtension`] as a [`TransactionExtension`].
+///
+/// The signed extension is given the account of the signed origin returned by the preceding
+/// extensions of the pipeline and fails with [`InvalidTransaction::BadSigner`] if the origin is
+/// not signed. The origin is passed on unchanged. The implicit data of the extension is its
+/// additional signed data.
+#[derive(TypeInfo, Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct AsTransactionExtension<SE: SignedExtension>(pub SE);
+
+impl<SE: SignedExtension + Default> Default for AsTransactionExtension<SE> {
+	fn default() -> Self {
+		Self(SE::default())
+	}
+}
+
+impl<SE: SignedExtension> From<SE> for AsTransactionExtension<SE> {
+	fn from(value: SE) -> Self {
+		Self(value)
+	}
+}
+
+impl<SE: SignedExtension> TransactionExtension<SE::Call> for AsTransactionExtension<SE>
+where
+	SE::AdditionalSigned: StaticTypeInfo,
+	DispatchOriginOf<SE::Call>: AsSystemOriginSigner<SE::AccountId>,
+{
+	const IDENTIFIER: &'static str = SE::IDENTIFIER;
+	type Implicit = SE::AdditionalSigned;
+	type Val = ();
+	type Pre = SE::Pre;
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		self.0.additional_signed()
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, SE::Call> {
+		let who = origin.as_system_origin_signer().ok_or(InvalidTransaction::BadSigner)?;
+		let valid = SignedExtension::validate(&self.0, who, call, info, len)?;
+		Ok((valid, (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		origin: &DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let who = origin.as_system_origin_signer().ok_or(InvalidTransaction::BadSigner)?;
+		self.0.pre_dispatch(who, call, info, len)
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<SE::Call>,
+		post_info: &PostDispatchInfoOf<SE::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		SE::post_dispatch(Some(pre), info, post_info, len, result)
+	}
+
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		SE::metadata()
+	}
+}
@@ -0,0 +1,602 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The [`TransactionExtension`] trait and associated traits.
+//!
+//! Transaction extensions validate and prepare a transaction before it is dispatched, in the same
+//! way as a [`SignedExtension`](super::SignedExtension), but are not tied to a signed origin:
+//! each extension receives the origin of the transaction and returns the origin the next
+//! extension, and eventually the call, is dispatched with. This allows a pipeline of extensions,
+//! e.g. a tuple of extensions, to authorize a "general" transaction by other means than a
+//! signature of the whole transaction, such as a proof or a multisig carried in an extension.
+
+use crate::{
+	scale_info::StaticTypeInfo,
+	traits::{
+		DispatchInfoOf, DispatchOriginOf, Dispatchable, PostDispatchInfoOf, SignedExtensionMetadata,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+	DispatchResult,
+};
+use codec::{Codec, Encode, Output};
+use impl_trait_for_tuples::impl_for_tuples;
+use sp_std::{fmt::Debug, prelude::*};
+
+mod as_transaction_extension;
+
+pub use as_transaction_extension::AsTransactionExtension;
+
+/// Something that can extract the account id of a signed origin.
+///
+/// Implemented by the `RuntimeOrigin` generated by `construct_runtime!`.
+pub trait AsSystemOriginSigner<AccountId> {
+	/// Returns the account id of the signer if the origin is a signed system origin.
+	fn as_system_origin_signer(&self) -> Option<&AccountId>;
+}
+
+/// Something that can tell whether an origin is allowed to dispatch a transaction.
+///
+/// Implemented by the `RuntimeOrigin` generated by `construct_runtime!`.
+pub trait AsTransactionAuthorizedOrigin {
+	/// Whether the origin is authorized to dispatch a transaction.
+	///
+	/// The origin of a general transaction is checked after all of its transaction extensions
+	/// have been validated. An origin that none of the extensions authorized, i.e. the `None`
+	/// origin, must return `false`.
+	fn is_transaction_authorized(&self) -> bool;
+}
+
+/// The result of [`TransactionExtension::validate`]: the validity of the transaction, the value
+/// passed on to [`TransactionExtension::prepare`] and the origin the transaction is dispatched
+/// with.
+pub type ValidateResult<Val, Call> =
+	Result<(ValidTransaction, Val, DispatchOriginOf<Call>), TransactionValidityError>;
+
+/// Means by which a transaction may be extended. This type embodies both the data and the logic
+/// that should be additionally associated with the transaction. It should be plain old data.
+///
+/// Unlike a [`SignedExtension`](super::SignedExtension), a transaction extension receives the
+/// origin of the transaction and may transform it. Extensions are composed as a pipeline by using
+/// a tuple of extensions: every extension is given the origin returned by the previous one and
+/// the origin returned by the last one is used to dispatch the call.
+pub trait TransactionExtension<Call: Dispatchable>:
+	Codec + Debug + Sync + Send + Clone + Eq + PartialEq + StaticTypeInfo
+{
+	/// Unique identifier of this transaction extension.
+	///
+	/// This will be exposed in the metadata to identify the transaction extension used in an
+	/// extrinsic.
+	const IDENTIFIER: &'static str;
+
+	/// Any additional data that is not part of the transaction, but that the transaction implies,
+	/// e.g. the genesis hash of the chain.
+	///
+	/// The implicit data of an extension is part of the implication of all the extensions
+	/// preceding it in a pipeline.
+	type Implicit: Encode + StaticTypeInfo;
+
+	/// The type that encodes information that is passed from [`Self::validate`] to
+	/// [`Self::prepare`].
+	type Val;
+
+	/// The type that encodes information that is passed from [`Self::prepare`] to
+	/// [`Self::post_dispatch`].
+	type Pre;
+
+	/// Construct the implicit data of this extension. Can also perform any checks not depending
+	/// on the transaction and return an error if needed.
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError>;
+
+	/// Validate a transaction for the transaction queue.
+	///
+	/// This function can be called frequently by the transaction queue to obtain transaction
+	/// validity against current state. It should perform all checks that determine a valid
+	/// transaction, that can pay for its execution and quickly eliminate ones that are stale or
+	/// incorrect.
+	///
+	/// `origin` is the origin returned by the previous extension of the pipeline. The returned
+	/// origin is passed on to the next extension and eventually used to dispatch the call.
+	///
+	/// `inherited_implication` is everything the transaction implies from the point of view of
+	/// this extension: the explicit and implicit data of all the following extensions of the
+	/// pipeline followed by the call. An extension authorizing the transaction through a
+	/// signature should check the signature against the encoding of `inherited_implication`.
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call>;
+
+	/// Do any pre-flight stuff for a transaction after validation.
+	///
+	/// This is for actions which do not happen in the transaction queue, e.g. withdrawing fees.
+	/// Make sure to not repeat the checks of [`Self::validate`], which is always called before.
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError>;
+
+	/// Do any post-flight stuff for a transaction.
+	///
+	/// This gets given the `DispatchResult` `_result` from the extrinsic and can, if desired,
+	/// introduce a `TransactionValidityError`, causing the block to become invalid for including
+	/// it.
+	///
+	/// WARNING: It is dangerous to return an error here. To do so will fundamentally invalidate the
+	/// transaction and any block that it is included in, causing the block author to not be
+	/// compensated for their work in validating the transaction or producing the block so far.
+	fn post_dispatch(
+		_pre: Self::Pre,
+		_info: &DispatchInfoOf<Call>,
+		_post_info: &PostDispatchInfoOf<Call>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	/// Returns the metadata for this transaction extension.
+	///
+	/// As a [`TransactionExtension`] can be a tuple of [`TransactionExtension`]s we need to return
+	/// a `Vec` that holds the metadata of each one. Each individual `TransactionExtension` must
+	/// return *exactly* one [`SignedExtensionMetadata`], whose `additional_signed` type is the
+	/// [`Self::Implicit`] type.
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		sp_std::vec![SignedExtensionMetadata {
+			identifier: Self::IDENTIFIER,
+			ty: scale_info::meta_type::<Self>(),
+			additional_signed: scale_info::meta_type::<Self::Implicit>()
+		}]
+	}
+}
+
+/// The implication of an extension within a tuple of extensions.
+///
+/// Encodes as the tuple of the explicit data of the following extensions, the implicit data of
+/// the following extensions and the implication inherited by the tuple.
+struct Implication<'a, I> {
+	explicit: &'a [Vec<u8>],
+	implicit: &'a [Vec<u8>],
+	inherited: &'a I,
+}
+
+impl<'a, I: Encode> Encode for Implication<'a, I> {
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		self.explicit
+			.iter()
+			.chain(self.implicit.iter())
+			.for_each(|item| dest.write(item));
+		self.inherited.encode_to(dest);
+	}
+}
+
+#[impl_for_tuples(1, 12)]
+impl<Call: Dispatchable> TransactionExtension<Call> for Tuple {
+	for_tuples!( where #( Tuple: TransactionExtension<Call> )* );
+	const IDENTIFIER: &'static str = "Use `metadata()`!";
+	for_tuples!( type Implicit = ( #( Tuple::Implicit ),* ); );
+	for_tuples!( type Val = ( #( Tuple::Val ),* ); );
+	for_tuples!( type Pre = ( #( Tuple::Pre ),* ); );
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		Ok(for_tuples!( ( #( Tuple.implicit()? ),* ) ))
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		let explicit = for_tuples!( [ #( Tuple.encode() ),* ] );
+		let implicit = for_tuples!( [ #( Tuple.implicit()?.encode() ),* ] );
+
+		let mut valid = ValidTransaction::default();
+		let mut origin = origin;
+		let mut following = 0;
+		let val = for_tuples!( ( #( {
+			following += 1;
+			let implication = Implication {
+				explicit: &explicit[following..],
+				implicit: &implicit[following..],
+				inherited: inherited_implication,
+			};
+			let (item_valid, item_val, item_origin) =
+				Tuple.validate(origin, call, info, len, &implication)?;
+			valid = valid.combine_with(item_valid);
+			origin = item_origin;
+			item_val
+		} ),* ) );
+
+		Ok((valid, val, origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(
+			for_tuples!( ( #( Tuple::prepare(self.Tuple, val.Tuple, origin, call, info, len)? ),* ) ),
+		)
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Call>,
+		post_info: &PostDispatchInfoOf<Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		for_tuples!( #( Tuple::post_dispatch(pre.Tuple, info, post_info, len, result)?; )* );
+		Ok(())
+	}
+
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		let mut ids = Vec::new();
+		for_tuples!( #( ids.extend(Tuple::metadata()); )* );
+		ids
+	}
+}
+
+impl<Call: Dispatchable> TransactionExtension<Call> for () {
+	const IDENTIFIER: &'static str = "UnitTransactionExtension";
+	type Implicit = ();
+	type Val = ();
+	type Pre = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		_call: &Call,
+		_info: &DispatchInfoOf<Call>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<Call>,
+		_call: &Call,
+		_info: &DispatchInfoOf<Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// Validate, prepare and dispatch a transaction through a [`TransactionExtension`].
+///
+/// Implemented for all transaction extensions.
+pub trait DispatchTransaction<Call: Dispatchable> {
+	/// The value passed from validation to preparation.
+	type Val;
+	/// The value passed from preparation to post dispatch.
+	type Pre;
+
+	/// Validate the transaction without preparing it.
+	///
+	/// Fails with [`InvalidTransaction::UnknownOrigin`] if the resulting origin is not authorized
+	/// to dispatch a transaction.
+	fn validate_only(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> ValidateResult<Self::Val, Call>;
+
+	/// Validate and prepare the transaction, returning the value for post dispatch and the origin
+	/// to dispatch the call with.
+	fn validate_and_prepare(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<(Self::Pre, DispatchOriginOf<Call>), TransactionValidityError>;
+
+	/// Validate, prepare and dispatch the transaction, then run the post dispatch logic.
+	fn dispatch_transaction(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Call>>;
+}
+
+impl<T, Call> DispatchTransaction<Call> for T
+where
+	T: TransactionExtension<Call>,
+	Call: Dispatchable + Encode,
+	DispatchOriginOf<Call>: AsTransactionAuthorizedOrigin,
+{
+	type Val = T::Val;
+	type Pre = T::Pre;
+
+	fn validate_only(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> ValidateResult<Self::Val, Call> {
+		let (valid, val, origin) = self.validate(origin, call, info, len, call)?;
+		if !origin.is_transaction_authorized() {
+			return Err(InvalidTransaction::UnknownOrigin.into())
+		}
+		Ok((valid, val, origin))
+	}
+
+	fn validate_and_prepare(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<(Self::Pre, DispatchOriginOf<Call>), TransactionValidityError> {
+		let (_, val, origin) = self.validate_only(origin, call, info, len)?;
+		let pre = self.prepare(val, &origin, call, info, len)?;
+		Ok((pre, origin))
+	}
+
+	fn dispatch_transaction(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Call>> {
+		let (pre, origin) = self.validate_and_prepare(origin, &call, info, len)?;
+		let res = call.dispatch(origin);
+		let post_info = match res {
+			Ok(info) => info,
+			Err(err) => err.post_info,
+		};
+		T::post_dispatch(pre, info, &post_info, len, &res.map(|_| ()).map_err(|e| e.error))?;
+		Ok(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Decode;
+	use scale_info::TypeInfo;
+	use std::cell::RefCell;
+
+	/// The origin of [`TestCall`]: `None` or signed by an account.
+	#[derive(Clone, Debug, PartialEq, Eq)]
+	struct TestOrigin(Option<u64>);
+
+	impl AsTransactionAuthorizedOrigin for TestOrigin {
+		fn is_transaction_authorized(&self) -> bool {
+			self.0.is_some()
+		}
+	}
+
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+	struct TestCall(u8);
+
+	impl Dispatchable for TestCall {
+		type RuntimeOrigin = TestOrigin;
+		type Config = ();
+		type Info = ();
+		type PostInfo = ();
+
+		fn dispatch(self, origin: TestOrigin) -> crate::DispatchResultWithInfo<()> {
+			DISPATCHED.with(|d| d.borrow_mut().push((origin, self)));
+			Ok(())
+		}
+	}
+
+	thread_local! {
+		static DISPATCHED: RefCell<Vec<(TestOrigin, TestCall)>> = Default::default();
+		static IMPLICATIONS: RefCell<Vec<Vec<u8>>> = Default::default();
+	}
+
+	/// Authorizes the transaction as `who`, recording the implication it was given.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	struct Authorize(u64);
+
+	impl TransactionExtension<TestCall> for Authorize {
+		const IDENTIFIER: &'static str = "Authorize";
+		type Implicit = u8;
+		type Val = ();
+		type Pre = ();
+
+		fn implicit(&self) -> Result<u8, TransactionValidityError> {
+			Ok(42)
+		}
+
+		fn validate(
+			&self,
+			_origin: TestOrigin,
+			_call: &TestCall,
+			_info: &(),
+			_len: usize,
+			inherited_implication: &impl Encode,
+		) -> ValidateResult<(), TestCall> {
+			IMPLICATIONS.with(|i| i.borrow_mut().push(inherited_implication.encode()));
+			Ok((ValidTransaction::default(), (), TestOrigin(Some(self.0))))
+		}
+
+		fn prepare(
+			self,
+			_val: (),
+			_origin: &TestOrigin,
+			_call: &TestCall,
+			_info: &(),
+			_len: usize,
+		) -> Result<(), TransactionValidityError> {
+			Ok(())
+		}
+	}
+
+	/// Requires a signed origin, rejecting the transaction otherwise.
+	#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	struct RequireSigned(u16);
+
+	impl TransactionExtension<TestCall> for RequireSigned {
+		const IDENTIFIER: &'static str = "RequireSigned";
+		type Implicit = ();
+		type Val = u64;
+		type Pre = u64;
+
+		fn implicit(&self) -> Result<(), TransactionValidityError> {
+			Ok(())
+		}
+
+		fn validate(
+			&self,
+			origin: TestOrigin,
+			_call: &TestCall,
+			_info: &(),
+			_len: usize,
+			inherited_implication: &impl Encode,
+		) -> ValidateResult<u64, TestCall> {
+			IMPLICATIONS.with(|i| i.borrow_mut().push(inherited_implication.encode()));
+			let who = origin.0.ok_or(InvalidTransaction::BadSigner)?;
+			Ok((ValidTransaction { priority: self.0.into(), ..Default::default() }, who, origin))
+		}
+
+		fn prepare(
+			self,
+			val: u64,
+			_origin: &TestOrigin,
+			_call: &TestCall,
+			_info: &(),
+			_len: usize,
+		) -> Result<u64, TransactionValidityError> {
+			Ok(val)
+		}
+	}
+
+	fn take_implications() -> Vec<Vec<u8>> {
+		IMPLICATIONS.with(|i| std::mem::take(&mut *i.borrow_mut()))
+	}
+
+	#[test]
+	fn pipeline_transforms_origin() {
+		let call = TestCall(1);
+
+		let (valid, val, origin) = (Authorize(7), RequireSigned(3))
+			.validate_only(TestOrigin(None), &call, &(), 0)
+			.unwrap();
+		assert_eq!(valid.priority, 3);
+		assert_eq!(val, ((), 7));
+		assert_eq!(origin, TestOrigin(Some(7)));
+
+		// The order of the pipeline matters.
+		assert_eq!(
+			(RequireSigned(3), Authorize(7)).validate_only(TestOrigin(None), &call, &(), 0),
+			Err(InvalidTransaction::BadSigner.into()),
+		);
+	}
+
+	#[test]
+	fn implication_covers_following_extensions_and_call() {
+		let call = TestCall(1);
+		let _ = (Authorize(7), RequireSigned(3)).validate_only(TestOrigin(None), &call, &(), 0);
+
+		assert_eq!(
+			take_implications(),
+			vec![
+				// `Authorize` implies the explicit and implicit data of `RequireSigned` and the
+				// call.
+				(RequireSigned(3), (), &call).encode(),
+				// `RequireSigned` implies the call.
+				call.encode(),
+			],
+		);
+
+		// The implication of nested pipelines is the same as the one of a flat pipeline.
+		let _ = (Authorize(7), (RequireSigned(3), RequireSigned(4))).validate_only(
+			TestOrigin(None),
+			&call,
+			&(),
+			0,
+		);
+		assert_eq!(
+			take_implications(),
+			vec![
+				(RequireSigned(3), RequireSigned(4), (), (), &call).encode(),
+				(RequireSigned(4), (), &call).encode(),
+				call.encode(),
+			],
+		);
+	}
+
+	#[test]
+	fn unauthorized_origin_is_rejected() {
+		assert_eq!(
+			().validate_only(TestOrigin(None), &TestCall(1), &(), 0),
+			Err(InvalidTransaction::UnknownOrigin.into()),
+		);
+		assert_eq!(
+			().dispatch_transaction(TestOrigin(None), TestCall(1), &(), 0),
+			Err(InvalidTransaction::UnknownOrigin.into()),
+		);
+	}
+
+	#[test]
+	fn dispatch_transaction_uses_transformed_origin() {
+		assert_eq!(
+			(Authorize(7), RequireSigned(3)).dispatch_transaction(
+				TestOrigin(None),
+				TestCall(1),
+				&(),
+				0
+			),
+			Ok(Ok(())),
+		);
+		assert_eq!(
+			DISPATCHED.with(|d| d.borrow().clone()),
+			vec![(TestOrigin(Some(7)), TestCall(1))],
+		);
+	}
+
+	#[test]
+	fn metadata_lists_every_extension() {
+		let metadata = <(Authorize, RequireSigned) as TransactionExtension<TestCall>>::metadata();
+		assert_eq!(
+			metadata.iter().map(|m| m.identifier).collect::<Vec<_>>(),
+			vec!["Authorize", "RequireSigned"],
+		);
+		assert_eq!(metadata[0].additional_signed, scale_info::meta_type::<u8>());
+	}
+}
@@ -82,6 +82,9 @@ pub enum InvalidTransaction {
 	MandatoryValidation,
 	/// The sending address is disabled or known to be invalid.
 	BadSigner,
+	/// The origin resulting from the transaction extensions of a general transaction is not
+	/// authorized to dispatch a transaction.
+	UnknownOrigin,
 }
 
 impl InvalidTransaction {
@@ -113,6 +116,8 @@ impl From<InvalidTransaction> for &'static str {
 				"Transaction dispatch is mandatory; transactions must not be validated.",
 			InvalidTransaction::Custom(_) => "InvalidTransaction custom error",
 			InvalidTransaction::BadSigner => "Invalid signing address",
+			InvalidTransaction::UnknownOrigin =>
+				"The transaction extensions did not authorize any origin",
 		}
 	}
 }
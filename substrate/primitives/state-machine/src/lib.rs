@@ -169,7 +169,7 @@ mod execution {
 	};
 	use sp_externalities::Extensions;
 	use sp_trie::PrefixedMemoryDB;
-	use std::collections::{HashMap, HashSet};
+	use std::collections::{BTreeMap, HashMap, HashSet};
 
 	pub(crate) type CallResult<E> = Result<Vec<u8>, E>;
 
@@ -820,6 +820,102 @@ mod execution {
 			.expect("A recorder was set and thus, a storage proof can be extracted; qed"))
 	}
 
+	/// Keys and key prefixes of a single trie, to be proven by [`prove_keys_and_prefixes`].
+	#[derive(Debug, Default, Clone, PartialEq, Eq)]
+	pub struct KeysAndPrefixes {
+		/// Keys whose value, or absence, is proven.
+		pub keys: Vec<Vec<u8>>,
+		/// Prefixes whose whole content is proven.
+		pub prefixes: Vec<Vec<u8>>,
+	}
+
+	/// Values read from a proof by [`read_compact_proof_check`].
+	#[derive(Debug, Default, Clone, PartialEq, Eq)]
+	pub struct ProvenValues {
+		/// Values of the top trie, `None` for the proven absent keys.
+		pub top: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+		/// Values of the child tries, keyed by their unprefixed storage key.
+		pub children: BTreeMap<Vec<u8>, BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+	}
+
+	/// Generate storage read proof of the given keys and of all the entries under the given
+	/// prefixes, both in the top trie and in the given child tries.
+	pub fn prove_keys_and_prefixes<B, H>(
+		backend: B,
+		top: &KeysAndPrefixes,
+		children: &[(ChildInfo, KeysAndPrefixes)],
+	) -> Result<StorageProof, Box<dyn Error>>
+	where
+		B: AsTrieBackend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend();
+		prove_keys_and_prefixes_on_trie_backend(trie_backend, top, children)
+	}
+
+	/// Generate storage read proof of keys and prefixes on pre-created trie backend.
+	///
+	/// See [`prove_keys_and_prefixes`].
+	pub fn prove_keys_and_prefixes_on_trie_backend<S, H>(
+		trie_backend: &TrieBackend<S, H>,
+		top: &KeysAndPrefixes,
+		children: &[(ChildInfo, KeysAndPrefixes)],
+	) -> Result<StorageProof, Box<dyn Error>>
+	where
+		S: trie_backend_essence::TrieBackendStorage<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend =
+			TrieBackendBuilder::wrap(trie_backend).with_recorder(Default::default()).build();
+		read_keys_and_prefixes(&proving_backend, None, top)?;
+		for (child_info, query) in children {
+			read_keys_and_prefixes(&proving_backend, Some(child_info), query)?;
+		}
+
+		Ok(proving_backend
+			.extract_proof()
+			.expect("A recorder was set and thus, a storage proof can be extracted; qed"))
+	}
+
+	/// Reads the given keys and iterates over the given prefixes of a single trie.
+	fn read_keys_and_prefixes<B, H>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		query: &KeysAndPrefixes,
+	) -> Result<BTreeMap<Vec<u8>, Option<Vec<u8>>>, Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+	{
+		let mut values = BTreeMap::new();
+		for key in &query.keys {
+			let value = match child_info {
+				Some(child_info) => backend.child_storage(child_info, key),
+				None => backend.storage(key),
+			}
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+			values.insert(key.clone(), value);
+		}
+
+		for prefix in &query.prefixes {
+			let pairs = backend
+				.pairs(IterArgs {
+					child_info: child_info.cloned(),
+					prefix: Some(prefix.as_slice()),
+					..IterArgs::default()
+				})
+				.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+			for pair in pairs {
+				let (key, value) = pair.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+				values.insert(key, Some(value));
+			}
+		}
+
+		Ok(values)
+	}
+
 	/// Check storage read proof, generated by `prove_read` call.
 	pub fn read_proof_check<H, I>(
 		root: H::Out,
@@ -908,6 +1004,41 @@ mod execution {
 		Ok(result)
 	}
 
+	/// Check compact storage read proof of keys and prefixes, generated from the proof returned
+	/// by `prove_keys_and_prefixes`.
+	///
+	/// Fails if the proof doesn't match the `root` or if any of the keys, or any entry under the
+	/// prefixes, is missing from it.
+	pub fn read_compact_proof_check<H>(
+		root: H::Out,
+		proof: CompactProof,
+		top: &KeysAndPrefixes,
+		children: &[(ChildInfo, KeysAndPrefixes)],
+	) -> Result<ProvenValues, Box<dyn Error>>
+	where
+		H: Hasher + 'static,
+		H::Out: Ord + Codec,
+	{
+		let (proof, _) = proof
+			.to_storage_proof::<H>(Some(&root))
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+
+		let mut values = ProvenValues {
+			top: read_keys_and_prefixes(&proving_backend, None, top)?,
+			children: BTreeMap::new(),
+		};
+		for (child_info, query) in children {
+			let child_values = read_keys_and_prefixes(&proving_backend, Some(child_info), query)?;
+			values
+				.children
+				.entry(child_info.storage_key().to_vec())
+				.or_default()
+				.extend(child_values);
+		}
+		Ok(values)
+	}
+
 	/// Check storage read proof on pre-created proving backend.
 	pub fn read_proof_check_on_proving_backend<H>(
 		proving_backend: &TrieBackend<MemoryDB<H>, H>,
@@ -1578,6 +1709,67 @@ mod tests {
 		assert_eq!(local_result3.into_iter().collect::<Vec<_>>(), vec![(b"dummy".to_vec(), None)]);
 	}
 
+	#[test]
+	fn prove_keys_and_prefixes_and_compact_proof_check_works() {
+		let state_version = StateVersion::V1;
+		let child_info = ChildInfo::new_default(b"sub1");
+		let remote_backend = trie_backend::tests::test_trie(state_version, None, None);
+		let remote_root = remote_backend.storage_root(std::iter::empty(), state_version).0;
+
+		let top = KeysAndPrefixes {
+			keys: vec![b"key".to_vec(), b"missing".to_vec()],
+			prefixes: vec![b"value".to_vec()],
+		};
+		let children = vec![(
+			child_info.clone(),
+			KeysAndPrefixes { keys: vec![b"value3".to_vec()], ..Default::default() },
+		)];
+		let remote_proof = prove_keys_and_prefixes(remote_backend, &top, &children).unwrap();
+		let compact_proof = remote_proof.into_compact_proof::<BlakeTwo256>(remote_root).unwrap();
+
+		let values = read_compact_proof_check::<BlakeTwo256>(
+			remote_root,
+			compact_proof.clone(),
+			&top,
+			&children,
+		)
+		.unwrap();
+		assert_eq!(
+			values.top.into_iter().collect::<Vec<_>>(),
+			vec![
+				(b"key".to_vec(), Some(b"value".to_vec())),
+				(b"missing".to_vec(), None),
+				(b"value1".to_vec(), Some(vec![42])),
+				(b"value2".to_vec(), Some(vec![24])),
+			],
+		);
+		assert_eq!(
+			values.children.into_iter().collect::<Vec<_>>(),
+			vec![(
+				b"sub1".to_vec(),
+				vec![(b"value3".to_vec(), Some(vec![142; 33]))].into_iter().collect()
+			)],
+		);
+
+		// The proof doesn't cover the entries under other prefixes.
+		let other = KeysAndPrefixes { prefixes: vec![vec![130]], ..Default::default() };
+		assert!(read_compact_proof_check::<BlakeTwo256>(
+			remote_root,
+			compact_proof.clone(),
+			&other,
+			&[]
+		)
+		.is_err());
+		// Nor is it valid for another root.
+		assert!(read_compact_proof_check::<BlakeTwo256>(
+			Default::default(),
+			compact_proof,
+			&top,
+			&[]
+		)
+		.is_err());
+	}
+
 	#[test]
 	fn child_read_compact_stress_test() {
 		use rand::{rngs::SmallRng, RngCore, SeedableRng};
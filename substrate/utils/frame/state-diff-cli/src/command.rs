@@ -0,0 +1,221 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Command ran by the CLI
+
+use crate::{
+	decode::{to_hex, StorageDecoder},
+	diff::{child_changes, top_changes, StorageValueChange},
+};
+use clap::Parser;
+use log::{info, warn};
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams,
+};
+use sc_client_api::{HeaderBackend, StorageProvider, UsageProvider};
+use sp_api::{Metadata, ProvideRuntimeApi};
+use sp_core::storage::well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{
+	fmt::Debug,
+	io::{BufWriter, Write},
+	str::FromStr,
+	sync::Arc,
+};
+
+/// The `export-state-diff` command used to export the storage changes between two blocks.
+///
+/// The inserted, modified and deleted keys of the top trie and of the default child tries are
+/// printed as JSON. Whenever the runtime metadata describes the storage item a top trie key
+/// belongs to, the key is annotated with the pallet and storage item names, and the key and the
+/// values are decoded. The values of the first block are decoded with the metadata of its own
+/// runtime, so that the changes made by a runtime upgrade are decoded as well.
+#[derive(Debug, Clone, Parser)]
+pub struct ExportStateDiffCmd {
+	/// Block hash or number of the state to compare against.
+	#[arg(value_name = "FROM")]
+	pub from: BlockNumberOrHash,
+
+	/// Block hash or number of the changed state. Defaults to the best block.
+	#[arg(value_name = "TO")]
+	pub to: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportStateDiffCmd {
+	/// Run the `export-state-diff` command
+	pub async fn run<B, BA, C>(&self, client: Arc<C>) -> Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+		C::Api: Metadata<B>,
+		BA: sc_client_api::backend::Backend<B>,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let from = client.expect_block_hash_from_id(&self.from.parse()?)?;
+		let to = match self.to.as_ref().map(|b| b.parse()).transpose()? {
+			Some(id) => client.expect_block_hash_from_id(&id)?,
+			None => client.usage_info().chain.best_hash,
+		};
+
+		info!("Exporting state diff between {:?} and {:?}...", from, to);
+		let decoders = Decoders { from: decoder_at(&*client, from), to: decoder_at(&*client, to) };
+
+		// The changes are written as they are found, so that the diff is never kept in memory.
+		let stdout = std::io::stdout();
+		let mut out = BufWriter::new(stdout.lock());
+		write!(out, "{{\"from\":\"{:?}\",\"to\":\"{:?}\",\"top\":[", from, to)?;
+
+		// The roots of the changed child tries, the child tries themselves are exported after the
+		// top trie.
+		let mut child_tries = Vec::new();
+		let mut first = true;
+		for change in top_changes::<B, BA, C>(&*client, from, to)? {
+			let (key, change) = change?;
+			if let Some(storage_key) = key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+				child_tries.push(storage_key.to_vec());
+				continue
+			}
+			if !std::mem::take(&mut first) {
+				out.write_all(b",")?;
+			}
+			serde_json::to_writer(&mut out, &change_to_json(&key, &change, Some(&decoders)))
+				.map_err(std::io::Error::from)?;
+		}
+
+		out.write_all(b"],\"childrenDefault\":{")?;
+		for (index, storage_key) in child_tries.iter().enumerate() {
+			if index > 0 {
+				out.write_all(b",")?;
+			}
+			write!(out, "\"{}\":[", to_hex(storage_key))?;
+			let mut first = true;
+			for change in child_changes::<B, BA, C>(&*client, from, to, storage_key)? {
+				let (key, change) = change?;
+				if !std::mem::take(&mut first) {
+					out.write_all(b",")?;
+				}
+				serde_json::to_writer(&mut out, &change_to_json(&key, &change, None))
+					.map_err(std::io::Error::from)?;
+			}
+			out.write_all(b"]")?;
+		}
+		out.write_all(b"}}\n")?;
+		out.flush()?;
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportStateDiffCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The decoders of the runtimes of both blocks.
+struct Decoders {
+	from: Option<StorageDecoder>,
+	to: Option<StorageDecoder>,
+}
+
+fn decoder_at<B, C>(client: &C, hash: B::Hash) -> Option<StorageDecoder>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: Metadata<B>,
+{
+	let metadata = client
+		.runtime_api()
+		.metadata(hash)
+		.map_err(|e| warn!("Failed to fetch the runtime metadata at {:?}: {}", hash, e))
+		.ok()?;
+	StorageDecoder::from_metadata(&metadata)
+		.map_err(|e| warn!("Failed to read the runtime metadata at {:?}: {}", hash, e))
+		.ok()
+}
+
+fn change_to_json(
+	key: &[u8],
+	change: &StorageValueChange,
+	decoders: Option<&Decoders>,
+) -> serde_json::Value {
+	let (from, to) = match change {
+		StorageValueChange::Inserted(to) => (None, Some(to)),
+		StorageValueChange::Modified { from, to } => (Some(from), Some(to)),
+		StorageValueChange::Deleted(from) => (Some(from), None),
+	};
+	let mut json = serde_json::json!({
+		"key": to_hex(key),
+		"change": match change {
+			StorageValueChange::Inserted(_) => "inserted",
+			StorageValueChange::Modified { .. } => "modified",
+			StorageValueChange::Deleted(_) => "deleted",
+		},
+	});
+	if let Some(from) = from {
+		json["from"] = to_hex(from).into();
+	}
+	if let Some(to) = to {
+		json["to"] = to_hex(to).into();
+	}
+
+	let Some(decoders) = decoders else { return json };
+	// The key is looked up in the most recent runtime first, as it describes the new storage
+	// items of a runtime upgrade.
+	let key_decoder = decoders.to.iter().chain(&decoders.from);
+	if let Some(name) = key_decoder.clone().find_map(|decoder| decoder.name_of(key)) {
+		json["storage"] = name.into();
+	}
+	let mut decoded = serde_json::Map::new();
+	if let Some(key) = key_decoder.clone().find_map(|decoder| decoder.decode_key(key)) {
+		decoded.insert("key".into(), key);
+	}
+	let from = from.and_then(|from| decoders.from.as_ref()?.decode_value(key, from));
+	if let Some(from) = from {
+		decoded.insert("from".into(), from);
+	}
+	let to = to.and_then(|to| decoders.to.as_ref()?.decode_value(key, to));
+	if let Some(to) = to {
+		decoded.insert("to".into(), to);
+	}
+	if !decoded.is_empty() {
+		json["decoded"] = decoded.into();
+	}
+	json
+}
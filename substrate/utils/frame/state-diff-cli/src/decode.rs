@@ -0,0 +1,338 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of storage keys and values through the type registry of the runtime metadata.
+
+use codec::{Compact, Decode};
+use frame_metadata::{
+	v14::{PalletStorageMetadata, StorageEntryType, StorageHasher},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{Map, Value};
+use sp_core::twox_128;
+use std::collections::HashMap;
+
+/// A storage item described by the runtime metadata.
+struct StorageItem {
+	/// The name of the item, as `Pallet::Item`.
+	name: String,
+	/// The types of the keys and of the value of the item.
+	ty: StorageEntryType<PortableForm>,
+}
+
+/// Decodes the storage keys and values of a runtime through the type registry of its metadata.
+pub struct StorageDecoder {
+	/// The type registry of the runtime.
+	types: PortableRegistry,
+	/// `twox128(pallet prefix)` to the pallet name.
+	pallets: HashMap<Vec<u8>, String>,
+	/// `twox128(pallet prefix) ++ twox128(item name)` to the storage item.
+	items: HashMap<Vec<u8>, StorageItem>,
+}
+
+impl StorageDecoder {
+	/// Creates the decoder from the SCALE encoded, prefixed, runtime metadata.
+	///
+	/// Only the metadata versions 14 and 15 are supported.
+	pub fn from_metadata(metadata: &[u8]) -> Result<Self, String> {
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+			.map_err(|e| format!("failed to decode the runtime metadata: {}", e))?
+			.1;
+		let (types, storages): (_, Vec<PalletStorageMetadata<PortableForm>>) = match metadata {
+			RuntimeMetadata::V14(metadata) =>
+				(metadata.types, metadata.pallets.into_iter().filter_map(|p| p.storage).collect()),
+			RuntimeMetadata::V15(metadata) =>
+				(metadata.types, metadata.pallets.into_iter().filter_map(|p| p.storage).collect()),
+			metadata =>
+				return Err(format!("unsupported runtime metadata version {}", metadata.version())),
+		};
+
+		let mut decoder = Self { types, pallets: HashMap::new(), items: HashMap::new() };
+		for storage in storages {
+			let pallet_key = twox_128(storage.prefix.as_bytes()).to_vec();
+			for entry in storage.entries {
+				let item_key = [&pallet_key[..], &twox_128(entry.name.as_bytes())[..]].concat();
+				let name = format!("{}::{}", storage.prefix, entry.name);
+				decoder.items.insert(item_key, StorageItem { name, ty: entry.ty });
+			}
+			decoder.pallets.insert(pallet_key, storage.prefix);
+		}
+		Ok(decoder)
+	}
+
+	/// Returns the name of the storage item, or of the pallet, the given key belongs to.
+	pub fn name_of(&self, key: &[u8]) -> Option<String> {
+		// Well known keys, like `:code`, are human readable.
+		if key.starts_with(b":") {
+			return String::from_utf8(key.to_vec()).ok()
+		}
+		self.item_of(key)
+			.map(|item| item.name.clone())
+			.or_else(|| key.get(..16).and_then(|prefix| self.pallets.get(prefix)).cloned())
+	}
+
+	/// Decodes the keys of the map the given storage key belongs to.
+	///
+	/// Every key is decoded when its hasher concatenates it to the hash, otherwise its hash is
+	/// returned as hex. Returns `None` for the keys of plain storage items and the keys that
+	/// cannot be fully decoded.
+	pub fn decode_key(&self, key: &[u8]) -> Option<Value> {
+		let (hashers, key_ty) = match &self.item_of(key)?.ty {
+			StorageEntryType::Map { hashers, key, .. } => (hashers, key.id),
+			StorageEntryType::Plain(_) => return None,
+		};
+		// The keys of a map with several hashers are described by a tuple.
+		let key_tys = if hashers.len() == 1 {
+			vec![key_ty]
+		} else {
+			match &self.types.resolve(key_ty)?.type_def {
+				TypeDef::Tuple(tuple) => tuple.fields.iter().map(|ty| ty.id).collect(),
+				_ => return None,
+			}
+		};
+		if key_tys.len() != hashers.len() {
+			return None
+		}
+
+		let mut input = &key[32..];
+		let mut keys = Vec::with_capacity(hashers.len());
+		for (hasher, ty) in hashers.iter().zip(key_tys) {
+			let (hash_len, concat) = match hasher {
+				StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+				StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+				StorageHasher::Blake2_128Concat => (16, true),
+				StorageHasher::Twox64Concat => (8, true),
+				StorageHasher::Identity => (0, true),
+			};
+			let hash = input.get(..hash_len)?;
+			input = &input[hash_len..];
+			keys.push(if concat { self.decode(ty, &mut input)? } else { to_hex(hash).into() });
+		}
+		input.is_empty().then(|| Value::Array(keys))
+	}
+
+	/// Decodes the value stored under the given storage key.
+	///
+	/// Returns `None` if the key doesn't belong to a known storage item, or if the value cannot
+	/// be fully decoded.
+	pub fn decode_value(&self, key: &[u8], value: &[u8]) -> Option<Value> {
+		let ty = match &self.item_of(key)?.ty {
+			StorageEntryType::Plain(ty) => ty.id,
+			StorageEntryType::Map { value, .. } => value.id,
+		};
+		let mut input = value;
+		let value = self.decode(ty, &mut input)?;
+		input.is_empty().then_some(value)
+	}
+
+	fn item_of(&self, key: &[u8]) -> Option<&StorageItem> {
+		key.get(..32).and_then(|prefix| self.items.get(prefix))
+	}
+
+	/// Decodes a value of the type `ty` of the registry from `input`.
+	fn decode(&self, ty: u32, input: &mut &[u8]) -> Option<Value> {
+		let value = match &self.types.resolve(ty)?.type_def {
+			TypeDef::Composite(composite) => match &composite.fields[..] {
+				// Newtypes are transparent.
+				[field] if field.name.is_none() => self.decode(field.ty.id, input)?,
+				fields if fields.iter().all(|field| field.name.is_some()) => {
+					let mut object = Map::new();
+					for field in fields {
+						let name = field.name.clone().unwrap_or_default();
+						object.insert(name, self.decode(field.ty.id, input)?);
+					}
+					Value::Object(object)
+				},
+				fields => Value::Array(
+					fields
+						.iter()
+						.map(|field| self.decode(field.ty.id, input))
+						.collect::<Option<_>>()?,
+				),
+			},
+			TypeDef::Variant(variant) => {
+				let index = u8::decode(input).ok()?;
+				let variant = variant.variants.iter().find(|v| v.index == index)?;
+				if variant.fields.is_empty() {
+					Value::String(variant.name.clone())
+				} else {
+					let mut fields = Vec::with_capacity(variant.fields.len());
+					for field in &variant.fields {
+						fields.push((field.name.clone(), self.decode(field.ty.id, input)?));
+					}
+					let value = if fields.iter().all(|(name, _)| name.is_some()) {
+						Value::Object(
+							fields
+								.into_iter()
+								.map(|(name, v)| (name.unwrap_or_default(), v))
+								.collect(),
+						)
+					} else if fields.len() == 1 {
+						fields.remove(0).1
+					} else {
+						Value::Array(fields.into_iter().map(|(_, v)| v).collect())
+					};
+					let mut object = Map::new();
+					object.insert(variant.name.clone(), value);
+					Value::Object(object)
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input).ok()?.0 as usize;
+				self.decode_items(sequence.type_param.id, len, input)?
+			},
+			TypeDef::Array(array) =>
+				self.decode_items(array.type_param.id, array.len as usize, input)?,
+			TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Value::Null,
+			TypeDef::Tuple(tuple) => Value::Array(
+				tuple.fields.iter().map(|ty| self.decode(ty.id, input)).collect::<Option<_>>()?,
+			),
+			TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+			// All the compact encoded integers share the same encoding.
+			TypeDef::Compact(_) => {
+				let value = Compact::<u128>::decode(input).ok()?.0;
+				u64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into)
+			},
+			TypeDef::BitSequence(bits) => {
+				let store_len = match &self.types.resolve(bits.bit_store_type.id)?.type_def {
+					TypeDef::Primitive(TypeDefPrimitive::U8) => 1,
+					TypeDef::Primitive(TypeDefPrimitive::U16) => 2,
+					TypeDef::Primitive(TypeDefPrimitive::U32) => 4,
+					TypeDef::Primitive(TypeDefPrimitive::U64) => 8,
+					_ => return None,
+				};
+				let bits = Compact::<u32>::decode(input).ok()?.0 as usize;
+				let len = (bits + store_len * 8 - 1) / (store_len * 8) * store_len;
+				let bytes = input.get(..len)?;
+				*input = &input[len..];
+				to_hex(bytes).into()
+			},
+		};
+		Some(value)
+	}
+
+	/// Decodes `len` items of the type `ty`, as hex if they are bytes.
+	fn decode_items(&self, ty: u32, len: usize, input: &mut &[u8]) -> Option<Value> {
+		if let TypeDef::Primitive(TypeDefPrimitive::U8) = self.types.resolve(ty)?.type_def {
+			let bytes = input.get(..len)?;
+			*input = &input[len..];
+			return Some(to_hex(bytes).into())
+		}
+		// Every item is at least one byte long, which bounds the allocation.
+		let mut items = Vec::with_capacity(len.min(input.len()));
+		for _ in 0..len {
+			items.push(self.decode(ty, input)?);
+		}
+		Some(Value::Array(items))
+	}
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Option<Value> {
+	let value = match primitive {
+		TypeDefPrimitive::Bool => bool::decode(input).ok()?.into(),
+		TypeDefPrimitive::Char => char::from_u32(u32::decode(input).ok()?)?.to_string().into(),
+		TypeDefPrimitive::Str => String::decode(input).ok()?.into(),
+		TypeDefPrimitive::U8 => u8::decode(input).ok()?.into(),
+		TypeDefPrimitive::U16 => u16::decode(input).ok()?.into(),
+		TypeDefPrimitive::U32 => u32::decode(input).ok()?.into(),
+		TypeDefPrimitive::U64 => u64::decode(input).ok()?.into(),
+		TypeDefPrimitive::U128 => u128::decode(input).ok()?.to_string().into(),
+		TypeDefPrimitive::I8 => i8::decode(input).ok()?.into(),
+		TypeDefPrimitive::I16 => i16::decode(input).ok()?.into(),
+		TypeDefPrimitive::I32 => i32::decode(input).ok()?.into(),
+		TypeDefPrimitive::I64 => i64::decode(input).ok()?.into(),
+		TypeDefPrimitive::I128 => i128::decode(input).ok()?.to_string().into(),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			let bytes = input.get(..32)?;
+			*input = &input[32..];
+			to_hex(bytes).into()
+		},
+	};
+	Some(value)
+}
+
+pub(crate) fn to_hex(data: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{meta_type, Registry};
+	use sp_core::twox_64;
+
+	fn decoder() -> StorageDecoder {
+		let mut registry = Registry::new();
+		let key = registry.register_type(&meta_type::<u32>());
+		let value = registry.register_type(&meta_type::<(u64, Vec<u8>, Option<bool>)>());
+		let mut decoder = StorageDecoder {
+			types: registry.into(),
+			pallets: HashMap::new(),
+			items: HashMap::new(),
+		};
+
+		let pallet_key = twox_128(b"Pallet").to_vec();
+		decoder.items.insert(
+			[&pallet_key[..], &twox_128(b"Map")[..]].concat(),
+			StorageItem {
+				name: "Pallet::Map".into(),
+				ty: StorageEntryType::Map {
+					hashers: vec![StorageHasher::Twox64Concat],
+					key,
+					value,
+				},
+			},
+		);
+		decoder.pallets.insert(pallet_key, "Pallet".into());
+		decoder
+	}
+
+	#[test]
+	fn decodes_map_keys_and_values() {
+		let decoder = decoder();
+		let prefix = [twox_128(b"Pallet"), twox_128(b"Map")].concat();
+		let key = [&prefix[..], &twox_64(&7u32.encode())[..], &7u32.encode()[..]].concat();
+		let value = (5u64, vec![1u8, 2], Some(true)).encode();
+
+		assert_eq!(decoder.name_of(&key), Some("Pallet::Map".into()));
+		assert_eq!(decoder.decode_key(&key), Some(serde_json::json!([7])));
+		assert_eq!(
+			decoder.decode_value(&key, &value),
+			Some(serde_json::json!([5, "0x0102", { "Some": true }]))
+		);
+	}
+
+	#[test]
+	fn does_not_decode_partially() {
+		let decoder = decoder();
+		let prefix = [twox_128(b"Pallet"), twox_128(b"Map")].concat();
+		let key = [&prefix[..], &twox_64(&7u32.encode())[..], &7u32.encode()[..]].concat();
+		let value = (5u64, vec![1u8, 2], Some(true)).encode();
+
+		assert_eq!(decoder.decode_key(&[&key[..], &[0]].concat()), None);
+		assert_eq!(decoder.decode_value(&key, &[&value[..], &[0]].concat()), None);
+		assert_eq!(decoder.decode_value(&key, &value[..value.len() - 1]), None);
+		assert_eq!(
+			decoder.name_of(&[&twox_128(b"Pallet")[..], &[0; 16]].concat()),
+			Some("Pallet".into())
+		);
+		assert_eq!(decoder.name_of(b":code"), Some(":code".into()));
+	}
+}
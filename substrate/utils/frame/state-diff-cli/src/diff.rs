@@ -0,0 +1,210 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Streaming diff of the storage of two blocks.
+
+use sc_client_api::{backend::Backend, StorageProvider};
+use sp_core::storage::ChildInfo;
+use sp_runtime::traits::Block as BlockT;
+use std::{cmp::Ordering, iter::Peekable};
+
+/// The change of a single storage value between two states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageValueChange {
+	/// The key didn't exist in the first state.
+	Inserted(Vec<u8>),
+	/// The value of the key differs between the two states.
+	Modified {
+		/// The value in the first state.
+		from: Vec<u8>,
+		/// The value in the second state.
+		to: Vec<u8>,
+	},
+	/// The key doesn't exist in the second state anymore.
+	Deleted(Vec<u8>),
+}
+
+/// Iterator over the changes between two iterators of storage pairs, both ordered by key.
+///
+/// The iterators are merged while they are walked, so only the current pair of each of them is
+/// kept in memory.
+pub struct Changes<F: Iterator, T: Iterator> {
+	from: Peekable<F>,
+	to: Peekable<T>,
+}
+
+impl<F: Iterator, T: Iterator> Changes<F, T> {
+	/// Creates the iterator over the changes from the pairs of `from` to the pairs of `to`.
+	pub fn new(from: F, to: T) -> Self {
+		Self { from: from.peekable(), to: to.peekable() }
+	}
+}
+
+impl<F, T, E> Iterator for Changes<F, T>
+where
+	F: Iterator<Item = Result<(Vec<u8>, Vec<u8>), E>>,
+	T: Iterator<Item = Result<(Vec<u8>, Vec<u8>), E>>,
+{
+	type Item = Result<(Vec<u8>, StorageValueChange), E>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let ordering = match (self.from.peek(), self.to.peek()) {
+				(None, None) => return None,
+				(Some(Err(_)), _) => return self.from.next().and_then(Result::err).map(Err),
+				(_, Some(Err(_))) => return self.to.next().and_then(Result::err).map(Err),
+				(Some(Ok(_)), None) => Ordering::Less,
+				(None, Some(Ok(_))) => Ordering::Greater,
+				(Some(Ok((from, _))), Some(Ok((to, _)))) => from.cmp(to),
+			};
+
+			match ordering {
+				Ordering::Less => {
+					let (key, value) = self.from.next()?.ok()?;
+					return Some(Ok((key, StorageValueChange::Deleted(value))))
+				},
+				Ordering::Greater => {
+					let (key, value) = self.to.next()?.ok()?;
+					return Some(Ok((key, StorageValueChange::Inserted(value))))
+				},
+				Ordering::Equal => {
+					let (key, from) = self.from.next()?.ok()?;
+					let (_, to) = self.to.next()?.ok()?;
+					if from != to {
+						return Some(Ok((key, StorageValueChange::Modified { from, to })))
+					}
+				},
+			}
+		}
+	}
+}
+
+/// Returns the changes of the top trie between the blocks `from` and `to`.
+///
+/// The roots of the default child tries are reported like any other value, see [`child_changes`]
+/// for the changes of the child tries themselves.
+pub fn top_changes<'a, B, BA, C>(
+	client: &'a C,
+	from: B::Hash,
+	to: B::Hash,
+) -> sp_blockchain::Result<
+	Changes<
+		impl Iterator<Item = sp_blockchain::Result<(Vec<u8>, Vec<u8>)>> + 'a,
+		impl Iterator<Item = sp_blockchain::Result<(Vec<u8>, Vec<u8>)>> + 'a,
+	>,
+>
+where
+	B: BlockT,
+	BA: Backend<B> + 'a,
+	C: StorageProvider<B, BA>,
+{
+	let pairs = |hash| {
+		client
+			.storage_pairs(hash, None, None)
+			.map(|pairs| pairs.map(|(key, value)| sp_blockchain::Result::Ok((key.0, value.0))))
+	};
+	Ok(Changes::new(pairs(from)?, pairs(to)?))
+}
+
+/// Returns the changes of the default child trie stored under the (unprefixed) `storage_key`
+/// between the blocks `from` and `to`.
+pub fn child_changes<'a, B, BA, C>(
+	client: &'a C,
+	from: B::Hash,
+	to: B::Hash,
+	storage_key: &[u8],
+) -> sp_blockchain::Result<
+	Changes<
+		impl Iterator<Item = sp_blockchain::Result<(Vec<u8>, Vec<u8>)>> + 'a,
+		impl Iterator<Item = sp_blockchain::Result<(Vec<u8>, Vec<u8>)>> + 'a,
+	>,
+>
+where
+	B: BlockT,
+	BA: Backend<B> + 'a,
+	C: StorageProvider<B, BA>,
+{
+	let child_info = ChildInfo::new_default(storage_key);
+	let pairs = |hash: B::Hash| {
+		let child_info = child_info.clone();
+		client
+			.child_storage_keys(hash, child_info.clone(), None, None)
+			.map(move |keys| {
+				keys.map(move |key| -> sp_blockchain::Result<_> {
+					let value = client.child_storage(hash, &child_info, &key)?;
+					Ok((key.0, value.map(|value| value.0).unwrap_or_default()))
+				})
+			})
+	};
+	Ok(Changes::new(pairs(from)?, pairs(to)?))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pairs(pairs: &[(&[u8], &[u8])]) -> Vec<Result<(Vec<u8>, Vec<u8>), ()>> {
+		pairs.iter().map(|(k, v)| Ok((k.to_vec(), v.to_vec()))).collect()
+	}
+
+	#[test]
+	fn changes_reports_inserted_modified_and_deleted_keys() {
+		let from = pairs(&[(b"a", b"1"), (b"b", b"2"), (b"c", b"3")]);
+		let to = pairs(&[(b"b", b"2"), (b"c", b"4"), (b"d", b"5")]);
+
+		let changes = Changes::new(from.into_iter(), to.into_iter()).collect::<Vec<_>>();
+
+		assert_eq!(
+			changes,
+			vec![
+				Ok((b"a".to_vec(), StorageValueChange::Deleted(b"1".to_vec()))),
+				Ok((
+					b"c".to_vec(),
+					StorageValueChange::Modified { from: b"3".to_vec(), to: b"4".to_vec() }
+				)),
+				Ok((b"d".to_vec(), StorageValueChange::Inserted(b"5".to_vec()))),
+			]
+		);
+	}
+
+	#[test]
+	fn changes_of_same_pairs_are_empty() {
+		let from = pairs(&[(b"a", b"1"), (b"b", b"2")]);
+
+		assert_eq!(Changes::new(from.clone().into_iter(), from.into_iter()).count(), 0);
+	}
+
+	#[test]
+	fn changes_forward_errors() {
+		let from = vec![Ok((b"a".to_vec(), b"1".to_vec())), Err(())];
+		let to = pairs(&[(b"a", b"2")]);
+
+		let changes = Changes::new(from.into_iter(), to.into_iter()).collect::<Vec<_>>();
+
+		assert_eq!(
+			changes,
+			vec![
+				Ok((
+					b"a".to_vec(),
+					StorageValueChange::Modified { from: b"1".to_vec(), to: b"2".to_vec() }
+				)),
+				Err(()),
+			]
+		);
+	}
+}
@@ -0,0 +1,33 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export of the storage changes between two blocks of the local database.
+//!
+//! Both states are walked in key order at the same time, so that neither of them is ever loaded
+//! in memory. The changed keys and values are decoded through the type registry of the runtime
+//! metadata, whenever it describes the storage item they belong to.
+
+#![warn(missing_docs)]
+
+mod command;
+mod decode;
+mod diff;
+
+pub use command::ExportStateDiffCmd;
+pub use decode::StorageDecoder;
+pub use diff::{child_changes, top_changes, Changes, StorageValueChange};
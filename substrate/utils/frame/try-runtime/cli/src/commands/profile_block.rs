@@ -0,0 +1,714 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	build_executor, commands::execute_block::next_hash_of, full_extensions, rpc_err_handler,
+	LiveState, SharedParams, State, LOG_TARGET,
+};
+use frame_try_runtime::BlockHook;
+use parity_scale_codec::{Codec, Decode, Encode};
+use sc_executor::{sp_wasm_interface::HostFunctions, WasmExecutor};
+use sp_api::RuntimeApiInfo;
+use sp_core::{
+	storage::{well_known_keys, ChildInfo, StateVersion},
+	traits::{CallContext, ReadRuntimeVersion, RuntimeCode},
+	twox_128, Hasher,
+};
+use sp_externalities::Extensions;
+use sp_runtime::{
+	generic::SignedBlock,
+	traits::{Block as BlockT, HashingFor, Header as HeaderT, NumberFor},
+	ApplyExtrinsicResult,
+};
+use sp_state_machine::{
+	backend::BackendRuntimeCode, Backend, BackendTransaction, InMemoryBackend, IterArgs,
+	OverlayedChanges, StateMachine, StateMachineStats, StorageIterator, StorageKey, StorageValue,
+	TestExternalities, TrieBackendBuilder, UsageInfo, UsageUnit,
+};
+use sp_trie::{recorder::Recorder, MerkleValue};
+use sp_version::RuntimeVersion;
+use sp_weights::{constants::WEIGHT_REF_TIME_PER_NANOS, Weight};
+use std::{
+	cell::RefCell,
+	collections::{BTreeMap, BTreeSet},
+	fmt::Debug,
+	marker::PhantomData,
+	path::PathBuf,
+	str::FromStr,
+	time::{Duration, Instant},
+};
+use substrate_rpc_client::{ws_client, ChainApi};
+
+/// Configurations of the [`crate::Command::ProfileBlock`].
+///
+/// Replays the given block on top of some state, one step at a time: every hook of the block
+/// through `TryRuntime_execute_block_hook` and every extrinsic through
+/// `BlockBuilder_apply_extrinsic`. For each step, the wall time, the storage reads and writes, the
+/// proof size contribution and the weight declared by the runtime are reported as JSON, so that
+/// under-weighted calls can be spotted.
+///
+/// The steps are `on_runtime_upgrade` (along with the initialization of `frame_system`),
+/// `on_initialize`, the extrinsics, `on_poll`, `on_idle` and `on_finalize`. `on_poll`, or the
+/// step of the multi-block migrations while they are ongoing, runs once the inherents are
+/// applied, so it is part of the first extrinsic that is not an inherent, if any. `on_finalize`
+/// declares no weight of its own, its cost is expected to be accounted for by `on_initialize`.
+///
+/// If the runtime doesn't implement version 2 of [`frame_try_runtime::TryRuntime`], the hooks
+/// are profiled as part of `Core_initialize_block` and `BlockBuilder_finalize_block` instead.
+///
+/// The block is executed once before being profiled, so that the runtime instance is warmed up
+/// and the compilation of the runtime is not accounted to the first step.
+///
+/// The declared weight is read from `System::BlockWeight`, so this assumes a FRAME based runtime.
+/// The measured `ref_time` is the wall time of the step on this machine, thus comparing it with
+/// the declared weight is only meaningful on hardware similar to the reference hardware.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ProfileBlockCmd {
+	/// The ws uri from which to fetch the block.
+	///
+	/// This will always fetch the next block of whatever `state` is referring to, because this is
+	/// the only sensible combination. In other words, if you have the state of block `n`, you
+	/// should profile block `n+1` on top of it.
+	///
+	/// If `state` is `Live`, this can be ignored and the same uri is used for both.
+	#[arg(
+		long,
+		value_parser = crate::parse::url
+	)]
+	pub block_ws_uri: Option<String>,
+
+	/// Path to a file to write the JSON report into.
+	///
+	/// If not provided, the report is printed to the standard output.
+	#[arg(long)]
+	pub output: Option<PathBuf>,
+
+	/// The state type to use.
+	#[command(subcommand)]
+	pub state: State,
+}
+
+impl ProfileBlockCmd {
+	fn block_ws_uri<Block: BlockT>(&self) -> String
+	where
+		<Block::Hash as FromStr>::Err: Debug,
+	{
+		match (&self.block_ws_uri, &self.state) {
+			(Some(block_ws_uri), State::Snap { .. }) => block_ws_uri.to_owned(),
+			(Some(block_ws_uri), State::Live { .. }) => {
+				log::error!(target: LOG_TARGET, "--block-uri is provided while state type is live, Are you sure you know what you are doing?");
+				block_ws_uri.to_owned()
+			},
+			(None, State::Live(LiveState { uri, .. })) => uri.clone(),
+			(None, State::Snap { .. }) => {
+				panic!("either `--block-uri` must be provided, or state must be `live`");
+			},
+		}
+	}
+}
+
+pub(crate) async fn profile_block<Block, HostFns>(
+	shared: SharedParams,
+	command: ProfileBlockCmd,
+) -> sc_cli::Result<()>
+where
+	Block: BlockT + serde::de::DeserializeOwned,
+	<Block::Hash as FromStr>::Err: Debug,
+	Block::Hash: serde::de::DeserializeOwned,
+	Block::Header: serde::de::DeserializeOwned,
+	<NumberFor<Block> as TryInto<u64>>::Error: Debug,
+	HostFns: HostFunctions,
+{
+	let executor = build_executor::<HostFns>(&shared);
+	let mut ext = command
+		.state
+		.into_ext::<Block, HostFns>(&shared, &executor, None, false)
+		.await?;
+
+	let block_ws_uri = command.block_ws_uri::<Block>();
+	let rpc = ws_client(&block_ws_uri).await?;
+	let next_hash = next_hash_of::<Block>(&rpc, ext.block_hash).await?;
+
+	log::info!(target: LOG_TARGET, "fetching next block: {:?} ", next_hash);
+
+	let block = ChainApi::<(), Block::Hash, Block::Header, SignedBlock<Block>>::block(
+		&rpc,
+		Some(next_hash),
+	)
+	.await
+	.map_err(rpc_err_handler)?
+	.expect("header exists, block should also exist; qed")
+	.block;
+
+	// A digest item gets added when the runtime is processing the block, so we need to pop
+	// the last one to be consistent with what a gossiped block would contain.
+	let (mut header, extrinsics) = block.deconstruct();
+	header.digest_mut().pop();
+	let number: u64 = (*header.number())
+		.try_into()
+		.map_err(|_| "failed to convert block number to u64")?;
+
+	let hooks = supports_block_hooks::<Block, HostFns>(&executor, &mut ext)?;
+	if !hooks {
+		log::warn!(
+			target: LOG_TARGET,
+			"the runtime doesn't implement `TryRuntime_execute_block_hook`, the hooks are profiled \
+			as part of `initialize_block` and `finalize_block`"
+		);
+	}
+
+	let steps =
+		profile_steps::<Block, HostFns>(&ext.backend, &executor, &header, &extrinsics, hooks)?;
+
+	let under_weighted = steps.iter().filter(|step| step["under_weighted"] == true).count();
+	if under_weighted > 0 {
+		log::warn!(target: LOG_TARGET, "{} steps used more than their declared weight", under_weighted);
+	}
+
+	let report = serde_json::json!({
+		"block": {
+			"hash": format!("{:?}", next_hash),
+			"number": number,
+		},
+		"steps": steps,
+	})
+	.to_string();
+
+	match command.output {
+		Some(path) => {
+			log::info!(target: LOG_TARGET, "Writing block profile to {}", path.to_string_lossy());
+			std::fs::write(&path, report).map_err(|e| {
+				log::error!(
+					target: LOG_TARGET,
+					"Failed to write block profile to {}: {:?}",
+					path.to_string_lossy(),
+					e
+				);
+				e
+			})?;
+		},
+		None => println!("{}", report),
+	}
+
+	Ok(())
+}
+
+/// Whether the runtime of `ext` implements version 2 of [`frame_try_runtime::TryRuntime`], i.e.
+/// can execute the hooks of a block one by one.
+fn supports_block_hooks<Block: BlockT, HostFns: HostFunctions>(
+	executor: &WasmExecutor<HostFns>,
+	ext: &mut TestExternalities<HashingFor<Block>>,
+) -> sc_cli::Result<bool> {
+	let code = ext
+		.execute_with(|| sp_io::storage::get(well_known_keys::CODE))
+		.ok_or("the state contains no runtime code")?;
+	let version = executor
+		.read_runtime_version(&code, &mut ext.ext())
+		.map_err(|e| format!("failed to read the runtime version: {}", e))?;
+	let version = RuntimeVersion::decode(&mut &*version)
+		.map_err(|e| format!("failed to decode the runtime version: {:?}", e))?;
+
+	Ok(version
+		.api_version(&<dyn frame_try_runtime::TryRuntime<Block>>::ID)
+		.map_or(false, |api_version| api_version >= 2))
+}
+
+/// Profiles every step of the block made of `header` and `extrinsics` on top of `backend`.
+///
+/// The runtime is compiled and instantiated on its first call, so the block is executed once
+/// beforehand and its changes are discarded. This way every step, including the hooks, is timed on
+/// a warmed up instance.
+fn profile_steps<Block: BlockT, HostFns: HostFunctions>(
+	backend: &InMemoryBackend<HashingFor<Block>>,
+	executor: &WasmExecutor<HostFns>,
+	header: &Block::Header,
+	extrinsics: &[Block::Extrinsic],
+	hooks: bool,
+) -> sc_cli::Result<Vec<serde_json::Value>> {
+	let runtime_code_backend = BackendRuntimeCode::new(backend);
+	let runtime_code = runtime_code_backend.runtime_code()?;
+
+	log::info!(target: LOG_TARGET, "warming up the runtime instance");
+	execute_steps::<Block, HostFns>(backend, executor, &runtime_code, header, extrinsics, hooks)?;
+
+	execute_steps::<Block, HostFns>(backend, executor, &runtime_code, header, extrinsics, hooks)
+}
+
+/// Executes the steps of the block on top of `backend`, measuring each of them.
+///
+/// If `hooks` is set, every hook is a step of its own. Otherwise, they are part of the
+/// `initialize_block` and `finalize_block` steps.
+fn execute_steps<Block: BlockT, HostFns: HostFunctions>(
+	backend: &InMemoryBackend<HashingFor<Block>>,
+	executor: &WasmExecutor<HostFns>,
+	runtime_code: &RuntimeCode,
+	header: &Block::Header,
+	extrinsics: &[Block::Extrinsic],
+	hooks: bool,
+) -> sc_cli::Result<Vec<serde_json::Value>> {
+	// A single recorder is used for the whole block, so the proof size reported for every step
+	// is its contribution on top of all the previous steps.
+	let recorder = Recorder::default();
+	let proving_backend = TrieBackendBuilder::wrap(backend).with_recorder(recorder.clone()).build();
+
+	let mut profiler = BlockProfiler {
+		backend: TallyingBackend::new(&proving_backend),
+		recorder,
+		executor,
+		runtime_code,
+		changes: Default::default(),
+		extensions: full_extensions(executor.clone()),
+	};
+
+	let mut steps = Vec::with_capacity(extrinsics.len() + 5);
+
+	if hooks {
+		for hook in [BlockHook::OnRuntimeUpgrade, BlockHook::OnInitialize] {
+			steps.push(profiler.call_hook(header, hook)?);
+		}
+	} else {
+		let (_, usage) = profiler.call("Core_initialize_block", &header.encode())?;
+		steps.push(usage.to_json("initialize_block"));
+	}
+
+	for (index, extrinsic) in extrinsics.iter().enumerate() {
+		let encoded = extrinsic.encode();
+		let (result, usage) = profiler.call("BlockBuilder_apply_extrinsic", &encoded)?;
+		let result = ApplyExtrinsicResult::decode(&mut &*result)
+			.map_err(|e| format!("failed to decode the result of extrinsic {}: {:?}", index, e))?;
+
+		let mut step = usage.to_json("extrinsic");
+		step["index"] = index.into();
+		step["length"] = encoded.len().into();
+		match result {
+			Ok(Ok(())) => step["success"] = true.into(),
+			Ok(Err(e)) => {
+				step["success"] = false.into();
+				step["error"] = format!("{:?}", e).into();
+			},
+			Err(e) => {
+				step["success"] = false.into();
+				step["error"] = format!("{:?}", e).into();
+			},
+		}
+		steps.push(step);
+	}
+
+	if hooks {
+		for hook in [BlockHook::OnPoll, BlockHook::OnIdle, BlockHook::OnFinalize] {
+			steps.push(profiler.call_hook(header, hook)?);
+		}
+	} else {
+		let (_, usage) = profiler.call("BlockBuilder_finalize_block", &[])?;
+		steps.push(usage.to_json("finalize_block"));
+	}
+
+	Ok(steps)
+}
+
+/// The resources used by a single runtime call.
+struct CallUsage {
+	/// Wall time of the call.
+	elapsed: Duration,
+	/// Reads that hit the backend, i.e. were not served by the overlay.
+	reads: UsageUnit,
+	/// Number of distinct keys read from the backend.
+	read_keys: usize,
+	/// Keys whose value was changed by the call, along with the size of the new values.
+	writes: UsageUnit,
+	/// Growth of the storage proof caused by the call.
+	proof_size: u64,
+	/// Weight declared by the runtime for the call, if any.
+	declared_weight: Option<Weight>,
+}
+
+impl CallUsage {
+	/// Weight derived from the measurements of the call.
+	fn measured_weight(&self) -> Weight {
+		let ref_time = (self.elapsed.as_nanos() as u64).saturating_mul(WEIGHT_REF_TIME_PER_NANOS);
+		Weight::from_parts(ref_time, self.proof_size)
+	}
+
+	fn to_json(&self, step: &str) -> serde_json::Value {
+		let measured_weight = self.measured_weight();
+		serde_json::json!({
+			"step": step,
+			"wall_time_ns": self.elapsed.as_nanos() as u64,
+			"reads": {
+				"ops": self.reads.ops,
+				"keys": self.read_keys,
+				"bytes": self.reads.bytes,
+			},
+			"writes": {
+				"keys": self.writes.ops,
+				"bytes": self.writes.bytes,
+			},
+			"proof_size": self.proof_size,
+			"declared_weight": self.declared_weight.map(|declared_weight| serde_json::json!({
+				"ref_time": declared_weight.ref_time(),
+				"proof_size": declared_weight.proof_size(),
+			})),
+			"measured_weight": {
+				"ref_time": measured_weight.ref_time(),
+				"proof_size": measured_weight.proof_size(),
+			},
+			"under_weighted": self
+				.declared_weight
+				.map(|declared_weight| measured_weight.any_gt(declared_weight)),
+		})
+	}
+}
+
+/// Executes the steps of a block on top of the same changes, measuring each of them.
+struct BlockProfiler<'a, H: Hasher, B, HostFns: HostFunctions> {
+	backend: TallyingBackend<'a, B>,
+	recorder: Recorder<H>,
+	executor: &'a WasmExecutor<HostFns>,
+	runtime_code: &'a RuntimeCode<'a>,
+	changes: OverlayedChanges<H>,
+	extensions: Extensions,
+}
+
+impl<'a, H, B, HostFns> BlockProfiler<'a, H, B, HostFns>
+where
+	H: Hasher,
+	H::Out: Ord + Codec + 'static,
+	B: Backend<H>,
+	HostFns: HostFunctions,
+{
+	/// Calls `method` on top of the changes of the previous calls.
+	fn call(&mut self, method: &'static str, data: &[u8]) -> sc_cli::Result<(Vec<u8>, CallUsage)> {
+		let values_before = overlay_values(&self.changes);
+		let weight_before = consumed_weight(&self.changes);
+		let proof_size_before = self.recorder.estimate_encoded_size();
+
+		let started = Instant::now();
+		let result = StateMachine::new(
+			&self.backend,
+			&mut self.changes,
+			self.executor,
+			method,
+			data,
+			&mut self.extensions,
+			self.runtime_code,
+			CallContext::Onchain,
+		)
+		.execute()
+		.map_err(|e| format!("failed to execute {}: {}", method, e))?;
+		let elapsed = started.elapsed();
+
+		let (reads, read_keys) = self.backend.take_reads();
+		let writes = overlay_values(&self.changes)
+			.into_iter()
+			.filter(|(key, value)| values_before.get(key) != Some(value))
+			.fold(UsageUnit::default(), |mut writes, (_, value)| {
+				writes.ops += 1;
+				writes.bytes += value.map_or(0, |v| v.len() as u64);
+				writes
+			});
+		let proof_size = self.recorder.estimate_encoded_size().saturating_sub(proof_size_before);
+		// `System::BlockWeight` is reset when the block is initialized.
+		let declared_weight = if method == "Core_initialize_block" {
+			consumed_weight(&self.changes)
+		} else {
+			consumed_weight(&self.changes).saturating_sub(weight_before)
+		};
+
+		log::debug!(target: LOG_TARGET, "{} executed in {:?}", method, elapsed);
+
+		Ok((
+			result,
+			CallUsage {
+				elapsed,
+				reads,
+				read_keys,
+				writes,
+				proof_size: proof_size as u64,
+				declared_weight: Some(declared_weight),
+			},
+		))
+	}
+
+	/// Executes `hook` of the block with `header`, taking the weight returned by the runtime as the
+	/// declared weight of the hook.
+	fn call_hook<Header: Encode>(
+		&mut self,
+		header: &Header,
+		hook: BlockHook,
+	) -> sc_cli::Result<serde_json::Value> {
+		let (result, mut usage) =
+			self.call("TryRuntime_execute_block_hook", &(header, hook).encode())?;
+		let weight = Weight::decode(&mut &*result)
+			.map_err(|e| format!("failed to decode the weight of {:?}: {:?}", hook, e))?;
+		// `on_finalize` declares no weight of its own.
+		usage.declared_weight = (hook != BlockHook::OnFinalize).then_some(weight);
+
+		let step = match hook {
+			BlockHook::OnRuntimeUpgrade => "on_runtime_upgrade",
+			BlockHook::OnInitialize => "on_initialize",
+			BlockHook::OnPoll => "on_poll",
+			BlockHook::OnIdle => "on_idle",
+			BlockHook::OnFinalize => "on_finalize",
+		};
+		Ok(usage.to_json(step))
+	}
+}
+
+/// Returns all the values of the overlay, keyed by the prefixed key of the child trie (if any)
+/// followed by the storage key.
+fn overlay_values<H: Hasher>(
+	changes: &OverlayedChanges<H>,
+) -> BTreeMap<Vec<u8>, Option<StorageValue>> {
+	let top = changes.changes().map(|(key, value)| (key.clone(), value.value().cloned()));
+	let children = changes.children().flat_map(|(child_changes, child_info)| {
+		let prefix = child_info.prefixed_storage_key().into_inner();
+		child_changes
+			.map(move |(key, value)| ([&prefix[..], &key[..]].concat(), value.value().cloned()))
+	});
+	top.chain(children).collect()
+}
+
+/// Returns the weight consumed by the block so far, as recorded in `System::BlockWeight`.
+///
+/// The value is only looked up in the overlay, so that the profiler itself doesn't contribute to
+/// the reads and the storage proof.
+fn consumed_weight<H: Hasher>(changes: &OverlayedChanges<H>) -> Weight {
+	let key = [twox_128(b"System"), twox_128(b"BlockWeight")].concat();
+	changes
+		.storage(&key)
+		.flatten()
+		// `System::BlockWeight` holds the weight consumed by each of the three dispatch classes.
+		.and_then(|value| <[Weight; 3]>::decode(&mut &*value).ok())
+		.map_or(Weight::zero(), |per_class| {
+			per_class
+				.iter()
+				.fold(Weight::zero(), |total, weight| total.saturating_add(*weight))
+		})
+}
+
+/// A state backend that tallies the reads hitting the wrapped backend.
+#[derive(Debug)]
+struct TallyingBackend<'a, B> {
+	inner: &'a B,
+	reads: RefCell<UsageUnit>,
+	read_keys: RefCell<BTreeSet<Vec<u8>>>,
+}
+
+impl<'a, B> TallyingBackend<'a, B> {
+	fn new(inner: &'a B) -> Self {
+		Self { inner, reads: Default::default(), read_keys: Default::default() }
+	}
+
+	fn tally(&self, key: &[u8], bytes: usize) {
+		let mut reads = self.reads.borrow_mut();
+		reads.ops += 1;
+		reads.bytes += bytes as u64;
+		self.read_keys.borrow_mut().insert(key.to_vec());
+	}
+
+	fn tally_child(&self, child_info: &ChildInfo, key: &[u8], bytes: usize) {
+		self.tally(&[child_info.storage_key(), key].concat(), bytes)
+	}
+
+	/// Returns the reads tallied so far along with the number of distinct keys read, and resets
+	/// them.
+	fn take_reads(&self) -> (UsageUnit, usize) {
+		let read_keys = std::mem::take(&mut *self.read_keys.borrow_mut());
+		(self.reads.take(), read_keys.len())
+	}
+}
+
+impl<'a, H: Hasher, B: Backend<H>> Backend<H> for TallyingBackend<'a, B> {
+	type Error = B::Error;
+	type TrieBackendStorage = B::TrieBackendStorage;
+	type RawIter = TallyingRawIter<'a, B::RawIter, B>;
+
+	fn storage(&self, key: &[u8]) -> Result<Option<StorageValue>, Self::Error> {
+		let value = self.inner.storage(key)?;
+		self.tally(key, value.as_ref().map_or(0, |v| v.len()));
+		Ok(value)
+	}
+
+	fn storage_hash(&self, key: &[u8]) -> Result<Option<H::Out>, Self::Error> {
+		self.tally(key, 0);
+		self.inner.storage_hash(key)
+	}
+
+	fn closest_merkle_value(&self, key: &[u8]) -> Result<Option<MerkleValue<H::Out>>, Self::Error> {
+		self.tally(key, 0);
+		self.inner.closest_merkle_value(key)
+	}
+
+	fn child_closest_merkle_value(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<MerkleValue<H::Out>>, Self::Error> {
+		self.tally_child(child_info, key, 0);
+		self.inner.child_closest_merkle_value(child_info, key)
+	}
+
+	fn child_storage(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<StorageValue>, Self::Error> {
+		let value = self.inner.child_storage(child_info, key)?;
+		self.tally_child(child_info, key, value.as_ref().map_or(0, |v| v.len()));
+		Ok(value)
+	}
+
+	fn child_storage_hash(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<H::Out>, Self::Error> {
+		self.tally_child(child_info, key, 0);
+		self.inner.child_storage_hash(child_info, key)
+	}
+
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<StorageKey>, Self::Error> {
+		self.tally(key, 0);
+		self.inner.next_storage_key(key)
+	}
+
+	fn next_child_storage_key(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<StorageKey>, Self::Error> {
+		self.tally_child(child_info, key, 0);
+		self.inner.next_child_storage_key(child_info, key)
+	}
+
+	fn storage_root<'b>(
+		&self,
+		delta: impl Iterator<Item = (&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
+	) -> (H::Out, BackendTransaction<H>)
+	where
+		H::Out: Ord,
+	{
+		self.inner.storage_root(delta, state_version)
+	}
+
+	fn child_storage_root<'b>(
+		&self,
+		child_info: &ChildInfo,
+		delta: impl Iterator<Item = (&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
+	) -> (H::Out, bool, BackendTransaction<H>)
+	where
+		H::Out: Ord,
+	{
+		self.inner.child_storage_root(child_info, delta, state_version)
+	}
+
+	fn raw_iter(&self, args: IterArgs) -> Result<Self::RawIter, Self::Error> {
+		Ok(TallyingRawIter { inner: self.inner.raw_iter(args)?, _phantom: PhantomData })
+	}
+
+	fn register_overlay_stats(&self, stats: &StateMachineStats) {
+		self.inner.register_overlay_stats(stats)
+	}
+
+	fn usage_info(&self) -> UsageInfo {
+		let mut usage_info = self.inner.usage_info();
+		usage_info.reads = self.reads.borrow().clone();
+		usage_info
+	}
+}
+
+/// The raw iterator of [`TallyingBackend`].
+struct TallyingRawIter<'a, I, B> {
+	inner: I,
+	_phantom: PhantomData<&'a B>,
+}
+
+impl<'a, H: Hasher, B: Backend<H>> StorageIterator<H> for TallyingRawIter<'a, B::RawIter, B> {
+	type Backend = TallyingBackend<'a, B>;
+	type Error = B::Error;
+
+	fn next_key(&mut self, backend: &Self::Backend) -> Option<Result<StorageKey, Self::Error>> {
+		let key = self.inner.next_key(backend.inner)?;
+		if let Ok(ref key) = key {
+			backend.tally(key, 0);
+		}
+		Some(key)
+	}
+
+	fn next_pair(
+		&mut self,
+		backend: &Self::Backend,
+	) -> Option<Result<(StorageKey, StorageValue), Self::Error>> {
+		let pair = self.inner.next_pair(backend.inner)?;
+		if let Ok((ref key, ref value)) = pair {
+			backend.tally(key, value.len());
+		}
+		Some(pair)
+	}
+
+	fn was_complete(&self) -> bool {
+		self.inner.was_complete()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+	use substrate_test_runtime::{
+		genesismap::GenesisStorageBuilder, Block, ExtrinsicBuilder, Header, Transfer,
+	};
+
+	type HostFns = sp_io::SubstrateHostFunctions;
+
+	#[test]
+	fn profile_steps_reports_every_step_of_the_block() {
+		let storage = GenesisStorageBuilder::default().build();
+		let backend = InMemoryBackend::<HashingFor<Block>>::from((storage, StateVersion::V1));
+		let executor = WasmExecutor::<HostFns>::builder().build();
+		let header = Header::new(
+			1,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let extrinsics = vec![ExtrinsicBuilder::new_transfer(Transfer {
+			from: AccountKeyring::Alice.pair(),
+			to: AccountKeyring::Bob.into(),
+			amount: 69,
+			nonce: 0,
+		})
+		.build()];
+
+		let steps =
+			profile_steps::<Block, HostFns>(&backend, &executor, &header, &extrinsics).unwrap();
+
+		let names = steps.iter().map(|step| step["step"].as_str().unwrap()).collect::<Vec<_>>();
+		assert_eq!(names, vec!["initialize_block", "extrinsic", "finalize_block"]);
+		assert!(steps.iter().all(|step| step["wall_time_ns"].as_u64().unwrap() > 0));
+
+		let transfer = &steps[1];
+		assert_eq!(transfer["success"], true);
+		assert!(transfer["reads"]["keys"].as_u64().unwrap() > 0);
+		assert!(transfer["writes"]["keys"].as_u64().unwrap() > 0);
+		assert!(transfer["proof_size"].as_u64().unwrap() > 0);
+		assert!(transfer["declared_weight"]["ref_time"].as_u64().unwrap() > 0);
+	}
+}
@@ -88,6 +88,17 @@ pub enum Command {
 	/// more information.
 	ExecuteBlock(commands::execute_block::ExecuteBlockCmd),
 
+	/// Executes the given block against some state, one step at a time, and reports the resources
+	/// used by every step as JSON.
+	///
+	/// Unlike [`Command::ExecuteBlock`], every hook and every extrinsic is a step of its own. The
+	/// hooks are executed through `TryRuntime_execute_block_hook` and the extrinsics through
+	/// `BlockBuilder_apply_extrinsic`. For each step, the wall time, the storage reads and writes,
+	/// the proof size contribution and the declared weight are reported.
+	///
+	/// See [`commands::profile_block::ProfileBlockCmd`] for more information.
+	ProfileBlock(commands::profile_block::ProfileBlockCmd),
+
 	/// Executes *the offchain worker hooks* of a given block against some state.
 	///
 	/// This executes the same runtime api as normal block import, namely
@@ -434,6 +445,12 @@ impl TryRuntimeCmd {
 					cmd.clone(),
 				)
 				.await,
+			Command::ProfileBlock(cmd) =>
+				commands::profile_block::profile_block::<Block, HostFns>(
+					self.shared.clone(),
+					cmd.clone(),
+				)
+				.await,
 			Command::FollowChain(cmd) =>
 				commands::follow_chain::follow_chain::<Block, HostFns>(
 					self.shared.clone(),